@@ -122,6 +122,26 @@ Benchmark.ips do |x|
   x.compare!
 end
 
+# Hundreds of patterns are matched as one RegexSet, so they should cost about as much as a few
+SpellKit.load!(
+  dictionary: SpellKit::DEFAULT_DICTIONARY_URL,
+  protected_patterns: Array.new(300) { |i| {pattern: "[A-Z]{2,4}#{i}[a-z]?"} },
+  edit_distance: 2
+)
+puts
+puts "  ✓ Reloaded with 300 protected patterns"
+puts
+
+Benchmark.ips do |x|
+  x.config(time: 5, warmup: 2)
+
+  x.report("correct_tokens (300 patterns)") do
+    SpellKit.correct_tokens(MIXED_WORDS + WORDS_WITH_PROTECTED)
+  end
+
+  x.compare!
+end
+
 puts
 puts "-" * 80
 puts "Benchmark 5: Latency Distribution"
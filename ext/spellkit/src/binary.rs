@@ -0,0 +1,71 @@
+// Little-endian primitives for the binary index file (see `Engine::save_index`). Lengths are
+// u64 counts written before their items, so a truncated or corrupt file fails with an error
+// instead of a huge allocation
+use std::io::{self, Read, Write};
+
+pub fn write_u8(out: &mut impl Write, value: u8) -> io::Result<()> {
+    out.write_all(&[value])
+}
+
+pub fn write_u64(out: &mut impl Write, value: u64) -> io::Result<()> {
+    out.write_all(&value.to_le_bytes())
+}
+
+pub fn write_str(out: &mut impl Write, value: &str) -> io::Result<()> {
+    write_u64(out, value.len() as u64)?;
+    out.write_all(value.as_bytes())
+}
+
+pub fn read_u8(input: &mut impl Read) -> io::Result<u8> {
+    let mut buffer = [0; 1];
+    input.read_exact(&mut buffer)?;
+    Ok(buffer[0])
+}
+
+pub fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut buffer = [0; 8];
+    input.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+pub fn read_usize(input: &mut impl Read) -> io::Result<usize> {
+    usize::try_from(read_u64(input)?).map_err(|_| invalid("length out of range"))
+}
+
+pub fn read_string(input: &mut impl Read) -> io::Result<String> {
+    let length = read_u64(input)?;
+    let mut bytes = Vec::new();
+    input.by_ref().take(length).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != length {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|_| invalid("string is not valid UTF-8"))
+}
+
+pub fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_truncation() {
+        let mut out = Vec::new();
+        write_u8(&mut out, 7).unwrap();
+        write_u64(&mut out, u64::MAX).unwrap();
+        write_str(&mut out, "héllo").unwrap();
+
+        let mut input = out.as_slice();
+        assert_eq!(read_u8(&mut input).unwrap(), 7);
+        assert_eq!(read_u64(&mut input).unwrap(), u64::MAX);
+        assert_eq!(read_string(&mut input).unwrap(), "héllo");
+
+        // A length claiming more bytes than there are
+        let mut truncated = out[..out.len() - 2].as_ref();
+        read_u8(&mut truncated).unwrap();
+        read_u64(&mut truncated).unwrap();
+        assert_eq!(read_string(&mut truncated).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
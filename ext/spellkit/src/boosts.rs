@@ -0,0 +1,186 @@
+use hashbrown::HashSet;
+use regex::{Regex, RegexBuilder};
+use std::fmt::Write;
+use crate::guards::Guards;
+use crate::normalizer::Normalizer;
+
+#[derive(Clone)]
+enum Matcher {
+    // The regex and its flags as "imx" letters, which the compiled regex doesn't report
+    Pattern(Regex, String),
+    Terms(HashSet<String>),
+}
+
+// One load-time boost. `source` is the pattern or terms file path, reported in details
+#[derive(Clone)]
+pub struct Boost {
+    matcher: Matcher,
+    source: String,
+    multiplier: f64,
+}
+
+impl Boost {
+    // The load! key this boost came from: "pattern" or "terms_path"
+    pub fn kind(&self) -> &'static str {
+        match self.matcher {
+            Matcher::Pattern(..) => "pattern",
+            Matcher::Terms(_) => "terms_path",
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    fn matches(&self, term: &str, normalized: &str) -> bool {
+        match self.matcher {
+            Matcher::Pattern(ref regex, _) => regex.is_match(term) || regex.is_match(normalized),
+            Matcher::Terms(ref terms) => terms.contains(normalized),
+        }
+    }
+}
+
+// Frequency multipliers applied to correction candidates while ranking and thresholding.
+// The index keeps the real frequencies; boosts only change which candidate wins
+#[derive(Clone)]
+pub struct Boosts {
+    boosts: Vec<Boost>,
+    max_multiplier: f64,
+    // Terms and candidates are compared normalized
+    normalizer: Normalizer,
+}
+
+impl Boosts {
+    pub fn new(max_multiplier: f64) -> Self {
+        Self {
+            boosts: Vec::new(),
+            max_multiplier,
+            normalizer: Normalizer::default(),
+        }
+    }
+
+    // Call before adding terms
+    pub fn with_normalizer(mut self, normalizer: Normalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boosts.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.boosts.len()
+    }
+
+    pub fn get(&self, index: usize) -> &Boost {
+        &self.boosts[index]
+    }
+
+    pub fn add_pattern(
+        &mut self,
+        pattern: &str,
+        case_insensitive: bool,
+        multiline: bool,
+        extended: bool,
+        multiplier: f64,
+    ) -> Result<(), String> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .multi_line(multiline)
+            .ignore_whitespace(extended)
+            .build()
+            .map_err(|e| format!("Invalid boost pattern: {}", e))?;
+        let flags = [(case_insensitive, 'i'), (multiline, 'm'), (extended, 'x')]
+            .iter()
+            .filter_map(|&(set, flag)| set.then_some(flag))
+            .collect();
+
+        self.boosts.push(Boost {
+            matcher: Matcher::Pattern(regex, flags),
+            source: pattern.to_string(),
+            multiplier,
+        });
+        Ok(())
+    }
+
+    // `content` uses the protected terms file format: one term per line, # comments
+    pub fn add_terms(&mut self, path: &str, content: &str, multiplier: f64) {
+        let terms = Guards::protected_lines(content).map(|term| self.normalizer.normalize(term)).collect();
+
+        self.boosts.push(Boost {
+            matcher: Matcher::Terms(terms),
+            source: path.to_string(),
+            multiplier,
+        });
+    }
+
+    // Text of every boost in application order, terms sorted, for Engine::behavior_material.
+    // A terms boost is described by its terms, not the path they were read from
+    pub fn write_canonical(&self, out: &mut String) {
+        let _ = writeln!(out, "boosts.max\t{:?}", self.max_multiplier);
+        for boost in &self.boosts {
+            match boost.matcher {
+                Matcher::Pattern(ref regex, ref flags) => {
+                    let _ = writeln!(out, "boosts.pattern\t{:?}\t{}\t{:?}", boost.multiplier, flags, regex.as_str());
+                }
+                Matcher::Terms(ref terms) => {
+                    let mut sorted: Vec<&String> = terms.iter().collect();
+                    sorted.sort_unstable();
+                    let _ = writeln!(out, "boosts.terms\t{:?}\t{}", boost.multiplier, sorted.len());
+                    for term in sorted {
+                        let _ = writeln!(out, "boosts.term\t{}", term);
+                    }
+                }
+            }
+        }
+    }
+
+    // Combined multiplier for a candidate term, capped at max_multiplier, and the indices
+    // of the boosts that matched it
+    pub fn apply(&self, term: &str) -> (f64, Vec<usize>) {
+        if self.boosts.is_empty() {
+            return (1.0, Vec::new());
+        }
+
+        let normalized = self.normalizer.normalize(term);
+        let mut multiplier = 1.0;
+        let mut applied = Vec::new();
+
+        for (index, boost) in self.boosts.iter().enumerate() {
+            if boost.matches(term, &normalized) {
+                multiplier *= boost.multiplier;
+                applied.push(index);
+            }
+        }
+
+        (multiplier.min(self.max_multiplier), applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_boosts_multiply_up_to_the_cap() {
+        let mut boosts = Boosts::new(20.0);
+        boosts.add_pattern("ology$", false, false, false, 10.0).unwrap();
+        boosts.add_terms("glossary.txt", "# glossary\nOncology\n", 5.0);
+
+        assert_eq!(boosts.apply("biology"), (10.0, vec![0]));
+        assert_eq!(boosts.apply("oncology"), (20.0, vec![0, 1]));
+        assert_eq!(boosts.apply("cell"), (1.0, vec![]));
+    }
+
+    #[test]
+    fn test_invalid_boost_pattern_is_rejected() {
+        let mut boosts = Boosts::new(100.0);
+        assert!(boosts.add_pattern("(unclosed", false, false, false, 2.0).is_err());
+        assert!(boosts.is_empty());
+    }
+}
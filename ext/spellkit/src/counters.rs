@@ -0,0 +1,134 @@
+use crate::engine::Reason;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How often a checker is asked for suggestions and corrections, and what its token
+/// corrections did. Every count is a relaxed atomic add, so threads serving the same checker
+/// record without a lock
+#[derive(Debug, Default)]
+pub struct Counters {
+    suggest: AtomicU64,
+    correct: AtomicU64,
+    correct_tokens: AtomicU64,
+    tokens_processed: AtomicU64,
+    tokens_changed: AtomicU64,
+    tokens_guarded: AtomicU64,
+}
+
+impl Counters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Words looked up for suggestions
+    pub fn record_suggest(&self, words: u64) {
+        self.suggest.fetch_add(words, Ordering::Relaxed);
+    }
+
+    /// One single-word correction with the reason behind it
+    pub fn record_correct(&self, reason: Reason) {
+        self.correct.fetch_add(1, Ordering::Relaxed);
+        self.record_tokens([reason]);
+    }
+
+    /// One batch correction with the reason behind each token it decided
+    pub fn record_correct_tokens(&self, reasons: impl IntoIterator<Item = Reason>) {
+        self.correct_tokens.fetch_add(1, Ordering::Relaxed);
+        self.record_tokens(reasons);
+    }
+
+    // Tallied locally first, so a batch costs three atomic adds however long it is
+    fn record_tokens(&self, reasons: impl IntoIterator<Item = Reason>) {
+        let (mut processed, mut changed, mut guarded) = (0, 0, 0);
+        for reason in reasons {
+            processed += 1;
+            changed += u64::from(matches!(reason, Reason::Corrected | Reason::Completed));
+            guarded += u64::from(reason == Reason::Protected);
+        }
+        self.tokens_processed.fetch_add(processed, Ordering::Relaxed);
+        self.tokens_changed.fetch_add(changed, Ordering::Relaxed);
+        self.tokens_guarded.fetch_add(guarded, Ordering::Relaxed);
+    }
+
+    /// Every count by name. Concurrent recording may land between two loads, so the counts are
+    /// consistent with each other only when nothing else is recording
+    pub fn values(&self) -> [(&'static str, u64); 6] {
+        [
+            ("suggest", &self.suggest),
+            ("correct", &self.correct),
+            ("correct_tokens", &self.correct_tokens),
+            ("tokens_processed", &self.tokens_processed),
+            ("tokens_changed", &self.tokens_changed),
+            ("tokens_guarded", &self.tokens_guarded),
+        ]
+        .map(|(name, count)| (name, count.load(Ordering::Relaxed)))
+    }
+
+    pub fn reset(&self) {
+        for count in [
+            &self.suggest,
+            &self.correct,
+            &self.correct_tokens,
+            &self.tokens_processed,
+            &self.tokens_changed,
+            &self.tokens_guarded,
+        ] {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(counters: &Counters, name: &str) -> u64 {
+        counters.values().iter().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    #[test]
+    fn test_record_and_reset() {
+        let counters = Counters::new();
+        counters.record_suggest(3);
+        counters.record_correct(Reason::Corrected);
+        counters.record_correct_tokens([Reason::Exact, Reason::Protected, Reason::Corrected, Reason::Completed, Reason::NoCandidates]);
+
+        assert_eq!(
+            counters.values(),
+            [
+                ("suggest", 3),
+                ("correct", 1),
+                ("correct_tokens", 1),
+                ("tokens_processed", 6),
+                ("tokens_changed", 3),
+                ("tokens_guarded", 1),
+            ]
+        );
+
+        counters.reset();
+        assert!(counters.values().iter().all(|&(_, count)| count == 0));
+    }
+
+    #[test]
+    fn test_concurrent_recording() {
+        let counters = Counters::new();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        counters.record_suggest(1);
+                        counters.record_correct(Reason::Exact);
+                        counters.record_correct_tokens([Reason::Corrected, Reason::Protected]);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(value(&counters, "suggest"), 8000);
+        assert_eq!(value(&counters, "correct"), 8000);
+        assert_eq!(value(&counters, "correct_tokens"), 8000);
+        assert_eq!(value(&counters, "tokens_processed"), 24000);
+        assert_eq!(value(&counters, "tokens_changed"), 8000);
+        assert_eq!(value(&counters, "tokens_guarded"), 8000);
+    }
+}
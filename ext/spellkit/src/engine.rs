@@ -0,0 +1,4278 @@
+// The correction engine without any Ruby types: loading a dictionary with its guards, boosts,
+// and aliases, and the policy that decides what happens to each token. The Ruby bindings in
+// lib.rs are one caller; any Rust program can build an Engine from LoadOptions directly
+use flate2::read::MultiGzDecoder;
+use hashbrown::{HashMap, HashSet};
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::fmt::{self, Write};
+use std::io::{BufRead, Read, Write as _};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::binary::{read_u64, write_u64};
+use crate::boosts::Boosts;
+use crate::guards::{GuardMatch, Guards};
+use crate::html;
+use crate::latency::Latency;
+use crate::normalizer::Normalizer;
+use crate::symspell::{Combine, DistanceMetric, Layer, Lookup, Merge, PhraseTerm, Probe, Segmentation, Split, Suggestion, SymSpell, Verification, Warmup};
+use crate::tokenizer::{in_hyphenated_word, is_unsegmented_script, strip_ignorable, strip_punctuation, Tokenizer};
+use crate::units::{UnitMode, Units, DEFAULT_UNITS};
+
+/// Cap on the combined multiplier of all boosts matching one candidate
+pub const DEFAULT_MAX_BOOST: f64 = 100.0;
+
+/// Suggestions returned, and candidates a correction tries, when the call doesn't say
+pub const DEFAULT_MAX_SUGGESTIONS: usize = 5;
+
+/// Longest dictionary line read, in bytes. Anything longer is not a term<TAB>frequency row
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 1024;
+
+/// Longest dictionary term indexed, in characters. Deletes grow with term length, so one huge
+/// term can cost more than the rest of the dictionary
+pub const DEFAULT_MAX_WORD_LENGTH: usize = 100;
+
+/// Leading characters of a word its deletes are generated from. Longer words share buckets with
+/// the others of the same prefix and are told apart when candidates are verified
+pub const DEFAULT_PREFIX_LENGTH: usize = 7;
+
+/// Largest dictionary file loaded, in bytes. Far above any real dictionary, low enough that
+/// pointing the loader at a corpus fails before reading it rather than running out of memory
+pub const DEFAULT_MAX_DICTIONARY_BYTES: u64 = 1 << 30;
+
+/// Most dictionary entries parsed before the load is aborted
+pub const DEFAULT_MAX_WORDS: usize = 10_000_000;
+
+/// Leading bytes of an index file written by `Engine::save_index`
+pub const INDEX_MAGIC: &[u8; 8] = b"SPKINDEX";
+
+/// Bumped whenever the index file layout changes. Files of another version are rejected and have
+/// to be rebuilt with `save_index`
+pub const INDEX_FORMAT_VERSION: u64 = 5;
+
+/// Dictionary words (each also queried with one typo) a warm-up runs when no sample size is given
+pub const DEFAULT_WARMUP_SAMPLE: usize = 10_000;
+
+/// Bumped whenever `Engine::behavior_material` changes format or the same material starts
+/// producing different corrections, so digests of it never collide across versions
+pub const BEHAVIOR_VERSION: u32 = 6;
+
+/// Why loading failed. The Ruby bindings raise `Invalid` as ArgumentError, `Io` as RuntimeError,
+/// `Limit` as SpellKit::DictionaryError, `Parse` as SpellKit::DictionaryParseError, and `Pattern`
+/// as SpellKit::InvalidPatternError
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// An option value or file content that can't be used
+    Invalid(String),
+    /// A file that couldn't be opened or read
+    Io(String),
+    /// A dictionary over max_dictionary_bytes or max_words
+    Limit(String),
+    /// A dictionary entry rejected under `strict:` or `on_duplicate: error`
+    Parse(String),
+    /// A protected or boost pattern that doesn't compile
+    Pattern(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Invalid(message)
+            | LoadError::Io(message)
+            | LoadError::Limit(message)
+            | LoadError::Parse(message)
+            | LoadError::Pattern(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// A regex with the flags of the Ruby Regexp it came from
+#[derive(Debug, Clone)]
+pub struct PatternSpec {
+    pub source: String,
+    pub case_insensitive: bool,
+    pub multiline: bool,
+    pub extended: bool,
+    /// Match the whole token only. Unanchored patterns protect any token they match part of
+    pub anchored: bool,
+    /// Reported with the pattern when it protects a token (see `GuardMatch`)
+    pub name: Option<String>,
+}
+
+impl PatternSpec {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            case_insensitive: false,
+            multiline: false,
+            extended: false,
+            anchored: false,
+            name: None,
+        }
+    }
+}
+
+/// What a ranking boost matches: candidate terms against a pattern, or the terms in a file
+#[derive(Debug, Clone)]
+pub enum BoostSource {
+    Pattern(PatternSpec),
+    TermsPath(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BoostSpec {
+    pub source: BoostSource,
+    pub multiplier: f64,
+}
+
+/// How a correction and a two-word split of the same token are weighed when both exist
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentationPreference {
+    /// The higher score wins: the correction's (boosted) frequency against the split's score
+    Score,
+    Segmentation,
+    Correction,
+}
+
+/// How much of the deletes index is built at load. `QueryExpanded` indexes one distance level
+/// less and has queries generate the rest, trading query work for memory with the same results
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexStrategy {
+    Full,
+    QueryExpanded,
+}
+
+impl IndexStrategy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(Self::Full),
+            "query_expanded" => Some(Self::QueryExpanded),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::QueryExpanded => "query_expanded",
+        }
+    }
+}
+
+/// How dictionary lines are read. `Counts` lines are a term and its frequency, `Wordlist`
+/// lines a bare term given `LoadOptions::default_frequency`. `Auto` takes whichever the first
+/// non-empty line looks like
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DictionaryFormat {
+    Counts,
+    Wordlist,
+    Auto,
+}
+
+impl DictionaryFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "counts" => Some(Self::Counts),
+            "wordlist" => Some(Self::Wordlist),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Counts => "counts",
+            Self::Wordlist => "wordlist",
+            Self::Auto => "auto",
+        }
+    }
+}
+
+/// What a dictionary entry for a word already loaded from the same dictionary does: add its
+/// count, keep the larger of the two, or fail the load. Casing variants count as the same word
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnDuplicate {
+    Sum,
+    Max,
+    Error,
+}
+
+impl OnDuplicate {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Self::Sum),
+            "max" => Some(Self::Max),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Max => "max",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Whether dictionary files are decompressed while they're read. `Auto` takes gzip for paths
+/// ending in ".gz"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Auto,
+    None,
+    Gzip,
+}
+
+impl Compression {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Self::Auto),
+            "none" => Some(Self::None),
+            "gzip" => Some(Self::Gzip),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::None => "none",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    fn is_gzip(self, path: &str) -> bool {
+        match self {
+            Self::Auto => path.ends_with(".gz"),
+            Self::None => false,
+            Self::Gzip => true,
+        }
+    }
+}
+
+/// What corrections do when the best passing candidates tie exactly: same distance and the same
+/// boosted frequency. `Lexicographic` takes the alphabetically first, `None` declines to correct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TieBreak {
+    Lexicographic,
+    None,
+}
+
+impl TieBreak {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "lexicographic" => Some(Self::Lexicographic),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lexicographic => "lexicographic",
+            Self::None => "none",
+        }
+    }
+}
+
+/// The frequencies a correction must reach: `min_suggestion_frequency` for an unknown word, and
+/// `frequency_ratio` times its own frequency for a dictionary word, among the best
+/// `max_suggestions` candidates, and which tokens are passed through before any lookup. Fixed at
+/// load, and `Engine::correct_token_with` takes others for a single call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub min_suggestion_frequency: f64,
+    pub frequency_ratio: f64,
+    pub max_suggestions: usize,
+    /// Leave tokens containing a digit ("2024", "a1") as written
+    pub skip_tokens_with_digits: bool,
+    /// Leave tokens of fewer characters than this as written. 0 skips nothing
+    pub min_word_length: usize,
+    /// Leave tokens of two or more uppercase letters ("NASA", "ÉTÉ") as written, once no guard
+    /// has protected them
+    pub skip_all_caps: bool,
+}
+
+impl Thresholds {
+    /// The frequency a correction must reach, given the word's own frequency if it's in the
+    /// dictionary
+    pub fn required_frequency(&self, original_frequency: Option<u64>) -> f64 {
+        match original_frequency {
+            // Word not in dictionary: require suggestion frequency >= absolute floor
+            None => self.min_suggestion_frequency,
+            // Word in dictionary: require suggestion frequency >= ratio * original frequency
+            Some(frequency) => self.frequency_ratio * frequency as f64,
+        }
+    }
+
+    /// Names the first threshold that isn't a finite, non-negative number, or a candidate pool
+    /// that's empty
+    pub fn validate(&self) -> Result<(), String> {
+        check_threshold("min_suggestion_frequency", self.min_suggestion_frequency)?;
+        check_threshold("frequency_ratio", self.frequency_ratio)?;
+        if self.max_suggestions == 0 {
+            return Err("max_suggestions must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Why `token` (without edge punctuation) is passed through uncorrected, if it is. Checked
+    /// before guards and lookups, so it costs a scan of the token
+    pub fn skip_reason(&self, token: &str) -> Option<Reason> {
+        if self.skip_tokens_with_digits && token.chars().any(char::is_numeric) {
+            return Some(Reason::HasDigits);
+        }
+        if self.min_word_length > 0 && token.chars().nth(self.min_word_length - 1).is_none() {
+            return Some(Reason::TooShort);
+        }
+        None
+    }
+
+    /// Whether `token` is left as written under `skip_all_caps`
+    pub fn skips_all_caps(&self, token: &str) -> bool {
+        self.skip_all_caps && token.chars().nth(1).is_some() && token.chars().all(char::is_uppercase)
+    }
+}
+
+pub fn check_threshold(name: &str, value: f64) -> Result<(), String> {
+    if !value.is_finite() {
+        return Err(format!("{} must be finite (not NaN or Infinity)", name));
+    }
+
+    if value < 0.0 {
+        return Err(format!("{} must be non-negative, got: {}", name, value));
+    }
+    Ok(())
+}
+
+/// How the dictionary was obtained. `kind` is "file", "url", "index", or "memory"; for URLs
+/// `bytes` describes the cached download that was parsed, and it's 0 for in-memory entries.
+/// `Engine::load` fills in `bytes` itself
+#[derive(Debug, Clone)]
+pub struct DictionarySource {
+    pub kind: String,
+    pub path_or_url: String,
+    pub sha256: Option<String>,
+    pub bytes: usize,
+}
+
+/// Everything `Engine::load` needs. `LoadOptions::new` gives the same defaults as `load!`
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    pub dictionary_path: String,
+    /// Word and frequency pairs loaded in place of dictionary_path (see `LoadOptions::from_entries`)
+    pub entries: Option<Vec<(String, u64)>>,
+    pub edit_distance: usize,
+    /// Deprecated alias that sets both min_suggestion_frequency and frequency_ratio
+    pub frequency_threshold: f64,
+    /// Frequency a correction of an unknown word must reach. None falls back to frequency_threshold
+    pub min_suggestion_frequency: Option<f64>,
+    /// Multiple of a known word's own frequency its correction must reach. None falls back to
+    /// frequency_threshold
+    pub frequency_ratio: Option<f64>,
+    /// How many suggestions `suggest` callers get, and candidates a correction tries, by default
+    pub max_suggestions: usize,
+    /// See `Thresholds::skip_tokens_with_digits`, `Thresholds::min_word_length`, and
+    /// `Thresholds::skip_all_caps`
+    pub skip_tokens_with_digits: bool,
+    pub min_word_length: usize,
+    pub skip_all_caps: bool,
+    /// u32 frequencies, divided by frequency_scale at load and multiplied back in outputs
+    pub compact: bool,
+    pub frequency_scale: u64,
+    /// Only differs from `Full` at edit distance 2
+    pub index_strategy: IndexStrategy,
+    /// Index deletes of only this many leading characters of each word (see
+    /// `SymSpell::with_prefix_length`). Must be above edit_distance. None for whole words
+    pub prefix_length: Option<usize>,
+    /// How base, user, and trained counts of a word combine into its frequency
+    pub frequency_combine: Combine,
+    /// How candidates are verified against the query and their distance reported
+    pub distance_metric: DistanceMetric,
+    /// How words become keys: for the index, queries, protected terms, boosts, and aliases
+    pub normalizer: Normalizer,
+    pub format: DictionaryFormat,
+    pub on_duplicate: OnDuplicate,
+    /// Applies to dictionary_path. Protected terms, aliases, and boost term files are
+    /// decompressed by extension
+    pub compression: Compression,
+    /// Splits counts lines into columns. None takes tabs if the line has any, else whitespace,
+    /// and then a line needs exactly the columns the indexes name; with a separator extra
+    /// columns are ignored
+    pub separator: Option<String>,
+    pub term_index: usize,
+    pub count_index: usize,
+    /// The frequency of every word read from a wordlist
+    pub default_frequency: u64,
+    pub max_line_length: usize,
+    pub max_word_length: usize,
+    /// Fail the load on an overlong line or term, or a line in the other format from the rest
+    /// of a wordlist, instead of skipping it
+    pub strict: bool,
+    /// Checked against the file size before anything is read. None for no limit
+    pub max_dictionary_bytes: Option<u64>,
+    /// Parsing stops with an error once more entries than this are read. None for no limit
+    pub max_words: Option<usize>,
+    /// word<TAB>count lines (or bare words at default_frequency) loaded into the user layer, so
+    /// they outrank dictionary words at the same distance
+    pub user_dictionary_path: Option<String>,
+    pub protected_path: Option<String>,
+    pub protected_prefixes: Vec<String>,
+    pub protected_patterns: Vec<PatternSpec>,
+    /// Names of the built-in skip presets whose patterns are among `protected_patterns`,
+    /// reported in stats
+    pub skip_presets: Vec<String>,
+    pub protected_as_known: bool,
+    pub protect_only_unknown: bool,
+    pub protected_case_sensitive: bool,
+    pub confident_frequency: u64,
+    pub aliases_path: Option<String>,
+    /// One word per line that is never suggested or corrected to, though typing it is not
+    /// corrected away
+    pub denylist_path: Option<String>,
+    pub autocorrect_known_rare: Option<u64>,
+    pub boosts: Vec<BoostSpec>,
+    pub max_boost: f64,
+    pub tokenizer: Tokenizer,
+    /// Two-word splits of unknown tokens compete with corrections when set
+    pub segmentation: Option<SegmentationPreference>,
+    /// Only correct to candidates of the same shape as the token (see `same_shape`)
+    pub structural_match: bool,
+    /// Default for carrying the input's casing over to corrections (`OutputMode::PreserveCase`)
+    pub preserve_case: bool,
+    pub tie_break: TieBreak,
+    /// Numbers, and numbers followed by a unit from `unit_list`, bypass guards and correction
+    pub units: UnitMode,
+    /// Defaults to `DEFAULT_UNITS`
+    pub unit_list: Option<Vec<String>>,
+    /// Drop zero-width and bidi characters (see `strip_ignorable`) from dictionary terms,
+    /// protected terms and prefixes, and queries
+    pub strip_ignorable: bool,
+    /// Load a prebuilt index written by `Engine::save_index` instead of parsing and indexing
+    /// dictionary_path. Its indexing options must match these
+    pub index_path: Option<String>,
+    /// Defaults to a "file" source at dictionary_path, or an "index" source at index_path
+    pub source: Option<DictionarySource>,
+    /// Keep latency histograms for callers to record into (see `Engine::latency`)
+    pub latency_stats: bool,
+    /// Warm the index with this many sampled words before `load` returns (see `Engine::warmup`)
+    pub warmup: Option<usize>,
+}
+
+impl LoadOptions {
+    pub fn new(dictionary_path: &str) -> Self {
+        Self {
+            dictionary_path: dictionary_path.to_string(),
+            entries: None,
+            edit_distance: 1,
+            frequency_threshold: 10.0,
+            min_suggestion_frequency: None,
+            frequency_ratio: None,
+            max_suggestions: DEFAULT_MAX_SUGGESTIONS,
+            skip_tokens_with_digits: false,
+            min_word_length: 0,
+            skip_all_caps: false,
+            compact: false,
+            frequency_scale: 1,
+            index_strategy: IndexStrategy::Full,
+            prefix_length: Some(DEFAULT_PREFIX_LENGTH),
+            frequency_combine: Combine::Sum,
+            distance_metric: DistanceMetric::Levenshtein,
+            normalizer: Normalizer::default(),
+            format: DictionaryFormat::Counts,
+            on_duplicate: OnDuplicate::Max,
+            compression: Compression::Auto,
+            separator: None,
+            term_index: 0,
+            count_index: 1,
+            default_frequency: 1,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            max_word_length: DEFAULT_MAX_WORD_LENGTH,
+            strict: false,
+            max_dictionary_bytes: Some(DEFAULT_MAX_DICTIONARY_BYTES),
+            max_words: Some(DEFAULT_MAX_WORDS),
+            user_dictionary_path: None,
+            protected_path: None,
+            protected_prefixes: Vec::new(),
+            protected_patterns: Vec::new(),
+            skip_presets: Vec::new(),
+            protected_as_known: false,
+            protect_only_unknown: false,
+            protected_case_sensitive: false,
+            confident_frequency: 1000,
+            aliases_path: None,
+            denylist_path: None,
+            autocorrect_known_rare: None,
+            boosts: Vec::new(),
+            max_boost: DEFAULT_MAX_BOOST,
+            tokenizer: Tokenizer::Unicode,
+            segmentation: None,
+            structural_match: true,
+            preserve_case: false,
+            tie_break: TieBreak::Lexicographic,
+            units: UnitMode::Off,
+            unit_list: None,
+            strip_ignorable: true,
+            index_path: None,
+            source: None,
+            latency_stats: true,
+            warmup: None,
+        }
+    }
+
+    /// Options loading `entries` instead of a dictionary file, checked and counted like file
+    /// lines. Their source kind is "memory"
+    pub fn from_entries(entries: Vec<(String, u64)>) -> Self {
+        Self { entries: Some(entries), ..Self::new("") }
+    }
+}
+
+// Minimum time between progress callbacks within a phase
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Optional load progress callback, invoked as (phase, processed, total) at the start and end
+/// of each phase and at most every 250ms in between. An error from the callback aborts the load
+pub struct Progress<F> {
+    callback: Option<F>,
+    last_report: Instant,
+}
+
+impl<F, E> Progress<F>
+where
+    F: FnMut(&str, usize, usize) -> Result<(), E>,
+{
+    pub fn new(callback: Option<F>) -> Self {
+        Self {
+            callback,
+            last_report: Instant::now(),
+        }
+    }
+
+    pub fn boundary(&mut self, phase: &str, processed: usize, total: usize) -> Result<(), E> {
+        if let Some(ref mut callback) = self.callback {
+            callback(phase, processed, total)?;
+            self.last_report = Instant::now();
+        }
+        Ok(())
+    }
+
+    // Cheap per-item check: only every 1024th item looks at the clock
+    fn tick(&mut self, phase: &str, item: usize, processed: usize, total: usize) -> Result<(), E> {
+        if self.callback.is_none() || item & 1023 != 0 || self.last_report.elapsed() < PROGRESS_INTERVAL {
+            return Ok(());
+        }
+        self.boundary(phase, processed, total)
+    }
+}
+
+/// Why a token came out of the correction pipeline the way it did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reason {
+    Exact,
+    Protected,
+    Corrected,
+    BelowThreshold,
+    NoCandidates,
+    /// The best candidates tied exactly under `TieBreak::None`
+    Ambiguous,
+    /// A number, with or without a listed unit, under `units: skip` or `normalize`
+    Numeric,
+    /// Contained a digit under `skip_tokens_with_digits`
+    HasDigits,
+    /// Shorter than `min_word_length`
+    TooShort,
+    /// All uppercase letters under `skip_all_caps`
+    AllCaps,
+    /// Only from the last_token policies
+    Completed,
+    Skipped,
+}
+
+impl Reason {
+    /// Reasons of the standard pipeline, as counted by evaluate_corpus
+    pub const ALL: [Reason; 10] = [
+        Reason::Exact,
+        Reason::Protected,
+        Reason::Corrected,
+        Reason::BelowThreshold,
+        Reason::NoCandidates,
+        Reason::Ambiguous,
+        Reason::Numeric,
+        Reason::HasDigits,
+        Reason::TooShort,
+        Reason::AllCaps,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Reason::Exact => "exact",
+            Reason::Protected => "protected",
+            Reason::Corrected => "corrected",
+            Reason::BelowThreshold => "below_threshold",
+            Reason::NoCandidates => "no_candidates",
+            Reason::Ambiguous => "ambiguous",
+            Reason::Numeric => "numeric",
+            Reason::HasDigits => "has_digits",
+            Reason::TooShort => "too_short",
+            Reason::AllCaps => "all_caps",
+            Reason::Completed => "completed",
+            Reason::Skipped => "skipped",
+        }
+    }
+}
+
+/// Outcome of correcting a single token. Distance and frequency describe the output term
+/// when it came from the dictionary (exact match or correction)
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub output: String,
+    pub reason: Reason,
+    pub distance: Option<usize>,
+    pub frequency: Option<u64>,
+    /// Highest-ranked candidate that failed the frequency threshold, for BelowThreshold
+    pub best_rejected: Option<Rejected>,
+    /// Combined boost multiplier of a corrected output, and indices of the boosts behind it
+    /// (see `Engine::boosts`)
+    pub boost: f64,
+    pub boosts: Vec<usize>,
+    /// With segmentation on: which path produced the output and both paths' scores
+    pub path: Option<PathChoice>,
+    /// The guard behind a Protected decision
+    pub guard: Option<GuardMatch>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PathChoice {
+    pub segmented: bool,
+    pub correction_score: Option<f64>,
+    pub segmentation_score: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rejected {
+    pub term: String,
+    pub distance: usize,
+    pub frequency: u64,
+    pub required_frequency: f64,
+}
+
+/// Everything behind one token's decision, from `Engine::explain`
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    /// The token as corrected: cleaned, and without edge punctuation when that was stripped
+    pub term: String,
+    pub normalized: String,
+    /// Dictionary frequency of `term`, None when it isn't a dictionary word
+    pub frequency: Option<u64>,
+    pub guard: Option<GuardMatch>,
+    /// Every dictionary word within edit distance of `term`, best first
+    pub candidates: Vec<Suggestion>,
+    /// What a correction of `term` had to reach (see `Thresholds::required_frequency`)
+    pub required_frequency: f64,
+    pub decision: Decision,
+}
+
+/// A phrase corrected by `Engine::correct_phrase`, with its words joined by single spaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhraseCorrection {
+    pub output: String,
+    /// Total edits over all words, plus one for each space added or removed
+    pub distance: usize,
+}
+
+/// A replacement `Engine::propose_text_changes` would make: `original` is the source slice at
+/// byte offsets [start, end) and `proposed` its replacement as it would be written
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChange {
+    pub start: usize,
+    pub end: usize,
+    pub original: String,
+    pub proposed: String,
+    pub confidence: Option<f64>,
+}
+
+/// What `Engine::evaluate_corpus` saw: lines read, word tokens corrected, and how many of them
+/// ended with each reason
+#[derive(Debug, Default)]
+pub struct CorpusSummary {
+    pub lines: usize,
+    pub tokens: usize,
+    pub reasons: HashMap<Reason, usize>,
+}
+
+impl CorpusSummary {
+    pub fn count(&self, reason: Reason) -> usize {
+        self.reasons.get(&reason).copied().unwrap_or(0)
+    }
+}
+
+/// A pair `Engine::run_golden` got wrong, with the reason behind what it got
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenFailure {
+    pub input: String,
+    pub expected: String,
+    pub got: String,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoldenReport {
+    pub passed: usize,
+    pub failed: Vec<GoldenFailure>,
+}
+
+impl Decision {
+    fn unchanged(word: &str, reason: Reason) -> Self {
+        Self {
+            output: word.to_string(),
+            reason,
+            distance: None,
+            frequency: None,
+            best_rejected: None,
+            boost: 1.0,
+            boosts: Vec::new(),
+            path: None,
+            guard: None,
+        }
+    }
+
+    fn protected(word: &str, guard: GuardMatch) -> Self {
+        Self {
+            guard: Some(guard),
+            ..Self::unchanged(word, Reason::Protected)
+        }
+    }
+
+    fn exact(suggestion: &Suggestion) -> Self {
+        Self {
+            output: suggestion.term.clone(),
+            reason: Reason::Exact,
+            distance: Some(0),
+            frequency: Some(suggestion.frequency),
+            best_rejected: None,
+            boost: 1.0,
+            boosts: Vec::new(),
+            path: None,
+            guard: None,
+        }
+    }
+}
+
+/// How the final token of a query is treated; every other token is corrected normally
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LastToken {
+    Normal,
+    Skip,
+    Complete,
+}
+
+/// Output form for corrected tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputMode {
+    Canonical,
+    Normalized,
+    PreserveCase,
+}
+
+impl OutputMode {
+    pub fn apply(self, original: &str, output: String, normalizer: &Normalizer) -> String {
+        match self {
+            OutputMode::Canonical => output,
+            OutputMode::Normalized => normalizer.normalize(&output),
+            OutputMode::PreserveCase => transfer_case(original, &output),
+        }
+    }
+}
+
+fn has_joiner(term: &str) -> bool {
+    term.chars().any(|c| matches!(c, '-' | '\'' | '\u{2010}' | '\u{2011}' | '\u{2019}'))
+}
+
+/// Whether `candidate` is structurally compatible with `word`: both or neither contain digits,
+/// and a word with a hyphen or apostrophe only matches candidates with one. An alphabetic word
+/// may still gain one, so "mcdonalds" can become "McDonald's"
+pub fn same_shape(word: &str, candidate: &str) -> bool {
+    word.chars().any(char::is_numeric) == candidate.chars().any(char::is_numeric)
+        && (!has_joiner(word) || has_joiner(candidate))
+}
+
+// Carries the input's casing pattern over to the output: all-caps stays all-caps and an
+// initial capital stays an initial capital. Lowercase and mixed-case input keep the output as-is.
+// Edge punctuation is looked past on both sides, so "(Helo)" becomes "(Hello)"
+fn transfer_case(original: &str, output: &str) -> String {
+    let (_, original, _) = strip_punctuation(original);
+    let has_upper = original.chars().any(char::is_uppercase);
+    let has_lower = original.chars().any(char::is_lowercase);
+
+    if has_upper && !has_lower {
+        return output.to_uppercase();
+    }
+
+    let mut chars = original.chars();
+    let initial_upper = chars.next().is_some_and(char::is_uppercase);
+    if initial_upper && !chars.any(char::is_uppercase) {
+        let (lead, core, trail) = strip_punctuation(output);
+        let mut out_chars = core.chars();
+        if let Some(first) = out_chars.next() {
+            return format!("{}{}{}", lead, first.to_uppercase().chain(out_chars).collect::<String>(), trail);
+        }
+    }
+
+    output.to_string()
+}
+
+// Parses alias<TAB>canonical lines; blank lines and # comments are skipped
+fn parse_aliases(content: &str, normalizer: &Normalizer) -> Result<HashMap<String, String>, String> {
+    let mut aliases = HashMap::new();
+
+    for (line_index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (alias, canonical) = match trimmed.split_once('\t') {
+            Some((alias, canonical)) if !alias.trim().is_empty() && !canonical.trim().is_empty() => {
+                (alias.trim(), canonical.trim())
+            }
+            _ => return Err(format!("Malformed alias on line {}: expected alias<TAB>canonical", line_index + 1)),
+        };
+
+        aliases.insert(normalizer.normalize(alias), canonical.to_string());
+    }
+
+    Ok(aliases)
+}
+
+// Parses word<TAB>count lines, or bare words counted at default_frequency; blank lines and #
+// comments are skipped. Every other line must hold one word, whatever `strict` says
+fn parse_user_dictionary(content: &str, options: &LoadOptions) -> Result<Vec<(String, u64)>, LoadError> {
+    let mut entries = Vec::new();
+
+    for (line_index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let malformed = |reason: &str| LoadError::Parse(format!("User dictionary line {} is malformed: {}", line_index + 1, reason));
+        let (term, count) = match trimmed.split_once('\t') {
+            Some((term, count)) => {
+                let count = count.trim();
+                let count = count
+                    .parse::<u64>()
+                    .map_err(|_| malformed(&format!("frequency {:?} is not a non-negative integer", count)))?;
+                (term, count)
+            }
+            None => (trimmed, options.default_frequency),
+        };
+        let term = match clean_term(term, options) {
+            Ok(term) => term,
+            Err(SkippedTerm::Malformed) => return Err(malformed("empty term")),
+            Err(SkippedTerm::Multiword) => return Err(malformed("expected a single word")),
+            Err(SkippedTerm::TooLong) => {
+                return Err(malformed(&format!("longer than max_word_length ({} characters)", options.max_word_length)));
+            }
+        };
+        entries.push((term.into_owned(), count));
+    }
+
+    Ok(entries)
+}
+
+// Writes canonical<TAB>frequency lines to a temporary file next to `path` and renames it into
+// place, so a reader never sees a partial dictionary
+fn write_entries<'a>(path: &str, entries: impl IntoIterator<Item = (&'a str, u64)>) -> Result<(), LoadError> {
+    use std::io::Write as _;
+
+    let temporary = format!("{}.tmp", path);
+    let write = || -> std::io::Result<()> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(&temporary)?);
+        for (canonical, frequency) in entries {
+            writeln!(out, "{}\t{}", canonical, frequency)?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&temporary, path)
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&temporary);
+        LoadError::Io(format!("Failed to write dictionary file '{}': {}", path, e))
+    })
+}
+
+// Reads one line into `line` without its line ending and returns its full length in bytes, or
+// None at end of input. Only the first `max` bytes are buffered: the rest of an overlong line
+// is consumed in place, so a huge newline-free blob never gets allocated
+// Parses the dictionary file named by the options, or takes their in-memory entries, and
+// indexes it, returning the index, the parse counts, and the file size
+fn parse_dictionary<F, E>(options: &LoadOptions, progress: &mut Progress<F>) -> Result<(SymSpell, LoadStats, usize), E>
+where
+    F: FnMut(&str, usize, usize) -> Result<(), E>,
+    E: From<LoadError>,
+{
+    // Parse into entries, then build the index in a separate pass so each phase can report
+    // its own progress
+    let (entries, mut stats, total_bytes) = match options.entries {
+        Some(ref pairs) => memory_entries(pairs, options, progress)?,
+        None => read_dictionary_file(options, progress)?,
+    };
+
+    let mut symspell = if options.compact {
+        SymSpell::new_compact(options.edit_distance, options.frequency_scale)
+    } else {
+        SymSpell::new(options.edit_distance)
+    };
+    if options.index_strategy == IndexStrategy::QueryExpanded {
+        symspell = symspell.query_expanded();
+    }
+    symspell = symspell
+        .with_prefix_length(options.prefix_length)
+        .with_combine(options.frequency_combine)
+        .with_metric(options.distance_metric)
+        .with_normalizer(options.normalizer);
+    let total_entries = entries.len();
+    progress.boundary("indexing", 0, total_entries)?;
+
+    let merge = if options.on_duplicate == OnDuplicate::Sum { Merge::Sum } else { Merge::Max };
+    for (i, (term, freq)) in entries.iter().enumerate() {
+        let normalized = symspell.normalize(term);
+        if options.on_duplicate == OnDuplicate::Error {
+            if let Some(first) = symspell.canonical(&normalized) {
+                return Err(LoadError::Parse(format!(
+                    "Dictionary term {:?} duplicates {:?}, loaded earlier (on_duplicate: error)",
+                    term, first
+                ))
+                .into());
+            }
+        }
+        let was_new = symspell.merge_word(&normalized, term, *freq, merge);
+        if was_new {
+            stats.dictionary_size += 1;
+        } else {
+            stats.skipped_duplicates += 1;
+        }
+        progress.tick("indexing", i, i + 1, total_entries)?;
+    }
+    drop(entries);
+
+    progress.boundary("indexing", total_entries, total_entries)?;
+    Ok((symspell, stats, total_bytes))
+}
+
+// Entries read from a dictionary, the parse counts, and the file size
+type Parsed = (Vec<(String, u64)>, LoadStats, usize);
+
+// Why a dictionary term can't be indexed
+enum SkippedTerm {
+    Malformed,
+    Multiword,
+    TooLong,
+}
+
+// Trims a dictionary term, dropping ignorable characters if the options ask for it
+fn clean_term<'a>(term: &'a str, options: &LoadOptions) -> Result<Cow<'a, str>, SkippedTerm> {
+    let term = if options.strip_ignorable { strip_ignorable(term.trim()) } else { Cow::Borrowed(term.trim()) };
+    if term.is_empty() {
+        Err(SkippedTerm::Malformed)
+    } else if term.contains(char::is_whitespace) {
+        // SymSpell doesn't support phrases
+        Err(SkippedTerm::Multiword)
+    } else if term.chars().count() > options.max_word_length {
+        Err(SkippedTerm::TooLong)
+    } else {
+        Ok(term)
+    }
+}
+
+fn max_words_error(limit: usize, reached: String) -> LoadError {
+    LoadError::Limit(format!("Dictionary has more than max_words ({}) entries; {}", limit, reached))
+}
+
+// Checks `LoadOptions::entries` the way dictionary lines are checked, with entry counts in
+// place of bytes for progress
+fn memory_entries<F, E>(pairs: &[(String, u64)], options: &LoadOptions, progress: &mut Progress<F>) -> Result<Parsed, E>
+where
+    F: FnMut(&str, usize, usize) -> Result<(), E>,
+    E: From<LoadError>,
+{
+    let mut entries = Vec::with_capacity(pairs.len());
+    let mut stats = LoadStats::default();
+    progress.boundary("parsing", 0, pairs.len())?;
+
+    for (i, (term, freq)) in pairs.iter().enumerate() {
+        progress.tick("parsing", i, i + 1, pairs.len())?;
+        match clean_term(term, options) {
+            Ok(term) => entries.push((term.into_owned(), *freq)),
+            Err(SkippedTerm::Malformed) if options.strict => {
+                return Err(LoadError::Parse(format!("Dictionary entry {} is malformed: empty term", i + 1)).into());
+            }
+            Err(SkippedTerm::Malformed) => stats.skipped_malformed += 1,
+            Err(SkippedTerm::Multiword) => stats.skipped_multiword += 1,
+            Err(SkippedTerm::TooLong) if options.strict => {
+                return Err(LoadError::Parse(format!(
+                    "Dictionary term {:?} is longer than max_word_length ({} characters)",
+                    term, options.max_word_length
+                ))
+                .into());
+            }
+            Err(SkippedTerm::TooLong) => stats.skipped_long_words += 1,
+        }
+
+        if let Some(limit) = options.max_words.filter(|&limit| entries.len() > limit) {
+            return Err(max_words_error(limit, format!("stopped at entry {} of {}", i + 1, pairs.len())).into());
+        }
+    }
+
+    progress.boundary("parsing", pairs.len(), pairs.len())?;
+    stats.lines_read = pairs.len();
+    Ok((entries, stats, 0))
+}
+
+// Reads the dictionary file line by line into entries, with the parse counts and file size
+fn read_dictionary_file<F, E>(options: &LoadOptions, progress: &mut Progress<F>) -> Result<Parsed, E>
+where
+    F: FnMut(&str, usize, usize) -> Result<(), E>,
+    E: From<LoadError>,
+{
+    let file = std::fs::File::open(&options.dictionary_path)
+        .map_err(|e| LoadError::Io(format!("Failed to open dictionary file: {}", e)))?;
+
+    let total_bytes = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+    if let Some(limit) = options.max_dictionary_bytes.filter(|&limit| total_bytes as u64 > limit) {
+        return Err(LoadError::Limit(format!(
+            "Dictionary file is {} bytes, over max_dictionary_bytes ({}); nothing was read",
+            total_bytes, limit
+        ))
+        .into());
+    }
+
+    // A compressed file is inflated as it's read, with progress and limits measured in the
+    // compressed bytes consumed so they stay comparable to the file size
+    let compressed = options.compression.is_gzip(&options.dictionary_path).then(|| Rc::new(Cell::new(0)));
+    let mut reader: Box<dyn BufRead> = match compressed {
+        Some(ref consumed) => Box::new(std::io::BufReader::new(MultiGzDecoder::new(Counted {
+            inner: file,
+            count: Rc::clone(consumed),
+        }))),
+        None => Box::new(std::io::BufReader::new(file)),
+    };
+    let position = |bytes_read: usize| compressed.as_ref().map_or(bytes_read, |consumed| consumed.get()).min(total_bytes);
+    let mut line_buffer = Vec::new();
+    let mut entries: Vec<(String, u64)> = Vec::new();
+    let mut bytes_read = 0;
+    let mut stats = LoadStats::default();
+    let columns = options.term_index.max(options.count_index) + 1;
+    // Resolved from the first non-empty line under `Auto`, with the line number that decided it
+    let mut format = match options.format {
+        DictionaryFormat::Auto => None,
+        format => Some((format, None)),
+    };
+
+    progress.boundary("parsing", 0, total_bytes)?;
+
+    for line_index in 0.. {
+        let length = read_capped_line(&mut reader, &mut line_buffer, options.max_line_length).map_err(|e| match compressed {
+            Some(_) => LoadError::Io(format!("Failed to decompress dictionary file '{}': {}", options.dictionary_path, e)),
+            None => LoadError::Io(format!("Failed to read line: {}", e)),
+        })?;
+        let Some(length) = length else { break };
+        bytes_read += length + 1;
+        stats.lines_read += 1;
+        progress.tick("parsing", line_index, position(bytes_read), total_bytes)?;
+
+        if length > options.max_line_length {
+            if options.strict {
+                return Err(LoadError::Parse(format!(
+                    "Dictionary line {} is longer than max_line_length ({} bytes)",
+                    line_index + 1,
+                    options.max_line_length
+                ))
+                .into());
+            }
+            stats.skipped_long_lines += 1;
+            continue;
+        }
+
+        let line = std::str::from_utf8(&line_buffer)
+            .map_err(|_| LoadError::Io("Failed to read line: stream did not contain valid UTF-8".into()))?;
+
+        // Blank lines count as malformed, but even strict loads pass over them
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            stats.comment_lines += 1;
+            continue;
+        }
+        if trimmed.is_empty() {
+            stats.skipped_malformed += 1;
+            continue;
+        }
+        let malformed = |reason: String| -> Result<(), LoadError> {
+            if options.strict {
+                return Err(LoadError::Parse(format!("Dictionary line {} is malformed: {}", line_index + 1, reason)));
+            }
+            Ok(())
+        };
+
+        // Try tab-separated first (allows multi-word terms), then space-separated (SymSpell format)
+        let parts: Vec<&str> = match options.separator {
+            Some(ref separator) => line.split(separator.as_str()).collect(),
+            None if line.contains('\t') => line.split('\t').collect(),
+            None => line.split_whitespace().collect(),
+        };
+
+        if format.is_none() {
+            let detected = if parts.len() == 1 { DictionaryFormat::Wordlist } else { DictionaryFormat::Counts };
+            format = Some((detected, Some(line_index + 1)));
+        }
+        let wordlist = matches!(format, Some((DictionaryFormat::Wordlist, _)));
+
+        // A bare term in a counts file, or a term with a trailing count in a wordlist, means the
+        // file mixes the two formats
+        let mixed = if wordlist {
+            parts.len() == 2 && parts[1].trim().parse::<u64>().is_ok()
+        } else {
+            parts.len() == 1
+        };
+        if mixed {
+            if options.strict {
+                let (has, format_name) = if wordlist { ("a frequency column", "wordlist") } else { ("no frequency column", "counts") };
+                let detected = match format {
+                    Some((_, Some(line))) => format!(" (detected from line {})", line),
+                    _ => String::new(),
+                };
+                return Err(LoadError::Parse(format!(
+                    "Dictionary line {} has {}, but the file's format is {}{}",
+                    line_index + 1,
+                    has,
+                    format_name,
+                    detected
+                ))
+                .into());
+            }
+            stats.skipped_malformed += 1;
+            continue;
+        }
+
+        // Counts lines have the term and frequency columns (and exactly those without a
+        // separator). Wordlist lines are a single term, so any whitespace left in one makes it
+        // a phrase
+        let (term, freq_str) = if wordlist {
+            (line, "")
+        } else if parts.len() == columns || (options.separator.is_some() && parts.len() > columns) {
+            (parts[options.term_index], parts[options.count_index].trim())
+        } else {
+            let expected = if options.separator.is_some() { "at least" } else { "exactly" };
+            malformed(format!("expected {} {} columns, found {}", expected, columns, parts.len()))?;
+            stats.skipped_malformed += 1;
+            continue;
+        };
+        // Skip empty terms or frequencies
+        if !wordlist && freq_str.is_empty() {
+            malformed("empty frequency".into())?;
+            stats.skipped_malformed += 1;
+            continue;
+        }
+
+        let term = match clean_term(term, options) {
+            Ok(term) => term,
+            Err(SkippedTerm::Malformed) => {
+                malformed("empty term".into())?;
+                stats.skipped_malformed += 1;
+                continue;
+            }
+            Err(SkippedTerm::Multiword) => {
+                stats.skipped_multiword += 1;
+                continue;
+            }
+            Err(SkippedTerm::TooLong) if options.strict => {
+                return Err(LoadError::Parse(format!(
+                    "Dictionary term on line {} is longer than max_word_length ({} characters)",
+                    line_index + 1,
+                    options.max_word_length
+                ))
+                .into());
+            }
+            Err(SkippedTerm::TooLong) => {
+                stats.skipped_long_words += 1;
+                continue;
+            }
+        };
+
+        // Parse frequency
+        if wordlist {
+            entries.push((term.to_string(), options.default_frequency));
+        } else {
+            match freq_str.parse::<u64>() {
+                Ok(freq) => entries.push((term.to_string(), freq)),
+                Err(_) => {
+                    malformed(format!("frequency {:?} is not a non-negative integer", freq_str))?;
+                    stats.skipped_invalid_freq += 1;
+                }
+            }
+        }
+
+        if let Some(limit) = options.max_words.filter(|&limit| entries.len() > limit) {
+            let reached = format!("stopped at line {} after {} of {} bytes", line_index + 1, position(bytes_read), total_bytes);
+            return Err(max_words_error(limit, reached).into());
+        }
+    }
+
+    progress.boundary("parsing", total_bytes, total_bytes)?;
+    stats.format = format.map(|(format, _)| format);
+    Ok((entries, stats, total_bytes))
+}
+
+// Reads an index written by `Engine::save_index`, checking that it was built with the same
+// indexing options as this load asks for. The distance metric only affects verification, so
+// the one in the options is used rather than the saved one
+fn read_index<F, E>(path: &str, options: &LoadOptions, progress: &mut Progress<F>) -> Result<(SymSpell, LoadStats, usize), E>
+where
+    F: FnMut(&str, usize, usize) -> Result<(), E>,
+    E: From<LoadError>,
+{
+    let file = std::fs::File::open(path).map_err(|e| LoadError::Io(format!("Failed to open index file '{}': {}", path, e)))?;
+    let total_bytes = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+    progress.boundary("reading_index", 0, total_bytes)?;
+
+    let mut reader = std::io::BufReader::new(file);
+    let corrupt = |e: std::io::Error| LoadError::Invalid(format!("Index file '{}' is truncated or corrupt: {}", path, e));
+    let mut magic = [0; INDEX_MAGIC.len()];
+    if reader.read_exact(&mut magic).is_err() || magic != *INDEX_MAGIC {
+        return Err(LoadError::Invalid(format!("'{}' is not a SpellKit index file", path)).into());
+    }
+    let version = read_u64(&mut reader).map_err(corrupt)?;
+    if version != INDEX_FORMAT_VERSION {
+        return Err(LoadError::Invalid(format!(
+            "Index file '{}' has format version {}, this build reads version {}; rebuild it with save_index",
+            path, version, INDEX_FORMAT_VERSION
+        ))
+        .into());
+    }
+    let symspell = SymSpell::read_index(&mut reader).map_err(corrupt)?;
+
+    let mismatch = |saved: String, requested: String| {
+        LoadError::Invalid(format!(
+            "Index file '{}' was built with {}, but {} was requested; rebuild it with save_index",
+            path, saved, requested
+        ))
+    };
+    if symspell.max_edit_distance() != options.edit_distance {
+        return Err(mismatch(format!("edit_distance {}", symspell.max_edit_distance()), format!("edit_distance {}", options.edit_distance)).into());
+    }
+    let strategy = if symspell.is_query_expanded() { IndexStrategy::QueryExpanded } else { IndexStrategy::Full };
+    let requested_strategy = if options.edit_distance < 2 { IndexStrategy::Full } else { options.index_strategy };
+    if strategy != requested_strategy {
+        return Err(mismatch(format!("index_strategy {}", strategy.name()), format!("index_strategy {}", requested_strategy.name())).into());
+    }
+    if symspell.prefix_length() != options.prefix_length {
+        let describe = |length: Option<usize>| length.map_or("whole-word deletes".to_string(), |length| format!("prefix_length {}", length));
+        return Err(mismatch(describe(symspell.prefix_length()), describe(options.prefix_length)).into());
+    }
+    if symspell.is_compact() != options.compact || (options.compact && symspell.frequency_scale() != options.frequency_scale) {
+        let describe = |compact: bool, scale: u64| if compact { format!("compact storage (frequency_scale {})", scale) } else { "full storage".to_string() };
+        return Err(mismatch(
+            describe(symspell.is_compact(), symspell.frequency_scale()),
+            describe(options.compact, options.frequency_scale),
+        )
+        .into());
+    }
+    if *symspell.normalizer() != options.normalizer {
+        return Err(mismatch(symspell.normalizer().differences(&options.normalizer), options.normalizer.differences(symspell.normalizer())).into());
+    }
+    if symspell.combine() != options.frequency_combine {
+        return Err(mismatch(
+            format!("frequency_combine {}", symspell.combine().name()),
+            format!("frequency_combine {}", options.frequency_combine.name()),
+        ).into());
+    }
+
+    progress.boundary("reading_index", total_bytes, total_bytes)?;
+    let stats = LoadStats { dictionary_size: symspell.len(), ..LoadStats::default() };
+    Ok((symspell.with_metric(options.distance_metric), stats, total_bytes))
+}
+
+fn read_capped_line<R: BufRead>(reader: &mut R, line: &mut Vec<u8>, max: usize) -> std::io::Result<Option<usize>> {
+    line.clear();
+    let mut length = 0;
+    let mut read_any = false;
+    let mut last_byte = None;
+
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            return Ok(read_any.then_some(length));
+        }
+        read_any = true;
+
+        let newline = available.iter().position(|&b| b == b'\n');
+        let chunk = &available[..newline.unwrap_or(available.len())];
+        let room = max.saturating_sub(length).min(chunk.len());
+        line.extend_from_slice(&chunk[..room]);
+        length += chunk.len();
+        last_byte = chunk.last().copied().or(last_byte);
+
+        let consumed = chunk.len() + usize::from(newline.is_some());
+        reader.consume(consumed);
+
+        if newline.is_some() {
+            // Same line endings as BufRead::lines: "\n" or "\r\n"
+            if last_byte == Some(b'\r') {
+                length -= 1;
+                line.truncate(length);
+            }
+            return Ok(Some(length));
+        }
+    }
+}
+
+/// `f(index, item)` for every item, split into at most `threads` contiguous chunks run on
+/// rayon's global pool, so repeated calls reuse its workers instead of spawning threads of their
+/// own. Results come back in input order whatever the thread count
+pub fn parallel_map<T: Sync, R: Send>(items: &[T], threads: usize, f: impl Fn(usize, &T) -> R + Sync) -> Vec<R> {
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.iter().enumerate().map(|(i, item)| f(i, item)).collect();
+    }
+
+    items
+        .par_iter()
+        .enumerate()
+        .with_min_len(items.len().div_ceil(threads))
+        .map(|(i, item)| f(i, item))
+        .collect()
+}
+
+// Counts the bytes read through it into a shared cell
+struct Counted<R> {
+    inner: R,
+    count: Rc<Cell<usize>>,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read);
+        Ok(read)
+    }
+}
+
+// Reads a whole file, decompressing it first if the path ends in ".gz"
+fn read_file(path: &str, what: &str) -> Result<String, LoadError> {
+    let read = || {
+        if !Compression::Auto.is_gzip(path) {
+            return std::fs::read_to_string(path);
+        }
+        let mut content = String::new();
+        MultiGzDecoder::new(std::fs::File::open(path)?).read_to_string(&mut content)?;
+        Ok(content)
+    };
+    read().map_err(|e| LoadError::Io(format!("Failed to read {} '{}': {}", what, path, e)))
+}
+
+/// What a load read and skipped, for monitoring
+#[derive(Debug, Clone, Default)]
+pub struct LoadStats {
+    /// Unix seconds
+    pub loaded_at: Option<u64>,
+    pub dictionary_size: usize,
+    pub skipped_malformed: usize,
+    pub skipped_multiword: usize,
+    pub skipped_invalid_freq: usize,
+    pub skipped_duplicates: usize,
+    /// Lines over max_line_length and terms over max_word_length
+    pub skipped_long_lines: usize,
+    pub skipped_long_words: usize,
+    /// How the dictionary's lines were read, with `Auto` resolved. None for an index, or an
+    /// `Auto` load of a file with no non-empty lines
+    pub format: Option<DictionaryFormat>,
+    /// Protected terms that were already dictionary words at or above confident_frequency
+    pub protected_overlap: Vec<String>,
+    /// The warm-up run as part of the load, if `LoadOptions::warmup` asked for one
+    pub warmup: Option<Warmup>,
+    /// Rough heap size of the loaded engine (see `Engine::estimated_bytes`)
+    pub estimated_bytes: usize,
+    /// Rough size of the deletes map with word-id buckets, and what it would take if every
+    /// bucket held the words' strings instead
+    pub deletes_bytes: usize,
+    pub string_deletes_bytes: usize,
+    /// Dictionary lines, comments and blank ones included, or in-memory entries
+    pub lines_read: usize,
+    /// Lines starting with "#"
+    pub comment_lines: usize,
+    /// Keys in the deletes index
+    pub index_entries: usize,
+    /// Word ids across all buckets of the deletes index
+    pub delete_entries: usize,
+    /// The whole load, warm-up included
+    pub load_time: Duration,
+}
+
+impl LoadStats {
+    /// Lines read that loaded no entry, other than duplicates of earlier ones
+    pub fn lines_skipped(&self) -> usize {
+        self.comment_lines
+            + self.skipped_malformed
+            + self.skipped_multiword
+            + self.skipped_invalid_freq
+            + self.skipped_long_lines
+            + self.skipped_long_words
+    }
+}
+
+/// A loaded dictionary with its guards, boosts, and aliases, and the correction policy over them.
+/// Runtime edits take `&mut self`: a serving engine is cloned, edited, and swapped in, so calls
+/// already running on it (and snapshots of it) never see an edit
+#[derive(Clone)]
+pub struct Engine {
+    pub(crate) symspell: SymSpell,
+    // Runtime edits made since the load
+    pub(crate) revision: u64,
+    pub(crate) guards: Guards,
+    pub(crate) skip_presets: Vec<String>,
+    pub(crate) thresholds: Thresholds,
+    pub(crate) edit_distance: usize,
+    pub(crate) stats: LoadStats,
+    pub(crate) tokenizer: Tokenizer,
+    // Known words below this frequency are corrected like unknown ones
+    pub(crate) autocorrect_known_rare: Option<u64>,
+    // Normalized alias -> canonical concept, applied after correction on request
+    pub(crate) aliases: HashMap<String, String>,
+    // Normalized words left out of suggestions, corrections, and completions
+    pub(crate) denylist: HashSet<String>,
+    // Ranking-time frequency multipliers for correction candidates
+    pub(crate) boosts: Boosts,
+    pub(crate) segmentation: Option<SegmentationPreference>,
+    pub(crate) structural_match: bool,
+    pub(crate) preserve_case: bool,
+    pub(crate) tie_break: TieBreak,
+    pub(crate) units: Units,
+    // Queries are cleaned the same way the dictionary and guards were
+    pub(crate) strip_ignorable: bool,
+    // The index's normalizer, kept here for guards and aliases
+    pub(crate) normalizer: Normalizer,
+    pub(crate) source: DictionarySource,
+    // Shared by the clones runtime edits make, so the histograms span them
+    pub(crate) latency: Option<Arc<Latency>>,
+}
+
+impl Engine {
+    /// Reads the dictionary and every file the options name, and builds the index
+    pub fn load(options: &LoadOptions) -> Result<Self, LoadError> {
+        let mut progress = Progress::<fn(&str, usize, usize) -> Result<(), LoadError>>::new(None);
+        Self::load_with_progress(options, &mut progress)
+    }
+
+    /// `load` with progress reporting. The final "finalizing" boundary is left to the caller,
+    /// to report once the engine is installed wherever it is served from
+    pub fn load_with_progress<F, E>(options: &LoadOptions, progress: &mut Progress<F>) -> Result<Self, E>
+    where
+        F: FnMut(&str, usize, usize) -> Result<(), E>,
+        E: From<LoadError>,
+    {
+        let started = Instant::now();
+        let edit_dist = options.edit_distance;
+        if !(1..=2).contains(&edit_dist) {
+            return Err(LoadError::Invalid(format!("edit_distance must be 1 or 2, got: {}", edit_dist)).into());
+        }
+
+        if options.frequency_scale == 0 {
+            return Err(LoadError::Invalid("frequency_scale must be at least 1".into()).into());
+        }
+
+        if let Some(prefix_length) = options.prefix_length.filter(|&length| length <= edit_dist) {
+            return Err(LoadError::Invalid(format!(
+                "prefix_length must be greater than edit_distance ({}), got: {}",
+                edit_dist, prefix_length
+            ))
+            .into());
+        }
+
+        if options.term_index == options.count_index {
+            return Err(LoadError::Invalid(format!(
+                "term_index and count_index must be different columns, both are {}",
+                options.term_index
+            ))
+            .into());
+        }
+
+        if options.separator.as_deref() == Some("") {
+            return Err(LoadError::Invalid("separator must not be empty".into()).into());
+        }
+
+        if options.default_frequency == 0 {
+            return Err(LoadError::Invalid("default_frequency must be at least 1".into()).into());
+        }
+
+        if options.max_line_length == 0 || options.max_word_length == 0 {
+            return Err(LoadError::Invalid("max_line_length and max_word_length must be at least 1".into()).into());
+        }
+
+        let (mut symspell, stats, total_bytes) = match options.index_path {
+            Some(ref path) => read_index(path, options, progress)?,
+            None => parse_dictionary(options, progress)?,
+        };
+        progress.boundary("finalizing", 0, 1)?;
+
+        if let Some(ref path) = options.user_dictionary_path {
+            for (term, count) in parse_user_dictionary(&read_file(path, "user dictionary file")?, options)? {
+                symspell.add_to_layer(Layer::User, &symspell.normalize(&term), &term, count);
+            }
+        }
+
+        let mut guards = Guards::with_case_sensitivity(options.protected_case_sensitive).with_normalizer(options.normalizer);
+        let mut protected_overlap = Vec::new();
+
+        // Load optional protected terms file
+        if let Some(ref path) = options.protected_path {
+            let content = read_file(path, "protected terms file")?;
+
+            for line in Guards::protected_lines(&content) {
+                // A second tab-separated column is the canonical form the term is rewritten to
+                let (term, canonical) = match line.split_once('\t') {
+                    Some((term, canonical)) if !canonical.trim().is_empty() => (term.trim_end(), Some(canonical.trim())),
+                    Some((term, _)) => (term.trim_end(), None),
+                    None => (line, None),
+                };
+                let term = if options.strip_ignorable { strip_ignorable(term) } else { Cow::Borrowed(term) };
+                let term = term.as_ref();
+                if term.is_empty() {
+                    continue;
+                }
+
+                // Terms the dictionary already knows well never get "corrected", so protecting
+                // them only matters for rare-known autocorrect. Report them, and optionally skip
+                if symspell.get_frequency(term).is_some_and(|f| f >= options.confident_frequency) {
+                    if options.protect_only_unknown {
+                        continue;
+                    }
+                    protected_overlap.push(term.to_string());
+                }
+
+                match canonical {
+                    Some(canonical) => guards.add_canonical(term, canonical),
+                    None => guards.add_protected(term),
+                }
+
+                // Frequency 0 keeps protected terms known without making them correction targets
+                for term in std::iter::once(term).chain(canonical) {
+                    if options.protected_as_known && !symspell.contains(term) {
+                        symspell.add_word(&symspell.normalize(term), term, 0);
+                    }
+                }
+            }
+        }
+
+        // Load optional protected prefixes (namespaces like "rx:" or "ICD10:")
+        for prefix in &options.protected_prefixes {
+            guards.add_prefix(&if options.strip_ignorable { strip_ignorable(prefix) } else { Cow::Borrowed(prefix.as_str()) });
+        }
+
+        // Load optional protected patterns
+        for (index, pattern) in options.protected_patterns.iter().enumerate() {
+            guards
+                .add_pattern_with_flags(
+                    &pattern.source,
+                    pattern.case_insensitive,
+                    pattern.multiline,
+                    pattern.extended,
+                    pattern.anchored,
+                    pattern.name.as_deref(),
+                )
+                .map_err(|e| LoadError::Pattern(format!("protected_patterns[{}]: {}", index, e)))?;
+        }
+        guards.compile_patterns().map_err(|e| LoadError::Pattern(format!("protected_patterns: {}", e)))?;
+
+        // Load optional ranking boosts
+        let max_boost = options.max_boost;
+        if !max_boost.is_finite() || max_boost <= 0.0 {
+            return Err(LoadError::Invalid(format!("max_boost must be a positive finite number, got: {}", max_boost)).into());
+        }
+
+        let mut boosts = Boosts::new(max_boost).with_normalizer(options.normalizer);
+        for boost in &options.boosts {
+            let multiplier = boost.multiplier;
+            if !multiplier.is_finite() || multiplier <= 0.0 {
+                return Err(LoadError::Invalid(format!("boost multiplier must be a positive finite number, got: {}", multiplier)).into());
+            }
+
+            match boost.source {
+                BoostSource::Pattern(ref pattern) => {
+                    boosts.add_pattern(&pattern.source, pattern.case_insensitive, pattern.multiline, pattern.extended, multiplier)
+                        .map_err(LoadError::Pattern)?;
+                }
+                BoostSource::TermsPath(ref path) => {
+                    let content = read_file(path, "boost terms file")?;
+                    boosts.add_terms(path, &content, multiplier);
+                }
+            }
+        }
+
+        // Validate frequency thresholds
+        check_threshold("frequency_threshold", options.frequency_threshold).map_err(LoadError::Invalid)?;
+        let thresholds = Thresholds {
+            min_suggestion_frequency: options.min_suggestion_frequency.unwrap_or(options.frequency_threshold),
+            frequency_ratio: options.frequency_ratio.unwrap_or(options.frequency_threshold),
+            max_suggestions: options.max_suggestions,
+            skip_tokens_with_digits: options.skip_tokens_with_digits,
+            min_word_length: options.min_word_length,
+            skip_all_caps: options.skip_all_caps,
+        };
+        thresholds.validate().map_err(LoadError::Invalid)?;
+
+        // Load optional alias file
+        let aliases = match options.aliases_path {
+            Some(ref path) => parse_aliases(&read_file(path, "aliases file")?, &options.normalizer).map_err(LoadError::Invalid)?,
+            None => HashMap::new(),
+        };
+
+        // Load optional denylist, keyed like the index so any spelling of a word matches
+        let denylist = match options.denylist_path {
+            Some(ref path) => Guards::protected_lines(&read_file(path, "denylist file")?)
+                .map(|line| symspell.normalize(line))
+                .filter(|word| !word.is_empty())
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        let source = match options.source {
+            Some(ref source) => DictionarySource { bytes: total_bytes, ..source.clone() },
+            None => DictionarySource {
+                kind: match (&options.index_path, &options.entries) {
+                    (Some(_), _) => "index",
+                    (None, Some(_)) => "memory",
+                    (None, None) => "file",
+                }
+                .to_string(),
+                path_or_url: options.index_path.clone().unwrap_or_else(|| options.dictionary_path.clone()),
+                sha256: None,
+                bytes: total_bytes,
+            },
+        };
+
+        let loaded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+
+        let mut engine = Self {
+            symspell,
+            revision: 0,
+            guards,
+            skip_presets: options.skip_presets.clone(),
+            thresholds,
+            edit_distance: edit_dist,
+            stats: LoadStats { loaded_at, protected_overlap, ..stats },
+            tokenizer: options.tokenizer.clone(),
+            autocorrect_known_rare: options.autocorrect_known_rare,
+            aliases,
+            denylist,
+            boosts,
+            segmentation: options.segmentation,
+            structural_match: options.structural_match,
+            preserve_case: options.preserve_case,
+            tie_break: options.tie_break,
+            units: match options.unit_list {
+                Some(ref list) => Units::new(options.units, list),
+                None => Units::new(options.units, &DEFAULT_UNITS.iter().map(|unit| unit.to_string()).collect::<Vec<_>>()),
+            },
+            strip_ignorable: options.strip_ignorable,
+            normalizer: options.normalizer,
+            source,
+            latency: options.latency_stats.then(|| Arc::new(Latency::new())),
+        };
+        engine.stats.estimated_bytes = engine.estimated_bytes();
+        let symspell = &engine.symspell;
+        engine.stats.deletes_bytes = symspell.deletes_bytes();
+        engine.stats.string_deletes_bytes = symspell.string_deletes_bytes();
+        engine.stats.index_entries = symspell.deletes_len();
+        engine.stats.delete_entries = symspell.delete_entries();
+
+        // Before returning, so the caller never installs a cold index
+        if let Some(sample) = options.warmup {
+            progress.boundary("warmup", 0, sample)?;
+            engine.stats.warmup = Some(engine.warmup(sample, 0));
+            progress.boundary("warmup", sample, sample)?;
+        }
+
+        engine.stats.load_time = started.elapsed();
+        Ok(engine)
+    }
+
+    pub fn symspell(&self) -> &SymSpell {
+        &self.symspell
+    }
+
+    pub fn edit_distance(&self) -> usize {
+        self.edit_distance
+    }
+
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    /// The skip presets active since load
+    pub fn skip_presets(&self) -> &[String] {
+        &self.skip_presets
+    }
+
+    /// The strategy in effect: `QueryExpanded` requested at edit distance 1 reports `Full`
+    pub fn index_strategy(&self) -> IndexStrategy {
+        if self.symspell().is_query_expanded() {
+            IndexStrategy::QueryExpanded
+        } else {
+            IndexStrategy::Full
+        }
+    }
+
+    /// Writes the dictionary index, with user and trained counts, to `path` for a later load with
+    /// `index_path`. Written to a temporary file next to `path` and renamed into place, so a
+    /// reader never sees a partial index
+    pub fn save_index(&self, path: &str) -> Result<(), LoadError> {
+        use std::io::Write as _;
+
+        let temporary = format!("{}.tmp", path);
+        let write = || -> std::io::Result<()> {
+            let mut out = std::io::BufWriter::new(std::fs::File::create(&temporary)?);
+            out.write_all(INDEX_MAGIC)?;
+            write_u64(&mut out, INDEX_FORMAT_VERSION)?;
+            self.symspell().write_index(&mut out)?;
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            std::fs::rename(&temporary, path)
+        };
+        write().map_err(|e| {
+            let _ = std::fs::remove_file(&temporary);
+            LoadError::Io(format!("Failed to write index file '{}': {}", path, e))
+        })
+    }
+
+    /// Writes every entry as canonical<TAB>frequency to `path`, most frequent first, for a later
+    /// `load` of the dictionary the engine serves now: merged duplicates and user and trained
+    /// counts included, with each word's combined frequency. Written next to `path` and renamed
+    /// into place like `save_index`
+    pub fn export_dictionary(&self, path: &str) -> Result<(), LoadError> {
+        let symspell = self.symspell();
+        write_entries(path, symspell.ranked_entries().into_iter().map(|(_, canonical, frequency)| (canonical, frequency)))
+    }
+
+    /// Writes only the user layer as canonical<TAB>count to `path`, most counted first: the
+    /// words of `user_dictionary_path` and those added with `add_word`, without their base or
+    /// trained counts. Loading the file as `user_dictionary_path` restores the layer
+    pub fn export_user_dictionary(&self, path: &str) -> Result<(), LoadError> {
+        let symspell = self.symspell();
+        write_entries(path, symspell.user_entries())
+    }
+
+    pub fn load_stats(&self) -> &LoadStats {
+        &self.stats
+    }
+
+    /// Copies the user and trained counts of `previous` into this engine, so reloading the base
+    /// dictionary keeps them. Base counts come from this engine's own dictionary only. A user
+    /// count only tops up what this engine's `user_dictionary_path` already gave the word, so
+    /// reloading the same user dictionary doesn't count it twice
+    pub fn carry_layers_from(&mut self, previous: &Engine) {
+        let symspell = &mut self.symspell;
+        for (_, canonical, layers) in previous.symspell().layered_entries() {
+            // Keyed by this engine's normalizer, which the reload may have changed
+            let normalized = symspell.normalize(&canonical);
+            let loaded = symspell.layers(&canonical).unwrap_or_default();
+            for layer in [Layer::User, Layer::Trained] {
+                let count = match layer {
+                    Layer::User => layers[layer as usize].saturating_sub(loaded[layer as usize]),
+                    _ => layers[layer as usize],
+                };
+                if count > 0 {
+                    symspell.add_to_layer(layer, &normalized, &canonical, count);
+                }
+            }
+        }
+    }
+
+    /// Adds `frequency` to a word's user count, indexing it if it's new, and returns whether it
+    /// was. User counts are kept when the base dictionary is reloaded (see `carry_layers_from`),
+    /// rank the word like a `user_dictionary_path` entry, and are what `export_user_dictionary`
+    /// writes
+    pub fn add_word(&mut self, word: &str, frequency: u64) -> Result<bool, LoadError> {
+        let term = self.clean(word.trim());
+        if term.is_empty() || term.contains(char::is_whitespace) {
+            return Err(LoadError::Invalid(format!("add_word! takes a single word, got: {:?}", word)));
+        }
+
+        let normalized = self.normalize(&term);
+        let added = self.symspell.add_to_layer(Layer::User, &normalized, &term, frequency);
+        self.revision += 1;
+        Ok(added)
+    }
+
+    /// Takes a word out of the index with all its counts; false if it wasn't there. Reloading
+    /// the base dictionary brings back its base count
+    pub fn remove_word(&mut self, word: &str) -> bool {
+        let normalized = self.normalize(&self.clean(word.trim()));
+        let removed = self.symspell.remove_word(&normalized);
+        if removed {
+            self.revision += 1;
+        }
+        removed
+    }
+
+    /// Protects a term without a reload, stored as the terms of `protected_path` are. Calls that
+    /// start after it returns see the term protected
+    pub fn protect(&mut self, term: &str) -> Result<(), LoadError> {
+        let term = self.clean(term.trim());
+        if term.is_empty() {
+            return Err(LoadError::Invalid("protect! takes a non-empty term".to_string()));
+        }
+
+        self.guards.add_protected(&term);
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// Takes a protected term out of the guards, whether it was protected at load or with
+    /// `protect`; false if it wasn't there. Prefixes and patterns that match it still apply
+    pub fn unprotect(&mut self, term: &str) -> bool {
+        let removed = self.guards.remove_protected(&self.clean(term.trim()));
+        if removed {
+            self.revision += 1;
+        }
+        removed
+    }
+
+    /// The key `word` is indexed and looked up under (see `Normalizer`)
+    pub fn normalize(&self, word: &str) -> String {
+        self.normalizer.normalize(word)
+    }
+
+    /// Whether a guard protects `word`, checked as `correct_token` checks it: as written, then
+    /// without edge punctuation. Not counted in the guard hit counters
+    pub fn is_protected(&self, word: &str) -> bool {
+        let cleaned = self.clean(word);
+        let (_, core, _) = strip_punctuation(&cleaned);
+        let guarded =
+            |token: &str| !token.is_empty() && self.guards.match_reason(token, &self.normalize(token)).is_some();
+        guarded(&cleaned) || guarded(core)
+    }
+
+    /// Number of `add_word`, `remove_word`, `protect`, and `unprotect` edits since the load
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Words in the index now, counting runtime edits and carried-over user and trained words
+    pub fn dictionary_size(&self) -> usize {
+        self.symspell().len()
+    }
+
+    /// Approximate bytes held by the index and aliases, the bulk of an engine's memory. Walks
+    /// the whole index; `LoadStats::estimated_bytes` has the figure from load time
+    pub fn estimated_bytes(&self) -> usize {
+        let aliases: usize = self
+            .aliases
+            .iter()
+            .map(|(alias, canonical)| size_of::<(String, String)>() + alias.capacity() + canonical.capacity())
+            .sum();
+        self.symspell().estimated_bytes() + aliases
+    }
+
+    /// Where the dictionary came from, with its size in bytes
+    pub fn source(&self) -> &DictionarySource {
+        &self.source
+    }
+
+    /// Histograms for timing calls into this engine, unless disabled with `latency_stats`
+    pub fn latency(&self) -> Option<&Latency> {
+        self.latency.as_deref()
+    }
+
+    /// Clears the guard hit counters and latency histograms
+    pub fn reset_stats(&self) {
+        self.guards.reset_hits();
+        if let Some(ref latency) = self.latency {
+            latency.reset();
+        }
+    }
+
+    /// Checks up to `max` dictionary entries from position `start` of the index, for at most
+    /// about `budget` (see `SymSpell::verify`)
+    pub fn verify(&self, start: usize, max: usize, budget: Duration) -> Verification {
+        self.symspell().verify(start, max, Instant::now() + budget)
+    }
+
+    /// Runs `count` seeded dictionary words, each with a typo, through suggestions and checks
+    /// they come back (see `SymSpell::probe`)
+    pub fn probe(&self, count: usize, seed: u64) -> Probe {
+        self.symspell().probe(count, seed)
+    }
+
+    /// Pages in and cache-warms the index by running `sample` seeded dictionary words and a typo
+    /// of each through suggestions. Bypasses the latency histograms
+    pub fn warmup(&self, sample: usize, seed: u64) -> Warmup {
+        self.symspell().warmup(sample, seed)
+    }
+
+    /// Canonical text of everything that decides corrections: the effective options, guards,
+    /// boosts, aliases, and the (normalized, canonical, frequency) entries in key order. Two
+    /// engines built from the same entries in a different file order produce the same text, so a
+    /// hash of it works as a cache key for correction results
+    pub fn behavior_material(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "spellkit-behavior\tv{}", BEHAVIOR_VERSION);
+        let _ = writeln!(out, "edit_distance\t{}", self.edit_distance);
+        let _ = writeln!(out, "distance_metric\t{}", self.symspell().metric().name());
+        let _ = writeln!(out, "normalize\t{}", self.normalizer);
+        let _ = writeln!(out, "min_suggestion_frequency\t{:?}", self.thresholds.min_suggestion_frequency);
+        let _ = writeln!(out, "frequency_ratio\t{:?}", self.thresholds.frequency_ratio);
+        let _ = writeln!(out, "max_suggestions\t{}", self.thresholds.max_suggestions);
+        let _ = writeln!(out, "skip_tokens_with_digits\t{}", self.thresholds.skip_tokens_with_digits);
+        let _ = writeln!(out, "min_word_length\t{}", self.thresholds.min_word_length);
+        let _ = writeln!(out, "skip_all_caps\t{}", self.thresholds.skip_all_caps);
+        let _ = writeln!(out, "autocorrect_known_rare\t{:?}", self.autocorrect_known_rare);
+        let _ = writeln!(out, "segmentation\t{:?}", self.segmentation);
+        let _ = writeln!(out, "structural_match\t{}", self.structural_match);
+        let _ = writeln!(out, "preserve_case\t{}", self.preserve_case);
+        let _ = writeln!(out, "tie_break\t{}", self.tie_break.name());
+        let _ = writeln!(out, "units\t{}\t{}", self.units.mode().name(), self.units.sorted().join(" "));
+        let _ = writeln!(out, "strip_ignorable\t{}", self.strip_ignorable);
+        match self.tokenizer {
+            Tokenizer::Pattern(ref regex) => {
+                let _ = writeln!(out, "tokenizer\tpattern\t{:?}", regex.as_str());
+            }
+            ref tokenizer => {
+                let _ = writeln!(out, "tokenizer\t{}", tokenizer.name());
+            }
+        }
+        self.guards.write_canonical(&mut out);
+        self.boosts.write_canonical(&mut out);
+
+        let mut aliases: Vec<(&String, &String)> = self.aliases.iter().collect();
+        aliases.sort_unstable();
+        for (alias, canonical) in aliases {
+            let _ = writeln!(out, "alias\t{}\t{}", alias, canonical);
+        }
+
+        let mut denylist: Vec<&String> = self.denylist.iter().collect();
+        denylist.sort_unstable();
+        for word in denylist {
+            let _ = writeln!(out, "deny\t{}", word);
+        }
+
+        for (normalized, canonical, frequency) in self.symspell().sorted_entries() {
+            let _ = writeln!(out, "word\t{}\t{}\t{}", normalized, canonical, frequency);
+        }
+        out
+    }
+
+    /// Hex SHA-256 of `behavior_material`
+    pub fn behavior_digest(&self) -> String {
+        let hash = Sha256::digest(self.behavior_material().as_bytes());
+        hash.iter().fold(String::with_capacity(64), |mut out, byte| {
+            let _ = write!(out, "{:02x}", byte);
+            out
+        })
+    }
+
+    /// The load-time boosts that `Decision::boosts` indexes into
+    pub fn boosts(&self) -> &Boosts {
+        &self.boosts
+    }
+
+    /// Numeric tokens count as known under `units: skip` or `normalize`
+    pub fn contains(&self, word: &str) -> bool {
+        let word = self.clean(word);
+        self.units.apply(&word).is_some() || self.symspell().contains(&word)
+    }
+
+    /// `word` as the dictionary saw its terms: without default-ignorable characters unless
+    /// `strip_ignorable` was turned off
+    pub fn clean<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        if self.strip_ignorable {
+            strip_ignorable(word)
+        } else {
+            Cow::Borrowed(word)
+        }
+    }
+
+    /// Canonical form, frequency, and frequency rank of a known word (see `SymSpell::lookup`)
+    pub fn lookup(&self, word: &str) -> Option<Lookup> {
+        self.symspell().lookup(&self.clean(word))
+    }
+
+    /// Frequency of a known word, found the way `contains` finds it
+    pub fn frequency(&self, word: &str) -> Option<u64> {
+        self.symspell().get_frequency(&self.clean(word))
+    }
+
+    /// Up to `max` dictionary candidates within the loaded edit distance, best first
+    pub fn suggest(&self, word: &str, max: usize) -> Vec<Suggestion> {
+        self.suggest_within(word, max, self.edit_distance, false)
+    }
+
+    /// `suggest` with a tighter distance cutoff, optionally leaving out the word itself
+    pub fn suggest_within(&self, word: &str, max: usize, max_distance: usize, exclude_exact: bool) -> Vec<Suggestion> {
+        if max == 0 {
+            return Vec::new();
+        }
+
+        let symspell = self.symspell();
+        if self.denylist.is_empty() {
+            return symspell.suggestions_within(&self.clean(word), max, max_distance, exclude_exact);
+        }
+
+        // Denied words may fill the top `max`, so every candidate is ranked before the cut
+        let mut suggestions = symspell.suggestions_within(&self.clean(word), usize::MAX, max_distance, exclude_exact);
+        suggestions.retain(|s| !self.denied(symspell, s));
+        suggestions.truncate(max);
+        suggestions
+    }
+
+    // Whether a candidate is on the denylist. The word itself (distance 0) never is, so typing a
+    // denied word leaves it as it is rather than correcting it to something else
+    fn denied(&self, symspell: &SymSpell, suggestion: &Suggestion) -> bool {
+        suggestion.distance > 0 && self.denylist.contains(&symspell.normalize(&suggestion.term))
+    }
+
+    fn is_rare(&self, frequency: u64) -> bool {
+        self.autocorrect_known_rare.is_some_and(|limit| frequency < limit)
+    }
+
+    /// Single hop: the alias target is returned as-is, never looked up again.
+    /// Protected tokens are left exactly as written
+    pub fn resolve_alias(&self, decision: &Decision) -> Option<&str> {
+        if decision.reason == Reason::Protected {
+            return None;
+        }
+        self.aliases
+            .get(&self.normalize(&decision.output))
+            .map(String::as_str)
+    }
+
+    /// Runs the full correction policy for one token: guards, exact match, then the first
+    /// candidate within edit distance that passes the frequency threshold. Boosted frequencies
+    /// are used for both the ranking and the threshold
+    pub fn correct_token(&self, word: &str) -> Decision {
+        self.correct_token_with(word, None, self.thresholds)
+    }
+
+    /// `correct_token` under the given thresholds, optionally restricted to a closed set of
+    /// normalized terms. With a set, every candidate within edit distance is considered, not just
+    /// the top few, and only those in the set survive. The input's own dictionary frequency is
+    /// then ignored, so a known word outside the set can still be corrected into it
+    pub fn correct_token_with(&self, word: &str, within: Option<&HashSet<String>>, thresholds: Thresholds) -> Decision {
+        self.stripped(word, true, thresholds, |core| self.correct_core(core, within, thresholds))
+    }
+
+    /// The thresholds set at load
+    pub fn thresholds(&self) -> Thresholds {
+        self.thresholds
+    }
+
+    /// The load's `max_suggestions`, for callers that don't pass their own
+    pub fn max_suggestions(&self) -> usize {
+        self.thresholds.max_suggestions
+    }
+
+    /// Whether corrections carry the input's casing when a call doesn't say
+    pub fn preserve_case(&self) -> bool {
+        self.preserve_case
+    }
+
+    pub fn normalizer(&self) -> &Normalizer {
+        &self.normalizer
+    }
+
+    // Every token goes through the same order: strip edge punctuation, guard, correct what's
+    // left, re-attach. Guards see the token as written first, so a term protected with its
+    // punctuation stays protected. Dictionary words with edge punctuation ("e.g.") aren't stripped.
+    // Numeric tokens skip all of it when units are on, and tokens the thresholds skip come out
+    // as written before any guard is checked. All-caps tokens are only skipped once the guards
+    // have passed them, so a protected term's canonical form still applies. Unguarded, nothing
+    // is protected
+    fn stripped(&self, word: &str, guarded: bool, thresholds: Thresholds, decide: impl FnOnce(&str) -> Decision) -> Decision {
+        let word = &*self.clean(word);
+        let (lead, core, trail) = strip_punctuation(word);
+        if let Some(numeric) = self.units.apply(core) {
+            return Decision {
+                output: format!("{}{}{}", lead, numeric, trail),
+                ..Decision::unchanged(word, Reason::Numeric)
+            };
+        }
+        if let Some(reason) = thresholds.skip_reason(if core.is_empty() { word } else { core }) {
+            if guarded {
+                self.guards.record_shape();
+            }
+            return Decision::unchanged(word, reason);
+        }
+        let decide = |token: &str| {
+            if thresholds.skips_all_caps(token) {
+                return Decision::unchanged(token, Reason::AllCaps);
+            }
+            decide(token)
+        };
+        let guard = |token: &str| guarded.then(|| self.guards.check(token, &self.normalize(token))).flatten();
+        if core.len() == word.len() || core.is_empty() || self.symspell().contains(word) {
+            if let Some(guard) = guard(word) {
+                return self.protected_decision(word, word, guard);
+            }
+            return decide(word);
+        }
+        if let Some(guard) = guard(word) {
+            return self.protected_decision(word, word, guard);
+        }
+        if let Some(guard) = guard(core) {
+            return self.protected_decision(word, core, guard);
+        }
+
+        let mut decision = decide(core);
+        decision.output = format!("{}{}{}", lead, decision.output, trail);
+        decision
+    }
+
+    // A protected token is left as written, unless the protected file gives its term a canonical
+    // form. `token` is what the guard matched: the word, or its core inside edge punctuation
+    fn protected_decision(&self, word: &str, token: &str, guard: GuardMatch) -> Decision {
+        let canonical = self.guards.canonical_form(&guard);
+        let mut decision = Decision::protected(word, guard);
+        if let Some(canonical) = canonical {
+            let (lead, _, trail) = if token.len() == word.len() { ("", token, "") } else { strip_punctuation(word) };
+            decision.output = format!("{}{}{}", lead, canonical, trail);
+        }
+        decision
+    }
+
+    fn correct_core(&self, word: &str, within: Option<&HashSet<String>>, thresholds: Thresholds) -> Decision {
+        let symspell = self.symspell();
+
+        let mut suggestions = match within {
+            Some(allowed) => {
+                let mut candidates = symspell.suggestions_within(word, usize::MAX, self.edit_distance, false);
+                candidates.retain(|s| allowed.contains(&symspell.normalize(&s.term)));
+                candidates
+            }
+            // A boost can lift any candidate within edit distance, so all of them are ranked
+            None if !self.boosts.is_empty() => symspell.suggestions(word, usize::MAX),
+            // The shape filter may drop top candidates, so the cut to five comes after it
+            None if self.structural_match => symspell.suggestions(word, usize::MAX),
+            // The winner's tie partner may sit just past the cut
+            None if self.tie_break == TieBreak::None => symspell.suggestions(word, usize::MAX),
+            // Denied words may fill the pool too
+            None if !self.denylist.is_empty() => symspell.suggestions(word, usize::MAX),
+            None => symspell.suggestions(word, thresholds.max_suggestions),
+        };
+
+        // "b12" never becomes "bid", nor "hello" "h3llo"
+        if self.structural_match {
+            suggestions.retain(|s| s.distance == 0 || same_shape(word, &s.term));
+        }
+        if !self.denylist.is_empty() {
+            suggestions.retain(|s| !self.denied(symspell, s));
+        }
+        if within.is_none() && self.boosts.is_empty() && self.tie_break == TieBreak::Lexicographic {
+            suggestions.truncate(thresholds.max_suggestions);
+        }
+
+        // If exact match exists, return canonical form from dictionary, unless it is rare
+        // enough to be treated as a likely typo of a more frequent neighbour
+        let exact = suggestions.first().filter(|s| s.distance == 0);
+        if let Some(exact) = exact {
+            if !self.is_rare(exact.frequency) {
+                return Decision::exact(exact);
+            }
+        }
+
+        // Get original word's frequency (if it exists in dictionary)
+        let original_freq = if within.is_some() { None } else { symspell.get_frequency(word) };
+        let mut best_rejected = None;
+
+        // Apply frequency threshold
+        let required_frequency = thresholds.required_frequency(original_freq);
+
+        // Re-rank by boosted frequency; distance and then user-layer words still come first. The
+        // sort is stable, so without boosts the dictionary order is kept
+        let mut candidates: Vec<(&Suggestion, f64, Vec<usize>)> = suggestions
+            .iter()
+            .map(|s| {
+                let (boost, applied) = self.boosts.apply(&s.term);
+                (s, boost, applied)
+            })
+            .collect();
+        if !self.boosts.is_empty() {
+            candidates.sort_by(|(a, a_boost, _), (b, b_boost, _)| {
+                let a_freq = a.frequency as f64 * a_boost;
+                let b_freq = b.frequency as f64 * b_boost;
+                a.distance.cmp(&b.distance).then(b.user.cmp(&a.user)).then(b_freq.total_cmp(&a_freq))
+            });
+        }
+
+        // Find best correction with frequency threshold
+        let mut correction = None;
+        let mut ambiguous = false;
+        let mut ranked = candidates.into_iter().peekable();
+        while let Some((suggestion, boost, applied)) = ranked.next() {
+            if suggestion.distance > 0 && suggestion.distance <= self.edit_distance {
+                let score = suggestion.frequency as f64 * boost;
+                if score >= required_frequency {
+                    // Only the term order separates an exact tie, and a tied partner passes too
+                    let tied = ranked.peek().is_some_and(|(next, next_boost, _)| {
+                        next.distance == suggestion.distance
+                            && next.user == suggestion.user
+                            && next.frequency as f64 * next_boost == score
+                    });
+                    if tied && self.tie_break == TieBreak::None {
+                        ambiguous = true;
+                        break;
+                    }
+
+                    correction = Some(Decision {
+                        output: suggestion.term.clone(),
+                        reason: Reason::Corrected,
+                        distance: Some(suggestion.distance),
+                        frequency: Some(suggestion.frequency),
+                        best_rejected: None,
+                        boost,
+                        boosts: applied,
+                        path: None,
+                        guard: None,
+                    });
+                    break;
+                }
+
+                // Suggestions are ranked, so the first failure is the best one
+                if best_rejected.is_none() {
+                    best_rejected = Some(Rejected {
+                        term: suggestion.term.clone(),
+                        distance: suggestion.distance,
+                        frequency: suggestion.frequency,
+                        required_frequency,
+                    });
+                }
+            }
+        }
+
+        // Unknown tokens outside a closed set may also be split in two
+        match self.segmentation {
+            Some(preference) if within.is_none() && exact.is_none() => {
+                let split = symspell.best_split(word).filter(|split| split.score >= required_frequency);
+                if let Some(decision) = choose_path(preference, correction, split) {
+                    return decision;
+                }
+            }
+            _ => {
+                if let Some(correction) = correction {
+                    return correction;
+                }
+            }
+        }
+
+        // A rare known word with no better neighbour is still an exact match
+        if let Some(exact) = exact {
+            return Decision::exact(exact);
+        }
+
+        // No suggestions passed the threshold, or the best ones tied
+        let reason = if ambiguous {
+            Reason::Ambiguous
+        } else if best_rejected.is_some() {
+            Reason::BelowThreshold
+        } else {
+            Reason::NoCandidates
+        };
+        Decision {
+            best_rejected,
+            ..Decision::unchanged(word, reason)
+        }
+    }
+
+    /// Policy for a token that may still be being typed: guards and exact matches as usual, then
+    /// the most frequent dictionary word it is a prefix of, never an edit-distance correction
+    pub fn complete_token(&self, word: &str) -> Decision {
+        self.stripped(word, true, self.thresholds, |core| self.complete_core(core, self.thresholds))
+    }
+
+    fn complete_core(&self, word: &str, thresholds: Thresholds) -> Decision {
+        if let Some(exact) = self.symspell().suggestions_within(word, 1, 0, false).first() {
+            return Decision::exact(exact);
+        }
+
+        match self.symspell().complete_where(word, |key| !self.denylist.contains(key)) {
+            Some(completion) if completion.frequency as f64 >= thresholds.min_suggestion_frequency => Decision {
+                output: completion.term,
+                reason: Reason::Completed,
+                distance: Some(completion.distance),
+                frequency: Some(completion.frequency),
+                ..Decision::unchanged(word, Reason::Completed)
+            },
+            _ => Decision::unchanged(word, Reason::NoCandidates),
+        }
+    }
+
+    /// Corrects a whitespace-separated phrase as a whole (see `SymSpell::lookup_compound`), so
+    /// words may also be merged with the next one or split in two. Edge punctuation is stripped
+    /// and re-attached as in `correct_token`, and a word with punctuation between it and the next
+    /// is never merged with it. Protected, numeric, and skipped words are kept as written
+    pub fn correct_phrase(&self, phrase: &str) -> PhraseCorrection {
+        let words: Vec<Cow<str>> = phrase.split_whitespace().map(|word| self.clean(word)).collect();
+        let parts: Vec<(&str, &str, &str)> = words
+            .iter()
+            .map(|word| if self.symspell().contains(word) { ("", word.as_ref(), "") } else { strip_punctuation(word) })
+            .collect();
+
+        let terms: Vec<PhraseTerm> = parts
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, core, trail))| PhraseTerm {
+                text: core,
+                fixed: core.is_empty()
+                    || self.units.apply(core).is_some()
+                    || self.thresholds.skip_reason(core).is_some()
+                    || self.thresholds.skips_all_caps(core)
+                    || self.guards.is_protected_normalized(&words[i], &self.normalize(&words[i]))
+                    || self.guards.is_protected_normalized(core, &self.normalize(core)),
+                joins_next: trail.is_empty() && parts.get(i + 1).is_some_and(|&(lead, _, _)| lead.is_empty()),
+            })
+            .collect();
+
+        let mut output = Vec::with_capacity(words.len());
+        let mut distance = 0;
+        let mut i = 0;
+        for segment in self.symspell().lookup_compound(&terms, self.thresholds.min_suggestion_frequency) {
+            let last = i + segment.terms - 1;
+            output.push(if terms[i].fixed {
+                words[i].to_string()
+            } else {
+                format!("{}{}{}", parts[i].0, segment.output, parts[last].2)
+            });
+            distance += segment.distance;
+            i = last + 1;
+        }
+
+        PhraseCorrection { output: output.join(" "), distance }
+    }
+
+    /// Splits text without spaces, such as a hashtag or domain name, into its most probable
+    /// dictionary words (see `SymSpell::segment`)
+    pub fn segment(&self, text: &str, max_segment_length: usize) -> Segmentation {
+        self.symspell().segment(&self.clean(text), max_segment_length)
+    }
+
+    /// `correct_token` for every word, split across up to `threads` scoped threads. Decisions come
+    /// back in input order whatever the thread count
+    pub fn correct_many(&self, words: &[String], threads: usize) -> Vec<Decision> {
+        parallel_map(words, threads, |_, word| self.correct_token(word))
+    }
+
+    /// The decision for a query's final token under a last-token policy and thresholds
+    pub fn correct_last_token(&self, word: &str, policy: LastToken, thresholds: Thresholds) -> Decision {
+        self.last_token_decision(word, policy, thresholds, true)
+    }
+
+    fn last_token_decision(&self, word: &str, policy: LastToken, thresholds: Thresholds, guarded: bool) -> Decision {
+        match policy {
+            LastToken::Normal => self.stripped(word, guarded, thresholds, |core| self.correct_core(core, None, thresholds)),
+            LastToken::Skip => Decision::unchanged(word, Reason::Skipped),
+            LastToken::Complete => self.stripped(word, guarded, thresholds, |core| self.complete_core(core, thresholds)),
+        }
+    }
+
+    /// The replacement for a token in running text, if it should be replaced. Numbers,
+    /// punctuation-only tokens, and CJK/Thai text are left alone, and known words keep their
+    /// original casing rather than being replaced by the canonical form. With `guarded` false
+    /// protected tokens are corrected like any other
+    pub fn correct_in_text(&self, token: &str, policy: LastToken, guarded: bool) -> Option<String> {
+        if !token.chars().any(char::is_alphabetic) || token.chars().any(is_unsegmented_script) {
+            return None;
+        }
+
+        let cleaned = self.clean(token);
+        let (_, core, _) = strip_punctuation(&cleaned);
+        if [cleaned.as_ref(), core].iter().any(|t| self.symspell().get_frequency(t).is_some_and(|f| !self.is_rare(f))) {
+            return None;
+        }
+
+        // Only stripped ignorables don't make a replacement
+        let corrected = self.last_token_decision(token, policy, self.thresholds, guarded).output;
+        (corrected != cleaned).then_some(corrected)
+    }
+
+    /// Appends `text` to `output` with misspelled tokens replaced, calling `on_correction` with
+    /// (token, corrected, start, end) byte offsets into `text` for each replacement.
+    /// `last_token` applies to the final word token; trailing numbers and punctuation don't count.
+    /// Hyphenated words split by the unicode tokenizer are left whole
+    pub fn rewrite_text<E>(
+        &self,
+        tokenizer: &Tokenizer,
+        text: &str,
+        last_token: LastToken,
+        guarded: bool,
+        mut output: Option<&mut String>,
+        mut on_correction: impl FnMut(&str, &str, usize, usize) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut last_end = 0;
+        let split_hyphenated = matches!(tokenizer, Tokenizer::Unicode);
+        let tokens = tokenizer.tokens(text);
+        let last_word = tokens.iter().rposition(|(_, token)| {
+            token.chars().any(char::is_alphabetic) && !token.chars().any(is_unsegmented_script)
+        });
+
+        for (i, (start, token)) in tokens.into_iter().enumerate() {
+            let end = start + token.len();
+            if split_hyphenated && in_hyphenated_word(text, start, end) {
+                continue;
+            }
+
+            let policy = if Some(i) == last_word { last_token } else { LastToken::Normal };
+            let corrected = match self.correct_in_text(token, policy, guarded) {
+                Some(corrected) => corrected,
+                None => continue,
+            };
+
+            if let Some(output) = output.as_deref_mut() {
+                output.push_str(&text[last_end..start]);
+                output.push_str(&corrected);
+            }
+            last_end = end;
+
+            on_correction(token, &corrected, start, end)?;
+        }
+        if let Some(output) = output {
+            output.push_str(&text[last_end..]);
+        }
+
+        Ok(())
+    }
+
+    /// HTML variant of `rewrite_text`: markup and skip elements are copied through, text is
+    /// entity-decoded for tokenizing and replacements are re-encoded. Offsets refer to `html`
+    pub fn rewrite_html<E>(
+        &self,
+        tokenizer: &Tokenizer,
+        html: &str,
+        guarded: bool,
+        mut output: Option<&mut String>,
+        mut on_correction: impl FnMut(&str, &str, usize, usize) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let split_hyphenated = matches!(tokenizer, Tokenizer::Unicode);
+        for segment in html::segments(html) {
+            let (segment_start, segment_end) = match segment {
+                html::Segment::Raw(start, end) => {
+                    if let Some(output) = output.as_deref_mut() {
+                        output.push_str(&html[start..end]);
+                    }
+                    continue;
+                }
+                html::Segment::Text(start, end) => (start, end),
+            };
+
+            let source = &html[segment_start..segment_end];
+            let (decoded, offsets) = html::decode(source);
+            let mut last_end = 0;
+
+            for (start, token) in tokenizer.tokens(&decoded) {
+                let end = start + token.len();
+                // Don't correct around an entity we couldn't decode
+                let touches_opaque = token.contains(html::OPAQUE)
+                    || decoded[..start].ends_with(html::OPAQUE)
+                    || decoded[end..].starts_with(html::OPAQUE);
+                if touches_opaque || split_hyphenated && in_hyphenated_word(&decoded, start, end) {
+                    continue;
+                }
+
+                let corrected = match self.correct_in_text(token, LastToken::Normal, guarded) {
+                    Some(corrected) => corrected,
+                    None => continue,
+                };
+
+                let (source_start, source_end) = (offsets[start], offsets[end]);
+                if let Some(output) = output.as_deref_mut() {
+                    output.push_str(&source[last_end..source_start]);
+                    output.push_str(&html::encode(&corrected));
+                }
+                last_end = source_end;
+
+                on_correction(token, &corrected, segment_start + source_start, segment_start + source_end)?;
+            }
+            if let Some(output) = output.as_deref_mut() {
+                output.push_str(&source[last_end..]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// correct_text with dry_run: each replacement, without building the corrected text.
+    /// `proposed` is entity-encoded in HTML mode, so applying the changes to `text` reproduces
+    /// the corrected text exactly
+    pub fn propose_text_changes(
+        &self,
+        tokenizer: &Tokenizer,
+        text: &str,
+        html_mode: bool,
+        last_token: LastToken,
+        guarded: bool,
+    ) -> Vec<TextChange> {
+        let mut changes = Vec::new();
+        let record = |token: &str, corrected: &str, start: usize, end: usize| -> Result<(), Infallible> {
+            changes.push(TextChange {
+                start,
+                end,
+                original: text[start..end].to_string(),
+                proposed: if html_mode { html::encode(corrected) } else { corrected.to_string() },
+                confidence: self.confidence(token, corrected),
+            });
+            Ok(())
+        };
+
+        let Ok(()) = if html_mode {
+            self.rewrite_html(tokenizer, text, guarded, None, record)
+        } else {
+            self.rewrite_text(tokenizer, text, last_token, guarded, None, record)
+        };
+        changes
+    }
+
+    /// Corrects each `separator`-delimited field of `input`: as one token, or with `text_mode`
+    /// as running text. Splitting and rejoining on the same separator keeps empty fields and any
+    /// trailing separator
+    pub fn correct_lines(&self, input: &str, separator: &str, text_mode: bool) -> String {
+        let mut output = String::with_capacity(input.len());
+
+        for (i, line) in input.split(separator).enumerate() {
+            if i > 0 {
+                output.push_str(separator);
+            }
+
+            if text_mode {
+                let Ok(()) = self.rewrite_text(&self.tokenizer, line, LastToken::Normal, true, Some(&mut output), |_, _, _, _| {
+                    Ok::<(), Infallible>(())
+                });
+                continue;
+            }
+
+            // A CR left over from CRLF input when splitting on "\n" is not part of the field
+            let (field, cr) = match line.strip_suffix('\r') {
+                Some(field) => (field, "\r"),
+                None => (line, ""),
+            };
+            if !field.is_empty() {
+                output.push_str(&self.correct_token(field).output);
+            }
+            output.push_str(cr);
+        }
+
+        output
+    }
+
+    /// What correct_tokens outputs for `word` given its decision: the concept an alias resolves
+    /// to with `resolve_aliases`, else the correction, written in `output_mode`. Also returns
+    /// the alias, if one was applied
+    pub fn token_output(
+        &self,
+        word: &str,
+        decision: &Decision,
+        output_mode: OutputMode,
+        resolve_aliases: bool,
+    ) -> (String, Option<&str>) {
+        let alias = if resolve_aliases { self.resolve_alias(decision) } else { None };
+        let output = output_mode.apply(word, alias.map_or_else(|| decision.output.clone(), str::to_string), &self.normalizer);
+        (output, alias)
+    }
+
+    /// correct_tokens with dry_run: the output `token_output` would give `word` and the
+    /// confidence of its correction, or None when the output is `word` itself
+    pub fn propose_token_change(
+        &self,
+        word: &str,
+        decision: &Decision,
+        output_mode: OutputMode,
+        resolve_aliases: bool,
+    ) -> Option<(String, Option<f64>)> {
+        let (output, _) = self.token_output(word, decision, output_mode, resolve_aliases);
+        (output != word).then(|| (output, self.confidence(word, &decision.output)))
+    }
+
+    /// Replays a corpus through the correction pipeline, writing one TSV row per corrected token
+    /// (original, corrected, distance, freq, reason) to `output_path`. Input and output are both
+    /// streamed
+    pub fn evaluate_corpus(&self, tokenizer: &Tokenizer, input_path: &str, output_path: &str) -> Result<CorpusSummary, LoadError> {
+        let input = std::fs::File::open(input_path)
+            .map_err(|e| LoadError::Io(format!("Failed to open corpus file: {}", e)))?;
+        let output = std::fs::File::create(output_path)
+            .map_err(|e| LoadError::Io(format!("Failed to create output file: {}", e)))?;
+        let write_error = |e: std::io::Error| LoadError::Io(format!("Failed to write output file: {}", e));
+
+        let reader = std::io::BufReader::new(input);
+        let mut writer = std::io::BufWriter::new(output);
+        let mut summary = CorpusSummary::default();
+
+        for line in reader.lines() {
+            let line = line.map_err(|e| LoadError::Io(format!("Failed to read line: {}", e)))?;
+            summary.lines += 1;
+
+            for (_, token) in tokenizer.tokens(&line) {
+                if !token.chars().any(char::is_alphabetic) {
+                    continue;
+                }
+                summary.tokens += 1;
+
+                let decision = self.correct_token(token);
+                *summary.reasons.entry(decision.reason).or_insert(0) += 1;
+
+                if decision.reason == Reason::Corrected {
+                    writeln!(
+                        writer,
+                        "{}\t{}\t{}\t{}\t{}",
+                        token,
+                        decision.output,
+                        decision.distance.unwrap_or(0),
+                        decision.frequency.unwrap_or(0),
+                        decision.reason.as_str()
+                    )
+                    .map_err(write_error)?;
+                }
+            }
+        }
+        writer.flush().map_err(write_error)?;
+
+        Ok(summary)
+    }
+
+    /// Runs (input, expected) pairs through the full correction pipeline. Failures carry the
+    /// decision reason so a regression can be told apart from a threshold or guard change
+    pub fn run_golden(&self, pairs: impl IntoIterator<Item = (String, String)>) -> GoldenReport {
+        let mut report = GoldenReport::default();
+
+        for (input, expected) in pairs {
+            let decision = self.correct_token(&input);
+            if decision.output == expected {
+                report.passed += 1;
+                continue;
+            }
+
+            report.failed.push(GoldenFailure { input, expected, got: decision.output, reason: decision.reason });
+        }
+
+        report
+    }
+
+    /// Why `correct_token` decides what it does for `word`: the guard that matched, every
+    /// candidate, and the threshold they were held to. With `guarded` false the guards are
+    /// skipped, showing what would happen to a protected token otherwise. Guard hit counters
+    /// are left alone
+    pub fn explain(&self, word: &str, guarded: bool) -> Explanation {
+        let cleaned = self.clean(word);
+        let (_, core, _) = strip_punctuation(&cleaned);
+        let term = if core.is_empty() || self.symspell().contains(&cleaned) { &*cleaned } else { core };
+        let frequency = self.symspell().get_frequency(term);
+
+        // The same tokens `stripped` checks: as written, then without edge punctuation
+        let guarding = [&*cleaned, term]
+            .into_iter()
+            .filter(|_| guarded)
+            .find_map(|token| Some((token, self.guards.match_reason(token, &self.normalize(token))?)));
+        let mut decision = self.stripped(word, false, self.thresholds, |core| self.correct_core(core, None, self.thresholds));
+        // Numeric and skipped tokens are decided before guards are
+        let skipped = matches!(decision.reason, Reason::Numeric | Reason::HasDigits | Reason::TooShort);
+        if let Some((token, guard)) = guarding.clone().filter(|_| !skipped) {
+            decision = self.protected_decision(&cleaned, token, guard);
+        }
+        let guard = guarding.map(|(_, guard)| guard);
+
+        Explanation {
+            term: term.to_string(),
+            normalized: self.symspell().normalize(term),
+            frequency,
+            guard,
+            candidates: self.suggest_within(term, usize::MAX, self.edit_distance, false),
+            required_frequency: self.thresholds.required_frequency(frequency),
+            decision,
+        }
+    }
+
+    /// How strongly the dictionary backs replacing `word` with `output`: the output's share of
+    /// the frequency of every candidate at its edit distance, so 1.0 when nothing else was as
+    /// close. Case-only changes are 1.0. None when the output isn't a candidate of the word
+    /// (completions and segmentations)
+    pub fn confidence(&self, word: &str, output: &str) -> Option<f64> {
+        let symspell = self.symspell();
+        if symspell.normalize(&self.clean(word)) == symspell.normalize(output) {
+            return Some(1.0);
+        }
+
+        let cleaned = self.clean(word);
+        let (_, core, _) = strip_punctuation(&cleaned);
+        let (_, output_core, _) = strip_punctuation(output);
+        let target = symspell.normalize(output_core);
+
+        let candidates = symspell.suggestions_within(core, usize::MAX, self.edit_distance, false);
+        let chosen = candidates.iter().find(|c| symspell.normalize(&c.term) == target)?;
+        let total: u64 = candidates.iter().filter(|c| c.distance == chosen.distance).map(|c| c.frequency).sum();
+        if chosen.distance == 0 || total == 0 {
+            return Some(1.0);
+        }
+
+        Some(chosen.frequency as f64 / total as f64)
+    }
+}
+
+// Settles a correction against a split of the same token. Either alone wins; when both exist a
+// forced preference decides, otherwise the higher score
+fn choose_path(preference: SegmentationPreference, correction: Option<Decision>, split: Option<Split>) -> Option<Decision> {
+    let correction_score = correction.as_ref().map(|c| c.frequency.unwrap_or(0) as f64 * c.boost);
+    let segmentation_score = split.as_ref().map(|s| s.score);
+
+    let segmented = match (correction_score, segmentation_score) {
+        (None, None) => return None,
+        (Some(_), None) => false,
+        (None, Some(_)) => true,
+        (Some(correction_score), Some(segmentation_score)) => match preference {
+            SegmentationPreference::Segmentation => true,
+            SegmentationPreference::Correction => false,
+            SegmentationPreference::Score => segmentation_score > correction_score,
+        },
+    };
+
+    let path = Some(PathChoice { segmented, correction_score, segmentation_score });
+    match (segmented, correction, split) {
+        (true, _, Some(split)) => Some(Decision {
+            output: format!("{} {}", split.left, split.right),
+            reason: Reason::Corrected,
+            // Inserting the space is one edit
+            distance: Some(1),
+            frequency: None,
+            best_rejected: None,
+            boost: 1.0,
+            boosts: Vec::new(),
+            path,
+            guard: None,
+        }),
+        (false, Some(correction), _) => Some(Decision { path, ..correction }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guards::{GuardKind, TermForm};
+    use crate::normalizer::NormalForm;
+    use regex::{Regex, RegexBuilder};
+
+    // Writes a fixture under the temp dir, named per test since tests run in parallel
+    fn fixture(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("spellkit-engine-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn engine(name: &str, configure: impl FnOnce(&mut LoadOptions)) -> Engine {
+        let path = fixture(name, "hello\t10000\nhelp\t3000\nworld\t8000\nrare\t5\n");
+        let mut options = LoadOptions::new(&path);
+        configure(&mut options);
+        Engine::load(&options).unwrap()
+    }
+
+    #[test]
+    fn test_strip_accents() {
+        let path = fixture("strip_accents", "caf\u{e9}\t5000\nna\u{ef}ve\t3000\nM\u{fc}ller\t2000\ncafes\t10\n");
+        let load = |strip_accents: bool| {
+            let mut options = LoadOptions::new(&path);
+            options.normalizer.strip_accents = strip_accents;
+            Engine::load(&options).unwrap()
+        };
+        let engine = load(true);
+
+        let decision = engine.correct_token("cafe");
+        assert_eq!((decision.output.as_str(), decision.reason, decision.distance), ("caf\u{e9}", Reason::Exact, Some(0)));
+        assert_eq!(engine.correct_token("naive").output, "na\u{ef}ve");
+        assert_eq!(engine.correct_token("Muller").output, "M\u{fc}ller");
+        // The whole edit budget is left for the typo
+        assert_eq!(engine.correct_token("naiv").output, "na\u{ef}ve");
+        assert!(engine.contains("mull\u{e9}r"));
+        assert_eq!(engine.confidence("cafe", "caf\u{e9}"), Some(1.0));
+        assert_eq!(engine.explain("Caf\u{e9}", true).normalized, "cafe");
+
+        let accented = load(false);
+        assert_eq!(accented.correct_token("cafe").distance, Some(1));
+        assert_eq!(accented.correct_token("naiv").reason, Reason::NoCandidates);
+    }
+
+    #[test]
+    fn test_normalizer_options() {
+        let path = fixture("normalizer_options", "hello\t10000\nNASA\t5000\ne-mail\t3000\n");
+        let load = |normalizer: Normalizer| {
+            let mut options = LoadOptions::new(&path);
+            options.normalizer = normalizer;
+            options.aliases_path = Some(fixture("normalizer_aliases", "Email\tmessage\n"));
+            Engine::load(&options).unwrap()
+        };
+
+        let cased = load(Normalizer { form: NormalForm::Nfc, lowercase: false, ..Normalizer::default() });
+        assert!(cased.contains("NASA") && !cased.contains("nasa") && !cased.contains("Hello"));
+        assert_eq!(cased.correct_token("NASA").reason, Reason::Exact);
+        assert_eq!(cased.correct_token("hellp").output, "hello");
+        assert_eq!(cased.normalize("Caf\u{e9}"), "Caf\u{e9}");
+
+        let unpunctuated = load(Normalizer { strip_punctuation: true, ..Normalizer::default() });
+        let decision = unpunctuated.correct_token("email");
+        assert_eq!((decision.output.as_str(), decision.reason), ("e-mail", Reason::Exact));
+        assert_eq!(unpunctuated.resolve_alias(&decision), Some("message"));
+        assert_eq!(load(Normalizer::default()).correct_token("email").distance, Some(1));
+    }
+
+    #[test]
+    fn test_case_sensitive_dictionary() {
+        let path = fixture("case_sensitive", "WAS\t500\nwas\t90000\nBRCA1\t300\nthe\t80000\n");
+        let load = |lowercase: bool| {
+            let mut options = LoadOptions::new(&path);
+            options.normalizer.lowercase = lowercase;
+            options.protected_path = Some(fixture("case_sensitive_protected", "wsa\n"));
+            options.protected_patterns = vec![PatternSpec::new(r"^CDK\d+$")];
+            Engine::load(&options).unwrap()
+        };
+        let sensitive = load(false);
+        let folded = load(true);
+
+        // Each spelling is its own entry, and candidates are ranked by edits including case
+        for (word, output) in [("WAS", "WAS"), ("was", "was"), ("Was", "was"), ("WAZ", "WAS"), ("thE", "the")] {
+            assert_eq!(sensitive.correct_token(word).output, output, "{}", word);
+        }
+        assert_eq!(sensitive.dictionary_size(), 4);
+        assert_eq!(sensitive.correct_token("brca1").reason, Reason::NoCandidates);
+        assert!(sensitive.contains("BRCA1") && !sensitive.contains("Brca1"));
+
+        // The folded engine built from the same file is unaffected
+        assert_eq!(folded.dictionary_size(), 3);
+        assert_eq!(folded.correct_token("WAZ").output, "was");
+        assert_eq!(folded.correct_token("brca1").output, "BRCA1");
+
+        // Guards keep their own case handling
+        for engine in [&sensitive, &folded] {
+            assert_eq!(engine.correct_token("WSA").reason, Reason::Protected);
+            assert_eq!(engine.correct_token("CDK12").reason, Reason::Protected);
+        }
+    }
+
+    #[test]
+    fn test_correct_token_reasons() {
+        let engine = engine("reasons", |_| {});
+
+        let exact = engine.correct_token("hello");
+        assert_eq!(exact.reason, Reason::Exact);
+        assert_eq!(exact.frequency, Some(10000));
+
+        let corrected = engine.correct_token("helo");
+        assert_eq!(corrected.reason, Reason::Corrected);
+        assert_eq!(corrected.output, "hello");
+        assert_eq!(corrected.distance, Some(1));
+
+        assert_eq!(engine.correct_token("zzzzzz").reason, Reason::NoCandidates);
+    }
+
+    #[test]
+    fn test_confidence() {
+        let engine = engine("confidence", |_| {});
+
+        // "help" is as close to "helo" as "hello" is
+        assert_eq!(engine.confidence("helo", "hello"), Some(10000.0 / 13000.0));
+        assert_eq!(engine.confidence("(wrld)", "(world)"), Some(1.0));
+        assert_eq!(engine.confidence("HELLO", "hello"), Some(1.0));
+        assert_eq!(engine.confidence("wrld", "help"), None);
+    }
+
+    #[test]
+    fn test_corrections_follow_edit_distance() {
+        let path = fixture("edit_distance", "temperature\t650\nprotein\t1200\n");
+        let mut options = LoadOptions::new(&path);
+        let one = Engine::load(&options).unwrap();
+        options.edit_distance = 2;
+        let two = Engine::load(&options).unwrap();
+
+        // Two deletions and a transposition away
+        for (typo, expected) in [("temprture", "temperature"), ("protien", "protein")] {
+            let decision = two.correct_token(typo);
+            assert_eq!(decision.output, expected);
+            assert_eq!(decision.distance, Some(2));
+            assert_eq!(one.correct_token(typo).reason, Reason::NoCandidates);
+        }
+        assert_eq!(two.correct_many(&["temprture".to_string()], 1)[0].output, "temperature");
+    }
+
+    #[test]
+    fn test_correct_phrase() {
+        let path = fixture("phrase", "spell\t500\nchecker\t400\ncheck\t600\nfor\t10000\nruby\t300\n");
+        let mut options = LoadOptions::new(&path);
+        options.edit_distance = 2;
+        let engine = Engine::load(&options).unwrap();
+
+        let corrected = engine.correct_phrase("spel  cheker, for ruby!");
+        assert_eq!(corrected.output, "spell checker, for ruby!");
+        assert_eq!(corrected.distance, 2);
+        assert_eq!(engine.correct_phrase("spellcheker").output, "spell checker");
+        assert_eq!(engine.correct_phrase("chec ker").output, "checker");
+        // Punctuation between the words keeps them apart
+        assert_eq!(engine.correct_phrase("chec, ker").output, "check, for");
+
+        options.protected_patterns = vec![PatternSpec::new(r"^ker$")];
+        let protected = Engine::load(&options).unwrap();
+        assert_eq!(protected.correct_phrase("chec ker"), PhraseCorrection { output: "check ker".into(), distance: 1 });
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let path = fixture("tie_break", "and\t500\nabd\t500\n");
+        let mut options = LoadOptions::new(&path);
+        let lexicographic = Engine::load(&options).unwrap();
+        options.tie_break = TieBreak::None;
+        let none = Engine::load(&options).unwrap();
+
+        assert_eq!(lexicographic.correct_token("aod").output, "abd");
+        let declined = none.correct_token("aod");
+        assert_eq!(declined.reason, Reason::Ambiguous);
+        assert_eq!(declined.output, "aod");
+
+        // Suggestions still list both, and an untied winner is still taken
+        assert_eq!(none.suggest("aod", 5).len(), 2);
+        assert_eq!(none.correct_token("ad").reason, Reason::Ambiguous);
+        assert_eq!(none.correct_token("anf").output, "and");
+    }
+
+    #[test]
+    fn test_units() {
+        let off = engine("units_off", |_| {});
+        let skip = engine("units_skip", |options| {
+            options.units = UnitMode::Skip;
+            // Every numeric token would otherwise be protected before correction is tried
+            options.protected_patterns = vec![PatternSpec::new(r"^\d")];
+        });
+        let normalize = engine("units_normalize", |options| {
+            options.units = UnitMode::Normalize;
+            options.unit_list = Some(vec!["mg".to_string(), "°C".to_string()]);
+        });
+
+        assert_eq!(off.correct_token("500MG").reason, Reason::NoCandidates);
+        assert!(!off.contains("500"));
+
+        let skipped = skip.correct_token("(500MG),");
+        assert_eq!(skipped.reason, Reason::Numeric);
+        assert_eq!(skipped.output, "(500MG),");
+        assert!(skip.contains("37.5"));
+
+        assert_eq!(normalize.correct_token("500MG.").output, "500mg.");
+        assert_eq!(normalize.correct_token("37.5\u{b0}c").output, "37.5\u{b0}C");
+
+        // Not in this unit list, so it goes through correction
+        assert_eq!(normalize.correct_token("10ml").reason, Reason::NoCandidates);
+        assert!(!normalize.contains("10ml"));
+    }
+
+    #[test]
+    fn test_pattern_flags() {
+        let engine = engine("pattern_flags", |options| {
+            options.protected_patterns = vec![PatternSpec { case_insensitive: true, ..PatternSpec::new(r"^hgnc:\d+$") }];
+        });
+        assert_eq!(engine.correct_token("hgnc:1234").reason, Reason::Protected);
+        assert_eq!(engine.correct_token("HGNC:1234").reason, Reason::Protected);
+
+        let path = fixture("pattern_flags_invalid", "hello\t10000\n");
+        let mut options = LoadOptions::new(&path);
+        options.protected_patterns = vec![PatternSpec::new("^ok$"), PatternSpec::new("(unclosed")];
+        assert!(matches!(Engine::load(&options), Err(LoadError::Pattern(message)) if message.starts_with("protected_patterns[1]: ")));
+    }
+
+    #[test]
+    fn test_anchored_patterns() {
+        let unanchored = engine("unanchored", |options| options.protected_patterns = vec![PatternSpec::new(r"\d+")]);
+        let anchored = engine("anchored", |options| {
+            options.protected_patterns = vec![PatternSpec { anchored: true, ..PatternSpec::new(r"\d+") }];
+        });
+
+        assert_eq!(unanchored.correct_token("covid19variantt").reason, Reason::Protected);
+        assert_eq!(unanchored.correct_token("1234").reason, Reason::Protected);
+        assert_ne!(anchored.correct_token("covid19variantt").reason, Reason::Protected);
+        assert_eq!(anchored.correct_token("1234").reason, Reason::Protected);
+
+        // Alternation stays inside the anchors, and extended mode's comments can't escape them
+        let alternation = engine("anchored_alternation", |options| {
+            options.protected_patterns = vec![
+                PatternSpec { anchored: true, ..PatternSpec::new("ab|cd") },
+                PatternSpec { anchored: true, extended: true, ..PatternSpec::new("x y # comment") },
+            ];
+        });
+        assert!(alternation.is_protected("cd"));
+        assert!(alternation.is_protected("xy"));
+        assert!(!alternation.is_protected("abc"));
+        assert!(!alternation.is_protected("xyz"));
+    }
+
+    #[test]
+    fn test_many_protected_patterns() {
+        let specs: Vec<PatternSpec> = (0..300)
+            .map(|i| match i % 3 {
+                0 => PatternSpec::new(&format!("^ab{}x$", i)),
+                1 => PatternSpec { case_insensitive: true, anchored: true, ..PatternSpec::new(&format!("id{}-[a-z]+", i)) },
+                _ => PatternSpec::new(&format!("zz{}q", i)),
+            })
+            .collect();
+        // Each pattern on its own, tried in order, as the guards did before the RegexSet
+        let sequential: Vec<Regex> = specs
+            .iter()
+            .map(|spec| {
+                let source = if spec.anchored { format!(r"\A(?:{})\z", spec.source) } else { spec.source.clone() };
+                RegexBuilder::new(&source).case_insensitive(spec.case_insensitive).build().unwrap()
+            })
+            .collect();
+        let engine = engine("many_patterns", |options| options.protected_patterns = specs.clone());
+
+        let mut protected = 0;
+        for i in (0..320).step_by(7) {
+            for token in [
+                format!("ab{}x", i),
+                format!("AB{}X", i),
+                format!("id{}-foo", i),
+                format!("ID{}-FOO", i),
+                format!("id{}-foo1", i),
+                format!("xzz{}qy", i),
+                format!("zz{}", i),
+            ] {
+                let normalized = engine.normalize(&token);
+                let first = |token: &str| sequential.iter().position(|regex| regex.is_match(token));
+                let expected = first(&token).or_else(|| first(&normalized));
+                let guard = engine.guards.match_reason(&token, &normalized);
+                assert_eq!(guard.and_then(|guard| guard.index), expected, "{}", token);
+                protected += expected.is_some() as usize;
+            }
+        }
+        assert!(protected > 50);
+        assert!(!engine.is_protected("hello"));
+    }
+
+    #[test]
+    fn test_punctuation_stripped_before_guards() {
+        let engine = engine("stripping", |options| {
+            options.protected_patterns = vec![PatternSpec::new(r"^helo$")];
+            options.protected_prefixes = vec!["wrl".into()];
+        });
+
+        for token in ["helo", "(helo),", "“helo”", "[helo].", "¿helo?"] {
+            let decision = engine.correct_token(token);
+            assert_eq!(decision.reason, Reason::Protected, "{}", token);
+            assert_eq!(decision.output, token);
+        }
+        assert_eq!(engine.correct_token("(wrld)").reason, Reason::Protected);
+
+        assert_eq!(engine.correct_token("(hlp),").output, "(help),");
+        assert_eq!(engine.correct_token("«hello»").reason, Reason::Exact);
+        assert_eq!(engine.correct_token("...").reason, Reason::NoCandidates);
+        assert_eq!(engine.complete_token("(hel").output, "(hello");
+        assert_eq!(engine.correct_in_text("hello,", LastToken::Normal, true), None);
+        assert_eq!(engine.correct_in_text("(helo),", LastToken::Normal, true), None);
+        assert_eq!(engine.correct_in_text("(helo),", LastToken::Normal, false).as_deref(), Some("(hello),"));
+        assert_eq!(OutputMode::PreserveCase.apply("(Hlp),", "(help),".into(), &Normalizer::default()), "(Help),");
+        assert_eq!(OutputMode::PreserveCase.apply("“HLP”", "“help”".into(), &Normalizer::default()), "“HELP”");
+    }
+
+    #[test]
+    fn test_text_pipeline() {
+        let engine = engine("text_pipeline", |_| {});
+        let tokenizer = engine.tokenizer();
+
+        let mut output = String::new();
+        let mut corrections = Vec::new();
+        let Ok(()) = engine.rewrite_text(tokenizer, "helo wrld, hello", LastToken::Normal, true, Some(&mut output), |token, corrected, start, end| {
+            corrections.push((token.to_string(), corrected.to_string(), start, end));
+            Ok::<(), Infallible>(())
+        });
+        assert_eq!(output, "hello world, hello");
+        assert_eq!(corrections, [("helo".into(), "hello".into(), 0, 4), ("wrld".into(), "world".into(), 5, 9)]);
+
+        let changes = engine.propose_text_changes(tokenizer, "helo wrld, hello", false, LastToken::Normal, true);
+        assert_eq!(changes.iter().map(|c| (c.start, c.end, c.proposed.as_str())).collect::<Vec<_>>(), [(0, 4, "hello"), (5, 9, "world")]);
+        assert!(changes.iter().all(|c| c.confidence.is_some()));
+
+        let mut html = String::new();
+        let Ok(()) = engine.rewrite_html(tokenizer, "<p>helo</p><code>wrld</code>", true, Some(&mut html), |_, _, _, _| Ok::<(), Infallible>(()));
+        assert_eq!(html, "<p>hello</p><code>wrld</code>");
+
+        assert_eq!(engine.correct_lines("helo\r\nwrld\n", "\n", false), "hello\r\nworld\n");
+        assert_eq!(engine.correct_lines("helo wrld|hello", "|", true), "hello world|hello");
+
+        let decision = engine.correct_token("helo");
+        let proposed = engine.propose_token_change("helo", &decision, OutputMode::Canonical, false);
+        assert_eq!(proposed.map(|(output, _)| output).as_deref(), Some("hello"));
+        assert_eq!(engine.propose_token_change("hello", &engine.correct_token("hello"), OutputMode::Canonical, false), None);
+    }
+
+    #[test]
+    fn test_evaluate_corpus_and_golden() {
+        let engine = engine("corpus_engine", |_| {});
+        let input = fixture("corpus_input", "helo wrld\nhello 42\n");
+        let output = fixture("corpus_output", "");
+
+        let summary = engine.evaluate_corpus(engine.tokenizer(), &input, &output).unwrap();
+        assert_eq!((summary.lines, summary.tokens), (2, 3));
+        assert_eq!((summary.count(Reason::Corrected), summary.count(Reason::Exact), summary.count(Reason::Protected)), (2, 1, 0));
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "helo\thello\t1\t10000\tcorrected\nwrld\tworld\t1\t8000\tcorrected\n"
+        );
+        assert!(matches!(engine.evaluate_corpus(engine.tokenizer(), "/nonexistent/corpus.txt", &output), Err(LoadError::Io(_))));
+
+        let report = engine.run_golden([("helo".to_string(), "hello".to_string()), ("wrld".to_string(), "word".to_string())]);
+        assert_eq!(report.passed, 1);
+        assert_eq!(
+            report.failed,
+            [GoldenFailure { input: "wrld".into(), expected: "word".into(), got: "world".into(), reason: Reason::Corrected }]
+        );
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        let preserve = |original: &str, output: &str| OutputMode::PreserveCase.apply(original, output.into(), &Normalizer::default());
+        assert_eq!(preserve("TEH", "the"), "THE");
+        assert_eq!(preserve("Teh", "the"), "The");
+        assert_eq!(preserve("teh", "the"), "the");
+        // Mixed case falls back to the canonical form
+        assert_eq!(preserve("tEh", "the"), "the");
+        assert_eq!(preserve("TeH", "the"), "the");
+
+        // Unicode-aware uppercasing, including characters that expand
+        assert_eq!(preserve("Écolle", "école"), "École");
+        assert_eq!(preserve("ÉCOLLE", "école"), "ÉCOLE");
+        assert_eq!(preserve("STRASE", "straße"), "STRASSE");
+
+        assert!(!engine("preserve_case_off", |_| {}).preserve_case());
+        assert!(engine("preserve_case", |options| options.preserve_case = true).preserve_case());
+    }
+
+    #[test]
+    fn test_ignorable_characters_stripped() {
+        let path = fixture("ignorable", "hel\u{200B}lo\t10000\nworld\t8000\n\u{200E}help\t3000\n");
+        let protected = fixture("ignorable_protected", "CO\u{200B}VID\n");
+        let mut options = LoadOptions::new(&path);
+        options.protected_path = Some(protected);
+        let engine = Engine::load(&options).unwrap();
+
+        assert!(engine.contains("hello"));
+        assert!(engine.contains("wor\u{200B}ld\u{200E}"));
+        assert_eq!(engine.correct_token("\u{200E}world").reason, Reason::Exact);
+        assert_eq!(engine.correct_token("hel\u{200B}p").output, "help");
+        assert_eq!(engine.correct_token("wrld\u{200F}").output, "world");
+        assert_eq!(engine.correct_token("\u{200B}COVID").reason, Reason::Protected);
+        assert_eq!(engine.lookup("\u{200B}help").unwrap().frequency, 3000);
+        assert_eq!(engine.frequency("HEL\u{200B}LO"), Some(10000));
+        assert_eq!(engine.correct_in_text("world\u{200B}", LastToken::Normal, true), None);
+
+        options.strip_ignorable = false;
+        let engine = Engine::load(&options).unwrap();
+        assert!(!engine.contains("hello"));
+        assert!(!engine.contains("\u{200B}world"));
+    }
+
+    #[test]
+    fn test_correct_token_below_threshold() {
+        let engine = engine("threshold", |options| options.frequency_threshold = 100.0);
+
+        let decision = engine.correct_token("rore");
+        assert_eq!(decision.reason, Reason::BelowThreshold);
+        assert_eq!(decision.output, "rore");
+
+        let rejected = decision.best_rejected.unwrap();
+        assert_eq!(rejected.term, "rare");
+        assert_eq!(rejected.required_frequency, 100.0);
+    }
+
+    #[test]
+    fn test_zero_threshold_accepts_any_candidate() {
+        let path = fixture("zero_threshold", "hello\t10000\nhelo\t1\nzero\t0\n");
+        let mut options = LoadOptions::new(&path);
+        options.frequency_threshold = 0.0;
+        let engine = Engine::load(&options).unwrap();
+
+        // Absolute branch: even a zero-frequency word reaches 0
+        let decision = engine.correct_token("zer");
+        assert_eq!(decision.reason, Reason::Corrected);
+        assert_eq!(decision.output, "zero");
+
+        // Relative branch: 0 x the word's own frequency is 0, so a rare known word goes to any
+        // neighbour, and without autocorrect_known_rare known words stay as they are
+        assert_eq!(engine.correct_token("helo").reason, Reason::Exact);
+        options.autocorrect_known_rare = Some(2);
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.correct_token("helo").output, "hello");
+
+        options.frequency_threshold = -1.0;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid("frequency_threshold must be non-negative, got: -1".into()))
+        );
+    }
+
+    #[test]
+    fn test_split_thresholds() {
+        let path = fixture("split_thresholds", "hello\t10000\nhelo\t50\n");
+        let mut options = LoadOptions::new(&path);
+        options.autocorrect_known_rare = Some(100);
+        options.min_suggestion_frequency = Some(20_000.0);
+        options.frequency_ratio = Some(100.0);
+        let engine = Engine::load(&options).unwrap();
+
+        // Unknown word: the absolute floor applies, not the ratio
+        let decision = engine.correct_token("hellp");
+        assert_eq!(decision.reason, Reason::BelowThreshold);
+        assert_eq!(decision.best_rejected.unwrap().required_frequency, 20_000.0);
+
+        // Known word: 100 x 50 = 5000, and the floor doesn't apply
+        let decision = engine.correct_token("helo");
+        assert_eq!(decision.reason, Reason::Corrected);
+        assert_eq!(decision.output, "hello");
+
+        options.min_suggestion_frequency = Some(1_000.0);
+        options.frequency_ratio = Some(300.0);
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.correct_token("hellp").output, "hello");
+        // 300 x 50 = 15000 is out of reach, so the known word stays
+        assert_eq!(engine.correct_token("helo").reason, Reason::Exact);
+
+        // Unset options fall back to frequency_threshold
+        options.min_suggestion_frequency = None;
+        options.frequency_threshold = 20_000.0;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.correct_token("hellp").reason, Reason::BelowThreshold);
+        assert_eq!(engine.correct_token("helo").reason, Reason::Exact);
+
+        options.frequency_ratio = Some(f64::NAN);
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid("frequency_ratio must be finite (not NaN or Infinity)".into()))
+        );
+    }
+
+    #[test]
+    fn test_per_call_thresholds() {
+        let engine = engine("per_call_thresholds", |_| {});
+        let loaded = engine.thresholds();
+        let aggressive = Thresholds { min_suggestion_frequency: 1.0, frequency_ratio: 1.0, ..loaded };
+        let conservative = Thresholds { min_suggestion_frequency: 100.0, frequency_ratio: 10.0, ..loaded };
+
+        assert_eq!(engine.correct_token_with("rore", None, aggressive).output, "rare");
+        assert_eq!(engine.correct_token_with("rore", None, conservative).reason, Reason::BelowThreshold);
+        assert_eq!(engine.correct_last_token("rore", LastToken::Normal, aggressive).output, "rare");
+        assert_eq!(engine.correct_last_token("rar", LastToken::Complete, aggressive).output, "rare");
+        assert_eq!(engine.correct_last_token("rar", LastToken::Complete, conservative).reason, Reason::NoCandidates);
+
+        // The loaded thresholds still decide plain calls
+        assert_eq!(engine.thresholds(), loaded);
+        assert_eq!(engine.correct_token("rore").reason, Reason::BelowThreshold);
+
+        let invalid = Thresholds { min_suggestion_frequency: 1.0, frequency_ratio: -2.0, ..loaded };
+        assert_eq!(invalid.validate(), Err("frequency_ratio must be non-negative, got: -2".into()));
+    }
+
+    #[test]
+    fn test_explain() {
+        let engine = engine("explain", |options| {
+            options.protected_prefixes = vec!["wor".into()];
+            options.protected_patterns = vec![PatternSpec::new("^cdk\\d+$")];
+            options.frequency_threshold = 100.0;
+        });
+
+        let explanation = engine.explain("(Rore),", true);
+        assert_eq!(explanation.term, "Rore");
+        assert_eq!(explanation.normalized, "rore");
+        assert_eq!(explanation.frequency, None);
+        assert_eq!(explanation.guard, None);
+        assert_eq!(explanation.candidates.iter().map(|c| c.term.as_str()).collect::<Vec<_>>(), ["rare"]);
+        assert_eq!(explanation.required_frequency, 100.0);
+        assert_eq!(explanation.decision.reason, Reason::BelowThreshold);
+
+        // Known words are held to the ratio over their own frequency
+        let explanation = engine.explain("help", true);
+        assert_eq!(explanation.frequency, Some(3000));
+        assert_eq!(explanation.required_frequency, 300_000.0);
+        assert_eq!(explanation.decision.reason, Reason::Exact);
+
+        let explanation = engine.explain("Wrold", true);
+        assert_eq!(explanation.guard, None);
+        let explanation = engine.explain("Worl", true);
+        let guard = explanation.guard.as_ref().unwrap();
+        assert_eq!((guard.kind, guard.matched.as_str(), guard.index), (GuardKind::Prefix, "wor", None));
+        assert_eq!(explanation.decision.guard.as_ref(), Some(guard));
+        assert_eq!(explanation.decision.reason, Reason::Protected);
+        assert_eq!(explanation.decision.output, "Worl");
+        assert_eq!(engine.guards.hits(GuardKind::Prefix), 0);
+        let guard = engine.explain("CDK10", true).guard.unwrap();
+        assert_eq!((guard.kind, guard.matched.as_str()), (GuardKind::Pattern, "^cdk\\d+$"));
+
+        // Unguarded, the token is corrected as if nothing protected it
+        let explanation = engine.explain("Worl", false);
+        assert_eq!(explanation.guard, None);
+        assert_eq!(explanation.decision.output, "world");
+        assert_eq!(engine.guards.hits(GuardKind::Prefix), 0);
+    }
+
+    #[test]
+    fn test_guard_match_reason() {
+        let protected = fixture("match_reason_protected", "New York\nbrca1\n");
+        let engine = engine("match_reason", |options| {
+            options.protected_path = Some(protected);
+            options.protected_patterns = vec![
+                PatternSpec::new(r"^tp\d+$"),
+                PatternSpec { name: Some("gene_ids".into()), ..PatternSpec::new(r"^cdk\d+$") },
+            ];
+        });
+
+        let decision = engine.correct_token("cdk10");
+        let guard = decision.guard.unwrap();
+        assert_eq!((guard.kind, guard.index, guard.name.as_deref()), (GuardKind::Pattern, Some(1), Some("gene_ids")));
+        let guard = engine.correct_token("tp53").guard.unwrap();
+        assert_eq!((guard.index, guard.name), (Some(0), None));
+
+        let form = |word: &str| engine.correct_token(word).guard.and_then(|guard| guard.form);
+        assert_eq!(form("brca1"), Some(TermForm::Literal));
+        assert_eq!(form("BRCA1"), Some(TermForm::Lowercase));
+        assert_eq!(form("NewYork"), Some(TermForm::Lowercase));
+        assert_eq!(form("helo"), None);
+        assert_eq!(engine.guards.hits(GuardKind::Set), 3);
+        assert_eq!(engine.correct_token("helo").guard, None);
+    }
+
+    #[test]
+    fn test_correct_token_protected() {
+        let engine = engine("protected", |options| options.protected_prefixes = vec!["hel".into()]);
+
+        let decision = engine.correct_token("Helo");
+        assert_eq!(decision.reason, Reason::Protected);
+        assert_eq!(decision.output, "Helo");
+        assert_eq!(decision.guard.map(|guard| guard.matched), Some("hel".to_string()));
+    }
+
+    #[test]
+    fn test_case_sensitive_prefixes() {
+        let engine = engine("case_sensitive_prefixes", |options| {
+            options.protected_prefixes = vec!["Wor".into()];
+            options.protected_case_sensitive = true;
+        });
+
+        let decision = engine.correct_token("Worlf");
+        assert_eq!((decision.output.as_str(), decision.reason), ("Worlf", Reason::Protected));
+        assert_eq!(decision.guard.map(|guard| guard.matched), Some("Wor".to_string()));
+        assert_eq!(engine.correct_token("worlf").output, "world");
+        assert_eq!(engine.correct_token("WORLF").reason, Reason::Corrected);
+    }
+
+    #[test]
+    fn test_protected_case_folding() {
+        let protected = fixture("folding_protected", "straße\nistanbul\nΟΔΟΣ\n");
+        let engine = engine("folding", |options| options.protected_path = Some(protected));
+
+        for word in ["STRASSE", "Strasse", "STRA\u{1e9e}E", "stra\u{17f}\u{17f}e", "οδοσ", "Οδος", "ISTANBUL"] {
+            assert_eq!(engine.correct_token(word).reason, Reason::Protected, "{}", word);
+        }
+        // Full folding without Turkish locale rules: neither dotted nor dotless i is plain i
+        for word in ["\u{130}STANBUL", "\u{131}stanbul"] {
+            assert_ne!(engine.correct_token(word).reason, Reason::Protected, "{}", word);
+        }
+        assert_eq!(crate::guards::fold_case("\u{130}\u{131}\u{fb06}"), "i\u{307}\u{131}st");
+    }
+
+    #[test]
+    fn test_protected_canonical_forms() {
+        let protected = fixture("canonical_protected", "nacl\tNaCl\nmrna\tmRNA\nwrld\n");
+        let mut engine = engine("canonical", |options| options.protected_path = Some(protected));
+
+        let output = |word: &str| engine.correct_token(word).output;
+        assert_eq!(output("nacl"), "NaCl");
+        assert_eq!(output("NACL"), "NaCl");
+        assert_eq!(output("NaCl"), "NaCl");
+        assert_eq!(output("(mrna),"), "(mRNA),");
+        assert_eq!(engine.correct_token("nacl").reason, Reason::Protected);
+        assert_eq!(output("wrld"), "wrld");
+        assert_eq!(output("helo"), "hello");
+        assert_eq!(engine.explain("Nacl", true).decision.output, "NaCl");
+
+        assert!(engine.unprotect("NaCl"));
+        assert_eq!(engine.correct_token("nacl").output, "nacl");
+        engine.protect("nacl").unwrap();
+        assert_eq!(engine.correct_token("NACL").output, "NACL");
+    }
+
+    #[test]
+    fn test_last_token_policies() {
+        let engine = engine("last_token", |_| {});
+
+        assert_eq!(engine.correct_last_token("wor", LastToken::Skip, engine.thresholds()).reason, Reason::Skipped);
+
+        let completed = engine.correct_last_token("wor", LastToken::Complete, engine.thresholds());
+        assert_eq!(completed.reason, Reason::Completed);
+        assert_eq!(completed.output, "world");
+    }
+
+    #[test]
+    fn test_same_shape() {
+        assert!(same_shape("b12", "b13"));
+        assert!(!same_shape("b12", "bid"));
+        assert!(!same_shape("hello", "h3llo"));
+        assert!(same_shape("mcdonalds", "McDonald's"));
+        assert!(!same_shape("e-mail", "email"));
+        assert!(!same_shape("don\u{2019}t", "dont"));
+    }
+
+    #[test]
+    fn test_structural_match() {
+        let path = fixture("structural", "hello\t1000\nh3llo\t50000\nbid\t9000\nb13\t100\n");
+        let mut options = LoadOptions::new(&path);
+        let engine = Engine::load(&options).unwrap();
+
+        assert_eq!(engine.correct_token("hallo").output, "hello");
+        assert_eq!(engine.correct_token("b12").output, "b13");
+        assert_eq!(engine.correct_token("bi1").reason, Reason::NoCandidates);
+
+        options.structural_match = false;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.correct_token("hallo").output, "h3llo");
+    }
+
+    #[test]
+    fn test_query_expanded_strategy_matches_full() {
+        let path = fixture(
+            "query_expanded",
+            "hello\t10000\nhelp\t3000\nhelm\t900\nworld\t8000\nword\t6000\nwork\t5000\nwould\t4000\n",
+        );
+        let mut options = LoadOptions::new(&path);
+        options.edit_distance = 2;
+        let full = Engine::load(&options).unwrap();
+        options.index_strategy = IndexStrategy::QueryExpanded;
+        let expanded = Engine::load(&options).unwrap();
+
+        assert_eq!(full.index_strategy(), IndexStrategy::Full);
+        assert_eq!(expanded.index_strategy(), IndexStrategy::QueryExpanded);
+        for word in ["helo", "hlp", "wrold", "wrk", "wuld", "hello", "xyz", "wordl"] {
+            assert_eq!(expanded.correct_token(word).output, full.correct_token(word).output, "{}", word);
+        }
+
+        options.edit_distance = 1;
+        assert_eq!(Engine::load(&options).unwrap().index_strategy(), IndexStrategy::Full);
+    }
+
+    #[test]
+    fn test_layers_survive_base_reload() {
+        let first = fixture("layers_first", "hello\t10000\nhelp\t3000\n");
+        let second = fixture("layers_second", "hello\t500\nworld\t8000\n");
+        let mut previous = Engine::load(&LoadOptions::new(&first)).unwrap();
+        previous.add_word("hello", 40).unwrap();
+        previous.symspell.add_to_layer(Layer::Trained, "helpp", "helpp", 2);
+
+        let mut reloaded = Engine::load(&LoadOptions::new(&second)).unwrap();
+        reloaded.carry_layers_from(&previous);
+        assert_eq!(reloaded.symspell().layers("hello"), Some([500, 40, 0]));
+        assert_eq!(reloaded.symspell().get_frequency("hello"), Some(540));
+        assert_eq!(reloaded.symspell().layers("helpp"), Some([0, 0, 2]));
+        assert_eq!(reloaded.symspell().layers("help"), None);
+
+        let mut options = LoadOptions::new(&second);
+        options.frequency_combine = Combine::Max;
+        let mut max = Engine::load(&options).unwrap();
+        max.carry_layers_from(&previous);
+        assert_eq!(max.symspell().get_frequency("hello"), Some(500));
+    }
+
+    #[test]
+    fn test_user_dictionary_outranks_shared_words() {
+        let user = fixture("user_dictionary", "# tenant terms\nhelot\t12\nhelp\n");
+        let shared = engine("user_dictionary_shared", |_| {});
+        let tenant = engine("user_dictionary_tenant", |options| options.user_dictionary_path = Some(user.clone()));
+
+        // "helot" (12) and "hello" (10000) are both one edit from "hellot"
+        assert_eq!(shared.correct_token("hellot").output, "hello");
+        assert_eq!(tenant.correct_token("hellot").output, "helot");
+        assert_eq!(tenant.correct_token("helo").output, "help");
+        let ranked: Vec<(String, bool)> = tenant.suggest("helo", 3).into_iter().map(|s| (s.term, s.user)).collect();
+        assert_eq!(ranked, [("help".to_string(), true), ("helot".to_string(), true), ("hello".to_string(), false)]);
+        assert_eq!(tenant.symspell().layers("helot"), Some([0, 12, 0]));
+        assert_eq!(tenant.symspell().layers("help"), Some([3000, 1, 0]));
+
+        // Distance still comes first, and frequency decides between two user words
+        assert_eq!(tenant.correct_token("helpp").output, "help");
+        assert_eq!(tenant.correct_token("heloot").output, "helot");
+        assert_eq!(tenant.correct_token("wrld").output, "world");
+
+        // Boosts re-rank within the user words, never past them
+        let boosted = engine("user_dictionary_boosted", |options| {
+            options.user_dictionary_path = Some(user.clone());
+            options.boosts.push(BoostSpec { source: BoostSource::Pattern(PatternSpec::new("^hello$")), multiplier: 100.0 });
+        });
+        assert_eq!(boosted.correct_token("helo").output, "help");
+
+        let malformed = fixture("user_dictionary_malformed", "helot\t12\ntwo words\t3\n");
+        let mut options = LoadOptions::new(&fixture("user_dictionary_base", "hello\t10000\n"));
+        options.user_dictionary_path = Some(malformed);
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("User dictionary line 2 is malformed: expected a single word".into()))
+        );
+    }
+
+    #[test]
+    fn test_export_user_dictionary() {
+        let user = fixture("export_user_source", "helot\t12\nhelp\n");
+        let exported = std::env::temp_dir().join(format!("spellkit-engine-{}-export-user.tsv", std::process::id()));
+        let exported = exported.to_string_lossy().into_owned();
+        let mut previous = engine("export_user", |options| options.user_dictionary_path = Some(user.clone()));
+        previous.add_word("Kubectl", 5).unwrap();
+
+        previous.export_user_dictionary(&exported).unwrap();
+        assert_eq!(std::fs::read_to_string(&exported).unwrap(), "helot\t12\nKubectl\t5\nhelp\t1\n");
+
+        // Reloading with the same user dictionary doesn't count its words twice
+        let mut reloaded = engine("export_user", |options| options.user_dictionary_path = Some(user.clone()));
+        reloaded.carry_layers_from(&previous);
+        assert_eq!(reloaded.symspell().layers("helot"), Some([0, 12, 0]));
+        assert_eq!(reloaded.symspell().layers("kubectl"), Some([0, 5, 0]));
+        previous.symspell.add_to_layer(Layer::User, "helot", "helot", 3);
+        reloaded.carry_layers_from(&previous);
+        assert_eq!(reloaded.symspell().layers("helot"), Some([0, 15, 0]));
+
+        let restored = engine("export_user_restored", |options| options.user_dictionary_path = Some(exported.clone()));
+        assert_eq!(restored.symspell().user_entries(), [("helot", 12), ("Kubectl", 5), ("help", 1)]);
+        std::fs::remove_file(&exported).unwrap();
+    }
+
+    #[test]
+    fn test_denylist_blocks_candidates_but_not_input() {
+        let denylist = fixture("denylist", "# never offered\nHELLO\n");
+        let open = engine("denylist_open", |_| {});
+        let denied = engine("denylist_denied", |options| options.denylist_path = Some(denylist.clone()));
+        assert_eq!(denied.denylist.len(), 1);
+
+        assert_eq!(open.correct_token("helo").output, "hello");
+        assert_eq!(denied.correct_token("helo").output, "help");
+        assert!(denied.suggest("helo", 5).iter().all(|s| s.term != "hello"));
+        assert_eq!(denied.suggest("helo", 1).first().map(|s| s.term.as_str()), Some("help"));
+        assert_eq!(denied.complete_token("hel").output, "help");
+        assert!(denied.explain("helo", true).candidates.iter().all(|s| s.term != "hello"));
+
+        // Typing the word itself is neither corrected nor flagged
+        let typed = denied.correct_token("Hello");
+        assert_eq!((typed.output.as_str(), typed.reason), ("hello", Reason::Exact));
+        assert_eq!(denied.suggest("hello", 5).first().map(|s| (s.term.as_str(), s.distance)), Some(("hello", 0)));
+        assert!(denied.suggest_within("hello", 5, 1, true).is_empty());
+
+        assert_ne!(open.behavior_material(), denied.behavior_material());
+    }
+
+    #[test]
+    fn test_max_suggestions_sets_the_candidate_pool() {
+        // Five rare user words outrank "cat" at distance 1 from "bat", and all fall below the
+        // threshold, so only a pool of six reaches it
+        let user = fixture("max_suggestions_user", "bad\t2\nbag\t2\nban\t2\nbar\t2\nbay\t2\n");
+        let path = fixture("max_suggestions", "cat\t5000\nhello\t10000\n");
+        let load = |max_suggestions: usize| {
+            let mut options = LoadOptions::new(&path);
+            options.user_dictionary_path = Some(user.clone());
+            options.max_suggestions = max_suggestions;
+            Engine::load(&options)
+        };
+
+        let default = load(DEFAULT_MAX_SUGGESTIONS).unwrap();
+        assert_eq!(default.max_suggestions(), 5);
+        assert_eq!(default.suggest_within("bat", 10, 1, false).len(), 6);
+        let decision = default.correct_token("bat");
+        assert_eq!((decision.output.as_str(), decision.reason), ("bat", Reason::BelowThreshold));
+
+        let wide = load(6).unwrap();
+        assert_eq!(wide.correct_token("bat").output, "cat");
+        let pool = Thresholds { max_suggestions: 6, ..default.thresholds() };
+        assert_eq!(default.correct_token_with("bat", None, pool).output, "cat");
+
+        assert!(default.suggest_within("bat", 0, 1, false).is_empty());
+        assert_eq!(load(0).err(), Some(LoadError::Invalid("max_suggestions must be at least 1".into())));
+        assert_ne!(default.behavior_material(), wide.behavior_material());
+    }
+
+    #[test]
+    fn test_skip_tokens_with_digits_and_short_words() {
+        let path = fixture("skip_tokens", "at\t5000\nbid\t1000\nhello\t10000\n");
+        let mut options = LoadOptions::new(&path);
+        // Without the shape filter, digits alone don't keep "a1" from becoming "at". Skipping
+        // comes before guards, so these never report Protected
+        options.structural_match = false;
+        options.protected_patterns = vec![PatternSpec::new(r"^\d")];
+        let open = Engine::load(&options).unwrap();
+        options.skip_tokens_with_digits = true;
+        options.min_word_length = 3;
+        let skipping = Engine::load(&options).unwrap();
+
+        assert_eq!(open.correct_token("a1").output, "at");
+        assert_eq!(open.correct_token("bi2").output, "bid");
+        assert_eq!(open.correct_token("aa").output, "at");
+        assert_eq!(open.correct_token("t").output, "at");
+        assert_eq!(open.correct_token("2024").reason, Reason::Protected);
+
+        for (word, reason) in [("2024", Reason::HasDigits), ("a1", Reason::HasDigits), ("bi2", Reason::HasDigits), ("aa", Reason::TooShort), ("t", Reason::TooShort)] {
+            let decision = skipping.correct_token(word);
+            assert_eq!((decision.output.as_str(), decision.reason), (word, reason), "{}", word);
+        }
+        // Counted as the first guard tier, and the pattern never sees "2024"
+        assert_eq!(skipping.guards.hits(GuardKind::Shape), 5);
+        assert_eq!(skipping.guards.hits(GuardKind::Pattern), 0);
+        assert_eq!((open.guards.hits(GuardKind::Shape), open.guards.hits(GuardKind::Pattern)), (0, 1));
+        let wrapped = skipping.correct_token("(aa),");
+        assert_eq!((wrapped.output.as_str(), wrapped.reason), ("(aa),", Reason::TooShort));
+        assert_eq!(skipping.correct_token("helo").output, "hello");
+        assert_eq!(skipping.correct_token("at").reason, Reason::TooShort);
+        assert_eq!(skipping.correct_phrase("a1 helo").output, "a1 hello");
+
+        let per_call = Thresholds { skip_tokens_with_digits: true, min_word_length: 3, ..open.thresholds() };
+        assert_eq!(open.correct_token_with("a1", None, per_call).reason, Reason::HasDigits);
+        assert_eq!(open.correct_token_with("aa", None, per_call).reason, Reason::TooShort);
+        let relaxed = Thresholds { skip_tokens_with_digits: false, min_word_length: 0, ..skipping.thresholds() };
+        assert_eq!(skipping.correct_token_with("aa", None, relaxed).output, "at");
+
+        assert_ne!(open.behavior_material(), skipping.behavior_material());
+    }
+
+    #[test]
+    fn test_skip_all_caps() {
+        let path = fixture("skip_all_caps", "nasal\t3000\nhttps\t2000\nhello\t10000\n");
+        let mut options = LoadOptions::new(&path);
+        options.protected_path = Some(fixture("skip_all_caps_protected", "js\tJavaScript\n"));
+        let open = Engine::load(&options).unwrap();
+        options.skip_all_caps = true;
+        let skipping = Engine::load(&options).unwrap();
+
+        assert_eq!(open.correct_token("NASA").output, "nasal");
+        assert_eq!(OutputMode::PreserveCase.apply("NASA", open.correct_token("NASA").output, open.normalizer()), "NASAL");
+
+        for word in ["NASA", "HTTP", "(NASA),", "\u{c9}T\u{c9}"] {
+            let decision = skipping.correct_token(word);
+            assert_eq!((decision.output.as_str(), decision.reason), (word, Reason::AllCaps), "{}", word);
+        }
+        assert_eq!(OutputMode::PreserveCase.apply("NASA", skipping.correct_token("NASA").output, skipping.normalizer()), "NASA");
+        let mixed = skipping.correct_token("Nasa");
+        assert_eq!((mixed.output.as_str(), mixed.reason), ("nasal", Reason::Corrected));
+        assert_ne!(skipping.correct_token("N").reason, Reason::AllCaps);
+        assert_eq!(skipping.correct_phrase("NASA helo").output, "NASA hello");
+
+        // Guards come first, so a protected term still gets its canonical form
+        let guarded = skipping.correct_token("JS");
+        assert_eq!((guarded.output.as_str(), guarded.reason), ("JavaScript", Reason::Protected));
+
+        let per_call = Thresholds { skip_all_caps: true, ..open.thresholds() };
+        assert_eq!(open.correct_token_with("HTTP", None, per_call).reason, Reason::AllCaps);
+        assert_eq!(open.correct_token("HTTP").output, "https");
+        assert_ne!(open.behavior_material(), skipping.behavior_material());
+    }
+
+    #[test]
+    fn test_add_and_remove_words() {
+        let mut engine = engine("runtime_words", |_| {});
+        let before = engine.clone();
+
+        assert_eq!(engine.correct_token("kubernets").output, "kubernets");
+        assert!(engine.add_word("Kubernetes", 500).unwrap());
+        assert!(!engine.add_word("kubernetes", 100).unwrap());
+        assert_eq!(engine.correct_token("kubernets").output, "Kubernetes");
+        assert_eq!(engine.symspell().layers("kubernetes"), Some([0, 600, 0]));
+        assert_eq!(engine.dictionary_size(), 5);
+        assert!(engine.add_word("two words", 1).is_err());
+
+        assert!(engine.remove_word("HELLO"));
+        assert!(!engine.remove_word("hello"));
+        assert_eq!(engine.correct_token("helo").output, "help");
+        assert_eq!(engine.dictionary_size(), 4);
+        assert_eq!(engine.revision(), 3);
+
+        // Edits go to this engine only, not to clones taken before them
+        assert_eq!(before.correct_token("kubernets").output, "kubernets");
+        assert_eq!(before.correct_token("helo").output, "hello");
+        assert_eq!(before.revision(), 0);
+    }
+
+    #[test]
+    fn test_protect_and_unprotect() {
+        let mut engine = engine("runtime_protect", |_| {});
+        let before = engine.clone();
+        let digest = engine.behavior_material();
+
+        assert_eq!(engine.correct_token("Helo").output, "hello");
+        engine.protect("HELO").unwrap();
+        assert!(engine.is_protected("helo"));
+        assert!(engine.is_protected("(Helo),"));
+        assert!(!before.is_protected("helo"));
+        assert_eq!(engine.correct_token("Helo").reason, Reason::Protected);
+        assert_eq!(engine.guards.hits(GuardKind::Set), 1);
+        assert_ne!(engine.behavior_material(), digest);
+        assert!(engine.protect(" ").is_err());
+
+        assert!(engine.unprotect("helo"));
+        assert!(!engine.unprotect("helo"));
+        assert!(!engine.is_protected("helo"));
+        assert_eq!(engine.correct_token("Helo").output, "hello");
+        assert_eq!(engine.behavior_material(), digest);
+        assert_eq!(engine.revision(), 2);
+    }
+
+    #[test]
+    fn test_load_warmup_reports_before_returning() {
+        let path = fixture("warmup", "hello\t10000\nhelp\t3000\nworld\t8000\n");
+        let mut options = LoadOptions::new(&path);
+        assert!(Engine::load(&options).unwrap().load_stats().warmup.is_none());
+
+        options.warmup = Some(50);
+        let mut phases = Vec::new();
+        let mut progress = Progress::new(Some(|phase: &str, processed: usize, total: usize| {
+            phases.push((phase.to_string(), processed, total));
+            Ok::<(), LoadError>(())
+        }));
+        let engine = Engine::load_with_progress(&options, &mut progress).unwrap();
+
+        let warmup = engine.load_stats().warmup.unwrap();
+        assert_eq!(warmup.queries, 100);
+        assert!(warmup.entries_touched > 0);
+        assert_eq!(phases[phases.len() - 2..], [("warmup".to_string(), 0, 50), ("warmup".to_string(), 50, 50)]);
+    }
+
+    #[test]
+    fn test_behavior_material_ignores_file_order() {
+        let forward = fixture("behavior_forward", "hello\t10000\nhelp\t3000\nworld\t8000\n");
+        let backward = fixture("behavior_backward", "world\t8000\nhelp\t3000\nhello\t10000\n");
+        let material = |path: &str, configure: fn(&mut LoadOptions)| {
+            let mut options = LoadOptions::new(path);
+            options.protected_prefixes = vec!["CDK".into(), "cdk1".into()];
+            configure(&mut options);
+            Engine::load(&options).unwrap().behavior_material()
+        };
+
+        let base = material(&forward, |_| {});
+        assert!(base.starts_with("spellkit-behavior\tv6\n"));
+        assert!(base.contains("guards.prefix\tcdk\n"));
+        assert!(!base.contains("cdk1"));
+        assert_eq!(material(&backward, |_| {}), base);
+        assert_eq!(material(&forward, |options| options.latency_stats = false), base);
+
+        assert_ne!(material(&forward, |options| options.frequency_threshold = 20.0), base);
+        assert_ne!(material(&forward, |options| options.frequency_ratio = Some(20.0)), base);
+        assert_ne!(material(&forward, |options| options.protected_prefixes.push("xy".into())), base);
+        assert_ne!(material(&forward, |options| options.normalizer.strip_accents = true), base);
+        assert_ne!(material(&forward, |options| options.normalizer.form = NormalForm::Nfc), base);
+        let extended = fixture("behavior_extended", "hello\t10000\nhelp\t3000\nworld\t8000\nword\t5\n");
+        assert_ne!(material(&extended, |_| {}), base);
+    }
+
+    #[test]
+    fn test_behavior_digest_hashes_material() {
+        let forward = fixture("digest_forward", "hello\t10000\nhelp\t3000\n");
+        let backward = fixture("digest_backward", "help\t3000\nhello\t10000\n");
+        let digest = |path: &str| Engine::load(&LoadOptions::new(path)).unwrap().behavior_digest();
+
+        let base = digest(&forward);
+        assert_eq!(base.len(), 64);
+        assert!(base.bytes().all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase()));
+        assert_eq!(digest(&backward), base);
+        assert_ne!(digest(&fixture("digest_other", "hello\t10000\n")), base);
+    }
+
+    #[test]
+    fn test_correct_many_keeps_order_across_threads() {
+        let engine = engine("correct_many", |_| {});
+        let words: Vec<String> = ["helo", "world", "wrld", "zzzzzz", "hlp"]
+            .iter()
+            .cycle()
+            .take(23)
+            .map(|w| w.to_string())
+            .collect();
+
+        let serial: Vec<String> = engine.correct_many(&words, 1).into_iter().map(|d| d.output).collect();
+        assert_eq!(serial[..5], ["hello", "world", "world", "zzzzzz", "help"]);
+        for threads in [2, 4, 64] {
+            let parallel: Vec<String> = engine.correct_many(&words, threads).into_iter().map(|d| d.output).collect();
+            assert_eq!(parallel, serial);
+            assert_eq!(parallel_map(&words, threads, |i, _| i), (0..words.len()).collect::<Vec<_>>());
+        }
+        assert!(engine.correct_many(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_options() {
+        let mut options = LoadOptions::new("/nonexistent/dictionary.tsv");
+        for edit_distance in [0, 3] {
+            options.edit_distance = edit_distance;
+            assert_eq!(
+                Engine::load(&options).err(),
+                Some(LoadError::Invalid(format!("edit_distance must be 1 or 2, got: {}", edit_distance)))
+            );
+        }
+
+        options.edit_distance = 2;
+        options.prefix_length = Some(2);
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid("prefix_length must be greater than edit_distance (2), got: 2".into()))
+        );
+
+        options.edit_distance = 1;
+        assert!(matches!(Engine::load(&options), Err(LoadError::Io(_))));
+    }
+
+    #[test]
+    fn test_load_stats() {
+        let path = fixture("stats", "hello\t10000\nbad line here\nhello\t5\nworld\tmany\n");
+        let engine = Engine::load(&LoadOptions::new(&path)).unwrap();
+
+        let stats = engine.load_stats();
+        assert_eq!(stats.dictionary_size, 1);
+        assert_eq!(stats.skipped_duplicates, 1);
+        assert_eq!(stats.skipped_invalid_freq, 1);
+        assert_eq!(engine.source().kind, "file");
+    }
+
+    #[test]
+    fn test_load_wordlist() {
+        let path = fixture("wordlist", "A\na's\naardvark\n\nabacus\nNew York\nhello\n");
+        let mut options = LoadOptions::new(&path);
+
+        // As counts, every line lacks a frequency
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 0);
+        assert_eq!(engine.load_stats().skipped_malformed, 6);
+
+        options.format = DictionaryFormat::Wordlist;
+        options.default_frequency = 5;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 5);
+        assert_eq!(engine.load_stats().skipped_malformed, 1);
+        assert_eq!(engine.load_stats().skipped_multiword, 1);
+        assert_eq!(engine.load_stats().format, Some(DictionaryFormat::Wordlist));
+        assert!(engine.symspell().contains("aardvark"));
+        assert_eq!(engine.symspell().get_frequency("hello"), Some(5));
+
+        options.format = DictionaryFormat::Auto;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 5);
+        assert_eq!(engine.load_stats().format, Some(DictionaryFormat::Wordlist));
+
+        options.default_frequency = 0;
+        assert_eq!(Engine::load(&options).err(), Some(LoadError::Invalid("default_frequency must be at least 1".into())));
+    }
+
+    #[test]
+    fn test_load_separator_and_columns() {
+        let path = fixture("columns", "count,term\n10000,hello\n8000,world,extra\n3000\n");
+        let mut options = LoadOptions::new(&path);
+        options.separator = Some(",".into());
+        options.term_index = 1;
+        options.count_index = 0;
+
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 2);
+        assert_eq!(engine.symspell().get_frequency("world"), Some(8000));
+        assert_eq!(engine.load_stats().skipped_invalid_freq, 1);
+        assert_eq!(engine.load_stats().skipped_malformed, 1);
+
+        // Without a separator the columns are still swapped, but extra ones aren't allowed
+        let path = fixture("columns-whitespace", "10000 hello\n8000\tworld\n3000 help extra\n");
+        options.dictionary_path = path;
+        options.separator = None;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 2);
+        assert_eq!(engine.load_stats().skipped_malformed, 1);
+
+        options.count_index = 1;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid("term_index and count_index must be different columns, both are 1".into()))
+        );
+    }
+
+    fn gzip_fixture(name: &str, content: &[u8]) -> String {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        let path = std::env::temp_dir().join(format!("spellkit-engine-{}-{}", std::process::id(), name));
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_load_gzip() {
+        let content = "hello\t10000\nhelp\t3000\nworld\t8000\n".repeat(200);
+        let path = gzip_fixture("dictionary.tsv.gz", content.as_bytes());
+        let protected = gzip_fixture("protected.txt.gz", b"CDK10\n");
+        let mut options = LoadOptions::new(&path);
+        options.protected_path = Some(protected);
+
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 3);
+        assert_eq!(engine.load_stats().skipped_duplicates, 597);
+        assert_eq!(engine.symspell().get_frequency("hello"), Some(10_000));
+        assert_eq!(engine.correct_token("helo").output, "hello");
+        assert_eq!(engine.correct_token("CDK10").reason, Reason::Protected);
+
+        // Read as plain text, the compressed bytes aren't a dictionary
+        options.compression = Compression::None;
+        options.protected_path = None;
+        assert!(matches!(Engine::load(&options), Err(LoadError::Io(_))));
+
+        // A forced gzip path needs no extension
+        let renamed = fixture("dictionary-gzip", "");
+        std::fs::copy(&path, &renamed).unwrap();
+        options.dictionary_path = renamed;
+        options.compression = Compression::Gzip;
+        assert_eq!(Engine::load(&options).unwrap().dictionary_size(), 3);
+    }
+
+    #[test]
+    fn test_load_corrupt_gzip() {
+        let path = gzip_fixture("corrupt.tsv.gz", "hello\t10000\nworld\t8000\n".repeat(100).as_bytes());
+        let mut bytes = std::fs::read(&path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle..middle + 8].fill(0xff);
+        std::fs::write(&path, &bytes).unwrap();
+
+        let error = Engine::load(&LoadOptions::new(&path)).err().unwrap();
+        assert!(
+            matches!(&error, LoadError::Io(message) if message.starts_with(&format!("Failed to decompress dictionary file '{}'", path))),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_on_duplicate() {
+        let path = fixture("duplicates", "hello\t100\nHELLO\t300\nworld\t50\nhello\t200\n");
+        let mut options = LoadOptions::new(&path);
+
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.symspell().get_frequency("hello"), Some(300));
+        assert_eq!(engine.symspell().canonical("hello"), Some("HELLO"));
+        assert_eq!(engine.load_stats().skipped_duplicates, 2);
+
+        options.on_duplicate = OnDuplicate::Sum;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.symspell().get_frequency("hello"), Some(600));
+        assert_eq!(engine.symspell().canonical("hello"), Some("HELLO"));
+
+        options.on_duplicate = OnDuplicate::Error;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("Dictionary term \"HELLO\" duplicates \"hello\", loaded earlier (on_duplicate: error)".into()))
+        );
+    }
+
+    #[test]
+    fn test_load_entries() {
+        let entries = vec![
+            ("hello".to_string(), 1000),
+            ("Help".to_string(), 750),
+            (" world ".to_string(), 500),
+            ("hello".to_string(), 10),
+            ("New York".to_string(), 90),
+            (String::new(), 5),
+        ];
+        let mut options = LoadOptions::from_entries(entries);
+
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 3);
+        assert_eq!(engine.symspell().get_frequency("hello"), Some(1000));
+        assert_eq!(engine.correct_token("wrld").output, "world");
+        assert_eq!(engine.source().kind, "memory");
+        let stats = engine.load_stats();
+        assert_eq!((stats.skipped_duplicates, stats.skipped_multiword, stats.skipped_malformed), (1, 1, 1));
+
+        options.max_words = Some(2);
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Limit("Dictionary has more than max_words (2) entries; stopped at entry 3 of 6".into()))
+        );
+    }
+
+    #[test]
+    fn test_load_mixed_formats() {
+        let path = fixture("mixed", "hello\t100\nworld\nhelp\t50\n");
+        let mut options = LoadOptions::new(&path);
+        options.format = DictionaryFormat::Auto;
+
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.load_stats().format, Some(DictionaryFormat::Counts));
+        assert_eq!(engine.dictionary_size(), 2);
+        assert_eq!(engine.load_stats().skipped_malformed, 1);
+
+        options.strict = true;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse(
+                "Dictionary line 2 has no frequency column, but the file's format is counts (detected from line 1)".into()
+            ))
+        );
+
+        let path = fixture("mixed-wordlist", "hello\nworld 100\n");
+        options.dictionary_path = path;
+        options.format = DictionaryFormat::Wordlist;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("Dictionary line 2 has a frequency column, but the file's format is wordlist".into()))
+        );
+    }
+
+    #[test]
+    fn test_load_report_and_strict_lines() {
+        let path = fixture("report", "# generated 2024-01-01\nhello\t10000\n\nworld\tmany\n  # trailing note\nhelp\t3000\nHello\t50\nlone\n");
+        let mut options = LoadOptions::new(&path);
+
+        let engine = Engine::load(&options).unwrap();
+        let stats = engine.load_stats();
+        assert_eq!(stats.lines_read, 8);
+        assert_eq!(stats.comment_lines, 2);
+        assert_eq!(stats.dictionary_size, 2);
+        assert_eq!(stats.skipped_duplicates, 1);
+        assert_eq!((stats.skipped_malformed, stats.skipped_invalid_freq), (2, 1));
+        assert_eq!(stats.lines_skipped(), 5);
+        assert_eq!(stats.index_entries, engine.symspell().deletes_len());
+        assert!(stats.index_entries > 0);
+
+        // Comments and blank lines pass, the first bad line fails
+        options.strict = true;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("Dictionary line 4 is malformed: frequency \"many\" is not a non-negative integer".into()))
+        );
+
+        let path = fixture("report-columns", "# words\nhello\t10000\nhello world 5\n");
+        options.dictionary_path = path;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("Dictionary line 3 is malformed: expected exactly 2 columns, found 3".into()))
+        );
+    }
+
+    #[test]
+    fn test_estimated_bytes_scale_with_dictionary() {
+        let words = |count: usize| -> Vec<(String, u64)> {
+            (0..count)
+                .map(|i| {
+                    let word: String = [i / 676, i / 26 % 26, i % 26].iter().map(|&c| (b'a' + c as u8) as char).collect();
+                    (format!("{}word", word), 1000 - i as u64 % 1000)
+                })
+                .collect()
+        };
+        let small = Engine::load(&LoadOptions::from_entries(words(100))).unwrap();
+        let large = Engine::load(&LoadOptions::from_entries(words(2000))).unwrap();
+
+        for engine in [&small, &large] {
+            let stats = engine.load_stats();
+            assert_eq!(stats.estimated_bytes, engine.estimated_bytes());
+            assert_eq!(stats.delete_entries, engine.symspell().delete_entries());
+            assert!(stats.delete_entries >= stats.index_entries);
+            assert!(stats.estimated_bytes > stats.deletes_bytes);
+        }
+        assert!(large.load_stats().estimated_bytes > 10 * small.load_stats().estimated_bytes);
+        assert!(large.load_stats().delete_entries > 10 * small.load_stats().delete_entries);
+    }
+
+    #[test]
+    fn test_read_capped_line() {
+        let mut reader = std::io::BufReader::with_capacity(4, "short\r\nwaytoolongline\nlast".as_bytes());
+        let mut line = Vec::new();
+
+        assert_eq!(read_capped_line(&mut reader, &mut line, 8).unwrap(), Some(5));
+        assert_eq!(line, b"short");
+
+        assert_eq!(read_capped_line(&mut reader, &mut line, 8).unwrap(), Some(14));
+        assert_eq!(line, b"waytoolo");
+
+        assert_eq!(read_capped_line(&mut reader, &mut line, 8).unwrap(), Some(4));
+        assert_eq!(line, b"last");
+        assert_eq!(read_capped_line(&mut reader, &mut line, 8).unwrap(), None);
+    }
+
+    #[test]
+    fn test_load_skips_overlong_lines_and_terms() {
+        let blob = "x".repeat(5000);
+        let long_word = "y".repeat(101);
+        let content = format!("hello\t100\n{}\t5\n{}\t7\nworld\t50\n", blob, long_word);
+        let path = fixture("overlong", &content);
+
+        let engine = Engine::load(&LoadOptions::new(&path)).unwrap();
+        let stats = engine.load_stats();
+        assert_eq!(stats.dictionary_size, 2);
+        assert_eq!(stats.skipped_long_lines, 1);
+        assert_eq!(stats.skipped_long_words, 1);
+        assert_eq!(stats.skipped_malformed, 0);
+
+        let mut options = LoadOptions::new(&path);
+        options.strict = true;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("Dictionary line 2 is longer than max_line_length (1024 bytes)".into()))
+        );
+
+        // Under a looser line cap the blob is still an overlong term
+        options.max_line_length = 10_000;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Parse("Dictionary term on line 2 is longer than max_word_length (100 characters)".into()))
+        );
+    }
+
+    #[test]
+    fn test_load_size_limits() {
+        let path = fixture("limits", "hello\t100\nworld\t50\nhelp\t30\n");
+        let mut options = LoadOptions::new(&path);
+
+        options.max_dictionary_bytes = Some(10);
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Limit("Dictionary file is 27 bytes, over max_dictionary_bytes (10); nothing was read".into()))
+        );
+
+        options.max_dictionary_bytes = None;
+        options.max_words = Some(2);
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Limit(
+                "Dictionary has more than max_words (2) entries; stopped at line 3 after 27 of 27 bytes".into()
+            ))
+        );
+
+        options.max_words = None;
+        assert_eq!(Engine::load(&options).unwrap().load_stats().dictionary_size, 3);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let suggestions = |engine: &Engine, word: &str| -> Vec<(String, usize, u64)> {
+            engine.suggest(word, 10).into_iter().map(|s| (s.term, s.distance, s.frequency)).collect()
+        };
+        let footprint = |engine: &Engine| {
+            let stats = engine.load_stats();
+            (stats.dictionary_size, stats.index_entries, stats.delete_entries, stats.deletes_bytes, stats.estimated_bytes)
+        };
+        let path = fixture("export-source", "Hello\t50\nhello\t10000\nhelp\t3000\nworld\t8000\nrare\t8000\niPhone\t900\n");
+        let exported = std::env::temp_dir().join(format!("spellkit-engine-{}-export.tsv", std::process::id()));
+        let exported = exported.to_string_lossy().into_owned();
+        let mut options = LoadOptions::new(&exported);
+        options.strict = true;
+
+        let mut built = Engine::load(&LoadOptions::new(&path)).unwrap();
+        built.export_dictionary(&exported).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&exported).unwrap(),
+            "hello\t10000\nrare\t8000\nworld\t8000\nhelp\t3000\niPhone\t900\n"
+        );
+
+        let loaded = Engine::load(&options).unwrap();
+        assert_eq!(footprint(&loaded), footprint(&built));
+        for word in ["helo", "hello", "wrld", "hep", "rar", "iphone", "xyz"] {
+            assert_eq!(suggestions(&loaded, word), suggestions(&built, word), "{}", word);
+        }
+        assert_eq!(loaded.symspell().canonical("IPHONE"), Some("iPhone"));
+
+        // Runtime additions are exported with the rest
+        built.add_word("helo", 20).unwrap();
+        built.export_dictionary(&exported).unwrap();
+        let loaded = Engine::load(&options).unwrap();
+        assert_eq!(loaded.dictionary_size(), 6);
+        assert_eq!(suggestions(&loaded, "helo"), suggestions(&built, "helo"));
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        let suggestions = |engine: &Engine, word: &str| -> Vec<(String, usize, u64)> {
+            engine.suggest(word, 10).into_iter().map(|s| (s.term, s.distance, s.frequency)).collect()
+        };
+        let index = std::env::temp_dir().join(format!("spellkit-engine-{}-index.bin", std::process::id()));
+        let index = index.to_string_lossy().into_owned();
+
+        for compact in [false, true] {
+            let built = engine("index-source", |options| {
+                options.edit_distance = 2;
+                options.compact = compact;
+                options.frequency_scale = if compact { 10 } else { 1 };
+            });
+            built.save_index(&index).unwrap();
+
+            let mut options = LoadOptions::new("missing.tsv");
+            options.edit_distance = 2;
+            options.compact = compact;
+            options.frequency_scale = built.symspell().frequency_scale();
+            options.index_path = Some(index.clone());
+            let loaded = Engine::load(&options).unwrap();
+
+            for word in ["helo", "hello", "wrld", "hep", "rar", "xyz"] {
+                assert_eq!(suggestions(&loaded, word), suggestions(&built, word), "{}", word);
+            }
+            assert_eq!(loaded.load_stats().dictionary_size, 4);
+            assert_eq!(loaded.source().kind, "index");
+            assert_eq!(loaded.behavior_material(), built.behavior_material());
+        }
+
+        let mut options = LoadOptions::new("missing.tsv");
+        options.index_path = Some(index.clone());
+        options.edit_distance = 1;
+        options.compact = true;
+        options.frequency_scale = 10;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid(format!(
+                "Index file '{}' was built with edit_distance 2, but edit_distance 1 was requested; rebuild it with save_index",
+                index
+            )))
+        );
+
+        options.edit_distance = 2;
+        options.compact = false;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid(format!(
+                "Index file '{}' was built with compact storage (frequency_scale 10), but full storage was requested; rebuild it with save_index",
+                index
+            )))
+        );
+
+        options.compact = true;
+        options.normalizer.strip_accents = true;
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid(format!(
+                "Index file '{}' was built with strip_accents: false, but strip_accents: true was requested; rebuild it with save_index",
+                index
+            )))
+        );
+
+        // Only the settings that differ are named
+        options.normalizer = Normalizer { form: NormalForm::Nfc, lowercase: false, ..Normalizer::default() };
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid(format!(
+                "Index file '{}' was built with form: nfkd, lowercase: true, but form: nfc, lowercase: false was requested; rebuild it with save_index",
+                index
+            )))
+        );
+
+        // A dictionary file is not an index, and an index of another format version is stale
+        let bytes = std::fs::read(&index).unwrap();
+        options.index_path = Some(fixture("not-an-index", "hello\t10000\n"));
+        assert_eq!(
+            Engine::load(&options).err(),
+            Some(LoadError::Invalid(format!("'{}' is not a SpellKit index file", options.index_path.as_ref().unwrap())))
+        );
+
+        let mut stale = bytes.clone();
+        stale[INDEX_MAGIC.len()..INDEX_MAGIC.len() + 8].copy_from_slice(&99u64.to_le_bytes());
+        options.index_path = Some(index.clone());
+        std::fs::write(&index, &stale).unwrap();
+        assert!(matches!(Engine::load(&options), Err(LoadError::Invalid(message)) if message.contains("format version 99")));
+
+        std::fs::write(&index, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(Engine::load(&options), Err(LoadError::Invalid(message)) if message.contains("truncated or corrupt")));
+    }
+}
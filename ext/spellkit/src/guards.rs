@@ -1,75 +1,446 @@
-use hashbrown::HashSet;
-use regex::{Regex, RegexBuilder};
-use crate::symspell::SymSpell;
+use hashbrown::{HashMap, HashSet};
+use regex::{Regex, RegexBuilder, RegexSet};
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use crate::normalizer::Normalizer;
+
+// Guard types in evaluation order, cheapest first. A new guard type goes into this list
+// at its cost rank. Shape is the length and shape skips (skip_tokens_with_digits,
+// min_word_length): they depend on per-call thresholds, so the engine runs them itself before
+// asking the guards and reports their hits with `record_shape`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardKind {
+    Shape,
+    Prefix,
+    Set,
+    Pattern,
+}
+
+impl GuardKind {
+    pub const ORDER: [GuardKind; 4] = [GuardKind::Shape, GuardKind::Prefix, GuardKind::Set, GuardKind::Pattern];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GuardKind::Shape => "shape",
+            GuardKind::Prefix => "prefix",
+            GuardKind::Set => "set",
+            GuardKind::Pattern => "pattern",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// Unicode full case folding (CaseFolding.txt, statuses C and F) for guard storage and lookup.
+// Lowercasing alone isn't enough for terms whose cases don't round-trip: "STRASSE" lowercases
+// to "strasse" but "Straße" to "straße", and final sigma lowercases differently from medial.
+// The default folding isn't locale-aware, so Turkish "İ" folds to "i" plus a combining dot and
+// dotless "ı" folds to itself
+pub fn fold_case(s: &str) -> String {
+    caseless::default_case_fold_str(s)
+}
+
+// Character trie of protected prefixes, case-folded unless case_sensitive; node 0 is the root
+#[derive(Debug, Clone)]
+struct PrefixTrie {
+    children: Vec<HashMap<char, usize>>,
+    terminal: Vec<bool>,
+    case_sensitive: bool,
+}
+
+impl PrefixTrie {
+    fn new(case_sensitive: bool) -> Self {
+        Self {
+            children: vec![HashMap::new()],
+            terminal: vec![false],
+            case_sensitive,
+        }
+    }
+
+    // How prefixes are stored and tokens walked: as written, or case-folded
+    fn key<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.case_sensitive {
+            Cow::Borrowed(s)
+        } else {
+            Cow::Owned(fold_case(s))
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.children.len() == 1
+    }
+
+    fn insert(&mut self, prefix: &str) {
+        let mut node = 0;
+        for c in self.key(prefix).chars() {
+            node = match self.children[node].get(&c) {
+                Some(&next) => next,
+                None => {
+                    self.children.push(HashMap::new());
+                    self.terminal.push(false);
+                    let next = self.children.len() - 1;
+                    self.children[node].insert(c, next);
+                    next
+                }
+            };
+        }
+        self.terminal[node] = true;
+    }
+
+    // The shortest inserted prefixes, sorted. A prefix extending another never decides a
+    // match, so it is left out
+    fn prefixes(&self) -> Vec<String> {
+        let mut found = Vec::new();
+        let mut stack = vec![(0, String::new())];
+        while let Some((node, path)) = stack.pop() {
+            if self.terminal[node] {
+                found.push(path);
+                continue;
+            }
+            for (&c, &next) in &self.children[node] {
+                let mut extended = path.clone();
+                extended.push(c);
+                stack.push((next, extended));
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    // The shortest inserted prefix of `word`, in its stored form
+    fn matching_prefix(&self, word: &str) -> Option<String> {
+        let key = self.key(word);
+        let mut node = 0;
+        for (i, c) in key.char_indices() {
+            node = *self.children[node].get(&c)?;
+            if self.terminal[node] {
+                return Some(key[..i + c.len_utf8()].to_string());
+            }
+        }
+        None
+    }
+}
+
+/// The guard that protects a token, for explaining a decision: the protected prefix or term as
+/// stored (case-folded unless the guards are case-sensitive), or the pattern as given
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardMatch {
+    pub kind: GuardKind,
+    pub matched: String,
+    /// Patterns only: position among the protected patterns, and the name given with it
+    pub index: Option<usize>,
+    pub name: Option<String>,
+    /// Protected terms only: which form of the token was in the set
+    pub form: Option<TermForm>,
+}
+
+/// How a token hit the protected set: as written, case-folded, or normalized (see
+/// `Normalizer`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermForm {
+    Literal,
+    Lowercase,
+    Normalized,
+}
+
+impl TermForm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TermForm::Literal => "literal",
+            TermForm::Lowercase => "lowercase",
+            TermForm::Normalized => "normalized",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Guards {
+    // Case-folded protected terms, or the literal terms when case_sensitive is set
     protected_set: HashSet<String>,
-    protected_patterns: Vec<Regex>,
+    // Stored forms of two-column protected terms (variant<TAB>canonical), to the form a
+    // protected match is rewritten to. Kept in step with protected_set by remove_protected
+    canonical: HashMap<String, String>,
+    case_sensitive: bool,
+    // Gives protected terms the normalized form tokens are checked in
+    normalizer: Normalizer,
+    // Sources with their flags inlined, compiled together into one RegexSet by compile_patterns.
+    // Whether a token is protected is a single pass over the set, however many patterns there are
+    pattern_sources: Vec<String>,
+    // The same patterns as given with their names, for GuardMatch
+    patterns: Vec<(String, Option<String>)>,
+    // Each source compiled on its own, only run to find which pattern protected a token
+    pattern_regexes: Vec<Regex>,
+    protected_patterns: RegexSet,
+    protected_prefixes: PrefixTrie,
+    // Per-GuardKind count of tokens that guard protected. Checks run concurrently, hence
+    // atomics, and the clones runtime edits make share them
+    hits: Arc<[AtomicU64; GuardKind::ORDER.len()]>,
+}
+
+impl Default for Guards {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Guards {
     pub fn new() -> Self {
+        Self::with_case_sensitivity(false)
+    }
+
+    // Case-sensitive guards only protect terms and prefixes exactly as listed
+    pub fn with_case_sensitivity(case_sensitive: bool) -> Self {
         Self {
             protected_set: HashSet::new(),
-            protected_patterns: Vec::new(),
-        }
-    }
-
-    pub fn load_protected(&mut self, content: &str) {
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if !trimmed.is_empty() && !trimmed.starts_with('#') {
-                // Store literal form
-                self.protected_set.insert(trimmed.to_string());
-                // Store lowercase form
-                self.protected_set.insert(trimmed.to_lowercase());
-                // Store normalized form (strips whitespace, converts to lowercase)
-                // This ensures variants like "newyork" are protected if "New York" is in the list
-                let normalized = SymSpell::normalize_word(trimmed);
-                self.protected_set.insert(normalized);
-            }
+            canonical: HashMap::new(),
+            case_sensitive,
+            normalizer: Normalizer::default(),
+            pattern_sources: Vec::new(),
+            patterns: Vec::new(),
+            pattern_regexes: Vec::new(),
+            protected_patterns: RegexSet::empty(),
+            protected_prefixes: PrefixTrie::new(case_sensitive),
+            hits: Default::default(),
         }
     }
 
+    // The normalizer the engine's tokens are normalized with. Call before adding terms
+    pub fn with_normalizer(mut self, normalizer: Normalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    pub fn add_prefix(&mut self, prefix: &str) {
+        if !prefix.is_empty() {
+            self.protected_prefixes.insert(prefix);
+        }
+    }
+
+    // Terms listed in a protected file: one per line, blank lines and # comments skipped
+    pub fn protected_lines(content: &str) -> impl Iterator<Item = &str> {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    }
+
+    pub fn add_protected(&mut self, term: &str) {
+        for form in self.stored_forms(term) {
+            self.protected_set.insert(form);
+        }
+    }
+
+    /// Protects both `variant` and `canonical`, and rewrites a token matching either of them to
+    /// `canonical` (see `canonical_form`)
+    pub fn add_canonical(&mut self, variant: &str, canonical: &str) {
+        self.add_protected(variant);
+        self.add_protected(canonical);
+        for form in self.stored_forms(variant).into_iter().chain(self.stored_forms(canonical)) {
+            self.canonical.insert(form, canonical.to_string());
+        }
+    }
+
+    /// Takes out every form `add_protected` stored for `term`; false if none was there
+    pub fn remove_protected(&mut self, term: &str) -> bool {
+        let mut removed = false;
+        for form in self.stored_forms(term) {
+            removed |= self.protected_set.remove(&form);
+            self.canonical.remove(&form);
+        }
+        removed
+    }
+
+    /// The canonical form a protected-set match is rewritten to, if its term was given one
+    pub fn canonical_form(&self, guard: &GuardMatch) -> Option<String> {
+        if guard.kind != GuardKind::Set {
+            return None;
+        }
+        self.canonical.get(&guard.matched).cloned()
+    }
+
+    fn stored_forms(&self, term: &str) -> Vec<String> {
+        if self.case_sensitive {
+            return vec![term.to_string()];
+        }
+
+        // The folded form, and the folded normalized form (strips whitespace, decomposes)
+        // This ensures variants like "newyork" are protected if "New York" is in the list
+        let normalized = self.normalizer.normalize(term);
+        vec![fold_case(term), fold_case(&normalized)]
+    }
+
     pub fn add_pattern_with_flags(
         &mut self,
         pattern: &str,
         case_insensitive: bool,
         multiline: bool,
         extended: bool,
+        anchored: bool,
+        name: Option<&str>,
     ) -> Result<(), String> {
-        match RegexBuilder::new(pattern)
+        // Compile alone first so a bad pattern is reported on its own
+        RegexBuilder::new(pattern)
             .case_insensitive(case_insensitive)
             .multi_line(multiline)
             .ignore_whitespace(extended)
             .build()
-        {
-            Ok(regex) => {
-                self.protected_patterns.push(regex);
-                Ok(())
-            }
-            Err(e) => Err(format!("Invalid regex pattern: {}", e)),
+            .map_err(|e| format!("Invalid regex pattern: {}", e))?;
+
+        let mut flags = String::new();
+        if case_insensitive {
+            flags.push('i');
         }
+        if multiline {
+            flags.push('m');
+        }
+        if extended {
+            flags.push('x');
+        }
+        // In extended mode a trailing # comment would swallow the closing paren
+        let mut source = if extended {
+            format!("(?{}:{}\n)", flags, pattern)
+        } else {
+            format!("(?{}:{})", flags, pattern)
+        };
+        // \A and \z hold at the token's ends even in multiline mode, unlike ^ and $
+        if anchored {
+            source = format!("\\A{}\\z", source);
+        }
+
+        let regex = Regex::new(&source).map_err(|e| format!("Invalid regex pattern: {}", e))?;
+        self.pattern_sources.push(source);
+        self.pattern_regexes.push(regex);
+        self.patterns.push((pattern.to_string(), name.map(str::to_string)));
+        Ok(())
     }
 
-    pub fn is_protected(&self, word: &str) -> bool {
-        let lower = word.to_lowercase();
+    /// Builds the RegexSet from every pattern added so far. Patterns don't protect anything until
+    /// this runs, so a load compiles the set once rather than once per pattern
+    pub fn compile_patterns(&mut self) -> Result<(), String> {
+        self.protected_patterns =
+            RegexSet::new(&self.pattern_sources).map_err(|e| format!("Invalid regex pattern set: {}", e))?;
+        Ok(())
+    }
 
-        if self.protected_set.contains(word) || self.protected_set.contains(&lower) {
-            return true;
+    // The normalized form is lowercased, so case-sensitive guards only check the raw token
+    fn in_set(&self, word: &str, normalized: &str) -> bool {
+        let set = &self.protected_set;
+        if self.case_sensitive {
+            return set.contains(word);
         }
 
-        for pattern in &self.protected_patterns {
-            if pattern.is_match(word) {
-                return true;
+        set.contains(&fold_case(word)) || set.contains(&fold_case(normalized))
+    }
+
+    // Evaluates guards in GuardKind::ORDER, stopping at the first that protects the token.
+    // Prefixes only look at the raw token; the set and patterns also see the normalized form
+    fn protecting_kind(&self, word: &str, normalized: &str) -> Option<GuardKind> {
+        GuardKind::ORDER.into_iter().find(|kind| match kind {
+            // Already run by the engine, which only asks about tokens it let through
+            GuardKind::Shape => false,
+            GuardKind::Prefix => {
+                !self.protected_prefixes.is_empty() && self.protected_prefixes.matching_prefix(word).is_some()
+            }
+            GuardKind::Set => !self.protected_set.is_empty() && self.in_set(word, normalized),
+            GuardKind::Pattern => {
+                !self.pattern_sources.is_empty()
+                    && (self.protected_patterns.is_match(word) || self.protected_patterns.is_match(normalized))
             }
+        })
+    }
+
+    pub fn is_protected_normalized(&self, word: &str, normalized: &str) -> bool {
+        match self.protecting_kind(word, normalized) {
+            Some(kind) => {
+                self.hits[kind.index()].fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            None => false,
         }
+    }
 
-        false
+    /// `match_reason`, counted in the hit counters
+    pub fn check(&self, word: &str, normalized: &str) -> Option<GuardMatch> {
+        let guard = self.match_reason(word, normalized)?;
+        self.hits[guard.kind.index()].fetch_add(1, Ordering::Relaxed);
+        Some(guard)
     }
 
-    pub fn is_protected_normalized(&self, word: &str, normalized: &str) -> bool {
-        self.is_protected(word) || self.is_protected(normalized)
+    /// The guard `is_protected_normalized` would stop at, and what in it matched. Not counted in
+    /// the hit counters
+    pub fn match_reason(&self, word: &str, normalized: &str) -> Option<GuardMatch> {
+        let kind = self.protecting_kind(word, normalized)?;
+        let mut guard = GuardMatch { kind, matched: String::new(), index: None, name: None, form: None };
+        match kind {
+            GuardKind::Shape => return None,
+            GuardKind::Prefix => {
+                guard.matched = self.protected_prefixes.matching_prefix(word)?;
+            }
+            GuardKind::Set => {
+                let set = &self.protected_set;
+                let (matched, form) = if self.case_sensitive || set.contains(word) {
+                    (word.to_string(), TermForm::Literal)
+                } else if set.contains(&fold_case(word)) {
+                    (fold_case(word), TermForm::Lowercase)
+                } else {
+                    (fold_case(normalized), TermForm::Normalized)
+                };
+                guard.matched = matched;
+                guard.form = Some(form);
+            }
+            GuardKind::Pattern => {
+                // The set already said some pattern matches; only now run them one by one
+                let first = |token: &str| self.pattern_regexes.iter().position(|regex| regex.is_match(token));
+                let index = first(word).or_else(|| first(normalized))?;
+                let (pattern, name) = &self.patterns[index];
+                guard.matched = pattern.clone();
+                guard.index = Some(index);
+                guard.name = name.clone();
+            }
+        }
+        Some(guard)
+    }
+
+    // Order-independent text of what the guards protect, for Engine::behavior_material
+    pub fn write_canonical(&self, out: &mut String) {
+        let mut terms: Vec<&String> = self.protected_set.iter().collect();
+        terms.sort_unstable();
+        let mut patterns = self.pattern_sources.clone();
+        patterns.sort_unstable();
+
+        let _ = writeln!(out, "guards.case_sensitive\t{}", self.case_sensitive);
+        for prefix in self.protected_prefixes.prefixes() {
+            let _ = writeln!(out, "guards.prefix\t{}", prefix);
+        }
+        for term in terms {
+            let _ = writeln!(out, "guards.term\t{}", term);
+        }
+        let mut canonical: Vec<_> = self.canonical.iter().collect();
+        canonical.sort_unstable();
+        for (form, replacement) in canonical {
+            let _ = writeln!(out, "guards.canonical\t{}\t{}", form, replacement);
+        }
+        for pattern in patterns {
+            let _ = writeln!(out, "guards.pattern\t{:?}", pattern);
+        }
+    }
+
+    /// Counts a token the engine's length/shape checks left as written
+    pub fn record_shape(&self) {
+        self.hits[GuardKind::Shape.index()].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn hits(&self, kind: GuardKind) -> u64 {
+        self.hits[kind.index()].load(Ordering::Relaxed)
+    }
+
+    pub fn reset_hits(&self) {
+        for hits in self.hits.iter() {
+            hits.store(0, Ordering::Relaxed);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,243 @@
+// Lightweight streaming scanner for correct_text's HTML mode. It never builds a DOM: it only
+// separates correctable text from markup, and anything it can't classify is passed through
+
+// Elements whose contents are never corrected
+const SKIP_ELEMENTS: [&str; 3] = ["code", "pre", "script"];
+
+// Stands in for an entity we don't decode. Not alphanumeric, so it never joins a token, and
+// tokens touching it are left alone rather than corrected around it
+pub const OPAQUE: char = '\u{FFFC}';
+
+const NAMED_ENTITIES: [(&str, char); 62] = [
+    ("amp", '&'), ("lt", '<'), ("gt", '>'), ("quot", '"'), ("apos", '\''), ("nbsp", '\u{a0}'),
+    ("Agrave", 'À'), ("Aacute", 'Á'), ("Acirc", 'Â'), ("Atilde", 'Ã'), ("Auml", 'Ä'), ("Aring", 'Å'),
+    ("AElig", 'Æ'), ("Ccedil", 'Ç'), ("Egrave", 'È'), ("Eacute", 'É'), ("Ecirc", 'Ê'), ("Euml", 'Ë'),
+    ("Igrave", 'Ì'), ("Iacute", 'Í'), ("Icirc", 'Î'), ("Iuml", 'Ï'), ("Ntilde", 'Ñ'), ("Ograve", 'Ò'),
+    ("Oacute", 'Ó'), ("Ocirc", 'Ô'), ("Otilde", 'Õ'), ("Ouml", 'Ö'), ("Oslash", 'Ø'), ("Ugrave", 'Ù'),
+    ("Uacute", 'Ú'), ("Ucirc", 'Û'), ("Uuml", 'Ü'), ("Yacute", 'Ý'), ("szlig", 'ß'), ("agrave", 'à'),
+    ("aacute", 'á'), ("acirc", 'â'), ("atilde", 'ã'), ("auml", 'ä'), ("aring", 'å'), ("aelig", 'æ'),
+    ("ccedil", 'ç'), ("egrave", 'è'), ("eacute", 'é'), ("ecirc", 'ê'), ("euml", 'ë'), ("igrave", 'ì'),
+    ("iacute", 'í'), ("icirc", 'î'), ("iuml", 'ï'), ("ntilde", 'ñ'), ("ograve", 'ò'), ("oacute", 'ó'),
+    ("ocirc", 'ô'), ("otilde", 'õ'), ("ouml", 'ö'), ("oslash", 'ø'), ("ugrave", 'ù'), ("uacute", 'ú'),
+    ("ucirc", 'û'), ("uuml", 'ü'),
+];
+
+// Byte ranges of the input: Text may be corrected, Raw is copied through unchanged
+#[derive(Debug, PartialEq)]
+pub enum Segment {
+    Text(usize, usize),
+    Raw(usize, usize),
+}
+
+pub fn segments(html: &str) -> Vec<Segment> {
+    let bytes = html.as_bytes();
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'<' || !starts_markup(&bytes[i + 1..]) {
+            i += 1;
+            continue;
+        }
+
+        if text_start < i {
+            segments.push(Segment::Text(text_start, i));
+        }
+
+        // Unterminated markup is ambiguous: the rest of the input passes through
+        let end = match markup_end(html, i) {
+            Some(end) => end,
+            None => {
+                segments.push(Segment::Raw(i, html.len()));
+                return segments;
+            }
+        };
+
+        // A skip element's contents run to its closing tag, unterminated ones to the end
+        let end = match skip_element(&html[i..end]) {
+            Some(name) => find_closing_tag(html, end, name).unwrap_or(html.len()),
+            None => end,
+        };
+
+        segments.push(Segment::Raw(i, end));
+        i = end;
+        text_start = end;
+    }
+
+    if text_start < html.len() {
+        segments.push(Segment::Text(text_start, html.len()));
+    }
+    segments
+}
+
+// "<" only opens markup before a tag name, "/", "!" or "?"; otherwise it is text ("a < b")
+fn starts_markup(rest: &[u8]) -> bool {
+    rest.first()
+        .is_some_and(|&b| b.is_ascii_alphabetic() || b == b'/' || b == b'!' || b == b'?')
+}
+
+// Byte offset just past the markup starting at `start`, honoring quoted attribute values
+fn markup_end(html: &str, start: usize) -> Option<usize> {
+    if html[start..].starts_with("<!--") {
+        return html[start + 4..].find("-->").map(|p| start + 4 + p + 3);
+    }
+
+    let mut quote = None;
+    for (offset, b) in html.as_bytes()[start + 1..].iter().enumerate() {
+        match (quote, b) {
+            (Some(q), _) if *b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"') | (None, b'\'') => quote = Some(*b),
+            (None, b'>') => return Some(start + 1 + offset + 1),
+            (None, b'<') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn skip_element(tag: &str) -> Option<&'static str> {
+    let name: String = tag[1..]
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if tag.ends_with("/>") {
+        return None;
+    }
+    SKIP_ELEMENTS.iter().copied().find(|&skip| skip == name)
+}
+
+fn find_closing_tag(html: &str, from: usize, name: &str) -> Option<usize> {
+    let lower = html[from..].to_ascii_lowercase();
+    let needle = format!("</{}", name);
+    let mut search = 0;
+
+    while let Some(pos) = lower[search..].find(&needle) {
+        let tag_start = search + pos;
+        let after = tag_start + needle.len();
+        // "</pre" must not match "</preview"
+        if lower.as_bytes().get(after).is_some_and(u8::is_ascii_alphanumeric) {
+            search = after;
+            continue;
+        }
+        return lower[after..].find('>').map(|p| from + after + p + 1);
+    }
+    None
+}
+
+// Decodes entities in a text segment. Returns the decoded text and, for each decoded byte
+// offset that starts a char (plus the end), the source offset it came from
+pub fn decode(source: &str) -> (String, Vec<usize>) {
+    let mut text = String::with_capacity(source.len());
+    let mut offsets = Vec::with_capacity(source.len() + 1);
+    let mut i = 0;
+
+    while i < source.len() {
+        let (c, len) = match source[i..].starts_with('&').then(|| entity(&source[i..])).flatten() {
+            Some(decoded) => decoded,
+            None => {
+                let c = source[i..].chars().next().unwrap();
+                (c, c.len_utf8())
+            }
+        };
+
+        for _ in 0..c.len_utf8() {
+            offsets.push(i);
+        }
+        text.push(c);
+        i += len;
+    }
+
+    offsets.push(source.len());
+    (text, offsets)
+}
+
+// Decodes the entity at the start of `s` to (char, source length). Well-formed entities we
+// don't know become OPAQUE; a bare "&" is not an entity
+fn entity(s: &str) -> Option<(char, usize)> {
+    let semi = s[1..].find(';')? + 1;
+    let body = &s[1..semi];
+    if body.is_empty() || body.len() > 32 {
+        return None;
+    }
+
+    let c = if let Some(number) = body.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => number.parse().ok(),
+        };
+        code.and_then(char::from_u32).unwrap_or(OPAQUE)
+    } else if body.chars().all(|c| c.is_ascii_alphanumeric()) {
+        NAMED_ENTITIES
+            .iter()
+            .find(|(name, _)| *name == body)
+            .map_or(OPAQUE, |&(_, c)| c)
+    } else {
+        return None;
+    };
+
+    Some((c, semi + 1))
+}
+
+// Escapes a replacement word for HTML text
+pub fn encode(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    for c in word.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_split_tags_from_text() {
+        let html = r#"<p class="helo">helo <b>wrld</b></p>"#;
+        assert_eq!(
+            segments(html),
+            vec![
+                Segment::Raw(0, 16),
+                Segment::Text(16, 21),
+                Segment::Raw(21, 24),
+                Segment::Text(24, 28),
+                Segment::Raw(28, 32),
+                Segment::Raw(32, 36),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_segments_skip_element_contents() {
+        let html = "a <code>helo <i>x</i></code> b";
+        assert_eq!(
+            segments(html),
+            vec![Segment::Text(0, 2), Segment::Raw(2, 28), Segment::Text(28, 30)]
+        );
+    }
+
+    #[test]
+    fn test_segments_pass_unterminated_markup_through() {
+        assert_eq!(segments("helo <a href=\"x"), vec![Segment::Text(0, 5), Segment::Raw(5, 15)]);
+        assert_eq!(segments("1 < 2"), vec![Segment::Text(0, 5)]);
+    }
+
+    #[test]
+    fn test_decode_maps_offsets_back_to_source() {
+        let (text, offsets) = decode("caf&eacute; &amp; &bogus; &");
+        assert_eq!(text, format!("café & {} &", OPAQUE));
+        // 'é' is two bytes in the decoded text, both mapped to the entity start
+        assert_eq!(&offsets[3..5], &[3, 3]);
+        assert_eq!(offsets[5], 11);
+        assert_eq!(*offsets.last().unwrap(), 27);
+    }
+}
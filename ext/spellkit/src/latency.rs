@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Number of histogram buckets. Bucket 0 counts calls under 1µs, bucket i calls taking
+/// [2^(i-1), 2^i) µs, and the last bucket everything from 2^(BUCKETS-2) µs (about 4s) up
+pub const BUCKETS: usize = 24;
+
+/// Call durations in fixed log2 buckets. Recording is one relaxed atomic increment, so it
+/// can be shared across threads serving the same engine
+#[derive(Debug, Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS],
+    // Total recorded time, for exporters that report a sum alongside the buckets
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let bucket = ((u64::BITS - micros.leading_zeros()) as usize).min(BUCKETS - 1);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+    }
+
+    /// Bucket counts. Concurrent recording may land between two loads, so the array is a
+    /// consistent snapshot only when nothing else is recording
+    pub fn counts(&self) -> [u64; BUCKETS] {
+        std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed))
+    }
+
+    /// Sum of all recorded durations in µs
+    pub fn sum_micros(&self) -> u64 {
+        self.sum_micros.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.sum_micros.store(0, Ordering::Relaxed);
+    }
+
+    /// Exclusive upper bound of each bucket in µs; the last bucket has none
+    pub fn upper_bounds() -> [u64; BUCKETS - 1] {
+        std::array::from_fn(|i| 1 << i)
+    }
+
+    /// The `q` quantile (0.0 to 1.0) of `counts` at bucket resolution: the upper bound of the
+    /// bucket holding it, or the lower bound for the open last bucket. None without calls
+    pub fn percentile(counts: &[u64; BUCKETS], q: f64) -> Option<u64> {
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let rank = ((q * total as f64).ceil() as u64).clamp(1, total);
+        let mut seen = 0;
+        for (bucket, &count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(if bucket == BUCKETS - 1 { 1 << (bucket - 1) } else { 1 << bucket });
+            }
+        }
+        None
+    }
+}
+
+/// Per-call latency of the suggestion and per-token correction paths
+#[derive(Debug, Default)]
+pub struct Latency {
+    pub suggest: Histogram,
+    pub correct_tokens: Histogram,
+}
+
+impl Latency {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&self) {
+        self.suggest.reset();
+        self.correct_tokens.reset();
+    }
+}
+
+/// Runs `f`, recording its duration in `histogram` when there is one
+pub fn timed<T>(histogram: Option<&Histogram>, f: impl FnOnce() -> T) -> T {
+    let Some(histogram) = histogram else {
+        return f();
+    };
+
+    let started = Instant::now();
+    let result = f();
+    histogram.record(started.elapsed());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_buckets() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_nanos(500));
+        histogram.record(Duration::from_micros(1));
+        histogram.record(Duration::from_micros(3));
+        histogram.record(Duration::from_secs(3600));
+        assert_eq!(histogram.sum_micros(), 3_600_000_004);
+
+        let counts = histogram.counts();
+        assert_eq!(counts[0], 1);
+        assert_eq!(counts[1], 1);
+        assert_eq!(counts[2], 1);
+        assert_eq!(counts[BUCKETS - 1], 1);
+        assert_eq!(counts.iter().sum::<u64>(), 4);
+
+        histogram.reset();
+        assert_eq!(histogram.counts(), [0; BUCKETS]);
+        assert_eq!(histogram.sum_micros(), 0);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let mut counts = [0; BUCKETS];
+        assert_eq!(Histogram::percentile(&counts, 0.5), None);
+
+        // 90 calls in [4, 8) µs and 10 in [64, 128) µs
+        counts[3] = 90;
+        counts[7] = 10;
+        assert_eq!(Histogram::percentile(&counts, 0.5), Some(8));
+        assert_eq!(Histogram::percentile(&counts, 0.9), Some(8));
+        assert_eq!(Histogram::percentile(&counts, 0.95), Some(128));
+        assert_eq!(Histogram::percentile(&counts, 1.0), Some(128));
+
+        counts[BUCKETS - 1] = 1000;
+        assert_eq!(Histogram::percentile(&counts, 0.99), Some(1 << (BUCKETS - 2)));
+    }
+
+    #[test]
+    fn test_timed_without_histogram() {
+        assert_eq!(timed(None, || 42), 42);
+
+        let histogram = Histogram::new();
+        assert_eq!(timed(Some(&histogram), || "done"), "done");
+        assert_eq!(histogram.counts().iter().sum::<u64>(), 1);
+    }
+}
@@ -1,393 +1,2333 @@
-mod symspell;
-mod guards;
+mod binary;
+pub mod boosts;
+pub mod counters;
+pub mod engine;
+pub mod guards;
+pub mod latency;
+pub mod metrics;
+pub mod normalizer;
+mod html;
+pub mod symspell;
+pub mod tokenizer;
+pub mod training;
+pub mod units;
+
+pub use engine::{Decision, Engine, LoadError, LoadOptions, LoadStats, Reason};
+
+// Ruby bindings: argument parsing, conversion, and shaping results into Ruby values. Every
+// policy decision and the text, corpus, and golden pipelines live in `engine`
+#[cfg(feature = "ruby")]
+mod ruby {
+    use magnus::block::Proc;
+    use magnus::r_hash::ForEach;
+    use magnus::{class, define_module, function, method, prelude::*, Error, ExceptionClass, IntoValue, RArray, RHash, RModule, RString, Ruby, Value, TryConvert};
+    use hashbrown::{HashMap, HashSet};
+    use serde_json::{Map, Value as Json};
+    use std::borrow::Cow;
+    use std::ffi::c_void;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
+    use std::time::{Duration, Instant};
+    use crate::counters::Counters;
+    use crate::engine::{
+        check_threshold, parallel_map, same_shape, BoostSource, BoostSpec, Compression, Decision, DictionaryFormat, DictionarySource,
+        Engine, IndexStrategy, LastToken, LoadError, LoadOptions, LoadStats, OnDuplicate, OutputMode, PatternSpec, Progress, Reason,
+        SegmentationPreference, TextChange, Thresholds, TieBreak, DEFAULT_WARMUP_SAMPLE,
+    };
+    use crate::guards::{GuardKind, GuardMatch};
+    use crate::latency::{timed, Histogram, Latency};
+    use crate::metrics::Exposition;
+    use crate::html;
+    use arc_swap::ArcSwap;
+    use crate::normalizer::{NormalForm, Normalizer};
+    use crate::symspell::{self, Combine, DistanceMetric, Suggestion, Warmup};
+    use crate::units::UnitMode;
+    use crate::tokenizer::{self, unsegmented_runs, Tokenizer};
+    use crate::training;
+
+    #[derive(Clone)]
+    #[magnus::wrap(class = "SpellKit::Checker", free_immediately, size)]
+    struct Checker {
+        // Each load! swaps in a fresh snapshot. Calls load it without locking and keep it to the
+        // end, so they never see a half-installed load, and a Snapshot can pin its generation
+        state: Arc<ArcSwap<CheckerSnapshot>>,
+        // Held by load! and runtime edits while they swap, so each carries the layers and edits
+        // of the engine it replaces
+        writer: Arc<Mutex<()>>,
+        // Caller-registered closed sets for `within:` corrections. Not tied to the dictionary, so
+        // they survive reloads and are shared with snapshots
+        named_sets: Arc<RwLock<HashMap<String, NamedSet>>>,
+        // Set while load! runs, for monitoring
+        loading: Arc<AtomicBool>,
+        // States loaded into this checker that may still be alive. Shared with snapshots
+        generations: Arc<Mutex<Generations>>,
+        // Call and token counts for stats. Not tied to the dictionary, so they survive reloads
+        // and snapshots add to them
+        counters: Arc<Counters>,
+    }
 
-use magnus::{class, define_module, function, method, prelude::*, Error, RArray, RHash, Ruby, Value, TryConvert};
-use std::sync::{Arc, RwLock};
-use symspell::SymSpell;
-use guards::Guards;
+    // A `within:` set: its terms as given, and their keys under the normalizer current when it
+    // was defined. A reload with another normalizer re-keys it per call (see `NamedSet::keys`)
+    struct NamedSet {
+        terms: Vec<String>,
+        normalizer: Normalizer,
+        keys: HashSet<String>,
+    }
 
-use std::time::{SystemTime, UNIX_EPOCH};
+    impl NamedSet {
+        fn new(terms: Vec<String>, normalizer: Normalizer) -> Self {
+            let keys = terms.iter().map(|t| normalizer.normalize(t)).collect();
+            Self { terms, normalizer, keys }
+        }
 
-#[derive(Clone)]
-#[magnus::wrap(class = "SpellKit::Checker", free_immediately, size)]
-struct Checker {
-    state: Arc<RwLock<CheckerState>>,
-}
+        fn keys(&self, engine: &Engine) -> Cow<'_, HashSet<String>> {
+            if self.normalizer == *engine.normalizer() {
+                Cow::Borrowed(&self.keys)
+            } else {
+                Cow::Owned(self.terms.iter().map(|t| engine.normalize(t)).collect())
+            }
+        }
+    }
 
-struct CheckerState {
-    symspell: Option<SymSpell>,
-    guards: Guards,
-    loaded: bool,
-    frequency_threshold: f64,
-    loaded_at: Option<u64>,
-    dictionary_size: usize,
-    edit_distance: usize,
-    skipped_malformed: usize,
-    skipped_multiword: usize,
-    skipped_invalid_freq: usize,
-    skipped_duplicates: usize,
-}
+    // One entry of `suggestions`, as a SpellKit::Suggestion. `score` is the term's unigram
+    // probability in the dictionary it came from. `<=>` is the order the index ranks by
+    #[magnus::wrap(class = "SpellKit::Suggestion", free_immediately, size)]
+    struct RubySuggestion {
+        suggestion: Suggestion,
+        score: f64,
+        display_truncated: Option<String>,
+    }
+
+    impl RubySuggestion {
+        fn term(&self) -> String {
+            self.suggestion.term.clone()
+        }
 
-impl CheckerState {
-    fn new() -> Self {
-        Self {
-            symspell: None,
-            guards: Guards::new(),
-            loaded: false,
-            frequency_threshold: 10.0,
-            loaded_at: None,
-            dictionary_size: 0,
-            edit_distance: 1,
-            skipped_malformed: 0,
-            skipped_multiword: 0,
-            skipped_invalid_freq: 0,
-            skipped_duplicates: 0,
+        fn distance(&self) -> usize {
+            self.suggestion.distance
+        }
+
+        fn frequency(&self) -> u64 {
+            self.suggestion.frequency
+        }
+
+        fn score(&self) -> f64 {
+            self.score
+        }
+
+        fn user(&self) -> bool {
+            self.suggestion.user
+        }
+
+        fn display_truncated(&self) -> Option<String> {
+            self.display_truncated.clone()
+        }
+
+        // nil against anything but another Suggestion, as Comparable expects
+        fn compare(&self, other: Value) -> Option<i8> {
+            let other: &RubySuggestion = TryConvert::try_convert(other).ok()?;
+            Some(self.suggestion.cmp(&other.suggestion) as i8)
         }
     }
-}
 
-// Helper function to correct a single word
-// Returns the corrected word or the original if no correction is appropriate
-fn correct_word(
-    state: &CheckerState,
-    symspell: &SymSpell,
-    word: &str,
-) -> String {
-    // Always check if word is protected
-    let normalized = SymSpell::normalize_word(word);
-    if state.guards.is_protected_normalized(word, &normalized) {
-        return word.to_string();
-    }
-
-    let suggestions = symspell.suggestions(word, 5);
-
-    // If exact match exists, return canonical form from dictionary
-    if !suggestions.is_empty() && suggestions[0].distance == 0 {
-        return suggestions[0].term.clone();
-    }
-
-    // Get original word's frequency (if it exists in dictionary)
-    let original_freq = symspell.get_frequency(word);
-
-    // Find best correction with frequency threshold
-    for suggestion in &suggestions {
-        if suggestion.distance <= state.edit_distance {
-            // Apply frequency threshold
-            let passes_threshold = match original_freq {
-                // Word not in dictionary: require suggestion frequency >= absolute threshold
-                None => suggestion.frequency as f64 >= state.frequency_threshold,
-                // Word in dictionary: require suggestion frequency >= threshold * original frequency
-                Some(orig_freq) => {
-                    suggestion.frequency as f64 >= state.frequency_threshold * orig_freq as f64
-                }
-            };
+    // Weak references to every state a checker installed, with when it was installed. A state
+    // outlives its reload while snapshots (or calls in flight) hold it; dead ones are counted
+    // as retired when they are pruned
+    #[derive(Default)]
+    struct Generations {
+        live: Vec<(Weak<CheckerSnapshot>, Instant)>,
+        retired: u64,
+    }
 
-            if passes_threshold {
-                return suggestion.term.clone();
-            }
+    impl Generations {
+        fn prune(&mut self) {
+            let before = self.live.len();
+            self.live.retain(|(state, _)| state.strong_count() > 0);
+            self.retired += (before - self.live.len()) as u64;
         }
     }
 
-    // No suggestions passed the threshold
-    word.to_string()
-}
+    // What a checker serves: the engine from the last successful load!, if any, with the
+    // runtime edits made since. A state is never changed once installed; an edit installs an
+    // edited copy
+    struct CheckerSnapshot {
+        engine: Option<Engine>,
+        // Number of successful loads into this checker, so monitors can see reloads. Edited
+        // copies keep the number of the load they were made from
+        generation: u64,
+        // Engine::behavior_digest, computed on first use
+        behavior_digest: OnceLock<String>,
+        // Where incremental healthchecks have got to in this dictionary; a reload starts over
+        probe: Mutex<Probe>,
+        // load!'s symbolize_keys: whether `keyed` gives returned hashes Symbol keys
+        symbolize_keys: bool,
+    }
 
-impl Checker {
-    fn new() -> Self {
-        Self {
-            state: Arc::new(RwLock::new(CheckerState::new())),
+    // Progress of incremental healthchecks through the dictionary's entries
+    #[derive(Clone, Default)]
+    struct Probe {
+        cursor: usize,
+        // Full passes over the dictionary completed
+        passes: u64,
+        // Entries that failed verification, each reported once
+        failures: Vec<String>,
+    }
+
+    impl CheckerSnapshot {
+        fn engine(&self, ruby: &Ruby) -> Result<&Engine, Error> {
+            self.engine.as_ref()
+                .ok_or_else(|| Error::new(spellkit_error(ruby, "NotLoadedError"), "Dictionary not loaded. Call load! first"))
+        }
+
+        fn health_error(&self) -> Option<&'static str> {
+            if self.engine.is_none() {
+                return Some("Dictionary not loaded");
+            }
+
+            None
         }
     }
 
-    fn load_full(&self, config: RHash) -> Result<(), Error> {
-        let ruby = Ruby::get().unwrap();
+    impl From<LoadError> for Error {
+        fn from(error: LoadError) -> Self {
+            let ruby = Ruby::get().unwrap();
+            match error {
+                LoadError::Invalid(message) => Error::new(ruby.exception_arg_error(), message),
+                LoadError::Io(message) => Error::new(ruby.exception_runtime_error(), message),
+                LoadError::Limit(message) => Error::new(spellkit_error(&ruby, "DictionaryError"), message),
+                LoadError::Parse(message) => Error::new(spellkit_error(&ruby, "DictionaryParseError"), message),
+                LoadError::Pattern(message) => Error::new(spellkit_error(&ruby, "InvalidPatternError"), message),
+            }
+        }
+    }
+
+    // One of the SpellKit::Error subclasses `init` defines, looked up by name when raised
+    fn spellkit_error(ruby: &Ruby, name: &str) -> ExceptionClass {
+        ruby.class_object()
+            .const_get::<_, RModule>("SpellKit")
+            .and_then(|module| module.const_get(name))
+            .unwrap_or_else(|_| ruby.exception_runtime_error())
+    }
+
+    // Reads the optional "tokenizer"/"tokenizer_pattern" keys shared by load! and the text APIs
+    fn tokenizer_from_hash(ruby: &Ruby, hash: RHash) -> Result<Option<Tokenizer>, Error> {
+        let kind: Option<String> = match config_value(hash, "tokenizer") {
+            Some(v) => Some(TryConvert::try_convert(v)?),
+            None => None,
+        };
+
+        match kind {
+            Some(kind) => {
+                let pattern: Option<String> = match config_value(hash, "tokenizer_pattern") {
+                    Some(v) => Some(TryConvert::try_convert(v)?),
+                    None => None,
+                };
+                // With a pattern given, compiling it is the only way "pattern" can fail
+                let class = if kind == "pattern" && pattern.is_some() {
+                    spellkit_error(ruby, "InvalidPatternError")
+                } else {
+                    ruby.exception_arg_error()
+                };
+                Tokenizer::from_config(&kind, pattern.as_deref())
+                    .map(Some)
+                    .map_err(|e| Error::new(class, e))
+            }
+            None => Ok(None),
+        }
+    }
 
-        // Required: dictionary path
-        let dictionary_path: String = TryConvert::try_convert(
-            config.fetch::<_, Value>("dictionary_path")
-                .map_err(|_| Error::new(ruby.exception_arg_error(), "dictionary_path is required"))?
+    // Reads a {"source", "case_insensitive", "multiline", "extended", "anchored", "name"} pattern
+    // hash built by the Ruby side from a Regexp, String, or Hash
+    fn pattern_from_hash(ruby: &Ruby, hash: RHash) -> Result<PatternSpec, Error> {
+        let source: String = TryConvert::try_convert(
+            hash.fetch::<_, Value>("source")
+                .map_err(|_| Error::new(ruby.exception_arg_error(), "pattern hash missing 'source' key"))?
         )?;
 
-        // Optional: edit distance
-        let edit_dist: usize = config.get("edit_distance")
-            .and_then(|v: Value| TryConvert::try_convert(v).ok())
-            .unwrap_or(1);
+        let flag = |key: &str| -> Result<bool, Error> {
+            match hash.get(key) {
+                Some(v) => TryConvert::try_convert(v),
+                None => Ok(false),
+            }
+        };
+
+        Ok(PatternSpec {
+            source,
+            case_insensitive: flag("case_insensitive")?,
+            multiline: flag("multiline")?,
+            extended: flag("extended")?,
+            anchored: flag("anchored")?,
+            name: match hash.get("name") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => None,
+            },
+        })
+    }
 
-        if edit_dist > 2 {
-            return Err(Error::new(ruby.exception_arg_error(), "edit_distance must be 1 or 2"));
+    // The "normalize" load! option; absent keys keep the default pipeline's settings
+    fn normalizer_from_hash(ruby: &Ruby, hash: RHash) -> Result<Normalizer, Error> {
+        let mut normalizer = Normalizer::default();
+        if let Some(v) = hash.get("form") {
+            let name: String = TryConvert::try_convert(v)?;
+            normalizer.form = NormalForm::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown normalize form: {}", name))
+            })?;
         }
+        for (key, flag) in [
+            ("lowercase", &mut normalizer.lowercase),
+            ("strip_accents", &mut normalizer.strip_accents),
+            ("strip_punctuation", &mut normalizer.strip_punctuation),
+        ] {
+            if let Some(v) = hash.get(key) {
+                *flag = TryConvert::try_convert(v)?;
+            }
+        }
+        Ok(normalizer)
+    }
 
-        // Stream dictionary loading: read line-by-line and add directly to SymSpell
-        // This avoids buffering the entire file and intermediate Vec allocation
-        let file = std::fs::File::open(&dictionary_path)
-            .map_err(|e| Error::new(ruby.exception_runtime_error(), format!("Failed to open dictionary file: {}", e)))?;
+    fn last_token_from_hash(ruby: &Ruby, hash: RHash) -> Result<LastToken, Error> {
+        let mode: Option<String> = match hash.get("last_token") {
+            Some(v) => Some(TryConvert::try_convert(v)?),
+            None => None,
+        };
+
+        match mode.as_deref() {
+            None | Some("normal") => Ok(LastToken::Normal),
+            Some("skip") => Ok(LastToken::Skip),
+            Some("complete") => Ok(LastToken::Complete),
+            Some(other) => Err(Error::new(
+                ruby.exception_arg_error(),
+                format!("last_token must be \"normal\", \"skip\", or \"complete\", got: {}", other),
+            )),
+        }
+    }
 
-        let reader = std::io::BufReader::new(file);
-        let mut symspell = SymSpell::new(edit_dist);
-        let mut dictionary_size = 0;
-        let mut skipped_malformed = 0;
-        let mut skipped_multiword = 0;
-        let mut skipped_invalid_freq = 0;
-        let mut skipped_duplicates = 0;
+    // The loaded thresholds, overridden for one call by "frequency_threshold" (both) and then
+    // "min_suggestion_frequency", "frequency_ratio", "max_suggestions", "skip_tokens_with_digits",
+    // "min_word_length", and "skip_all_caps"
+    fn thresholds_from_hash(ruby: &Ruby, engine: &Engine, hash: RHash) -> Result<Thresholds, Error> {
+        let mut thresholds = engine.thresholds();
+        if let Some(v) = hash.get("frequency_threshold") {
+            let value: f64 = TryConvert::try_convert(v)?;
+            check_threshold("frequency_threshold", value).map_err(|e| Error::new(ruby.exception_arg_error(), e))?;
+            thresholds = Thresholds { min_suggestion_frequency: value, frequency_ratio: value, ..thresholds };
+        }
+        if let Some(v) = hash.get("min_suggestion_frequency") {
+            thresholds.min_suggestion_frequency = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = hash.get("frequency_ratio") {
+            thresholds.frequency_ratio = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = hash.get("max_suggestions") {
+            thresholds.max_suggestions = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = hash.get("skip_tokens_with_digits") {
+            thresholds.skip_tokens_with_digits = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = hash.get("min_word_length") {
+            thresholds.min_word_length = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = hash.get("skip_all_caps") {
+            thresholds.skip_all_caps = TryConvert::try_convert(v)?;
+        }
+        thresholds.validate().map_err(|e| Error::new(ruby.exception_arg_error(), e))?;
+        Ok(thresholds)
+    }
 
-        use std::io::BufRead;
-        for line in reader.lines() {
-            let line = line.map_err(|e| Error::new(ruby.exception_runtime_error(), format!("Failed to read line: {}", e)))?;
+    // "output" when given. Otherwise "preserve_case", falling back to the engine's load default,
+    // picks between preserve_case and canonical
+    fn output_mode_from_hash(ruby: &Ruby, hash: RHash, preserve_case: bool) -> Result<OutputMode, Error> {
+        let mode: Option<String> = match hash.get("output") {
+            Some(v) => Some(TryConvert::try_convert(v)?),
+            None => None,
+        };
+        let preserve_case_option: Option<bool> = match hash.get("preserve_case") {
+            Some(v) => Some(TryConvert::try_convert(v)?),
+            None => None,
+        };
+
+        let output_mode = match mode.as_deref() {
+            None if preserve_case_option.unwrap_or(preserve_case) => OutputMode::PreserveCase,
+            None | Some("canonical") => OutputMode::Canonical,
+            Some("normalized") => OutputMode::Normalized,
+            Some("preserve_case") => OutputMode::PreserveCase,
+            Some(other) => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("output must be \"canonical\", \"normalized\", or \"preserve_case\", got: {}", other),
+                ))
+            }
+        };
+        if preserve_case_option == Some(true) && output_mode != OutputMode::PreserveCase {
+            return Err(Error::new(
+                ruby.exception_arg_error(),
+                format!("preserve_case: true can't be combined with output: \"{}\"", mode.unwrap_or_default()),
+            ));
+        }
+        Ok(output_mode)
+    }
 
-            // Try tab-separated first (allows multi-word terms), then space-separated (SymSpell format)
-            let parts: Vec<&str> = if line.contains('\t') {
-                line.split('\t').collect()
-            } else {
-                line.split_whitespace().collect()
+    // Whether a bad batch element raises or becomes an {"error" => message} entry at its position
+    #[derive(Clone, Copy, PartialEq)]
+    enum OnError {
+        Raise,
+        Partial,
+    }
+
+    impl OnError {
+        fn from_hash(ruby: &Ruby, hash: RHash) -> Result<Self, Error> {
+            let mode: Option<String> = match hash.get("on_error") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
             };
 
-            // Validate we have exactly 2 columns (term and frequency)
-            if parts.len() != 2 {
-                skipped_malformed += 1;
-                continue;
+            match mode.as_deref() {
+                None | Some("raise") => Ok(OnError::Raise),
+                Some("partial") => Ok(OnError::Partial),
+                Some(other) => Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("on_error must be \"raise\" or \"partial\", got: {}", other),
+                )),
+            }
+        }
+
+        // Pushes one batch result, or its error under the partial policy
+        fn push<T: IntoValue>(self, result: RArray, element: Result<T, Error>) -> Result<(), Error> {
+            match (element, self) {
+                (Ok(value), _) => result.push(value),
+                (Err(e), OnError::Raise) => Err(e),
+                (Err(e), OnError::Partial) => {
+                    let entry = RHash::new();
+                    entry.aset("error", e.to_string())?;
+                    result.push(entry)
+                }
             }
+        }
+    }
+
+    // A suggest_many word, checked like validate_word! on the Ruby side
+    fn batch_word(ruby: &Ruby, value: Value) -> Result<String, Error> {
+        if value.is_nil() {
+            return Err(Error::new(ruby.exception_arg_error(), "word cannot be nil"));
+        }
+
+        let word: String = TryConvert::try_convert(value)?;
+        if word.is_empty() {
+            return Err(Error::new(ruby.exception_arg_error(), "word cannot be empty"));
+        }
+        Ok(word)
+    }
 
-            let term = parts[0].trim();
-            let freq_str = parts[1].trim();
+    // SpellKit::Suggestion objects, or with `hashes` the {"term", "distance", "freq"} hashes
+    // suggestions returned before. With `truncate_display`, each also gets the term cut to that
+    // many display cells
+    fn suggestion_values(engine: &Engine, suggestions: Vec<Suggestion>, truncate_display: Option<usize>, hashes: bool) -> Result<RArray, Error> {
+        let result = RArray::new();
 
-            // Skip empty terms or frequencies
-            if term.is_empty() || freq_str.is_empty() {
-                skipped_malformed += 1;
+        for suggestion in suggestions {
+            let display = truncate_display.map(|cells| tokenizer::truncate_display(&suggestion.term, cells));
+            if !hashes {
+                let score = engine.symspell().probability(suggestion.frequency);
+                result.push(RubySuggestion { suggestion, score, display_truncated: display })?;
                 continue;
             }
 
-            // Check for multi-word terms (SymSpell algorithm doesn't support phrases)
-            if term.contains(char::is_whitespace) {
-                skipped_multiword += 1;
-                continue;
+            let hash = RHash::new();
+            hash.aset("term", suggestion.term)?;
+            hash.aset("distance", suggestion.distance)?;
+            hash.aset("freq", suggestion.frequency)?;
+            if let Some(display) = display {
+                hash.aset("display_truncated", display)?;
             }
+            result.push(hash)?;
+        }
 
-            // Parse frequency
-            match freq_str.parse::<u64>() {
-                Ok(freq) => {
-                    let normalized = SymSpell::normalize_word(term);
-                    let was_new = symspell.add_word(&normalized, term, freq);
-                    if was_new {
-                        dictionary_size += 1;
-                    } else {
-                        skipped_duplicates += 1;
-                    }
+        Ok(result)
+    }
+
+    // Each token converted to a String, with its decision. With more than one thread the
+    // decisions are made in parallel with the GVL released; results stay in input order
+    fn token_decisions(
+        engine: &Engine,
+        tokens: RArray,
+        last_token: LastToken,
+        thresholds: Thresholds,
+        threads: usize,
+    ) -> Vec<Result<(String, Decision), Error>> {
+        let last = tokens.len().checked_sub(1);
+        let words: Vec<Result<String, Error>> = tokens.into_iter().map(TryConvert::try_convert).collect();
+        let valid: Vec<(usize, &str)> = words
+            .iter()
+            .enumerate()
+            .filter_map(|(i, word)| word.as_deref().ok().map(|word| (i, word)))
+            .collect();
+
+        let decide = |_: usize, &(i, word): &(usize, &str)| {
+            timed(engine.latency().map(|l| &l.correct_tokens), || {
+                if Some(i) == last {
+                    engine.correct_last_token(word, last_token, thresholds)
+                } else {
+                    engine.correct_token_with(word, None, thresholds)
+                }
+            })
+        };
+        let decisions = if threads > 1 {
+            without_gvl(|| parallel_map(&valid, threads, decide)).unwrap_or_else(|| parallel_map(&valid, threads, decide))
+        } else {
+            parallel_map(&valid, 1, decide)
+        };
+
+        let mut decisions = decisions.into_iter();
+        words
+            .into_iter()
+            .map(|word| word.map(|word| (word, decisions.next().expect("one decision per converted token"))))
+            .collect()
+    }
+
+    // correct_tokens with dry_run: {"index", "original", "proposed", "confidence"} for each token
+    // whose output would differ from it, and no output array. Under the partial policy a token
+    // that isn't a String is reported as {"index", "error"}
+    fn propose_token_changes(
+        engine: &Engine,
+        decisions: Vec<Result<(String, Decision), Error>>,
+        output_mode: OutputMode,
+        resolve_aliases: bool,
+        on_error: OnError,
+    ) -> Result<RArray, Error> {
+        let changes = RArray::new();
+
+        for (i, token) in decisions.into_iter().enumerate() {
+            let (word, decision) = match (token, on_error) {
+                (Ok(token), _) => token,
+                (Err(e), OnError::Raise) => return Err(e),
+                (Err(e), OnError::Partial) => {
+                    let entry = RHash::new();
+                    entry.aset("index", i)?;
+                    entry.aset("error", e.to_string())?;
+                    changes.push(entry)?;
+                    continue;
+                }
+            };
+            let (proposed, confidence) = match engine.propose_token_change(&word, &decision, output_mode, resolve_aliases) {
+                Some(change) => change,
+                None => continue,
+            };
+
+            let change = RHash::new();
+            change.aset("index", i)?;
+            change.aset("confidence", confidence)?;
+            change.aset("original", word)?;
+            change.aset("proposed", proposed)?;
+            changes.push(change)?;
+        }
+
+        Ok(changes)
+    }
+
+    // correct_text with dry_run: {"original", "proposed", "start", "end", "confidence"} for each
+    // replacement Engine::propose_text_changes finds
+    fn text_changes(changes: Vec<TextChange>) -> Result<RArray, Error> {
+        let result = RArray::new();
+        for change in changes {
+            let hash = RHash::new();
+            hash.aset("original", change.original)?;
+            hash.aset("proposed", change.proposed)?;
+            hash.aset("start", change.start)?;
+            hash.aset("end", change.end)?;
+            hash.aset("confidence", change.confidence)?;
+            result.push(hash)?;
+        }
+
+        Ok(result)
+    }
+
+    // Per-token details for correct_tokens: the spelling step ("correction") and the alias hop
+    // ("alias") are reported separately from the final "output"
+    fn decision_hash(
+        token: &str,
+        decision: &Decision,
+        alias: Option<&str>,
+        output: String,
+        engine: &Engine,
+    ) -> Result<RHash, Error> {
+        let hash = RHash::new();
+        hash.aset("token", token)?;
+        hash.aset("output", output)?;
+        hash.aset("correction", decision.output.as_str())?;
+        hash.aset("reason", decision.reason.as_str())?;
+        hash.aset("distance", decision.distance)?;
+        hash.aset("freq", decision.frequency)?;
+        hash.aset("alias", alias)?;
+        hash.aset("boost", decision.boost)?;
+        hash.aset("guard", decision.guard.as_ref().map(guard_hash).transpose()?)?;
+
+        let applied = RArray::new();
+        for &index in &decision.boosts {
+            let boost = engine.boosts().get(index);
+            let boost_hash = RHash::new();
+            boost_hash.aset(boost.kind(), boost.source())?;
+            boost_hash.aset("multiplier", boost.multiplier())?;
+            applied.push(boost_hash)?;
+        }
+        hash.aset("boosts", applied)?;
+
+        if engine.segmentation.is_some() {
+            let (path, correction_score, segmentation_score) = match decision.path {
+                Some(ref choice) => (
+                    Some(if choice.segmented { "segmentation" } else { "correction" }),
+                    choice.correction_score,
+                    choice.segmentation_score,
+                ),
+                None => (None, None, None),
+            };
+            let scores = RHash::new();
+            scores.aset("correction", correction_score)?;
+            scores.aset("segmentation", segmentation_score)?;
+            hash.aset("path", path)?;
+            hash.aset("scores", scores)?;
+        }
+
+        if matches!(decision.reason, Reason::BelowThreshold | Reason::NoCandidates) {
+            let best_rejected = match decision.best_rejected {
+                Some(ref rejected) => {
+                    let rejected_hash = RHash::new();
+                    rejected_hash.aset("term", rejected.term.as_str())?;
+                    rejected_hash.aset("distance", rejected.distance)?;
+                    rejected_hash.aset("freq", rejected.frequency)?;
+                    rejected_hash.aset("required_freq", rejected.required_frequency)?;
+                    Some(rejected_hash)
+                }
+                None => None,
+            };
+            hash.aset("best_rejected", best_rejected)?;
+        }
+
+        Ok(hash)
+    }
+
+    // {"kind", "matched"} for the guard that protected a token, plus {"index", "name"} for a
+    // protected pattern or "form" ("literal", "lowercase", "normalized") for a protected term
+    fn guard_hash(guard: &GuardMatch) -> Result<RHash, Error> {
+        let hash = RHash::new();
+        hash.aset("kind", guard.kind.as_str())?;
+        hash.aset("matched", guard.matched.as_str())?;
+        match guard.kind {
+            GuardKind::Pattern => {
+                hash.aset("index", guard.index)?;
+                hash.aset("name", guard.name.as_deref())?;
+            }
+            GuardKind::Set => hash.aset("form", guard.form.map(|form| form.as_str()))?,
+            GuardKind::Shape | GuardKind::Prefix => {}
+        }
+        Ok(hash)
+    }
+
+    fn option_flag(hash: RHash, key: &str) -> Result<bool, Error> {
+        match hash.get(key) {
+            Some(v) => TryConvert::try_convert(v),
+            None => Ok(false),
+        }
+    }
+
+    // A load! config value under `key` as a Symbol or a String, so configs built by hand in Ruby
+    // can use either. The Symbol wins when both are present
+    fn config_value(config: RHash, key: &str) -> Option<Value> {
+        let ruby = Ruby::get().unwrap();
+        config.get(ruby.to_symbol(key)).or_else(|| config.get(key))
+    }
+
+    fn config_flag(config: RHash, key: &str) -> Result<bool, Error> {
+        match config_value(config, key) {
+            Some(v) => TryConvert::try_convert(v),
+            None => Ok(false),
+        }
+    }
+
+    // {"bucket_bounds_us", "suggest", "correct_tokens"}, each histogram with its bucket counts and
+    // bucket-resolution percentiles
+    fn latency_value(latency: &Latency) -> Json {
+        let histogram_value = |histogram: &Histogram| {
+            let counts = histogram.counts();
+            let mut block = Map::new();
+            block.insert("count".into(), counts.iter().sum::<u64>().into());
+            block.insert("buckets".into(), counts.to_vec().into());
+            for (key, q) in [("p50_us", 0.5), ("p95_us", 0.95), ("p99_us", 0.99)] {
+                block.insert(key.into(), Histogram::percentile(&counts, q).into());
+            }
+            Json::Object(block)
+        };
+
+        let mut value = Map::new();
+        value.insert("bucket_bounds_us".into(), Histogram::upper_bounds().to_vec().into());
+        value.insert("suggest".into(), histogram_value(&latency.suggest));
+        value.insert("correct_tokens".into(), histogram_value(&latency.correct_tokens));
+        Json::Object(value)
+    }
+
+    // {"elapsed_ms", "queries", "entries_touched"}
+    fn warmup_value(warmup: &Warmup) -> Json {
+        let mut value = Map::new();
+        value.insert("elapsed_ms".into(), (warmup.elapsed.as_secs_f64() * 1000.0).into());
+        value.insert("queries".into(), warmup.queries.into());
+        value.insert("entries_touched".into(), warmup.entries_touched.into());
+        Json::Object(value)
+    }
+
+    // What load! returns: {"lines_read", "entries_loaded", "lines_skipped", "duplicates_merged",
+    // "load_time_ms", "index_entries"}
+    fn load_report(stats: &LoadStats) -> Json {
+        let mut report = Map::new();
+        report.insert("lines_read".into(), stats.lines_read.into());
+        report.insert("entries_loaded".into(), stats.dictionary_size.into());
+        report.insert("lines_skipped".into(), stats.lines_skipped().into());
+        report.insert("duplicates_merged".into(), stats.skipped_duplicates.into());
+        report.insert("load_time_ms".into(), (stats.load_time.as_secs_f64() * 1000.0).into());
+        report.insert("index_entries".into(), stats.index_entries.into());
+        Json::Object(report)
+    }
+
+    // Builds the Ruby equivalent of a JSON value: Hash, Array, String, Integer, Float, true/false, nil
+    fn json_to_ruby(ruby: &Ruby, json: &Json) -> Result<Value, Error> {
+        Ok(match json {
+            Json::Null => ruby.into_value(()),
+            Json::Bool(b) => ruby.into_value(*b),
+            Json::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(u), _) => ruby.into_value(u),
+                (None, Some(i)) => ruby.into_value(i),
+                (None, None) => ruby.into_value(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            Json::String(s) => ruby.into_value(s.as_str()),
+            Json::Array(items) => {
+                let array = RArray::new();
+                for item in items {
+                    array.push(json_to_ruby(ruby, item)?)?;
                 }
-                Err(_) => {
-                    skipped_invalid_freq += 1;
+                ruby.into_value(array)
+            }
+            Json::Object(fields) => {
+                let hash = RHash::new();
+                for (key, value) in fields {
+                    hash.aset(key.as_str(), json_to_ruby(ruby, value)?)?;
                 }
+                ruby.into_value(hash)
+            }
+        })
+    }
+
+    fn symbolize_keys(ruby: &Ruby, value: Value) -> Result<Value, Error> {
+        if let Some(hash) = RHash::from_value(value) {
+            let symbolized = RHash::new();
+            hash.foreach(|key: Value, item: Value| {
+                let key = match RString::from_value(key) {
+                    Some(key) => ruby.to_symbol(key.to_string()?).as_value(),
+                    None => key,
+                };
+                symbolized.aset(key, symbolize_keys(ruby, item)?)?;
+                Ok(ForEach::Continue)
+            })?;
+            return Ok(symbolized.as_value());
+        }
+
+        if let Some(array) = RArray::from_value(value) {
+            let symbolized = RArray::with_capacity(array.len());
+            for item in array.into_iter() {
+                symbolized.push(symbolize_keys(ruby, item)?)?;
             }
+            return Ok(symbolized.as_value());
         }
 
-        let mut guards = Guards::new();
+        Ok(value)
+    }
 
-        // Load optional protected terms file
-        if let Some(protected_path) = config.get("protected_path") {
-            let path: String = TryConvert::try_convert(protected_path)?;
-            let content = std::fs::read_to_string(&path)
-                .map_err(|e| Error::new(ruby.exception_runtime_error(),
-                    format!("Failed to read protected terms file '{}': {}", path, e)))?;
-            guards.load_protected(&content);
+    // Builds engine options from the hash load! passes in. Top-level keys may be Symbols or
+    // Strings (see config_value); nested hashes are read as the Ruby side builds them
+    fn load_options_from_hash(ruby: &Ruby, config: RHash) -> Result<LoadOptions, Error> {
+        // Required: dictionary path, unless a prebuilt index is loaded instead
+        let index_path: Option<String> = match config_value(config, "index_path") {
+            Some(v) if !v.is_nil() => Some(TryConvert::try_convert(v)?),
+            _ => None,
+        };
+        // In-memory pairs, as a Hash or an Array of [word, frequency], replace the file
+        let entries: Option<Vec<(String, u64)>> = match config_value(config, "dictionary").filter(|v| !v.is_nil()) {
+            Some(v) => match RHash::from_value(v) {
+                Some(hash) => Some(hash.to_vec()?),
+                None => Some(TryConvert::try_convert(v)?),
+            },
+            None => None,
+        };
+        let dictionary_path: String = match (config_value(config, "dictionary_path").filter(|v| !v.is_nil()), &entries) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    "pass either dictionary_path or dictionary (word and frequency pairs), not both",
+                ))
+            }
+            (Some(v), None) => TryConvert::try_convert(v)?,
+            (None, Some(_)) => String::new(),
+            (None, None) => match index_path {
+                Some(ref path) => path.clone(),
+                None => {
+                    return Err(Error::new(
+                        ruby.exception_arg_error(),
+                        "dictionary_path is required (as :dictionary_path or \"dictionary_path\"), or dictionary with word and frequency pairs",
+                    ))
+                }
+            },
+        };
+        let mut options = LoadOptions::new(&dictionary_path);
+        options.index_path = index_path;
+        options.entries = entries;
+
+        if let Some(v) = config_value(config, "edit_distance") {
+            options.edit_distance = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "frequency_threshold") {
+            options.frequency_threshold = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "min_suggestion_frequency") {
+            options.min_suggestion_frequency = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "frequency_ratio") {
+            options.frequency_ratio = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "max_suggestions") {
+            options.max_suggestions = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "skip_tokens_with_digits") {
+            options.skip_tokens_with_digits = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "min_word_length") {
+            options.min_word_length = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "skip_all_caps") {
+            options.skip_all_caps = TryConvert::try_convert(v)?;
+        }
+
+        // Optional compact storage (u32 frequencies, divided by frequency_scale)
+        options.compact = config_flag(config, "compact")?;
+        if let Some(v) = config_value(config, "frequency_scale") {
+            options.frequency_scale = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "index_strategy") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.index_strategy = IndexStrategy::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown index_strategy: {}", name))
+            })?;
+        }
+        // nil indexes whole words
+        if let Some(v) = config_value(config, "prefix_length") {
+            options.prefix_length = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "frequency_combine") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.frequency_combine = Combine::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown frequency_combine: {}", name))
+            })?;
+        }
+        if let Some(v) = config_value(config, "distance_metric") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.distance_metric = DistanceMetric::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown distance_metric: {}", name))
+            })?;
+        }
+
+        if let Some(v) = config_value(config, "format") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.format = DictionaryFormat::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown format: {}", name))
+            })?;
+        }
+        if let Some(v) = config_value(config, "on_duplicate") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.on_duplicate = OnDuplicate::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown on_duplicate: {}", name))
+            })?;
+        }
+        if let Some(v) = config_value(config, "compression") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.compression = Compression::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown compression: {}", name))
+            })?;
+        }
+        if let Some(v) = config_value(config, "default_frequency") {
+            options.default_frequency = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "separator") {
+            options.separator = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "term_index") {
+            options.term_index = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "count_index") {
+            options.count_index = TryConvert::try_convert(v)?;
         }
 
-        // Load optional protected patterns
-        if let Some(patterns_value) = config.get("protected_patterns") {
-            let patterns: RArray = TryConvert::try_convert(patterns_value)?;
+        // Caps on malformed input; strict makes them fatal
+        if let Some(v) = config_value(config, "max_line_length") {
+            options.max_line_length = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "max_word_length") {
+            options.max_word_length = TryConvert::try_convert(v)?;
+        }
+        options.strict = config_flag(config, "strict")?;
+        // nil lifts the limit, so these are only left at their defaults when absent
+        if let Some(v) = config_value(config, "max_dictionary_bytes") {
+            options.max_dictionary_bytes = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "max_words") {
+            options.max_words = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "structural_match") {
+            options.structural_match = TryConvert::try_convert(v)?;
+        }
+        options.preserve_case = config_flag(config, "preserve_case")?;
+        if let Some(v) = config_value(config, "normalize") {
+            options.normalizer = normalizer_from_hash(&ruby, TryConvert::try_convert(v)?)?;
+        }
+        if let Some(v) = config_value(config, "skip_presets") {
+            options.skip_presets = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "tie_break") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.tie_break = TieBreak::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown tie_break: {}", name))
+            })?;
+        }
+        if let Some(v) = config_value(config, "units") {
+            let name: String = TryConvert::try_convert(v)?;
+            options.units = UnitMode::parse(&name).ok_or_else(|| {
+                Error::new(ruby.exception_arg_error(), format!("unknown units: {}", name))
+            })?;
+        }
+        if let Some(v) = config_value(config, "unit_list") {
+            options.unit_list = Some(TryConvert::try_convert(v)?);
+        }
+        if let Some(v) = config_value(config, "strip_ignorable") {
+            options.strip_ignorable = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "latency_stats") {
+            options.latency_stats = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "warmup") {
+            options.warmup = Some(TryConvert::try_convert(v)?);
+        }
+
+        options.protected_case_sensitive = config_flag(config, "protected_case_sensitive")?;
+        options.protected_as_known = config_flag(config, "protected_as_known")?;
+        options.protect_only_unknown = config_flag(config, "protect_only_unknown")?;
+        if let Some(v) = config_value(config, "confident_frequency") {
+            options.confident_frequency = TryConvert::try_convert(v)?;
+        }
+
+        if let Some(v) = config_value(config, "user_dictionary_path") {
+            options.user_dictionary_path = Some(TryConvert::try_convert(v)?);
+        }
+        if let Some(v) = config_value(config, "protected_path") {
+            options.protected_path = Some(TryConvert::try_convert(v)?);
+        }
+        if let Some(v) = config_value(config, "protected_prefixes") {
+            options.protected_prefixes = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "protected_patterns") {
+            let patterns: RArray = TryConvert::try_convert(v)?;
             for pattern_value in patterns.into_iter() {
                 let pattern_hash: RHash = TryConvert::try_convert(pattern_value)?;
+                options.protected_patterns.push(pattern_from_hash(ruby, pattern_hash)?);
+            }
+        }
 
-                let source: String = TryConvert::try_convert(
-                    pattern_hash.fetch::<_, Value>("source")
-                        .map_err(|_| Error::new(ruby.exception_arg_error(), "pattern hash missing 'source' key"))?
+        // Optional ranking boosts: {"multiplier", "pattern"} or {"multiplier", "terms_path"}
+        if let Some(v) = config_value(config, "max_boost") {
+            options.max_boost = TryConvert::try_convert(v)?;
+        }
+        if let Some(v) = config_value(config, "boosts") {
+            let entries: RArray = TryConvert::try_convert(v)?;
+            for entry in entries.into_iter() {
+                let entry: RHash = TryConvert::try_convert(entry)?;
+                let multiplier: f64 = TryConvert::try_convert(
+                    entry.fetch::<_, Value>("multiplier")
+                        .map_err(|_| Error::new(ruby.exception_arg_error(), "boost hash missing 'multiplier' key"))?
                 )?;
 
-                let case_insensitive: bool = pattern_hash.get("case_insensitive")
-                    .and_then(|v: Value| TryConvert::try_convert(v).ok())
-                    .unwrap_or(false);
+                let source = if let Some(pattern_value) = entry.get("pattern") {
+                    let pattern_hash: RHash = TryConvert::try_convert(pattern_value)?;
+                    BoostSource::Pattern(pattern_from_hash(ruby, pattern_hash)?)
+                } else if let Some(path_value) = entry.get("terms_path") {
+                    BoostSource::TermsPath(TryConvert::try_convert(path_value)?)
+                } else {
+                    return Err(Error::new(ruby.exception_arg_error(), "boost hash needs a 'pattern' or 'terms_path' key"));
+                };
+                options.boosts.push(BoostSpec { source, multiplier });
+            }
+        }
+
+        options.tokenizer = tokenizer_from_hash(ruby, config)?.unwrap_or(Tokenizer::Unicode);
+
+        if let Some(v) = config_value(config, "aliases_path") {
+            options.aliases_path = Some(TryConvert::try_convert(v)?);
+        }
+        if let Some(v) = config_value(config, "denylist_path") {
+            options.denylist_path = Some(TryConvert::try_convert(v)?);
+        }
+
+        let segmentation = config_flag(config, "segmentation")?;
+        let prefer_segmentation: Option<bool> = match config_value(config, "prefer_segmentation_over_distance") {
+            Some(v) => Some(TryConvert::try_convert(v)?),
+            None => None,
+        };
+        options.segmentation = segmentation.then_some(match prefer_segmentation {
+            None => SegmentationPreference::Score,
+            Some(true) => SegmentationPreference::Segmentation,
+            Some(false) => SegmentationPreference::Correction,
+        });
+
+        if let Some(v) = config_value(config, "autocorrect_known_rare") {
+            options.autocorrect_known_rare = Some(TryConvert::try_convert(v)?);
+        }
+
+        // The Ruby side describes URL downloads; direct loads default to a plain file
+        if let Some(v) = config_value(config, "source") {
+            let hash: RHash = TryConvert::try_convert(v)?;
+            let kind: String = TryConvert::try_convert(
+                hash.fetch::<_, Value>("kind")
+                    .map_err(|_| Error::new(ruby.exception_arg_error(), "source hash missing 'kind' key"))?
+            )?;
+            let path_or_url: Option<String> = match hash.get("path_or_url") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let sha256: Option<String> = match hash.get("sha256") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            options.source = Some(DictionarySource {
+                kind,
+                path_or_url: path_or_url.unwrap_or(dictionary_path),
+                sha256,
+                bytes: 0,
+            });
+        }
+
+        Ok(options)
+    }
 
-                let multiline: bool = pattern_hash.get("multiline")
-                    .and_then(|v: Value| TryConvert::try_convert(v).ok())
-                    .unwrap_or(false);
+    // What can stop a load running without the GVL: the load itself, or the progress callback
+    // raising. Turned into a Ruby error only once the GVL is back, since building one needs it
+    enum LoadFailure {
+        Load(LoadError),
+        Callback(Error),
+    }
 
-                let extended: bool = pattern_hash.get("extended")
-                    .and_then(|v: Value| TryConvert::try_convert(v).ok())
-                    .unwrap_or(false);
+    impl From<LoadError> for LoadFailure {
+        fn from(error: LoadError) -> Self {
+            LoadFailure::Load(error)
+        }
+    }
 
-                guards.add_pattern_with_flags(&source, case_insensitive, multiline, extended)
-                    .map_err(|e| Error::new(ruby.exception_arg_error(), e))?;
+    impl From<LoadFailure> for Error {
+        fn from(failure: LoadFailure) -> Self {
+            match failure {
+                LoadFailure::Load(error) => error.into(),
+                LoadFailure::Callback(error) => error,
             }
         }
+    }
 
-        // Optional frequency threshold
-        let frequency_threshold: f64 = config.get("frequency_threshold")
-            .and_then(|v: Value| TryConvert::try_convert(v).ok())
-            .unwrap_or(10.0);
+    // The closure to run and its result, passed through Ruby's void* thread callbacks
+    type GvlSlot<F, R> = (Option<F>, Option<std::thread::Result<R>>);
 
-        // Validate frequency threshold
-        if !frequency_threshold.is_finite() {
-            return Err(Error::new(ruby.exception_arg_error(), "frequency_threshold must be finite (not NaN or Infinity)"));
+    // Runs the closure in a GvlSlot, catching a panic so it never unwinds into Ruby's C frames
+    unsafe extern "C" fn call_slot<F: FnOnce() -> R, R>(data: *mut c_void) -> *mut c_void {
+        let slot = &mut *(data as *mut GvlSlot<F, R>);
+        if let Some(func) = slot.0.take() {
+            slot.1 = Some(panic::catch_unwind(AssertUnwindSafe(func)));
         }
+        std::ptr::null_mut()
+    }
+
+    fn take_slot<F, R>(slot: GvlSlot<F, R>) -> Option<R> {
+        slot.1.map(|result| result.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+    }
 
-        if frequency_threshold < 0.0 {
-            return Err(Error::new(ruby.exception_arg_error(), format!("frequency_threshold must be non-negative, got: {}", frequency_threshold)));
+    // Runs `func` with the GVL released so other Ruby threads keep running. `func` must not touch
+    // the Ruby API outside `with_gvl`. There's no unblock function, so a Thread#raise or kill
+    // waits for `func` to finish. None if an interrupt was already pending and `func` never ran
+    fn without_gvl<F: FnOnce() -> R, R>(func: F) -> Option<R> {
+        let mut slot: GvlSlot<F, R> = (Some(func), None);
+        unsafe {
+            rb_sys::rb_thread_call_without_gvl2(
+                Some(call_slot::<F, R>),
+                &mut slot as *mut GvlSlot<F, R> as *mut c_void,
+                None,
+                std::ptr::null_mut(),
+            );
         }
+        take_slot(slot)
+    }
 
-        let loaded_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .ok()
-            .map(|d| d.as_secs());
+    // Runs `func` with the GVL re-acquired. Only valid inside `without_gvl`
+    fn with_gvl<F: FnOnce() -> R, R>(func: F) -> R {
+        let mut slot: GvlSlot<F, R> = (Some(func), None);
+        unsafe {
+            rb_sys::rb_thread_call_with_gvl(Some(call_slot::<F, R>), &mut slot as *mut GvlSlot<F, R> as *mut c_void);
+        }
+        take_slot(slot).expect("rb_thread_call_with_gvl always runs its function")
+    }
 
-        let mut state = self.state.write().unwrap();
-        state.symspell = Some(symspell);
-        state.guards = guards;
-        state.frequency_threshold = frequency_threshold;
-        state.loaded = true;
-        state.loaded_at = loaded_at;
-        state.dictionary_size = dictionary_size;
-        state.edit_distance = edit_dist;
-        state.skipped_malformed = skipped_malformed;
-        state.skipped_multiword = skipped_multiword;
-        state.skipped_invalid_freq = skipped_invalid_freq;
-        state.skipped_duplicates = skipped_duplicates;
+    // Clears the checker's loading flag however load! exits
+    struct LoadingGuard<'a>(&'a AtomicBool);
 
-        Ok(())
+    impl Drop for LoadingGuard<'_> {
+        fn drop(&mut self) {
+            self.0.store(false, Ordering::Relaxed);
+        }
     }
 
-    fn suggestions(&self, word: String, max: Option<usize>) -> Result<RArray, Error> {
-        let ruby = Ruby::get().unwrap();
-        let max_suggestions = max.unwrap_or(5);
-        let state = self.state.read().unwrap();
+    impl Checker {
+        fn new() -> Self {
+            Self {
+                state: Arc::new(ArcSwap::new(Arc::new(CheckerSnapshot {
+                    engine: None,
+                    generation: 0,
+                    behavior_digest: OnceLock::new(),
+                    probe: Mutex::default(),
+                    symbolize_keys: false,
+                }))),
+                writer: Arc::new(Mutex::new(())),
+                named_sets: Arc::new(RwLock::new(HashMap::new())),
+                loading: Arc::new(AtomicBool::new(false)),
+                generations: Arc::new(Mutex::new(Generations::default())),
+                counters: Arc::new(Counters::new()),
+            }
+        }
+
+        // The accepted option name a misspelled one most likely meant, for "did you mean" hints
+        fn closest_option(name: String, accepted: Vec<String>) -> Option<String> {
+            symspell::closest(&name, accepted.iter().map(String::as_str)).map(str::to_string)
+        }
+
+        // Counts the words of the corpus at `input_path` into a dictionary at `output_path` (see
+        // `training::train`): {"lines", "tokens", "distinct", "written"}
+        fn train_from_text(input_path: String, output_path: String, min_count: u64) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let input = std::fs::File::open(&input_path)
+                .map_err(|e| Error::new(ruby.exception_runtime_error(), format!("Failed to open corpus file: {}", e)))?;
+            let output = std::fs::File::create(&output_path)
+                .map_err(|e| Error::new(ruby.exception_runtime_error(), format!("Failed to create output file: {}", e)))?;
+
+            let training = training::train(std::io::BufReader::new(input), std::io::BufWriter::new(output), min_count)
+                .map_err(|e| Error::new(ruby.exception_runtime_error(), format!("Failed to train from corpus: {}", e)))?;
+
+            let summary = RHash::new();
+            summary.aset("lines", training.lines)?;
+            summary.aset("tokens", training.tokens)?;
+            summary.aset("distinct", training.distinct)?;
+            summary.aset("written", training.written)?;
+            Ok(summary)
+        }
+
+        fn load_full(&self, config: RHash) -> Result<Value, Error> {
+            let ruby = Ruby::get().unwrap();
+            self.loading.store(true, Ordering::Relaxed);
+            let _loading = LoadingGuard(&self.loading);
+
+            let options = load_options_from_hash(&ruby, config)?;
+            let symbolize_keys: bool = match config_value(config, "symbolize_keys") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => false,
+            };
+            let callback: Option<Proc> = match config_value(config, "progress") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+
+            // Parsing and indexing run without the GVL, so a large load doesn't stall the
+            // process's other threads. Only progress callbacks take it back
+            let loaded = without_gvl(|| {
+                let mut progress = Progress::new(callback.map(|callback| {
+                    move |phase: &str, processed: usize, total: usize| -> Result<(), LoadFailure> {
+                        with_gvl(|| callback.call::<_, Value>((phase, processed, total)).map(|_| ()))
+                            .map_err(LoadFailure::Callback)
+                    }
+                }));
+                Engine::load_with_progress(&options, &mut progress)
+            });
+            let mut engine = match loaded {
+                Some(result) => result?,
+                None => return Err(Error::new(ruby.exception_runtime_error(), "load! was interrupted before it started")),
+            };
+            let report = load_report(engine.load_stats());
+
+            // Under the writer lock, so no layer update can land in the old engine after the copy
+            let writer = self.writer.lock().unwrap();
+            let current = self.state.load_full();
+            if let Some(previous) = current.engine.as_ref() {
+                engine.carry_layers_from(previous);
+            }
+            self.install(CheckerSnapshot {
+                engine: Some(engine),
+                generation: current.generation + 1,
+                behavior_digest: OnceLock::new(),
+                probe: Mutex::default(),
+                symbolize_keys,
+            });
+            drop(current);
+            drop(writer);
+
+            // Reported after the writer lock is released so the callback may use the checker
+            if let Some(callback) = callback {
+                callback.call::<_, Value>(("finalizing", 1, 1))?;
+            }
 
-        if !state.loaded {
-            return Err(Error::new(ruby.exception_runtime_error(), "Dictionary not loaded. Call load! first"));
+            json_to_ruby(&ruby, &report)
         }
 
-        if let Some(ref symspell) = state.symspell {
-            let suggestions = symspell.suggestions(&word, max_suggestions);
+        fn suggestions(&self, word: String, max: Option<usize>, options: RHash) -> Result<RArray, Error> {
+            let ruby = Ruby::get().unwrap();
+            let exclude_exact: bool = match options.get("exclude_exact") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => false,
+            };
+            let structural_match = option_flag(options, "structural_match")?;
+            let hashes = option_flag(options, "hashes")?;
+            let truncate_display: Option<usize> = match options.get("truncate_display") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let max_distance: Option<usize> = match options.get("max_distance") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let max_suggestions = max.unwrap_or(engine.max_suggestions());
+            let max_distance = max_distance.unwrap_or(engine.edit_distance());
+            if max_distance > engine.edit_distance() {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("max_distance {} exceeds the loaded edit_distance {}", max_distance, engine.edit_distance()),
+                ));
+            }
+
+            self.counters.record_suggest(1);
+            let suggestions = timed(engine.latency().map(|l| &l.suggest), || {
+                if !structural_match {
+                    return engine.suggest_within(&word, max_suggestions, max_distance, exclude_exact);
+                }
+                let mut suggestions = engine.suggest_within(&word, usize::MAX, max_distance, exclude_exact);
+                suggestions.retain(|s| s.distance == 0 || same_shape(&word, &s.term));
+                suggestions.truncate(max_suggestions);
+                suggestions
+            });
+            suggestion_values(engine, suggestions, truncate_display, hashes)
+        }
+
+        // Batch suggestions against one snapshot; `options` holds one {"max", "max_distance"} hash per word
+        fn suggest_many(&self, words: RArray, options: RArray, batch: RHash) -> Result<RArray, Error> {
+            let ruby = Ruby::get().unwrap();
+            let on_error = OnError::from_hash(&ruby, batch)?;
+            let hashes = option_flag(batch, "hashes")?;
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            if words.len() != options.len() {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    format!("expected {} per-word option hashes, got {}", words.len(), options.len()),
+                ));
+            }
+
             let result = RArray::new();
 
-            for suggestion in suggestions {
+            for (word, word_options) in words.into_iter().zip(options) {
+                let word_options: RHash = TryConvert::try_convert(word_options)?;
+
+                let max: usize = match word_options.get("max") {
+                    Some(v) => TryConvert::try_convert(v)?,
+                    None => engine.max_suggestions(),
+                };
+                let max_distance: usize = match word_options.get("max_distance") {
+                    Some(v) => TryConvert::try_convert(v)?,
+                    None => engine.edit_distance(),
+                };
+
+                if max_distance > engine.edit_distance() {
+                    return Err(Error::new(
+                        ruby.exception_arg_error(),
+                        format!("max_distance {} exceeds the loaded edit_distance {}", max_distance, engine.edit_distance()),
+                    ));
+                }
+
+                let suggestions = batch_word(&ruby, word).and_then(|word| {
+                    self.counters.record_suggest(1);
+                    suggestion_values(
+                        engine,
+                        timed(engine.latency().map(|l| &l.suggest), || {
+                            engine.suggest_within(&word, max, max_distance, false)
+                        }),
+                        None,
+                        hashes,
+                    )
+                });
+                on_error.push(result, suggestions)?;
+            }
+
+            Ok(result)
+        }
+
+        fn correct(&self, word: String) -> Result<bool, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.contains(&word))
+        }
+
+        // {"word", "term", "normalized", "in_dictionary", "frequency", "guard", "candidates",
+        // "required_frequency", "decision"}: why correct decides what it does for a word. "guard" is
+        // the guard hash or nil, and "decision" is the correct_tokens details hash
+        fn explain(&self, word: String, guarded: bool) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+            let explanation = engine.explain(&word, guarded);
+
+            let guard = explanation.guard.as_ref().map(guard_hash).transpose()?;
+            let candidates = RArray::new();
+            for candidate in &explanation.candidates {
                 let hash = RHash::new();
-                hash.aset("term", suggestion.term)?;
-                hash.aset("distance", suggestion.distance)?;
-                hash.aset("freq", suggestion.frequency)?;
-                result.push(hash)?;
+                hash.aset("term", candidate.term.as_str())?;
+                hash.aset("distance", candidate.distance)?;
+                hash.aset("frequency", candidate.frequency)?;
+                candidates.push(hash)?;
             }
+            let decision = &explanation.decision;
+
+            let hash = RHash::new();
+            hash.aset("word", word.as_str())?;
+            hash.aset("term", explanation.term.as_str())?;
+            hash.aset("normalized", explanation.normalized.as_str())?;
+            hash.aset("in_dictionary", explanation.frequency.is_some())?;
+            hash.aset("frequency", explanation.frequency)?;
+            hash.aset("guard", guard)?;
+            hash.aset("candidates", candidates)?;
+            hash.aset("required_frequency", explanation.required_frequency)?;
+            hash.aset("decision", decision_hash(&word, decision, None, decision.output.clone(), engine)?)?;
+            Ok(hash)
+        }
+
+        // The dictionary entry for a known word, or nil. Guards aren't consulted. "term" is the
+        // cleaned word as looked up. There is no per-entry metadata in the dictionary format yet,
+        // so "metadata" is always nil
+        fn lookup(&self, word: String) -> Result<Option<RHash>, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let Some(entry) = engine.lookup(&word) else {
+                return Ok(None);
+            };
+            let hash = RHash::new();
+            hash.aset("term", engine.clean(&word).as_ref())?;
+            hash.aset("canonical", entry.canonical)?;
+            hash.aset("frequency", entry.frequency)?;
+            hash.aset("rank", entry.rank)?;
+            hash.aset("metadata", ruby.qnil())?;
+            Ok(Some(hash))
+        }
+
+        fn frequency(&self, word: String) -> Result<Option<u64>, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.frequency(&word))
+        }
 
+        fn correct_if_unknown(&self, word: String, options: RHash) -> Result<String, Error> {
+            let ruby = Ruby::get().unwrap();
+            let resolve_aliases = option_flag(options, "resolve_aliases")?;
+            let inline_terms: Option<Vec<String>> = match options.get("within") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let set_name: Option<String> = match options.get("within_set") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let state = self.state.load_full();
+            let named_sets = self.named_sets.read().unwrap();
+            let engine = state.engine(&ruby)?;
+            let thresholds = thresholds_from_hash(&ruby, engine, options)?;
+
+            let within = match set_name {
+                Some(name) => Some(named_sets.get(&name).ok_or_else(|| {
+                    Error::new(ruby.exception_arg_error(), format!("Unknown set: {}", name))
+                })?.keys(engine)),
+                None => inline_terms.map(|terms| Cow::Owned(terms.iter().map(|t| engine.normalize(t)).collect())),
+            };
+
+            let output_mode = output_mode_from_hash(&ruby, options, engine.preserve_case())?;
+
+            let decision = engine.correct_token_with(&word, within.as_deref(), thresholds);
+            self.counters.record_correct(decision.reason);
+            let alias = if resolve_aliases { engine.resolve_alias(&decision) } else { None };
+            let output = match alias {
+                Some(alias) => alias.to_string(),
+                None => decision.output,
+            };
+            Ok(output_mode.apply(&word, output, engine.normalizer()))
+        }
+
+        // One correction per distinct word, against one snapshot and optionally across threads.
+        // The Ruby side pairs the outputs back up with the words and their counts
+        fn correct_unique(&self, words: Vec<String>, options: RHash) -> Result<Vec<String>, Error> {
+            let ruby = Ruby::get().unwrap();
+            let threads: usize = match options.get("threads") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 1,
+            };
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.correct_many(&words, threads).into_iter().map(|decision| decision.output).collect())
+        }
+
+        // {"output", "distance"} for a whole phrase, with words merged and split as needed
+        fn correct_phrase(&self, phrase: String) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let corrected = engine.correct_phrase(&phrase);
+            let hash = RHash::new();
+            hash.aset("output", corrected.output)?;
+            hash.aset("distance", corrected.distance)?;
+            Ok(hash)
+        }
+
+        // {"segments", "score"}: the words of text without spaces, and the split's log probability
+        fn segment(&self, text: String, options: RHash) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let max_segment_length: usize = match options.get("max_segment_length") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 24,
+            };
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let segmentation = engine.segment(&text, max_segment_length);
+            let hash = RHash::new();
+            hash.aset("segments", segmentation.segments)?;
+            hash.aset("score", segmentation.log_probability)?;
+            Ok(hash)
+        }
+
+        // Swaps in a new state, tracked in `generations` until it's freed. Call with the writer
+        // lock held
+        fn install(&self, state: CheckerSnapshot) {
+            let state = Arc::new(state);
+            let mut generations = self.generations.lock().unwrap();
+            generations.prune();
+            generations.live.push((Arc::downgrade(&state), Instant::now()));
+            drop(generations);
+            self.state.store(state);
+        }
+
+        // Runtime edits are copy-on-write: under the writer lock the current engine is cloned,
+        // edited, and installed as a new state of the same generation, so calls already running
+        // and snapshots keep the engine they started with, and a concurrent load! carries the
+        // edit into the engine it loads. A failed edit installs nothing
+        fn edit<T>(&self, edit: impl FnOnce(&mut Engine) -> Result<T, Error>) -> Result<T, Error> {
+            let ruby = Ruby::get().unwrap();
+            let _writer = self.writer.lock().unwrap();
+            let current = self.state.load_full();
+            let mut engine = current.engine(&ruby)?.clone();
+
+            let result = edit(&mut engine)?;
+            self.install(CheckerSnapshot {
+                engine: Some(engine),
+                generation: current.generation,
+                behavior_digest: OnceLock::new(),
+                probe: Mutex::new(current.probe.lock().unwrap().clone()),
+                symbolize_keys: current.symbolize_keys,
+            });
             Ok(result)
-        } else {
-            Err(Error::new(ruby.exception_runtime_error(), "SymSpell not initialized"))
         }
-    }
 
-    fn correct(&self, word: String) -> Result<bool, Error> {
-        let ruby = Ruby::get().unwrap();
-        let state = self.state.read().unwrap();
+        fn add_word(&self, word: String, frequency: u64) -> Result<bool, Error> {
+            self.edit(|engine| Ok(engine.add_word(&word, frequency)?))
+        }
 
-        if !state.loaded {
-            return Err(Error::new(ruby.exception_runtime_error(), "Dictionary not loaded. Call load! first"));
+        fn remove_word(&self, word: String) -> Result<bool, Error> {
+            self.edit(|engine| Ok(engine.remove_word(&word)))
         }
 
-        if let Some(ref symspell) = state.symspell {
-            Ok(symspell.contains(&word))
-        } else {
-            Err(Error::new(ruby.exception_runtime_error(), "SymSpell not initialized"))
+        fn protect(&self, terms: Vec<String>) -> Result<(), Error> {
+            self.edit(|engine| {
+                for term in &terms {
+                    engine.protect(term)?;
+                }
+                Ok(())
+            })
         }
-    }
 
-    fn correct_if_unknown(&self, word: String) -> Result<String, Error> {
-        let ruby = Ruby::get().unwrap();
-        let state = self.state.read().unwrap();
+        fn unprotect(&self, term: String) -> Result<bool, Error> {
+            self.edit(|engine| Ok(engine.unprotect(&term)))
+        }
 
-        if !state.loaded {
-            return Err(Error::new(ruby.exception_runtime_error(), "Dictionary not loaded. Call load! first"));
+        fn is_protected(&self, word: String) -> Result<bool, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.is_protected(&word))
         }
 
-        if let Some(ref symspell) = state.symspell {
-            Ok(correct_word(&state, symspell, &word))
-        } else {
-            Err(Error::new(ruby.exception_runtime_error(), "SymSpell not initialized"))
+        // Registers a named closed set for `within:`; redefining a name replaces it. Keyed with
+        // the loaded normalizer, or the default one before load!
+        fn define_set(&self, name: String, terms: Vec<String>) -> Result<usize, Error> {
+            let state = self.state.load_full();
+            let normalizer = state.engine.as_ref().map_or_else(Normalizer::default, |engine| *engine.normalizer());
+            let set = NamedSet::new(terms, normalizer);
+            let size = set.keys.len();
+            self.named_sets.write().unwrap().insert(name, set);
+            Ok(size)
         }
-    }
 
-    fn correct_tokens(&self, tokens: RArray) -> Result<RArray, Error> {
-        // Optimize batch correction by acquiring lock once for all tokens
-        // instead of calling correct_if_unknown per token (which re-locks each time)
-        let ruby = Ruby::get().unwrap();
-        let state = self.state.read().unwrap();
+        // A read-only view pinned to the current dictionary generation. The Ruby side wraps it in
+        // SpellKit::Snapshot, which exposes only the read methods
+        fn snapshot(&self) -> Self {
+            Self {
+                state: Arc::new(ArcSwap::new(self.state.load_full())),
+                writer: Arc::new(Mutex::new(())),
+                named_sets: self.named_sets.clone(),
+                loading: Arc::new(AtomicBool::new(false)),
+                generations: self.generations.clone(),
+                counters: self.counters.clone(),
+            }
+        }
+
+        // With "alignment", returns {"tokens", "alignment"} where alignment[i] is the [start, end)
+        // span of output indexes input token i produced: empty when "unknown" is "remove" dropped
+        // it, wider than one when "expand" split a multi-word output
+        fn correct_tokens(&self, tokens: RArray, options: RHash) -> Result<Value, Error> {
+            // Optimize batch correction by loading the snapshot once for all tokens
+            // instead of calling correct_if_unknown per token (which loads it each time)
+            let ruby = Ruby::get().unwrap();
+            let resolve_aliases = option_flag(options, "resolve_aliases")?;
+            let details = option_flag(options, "details")?;
+            let last_token = last_token_from_hash(&ruby, options)?;
+            let on_error = OnError::from_hash(&ruby, options)?;
+            let unknown: Option<String> = match options.get("unknown") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let remove_unknown = match unknown.as_deref() {
+                None | Some("keep") => false,
+                Some("remove") => true,
+                Some(other) => {
+                    return Err(Error::new(
+                        ruby.exception_arg_error(),
+                        format!("unknown must be \"keep\" or \"remove\", got: {}", other),
+                    ))
+                }
+            };
+            let expand = option_flag(options, "expand")?;
+            let alignment = option_flag(options, "alignment")?;
+            let dry_run = option_flag(options, "dry_run")?;
+            let threads: usize = match options.get("threads") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 1,
+            };
+
+            if details && (remove_unknown || expand) {
+                return Err(Error::new(ruby.exception_arg_error(), "unknown: \"remove\" and expand: true only apply without details"));
+            }
+            if dry_run && (details || remove_unknown || expand || alignment) {
+                return Err(Error::new(
+                    ruby.exception_arg_error(),
+                    "dry_run: true can't be combined with details, unknown: \"remove\", expand, or alignment",
+                ));
+            }
+
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let output_mode = output_mode_from_hash(&ruby, options, engine.preserve_case())?;
+            let thresholds = thresholds_from_hash(&ruby, engine, options)?;
+            let decisions = token_decisions(engine, tokens, last_token, thresholds, threads);
+            self.counters.record_correct_tokens(decisions.iter().filter_map(|token| token.as_ref().ok()).map(|(_, decision)| decision.reason));
+
+            if dry_run {
+                return propose_token_changes(engine, decisions, output_mode, resolve_aliases, on_error)
+                    .map(|changes| ruby.into_value(changes));
+            }
+
+            let result = RArray::new();
+            let spans = RArray::new();
+
+            for token in decisions {
+                let start = result.len();
+                let (word, decision) = match token {
+                    Ok(token) => token,
+                    Err(e) => {
+                        on_error.push(result, Err::<Value, _>(e))?;
+                        if alignment {
+                            spans.push((start, result.len()))?;
+                        }
+                        continue;
+                    }
+                };
+                let (output, alias) = engine.token_output(&word, &decision, output_mode, resolve_aliases);
+
+                if details {
+                    result.push(decision_hash(&word, &decision, alias, output, engine)?)?;
+                } else if remove_unknown && matches!(decision.reason, Reason::BelowThreshold | Reason::NoCandidates | Reason::Ambiguous) {
+                    // Dropped: nothing uncorrectable reaches the output
+                } else if expand {
+                    for part in output.split_whitespace() {
+                        result.push(part)?;
+                    }
+                } else {
+                    result.push(output)?;
+                }
+
+                if alignment {
+                    spans.push((start, result.len()))?;
+                }
+            }
+
+            if !alignment {
+                return Ok(ruby.into_value(result));
+            }
 
-        if !state.loaded {
-            return Err(Error::new(ruby.exception_runtime_error(), "Dictionary not loaded. Call load! first"));
+            let aligned = RHash::new();
+            aligned.aset("tokens", result)?;
+            aligned.aset("alignment", spans)?;
+            Ok(ruby.into_value(aligned))
         }
 
-        let result = RArray::new();
+        fn correct_text(&self, text: String, options: RHash) -> Result<Value, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let override_tokenizer = tokenizer_from_hash(&ruby, options)?;
+            let tokenizer = override_tokenizer.as_ref().unwrap_or(engine.tokenizer());
+
+            let mode: Option<String> = match options.get("mode") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let html_mode = match mode.as_deref() {
+                None | Some("text") => false,
+                Some("html") => true,
+                Some(other) => {
+                    return Err(Error::new(
+                        ruby.exception_arg_error(),
+                        format!("mode must be \"text\" or \"html\", got: {}", other),
+                    ))
+                }
+            };
+            let last_token = last_token_from_hash(&ruby, options)?;
+
+            let cjk: Option<String> = match options.get("cjk") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let flag_cjk = match cjk.as_deref() {
+                None | Some("passthrough") => false,
+                Some("flag") => true,
+                Some(other) => {
+                    return Err(Error::new(
+                        ruby.exception_arg_error(),
+                        format!("cjk must be \"passthrough\" or \"flag\", got: {}", other),
+                    ))
+                }
+            };
+
+            if html_mode && last_token != LastToken::Normal {
+                return Err(Error::new(ruby.exception_arg_error(), "last_token is only supported in text mode"));
+            }
+            let guarded = match options.get("guard") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => true,
+            };
+
+            if option_flag(options, "dry_run")? {
+                return text_changes(engine.propose_text_changes(tokenizer, &text, html_mode, last_token, guarded))
+                    .map(|changes| ruby.into_value(changes));
+            }
+
+            let corrections = RArray::new();
+            let mut output = String::with_capacity(text.len());
+            let record = |original: &str, corrected: &str, start: usize, end: usize| {
+                let correction = RHash::new();
+                correction.aset("original", original)?;
+                correction.aset("corrected", corrected)?;
+                correction.aset("start", start)?;
+                correction.aset("end", end)?;
+                corrections.push(correction)
+            };
+
+            if html_mode {
+                engine.rewrite_html(tokenizer, &text, guarded, Some(&mut output), record)?;
+            } else {
+                engine.rewrite_text(tokenizer, &text, last_token, guarded, Some(&mut output), record)?;
+            }
+
+            let result = RHash::new();
+            result.aset("text", output)?;
+            result.aset("corrections", corrections)?;
+
+            if flag_cjk {
+                let runs = RArray::new();
+                let text_ranges = if html_mode {
+                    html::segments(&text)
+                        .into_iter()
+                        .filter_map(|segment| match segment {
+                            html::Segment::Text(start, end) => Some((start, end)),
+                            html::Segment::Raw(..) => None,
+                        })
+                        .collect()
+                } else {
+                    vec![(0, text.len())]
+                };
+
+                for (offset, end) in text_ranges {
+                    for (start, run_end) in unsegmented_runs(&text[offset..end]) {
+                        let run = RHash::new();
+                        run.aset("text", &text[offset + start..offset + run_end])?;
+                        run.aset("start", offset + start)?;
+                        run.aset("end", offset + run_end)?;
+                        runs.push(run)?;
+                    }
+                }
+                result.aset("cjk", runs)?;
+            }
+
+            Ok(ruby.into_value(result))
+        }
+
+        // Applies changes proposed by correct_text(dry_run: true), given as [start, end, original,
+        // proposed] rows. Offsets are validated against `text` (see tokenizer::apply_changes)
+        fn apply_changes(&self, text: String, rows: Vec<(usize, usize, String, String)>) -> Result<String, Error> {
+            let ruby = Ruby::get().unwrap();
+            let changes = rows
+                .iter()
+                .map(|(start, end, original, proposed)| tokenizer::Change {
+                    start: *start,
+                    end: *end,
+                    original,
+                    proposed,
+                })
+                .collect();
+
+            tokenizer::apply_changes(&text, changes).map_err(|message| Error::new(ruby.exception_arg_error(), message))
+        }
 
-        if let Some(ref symspell) = state.symspell {
-            for token in tokens.into_iter() {
-                let word: String = TryConvert::try_convert(token)?;
-                let corrected = correct_word(&state, symspell, &word);
-                result.push(corrected)?;
+        // Bulk correction of separator-delimited fields in one string, with no per-field Ruby objects.
+        // Splitting and rejoining on the same separator keeps empty fields and any trailing separator
+        fn correct_lines(&self, input: String, options: RHash) -> Result<String, Error> {
+            let ruby = Ruby::get().unwrap();
+            let separator: String = match options.get("separator") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => "\n".to_string(),
+            };
+            let mode: Option<String> = match options.get("mode") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let text_mode = match mode.as_deref() {
+                None | Some("token") => false,
+                Some("text") => true,
+                Some(other) => {
+                    return Err(Error::new(
+                        ruby.exception_arg_error(),
+                        format!("mode must be \"token\" or \"text\", got: {}", other),
+                    ))
+                }
+            };
+
+            if separator.is_empty() {
+                return Err(Error::new(ruby.exception_arg_error(), "separator cannot be empty"));
+            }
+
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.correct_lines(&input, &separator, text_mode))
+        }
+
+        fn tokenize(&self, text: String, options: RHash) -> Result<RArray, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+
+            // Before the first load! the default tokenizer applies
+            let override_tokenizer = tokenizer_from_hash(&ruby, options)?;
+            let tokenizer = match (override_tokenizer.as_ref(), state.engine.as_ref()) {
+                (Some(tokenizer), _) => tokenizer,
+                (None, Some(engine)) => engine.tokenizer(),
+                (None, None) => &Tokenizer::Unicode,
+            };
+
+            let result = RArray::new();
+            for (_, token) in tokenizer.tokens(&text) {
+                result.push(token)?;
             }
 
             Ok(result)
-        } else {
-            Err(Error::new(ruby.exception_runtime_error(), "SymSpell not initialized"))
         }
-    }
 
-    fn stats(&self) -> Result<RHash, Error> {
-        let state = self.state.read().unwrap();
-        let stats = RHash::new();
+        // Replays a corpus through the correction pipeline, writing one TSV row per corrected token
+        // (original, corrected, distance, freq, reason). Input and output are both streamed
+        fn evaluate_corpus(&self, input_path: String, output_path: String, options: RHash) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let override_tokenizer = tokenizer_from_hash(&ruby, options)?;
+            let tokenizer = override_tokenizer.as_ref().unwrap_or(engine.tokenizer());
 
-        if !state.loaded {
-            stats.aset("loaded", false)?;
-            return Ok(stats);
+            let summary = engine.evaluate_corpus(tokenizer, &input_path, &output_path)?;
+
+            let reasons = RHash::new();
+            for reason in Reason::ALL {
+                reasons.aset(reason.as_str(), summary.count(reason))?;
+            }
+
+            let result = RHash::new();
+            result.aset("lines", summary.lines)?;
+            result.aset("tokens", summary.tokens)?;
+            result.aset("changed", summary.count(Reason::Corrected))?;
+            result.aset("reasons", reasons)?;
+            Ok(result)
         }
 
-        stats.aset("loaded", true)?;
-        stats.aset("dictionary_size", state.dictionary_size)?;
-        stats.aset("edit_distance", state.edit_distance)?;
-        stats.aset("skipped_malformed", state.skipped_malformed)?;
-        stats.aset("skipped_multiword", state.skipped_multiword)?;
-        stats.aset("skipped_invalid_freq", state.skipped_invalid_freq)?;
-        stats.aset("skipped_duplicates", state.skipped_duplicates)?;
+        // Realistic typos of `word` for test data; see SymSpell::perturb
+        fn perturb(&self, word: String, options: RHash) -> Result<RArray, Error> {
+            let ruby = Ruby::get().unwrap();
+            let distance: usize = match options.get("distance") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 1,
+            };
+            let count: usize = match options.get("count") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 5,
+            };
+            let seed: u64 = match options.get("seed") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 0,
+            };
+            let allow_known = option_flag(options, "allow_known")?;
+
+            if distance == 0 {
+                return Err(Error::new(ruby.exception_arg_error(), "distance must be at least 1"));
+            }
 
-        if let Some(loaded_at) = state.loaded_at {
-            stats.aset("loaded_at", loaded_at)?;
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(RArray::from_vec(engine.symspell().perturb(&word, distance, count, seed, allow_known)))
         }
 
-        Ok(stats)
-    }
+        // Dictionary words for synthetic corpora; see SymSpell::sample
+        fn sample_words(&self, count: usize, options: RHash) -> Result<RArray, Error> {
+            let ruby = Ruby::get().unwrap();
+            let seed: u64 = match options.get("seed") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 0,
+            };
+            let weighted = option_flag(options, "weighted")?;
 
-    fn healthcheck(&self) -> Result<(), Error> {
-        let ruby = Ruby::get().unwrap();
-        let state = self.state.read().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(RArray::from_vec(engine.symspell().sample(count, seed, weighted)))
+        }
+
+        // Writes the current dictionary's index for load!(index_path:); see Engine::save_index
+        fn save_index(&self, path: String) -> Result<(), Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.save_index(&path)?)
+        }
+
+        fn export_dictionary(&self, path: String) -> Result<(), Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.export_dictionary(&path)?)
+        }
+
+        // Writes the user layer alone; see Engine::export_user_dictionary
+        fn export_user_dictionary(&self, path: String) -> Result<(), Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(engine.export_user_dictionary(&path)?)
+        }
+
+        // [canonical, frequency] pairs, most frequent first, up to `limit`
+        fn words(&self, limit: Option<usize>) -> Result<RArray, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let entries = engine.symspell().ranked_entries();
+            let limit = limit.unwrap_or(entries.len());
+            Ok(ruby.ary_from_iter(entries.into_iter().take(limit).map(|(_, canonical, frequency)| (canonical, frequency))))
+        }
+
+        // Warms the current dictionary's index; see Engine::warmup
+        fn warmup(&self, options: RHash) -> Result<Value, Error> {
+            let ruby = Ruby::get().unwrap();
+            let sample: usize = match options.get("sample") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => DEFAULT_WARMUP_SAMPLE,
+            };
+            let seed: u64 = match options.get("seed") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 0,
+            };
+
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            json_to_ruby(&ruby, &warmup_value(&engine.warmup(sample, seed)))
+        }
 
-        if !state.loaded {
-            return Err(Error::new(ruby.exception_runtime_error(), "Dictionary not loaded"));
+        // Runs [input, expected] pairs through the full correction pipeline. Failures carry the
+        // decision reason so a regression can be told apart from a threshold or guard change
+        fn run_golden(&self, pairs: RArray) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let pairs: Vec<(String, String)> = pairs.into_iter().map(TryConvert::try_convert).collect::<Result<_, _>>()?;
+            let report = engine.run_golden(pairs);
+
+            let failed = RArray::new();
+            for failure in report.failed {
+                let hash = RHash::new();
+                hash.aset("input", failure.input)?;
+                hash.aset("expected", failure.expected)?;
+                hash.aset("got", failure.got)?;
+                hash.aset("reason", failure.reason.as_str())?;
+                failed.push(hash)?;
+            }
+
+            let result = RHash::new();
+            result.aset("passed", report.passed)?;
+            result.aset("failed", failed)?;
+            Ok(result)
         }
 
-        if state.symspell.is_none() {
-            return Err(Error::new(ruby.exception_runtime_error(), "SymSpell not initialized"));
+        // Engine::behavior_digest, computed once per state
+        fn behavior_digest(&self) -> Result<String, Error> {
+            let ruby = Ruby::get().unwrap();
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            Ok(state.behavior_digest.get_or_init(|| engine.behavior_digest()).clone())
         }
 
+        // Stats as JSON, the single source for both `stats` and `stats_json`
+        fn stats_value(&self) -> Json {
+            let state = self.state.load_full();
+            let mut stats = Map::new();
+
+            stats.insert("loaded".into(), state.engine.is_some().into());
+            stats.insert("loading".into(), self.loading.load(Ordering::Relaxed).into());
+            stats.insert("generation".into(), state.generation.into());
+
+            let Some(engine) = state.engine.as_ref() else {
+                return Json::Object(stats);
+            };
+
+            stats.insert("dictionary_size".into(), engine.dictionary_size().into());
+            stats.insert("edit_distance".into(), engine.edit_distance().into());
+            stats.insert("max_suggestions".into(), engine.max_suggestions().into());
+            stats.insert("skip_tokens_with_digits".into(), engine.thresholds().skip_tokens_with_digits.into());
+            stats.insert("min_word_length".into(), engine.thresholds().min_word_length.into());
+            stats.insert("skip_all_caps".into(), engine.thresholds().skip_all_caps.into());
+            stats.insert("skipped_malformed".into(), engine.load_stats().skipped_malformed.into());
+            stats.insert("skipped_multiword".into(), engine.load_stats().skipped_multiword.into());
+            stats.insert("skipped_invalid_freq".into(), engine.load_stats().skipped_invalid_freq.into());
+            stats.insert("skipped_duplicates".into(), engine.load_stats().skipped_duplicates.into());
+            stats.insert("skipped_long_lines".into(), engine.load_stats().skipped_long_lines.into());
+            stats.insert("skipped_long_words".into(), engine.load_stats().skipped_long_words.into());
+            stats.insert("format".into(), engine.load_stats().format.map(DictionaryFormat::name).into());
+            stats.insert("tokenizer".into(), engine.tokenizer().name().into());
+            stats.insert("compact".into(), engine.symspell().is_compact().into());
+            stats.insert("index_strategy".into(), engine.index_strategy().name().into());
+            stats.insert("prefix_length".into(), engine.symspell().prefix_length().into());
+            stats.insert("frequency_combine".into(), engine.symspell().combine().name().into());
+            stats.insert("distance_metric".into(), engine.symspell().metric().name().into());
+            stats.insert("symbolize_keys".into(), state.symbolize_keys.into());
+            let normalizer = engine.normalizer();
+            stats.insert("strip_accents".into(), normalizer.strip_accents.into());
+            stats.insert("case_sensitive".into(), (!normalizer.lowercase).into());
+            let mut normalize = Map::new();
+            normalize.insert("form".into(), normalizer.form.name().into());
+            normalize.insert("lowercase".into(), normalizer.lowercase.into());
+            normalize.insert("strip_accents".into(), normalizer.strip_accents.into());
+            normalize.insert("strip_punctuation".into(), normalizer.strip_punctuation.into());
+            stats.insert("normalize".into(), Json::Object(normalize));
+            stats.insert("tie_break".into(), engine.tie_break.name().into());
+            stats.insert("units".into(), engine.units.mode().name().into());
+            stats.insert("protected_overlap".into(), engine.load_stats().protected_overlap.clone().into());
+            stats.insert("aliases".into(), engine.aliases.len().into());
+            stats.insert("denylist".into(), engine.denylist.len().into());
+            stats.insert("boosts".into(), engine.boosts().len().into());
+
+            let mut guard_hits = Map::new();
+            for kind in GuardKind::ORDER {
+                guard_hits.insert(kind.as_str().into(), engine.guards.hits(kind).into());
+            }
+            stats.insert("guard_order".into(), GuardKind::ORDER.iter().map(|k| k.as_str()).collect());
+            stats.insert("guard_hits".into(), Json::Object(guard_hits));
+            stats.insert("skip_presets".into(), engine.skip_presets().into());
+            stats.insert("latency".into(), engine.latency().map_or(Json::Null, latency_value));
+            stats.insert("warmup".into(), engine.load_stats().warmup.as_ref().map_or(Json::Null, warmup_value));
+
+            let mut deletes_bytes = Map::new();
+            deletes_bytes.insert("ids".into(), engine.load_stats().deletes_bytes.into());
+            deletes_bytes.insert("strings".into(), engine.load_stats().string_deletes_bytes.into());
+            stats.insert("deletes_bytes".into(), Json::Object(deletes_bytes));
+            let load_stats = engine.load_stats();
+            stats.insert("estimated_bytes".into(), load_stats.estimated_bytes.into());
+            stats.insert("delete_entries".into(), load_stats.delete_entries.into());
+            stats.insert(
+                "average_bucket_size".into(),
+                (load_stats.delete_entries as f64 / load_stats.index_entries.max(1) as f64).into(),
+            );
+
+            let source = engine.source();
+            let mut block = Map::new();
+            block.insert("kind".into(), source.kind.clone().into());
+            block.insert("path_or_url".into(), source.path_or_url.clone().into());
+            block.insert("sha256".into(), source.sha256.clone().into());
+            block.insert("bytes".into(), source.bytes.into());
+            block.insert("compiled_version".into(), Json::Null);
+            stats.insert("source".into(), Json::Object(block));
+
+            if let Some(loaded_at) = engine.load_stats().loaded_at {
+                stats.insert("loaded_at".into(), loaded_at.into());
+            }
+
+            let mut generations = self.generations.lock().unwrap();
+            generations.prune();
+            let live: Vec<Json> = generations
+                .live
+                .iter()
+                .filter_map(|(weak, installed)| {
+                    let live = weak.upgrade()?;
+                    let mut entry = Map::new();
+                    entry.insert("generation".into(), live.generation.into());
+                    entry.insert("current".into(), Arc::ptr_eq(&live, &state).into());
+                    entry.insert("age_s".into(), installed.elapsed().as_secs_f64().into());
+                    entry.insert(
+                        "estimated_bytes".into(),
+                        live.engine.as_ref().map_or(0, |e| e.load_stats().estimated_bytes).into(),
+                    );
+                    Some(Json::Object(entry))
+                })
+                .collect();
+            stats.insert("generations".into(), live.into());
+            stats.insert("retired_generations_total".into(), generations.retired.into());
+
+            let mut metrics = Map::new();
+            for (name, count) in self.counters.values() {
+                metrics.insert(name.into(), count.into());
+            }
+            stats.insert("metrics".into(), Json::Object(metrics));
+
+            Json::Object(stats)
+        }
+
+        // Prometheus text format, built from the same counters as stats. Options: "prefix" for
+        // metric names and an optional "checker" label added to every sample
+        fn metrics_text(&self, options: RHash) -> Result<String, Error> {
+            let prefix: String = match options.get("prefix") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => "spellkit".to_string(),
+            };
+            let checker: Option<String> = match options.get("checker") {
+                Some(v) => Some(TryConvert::try_convert(v)?),
+                None => None,
+            };
+            let labels: Vec<(&str, &str)> = checker.iter().map(|name| ("checker", name.as_str())).collect();
+            let mut exposition = Exposition::new(&prefix, &labels);
+
+            let state = self.state.load_full();
+            let flag = |value: bool| if value { 1.0 } else { 0.0 };
+            exposition.gauge("loaded", "Whether a dictionary is loaded", flag(state.engine.is_some()));
+            exposition.gauge("loading", "Whether a load is in progress", flag(self.loading.load(Ordering::Relaxed)));
+            exposition.gauge("generation", "Successful loads into this checker", state.generation as f64);
+
+            let mut generations = self.generations.lock().unwrap();
+            generations.prune();
+            exposition.gauge("live_generations", "Loaded generations still in memory", generations.live.len() as f64);
+            exposition.counter(
+                "retired_generations_total",
+                "Replaced generations that have been freed",
+                &[(&[], generations.retired)],
+            );
+            drop(generations);
+
+            if let Some(engine) = state.engine.as_ref() {
+                exposition.gauge("dictionary_size", "Words in the dictionary", engine.dictionary_size() as f64);
+                exposition.gauge(
+                    "estimated_bytes",
+                    "Approximate size of the index and aliases",
+                    engine.load_stats().estimated_bytes as f64,
+                );
+
+                let guard_labels: Vec<[(&str, &str); 1]> =
+                    GuardKind::ORDER.iter().map(|kind| [("guard", kind.as_str())]).collect();
+                let hits: Vec<(&[(&str, &str)], u64)> = GuardKind::ORDER
+                    .iter()
+                    .zip(&guard_labels)
+                    .map(|(kind, labels)| (&labels[..], engine.guards.hits(*kind)))
+                    .collect();
+                exposition.counter("protected_total", "Tokens protected, by the guard that protected them", &hits);
+
+                if let Some(latency) = engine.latency() {
+                    exposition.histogram("suggest_seconds", "Latency per suggested word", &latency.suggest);
+                    exposition.histogram("correct_tokens_seconds", "Latency per corrected token", &latency.correct_tokens);
+                }
+            }
+
+            Ok(exposition.finish())
+        }
+
+        fn stats(&self) -> Result<Value, Error> {
+            json_to_ruby(&Ruby::get().unwrap(), &self.stats_value())
+        }
+
+        // Pre-serialized stats for monitoring endpoints: no Ruby objects besides the string
+        fn stats_json(&self) -> String {
+            self.stats_value().to_string()
+        }
+
+        // Clears guard hits and latency histograms of the current dictionary; a no-op before load!
+        fn reset_stats(&self) {
+            if let Some(ref engine) = self.state.load_full().engine {
+                engine.reset_stats();
+            }
+        }
+
+        // Zeroes the call and token counts under "metrics" in stats, shared with snapshots
+        fn reset_metrics(&self) {
+            self.counters.reset();
+        }
+
+        // `value` as the Ruby side built it, or with symbolize_keys: true at load! with every
+        // String key of its Hashes, nested ones included, made a Symbol. Keys follow the state
+        // this checker (or snapshot) serves
+        fn keyed(&self, value: Value) -> Result<Value, Error> {
+            if !self.state.load_full().symbolize_keys {
+                return Ok(value);
+            }
+            symbolize_keys(&Ruby::get().unwrap(), value)
+        }
+
+        fn healthcheck(&self) -> Result<(), Error> {
+            let ruby = Ruby::get().unwrap();
+
+            match self.state.load_full().health_error() {
+                Some(message) => Err(Error::new(spellkit_error(&ruby, "NotLoadedError"), message)),
+                None => Ok(()),
+            }
+        }
+
+        // Verifies the next "batch" dictionary entries, spending at most about "time_budget" seconds,
+        // and reports progress through the whole dictionary: {"healthy", "checked", "verified",
+        // "total", "coverage", "passes", "failures"}. Wraps around after the last entry
+        fn healthcheck_probe(&self, options: RHash) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let batch: usize = match options.get("batch") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 100,
+            };
+            let budget: f64 = match options.get("time_budget") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 0.01,
+            };
+            let budget = Duration::try_from_secs_f64(budget).map_err(|_| {
+                Error::new(ruby.exception_arg_error(), "time_budget must be a non-negative number of seconds")
+            })?;
+
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+            let mut probe = state.probe.lock().unwrap();
+
+            let verification = engine.verify(probe.cursor, batch, budget);
+            probe.cursor += verification.checked;
+            for failure in verification.failures {
+                if !probe.failures.contains(&failure) {
+                    probe.failures.push(failure);
+                }
+            }
+            if probe.cursor >= verification.total {
+                probe.cursor = 0;
+                probe.passes += 1;
+            }
+
+            let verified = if probe.passes > 0 { verification.total } else { probe.cursor };
+            let coverage = if verification.total == 0 { 100.0 } else { verified as f64 * 100.0 / verification.total as f64 };
+
+            let result = RHash::new();
+            result.aset("healthy", probe.failures.is_empty())?;
+            result.aset("checked", verification.checked)?;
+            result.aset("verified", verified)?;
+            result.aset("total", verification.total)?;
+            result.aset("coverage", coverage)?;
+            result.aset("passes", probe.passes)?;
+            result.aset("failures", RArray::from_vec(probe.failures.clone()))?;
+            Ok(result)
+        }
+
+        // Runs "probes" seeded dictionary words, each with a typo, through suggestions and
+        // returns {"status", "dictionary_size", "probes", "probes_passed", "probe_latency_us"},
+        // the latency being the mean per query. Raises if any word isn't suggested back or the
+        // index panics
+        fn healthcheck_deep(&self, options: RHash) -> Result<RHash, Error> {
+            let ruby = Ruby::get().unwrap();
+            let probes: usize = match options.get("probes") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 10,
+            };
+            let seed: u64 = match options.get("seed") {
+                Some(v) => TryConvert::try_convert(v)?,
+                None => 0,
+            };
+
+            let state = self.state.load_full();
+            let engine = state.engine(&ruby)?;
+
+            let probe = panic::catch_unwind(AssertUnwindSafe(|| engine.probe(probes, seed))).map_err(|payload| {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                Error::new(spellkit_error(&ruby, "HealthcheckError"), format!("Healthcheck probe panicked: {}", message))
+            })?;
+            if !probe.failures.is_empty() {
+                return Err(Error::new(
+                    spellkit_error(&ruby, "HealthcheckError"),
+                    format!(
+                        "Healthcheck probe failed: {} of {} sampled words weren't suggested from a typo: {}",
+                        probe.failures.len(),
+                        probe.probes,
+                        probe.failures.join(", ")
+                    ),
+                ));
+            }
+
+            let latency_us = if probe.probes == 0 { 0.0 } else { probe.elapsed.as_secs_f64() * 1e6 / probe.probes as f64 };
+            let result = RHash::new();
+            result.aset("status", "ok")?;
+            result.aset("dictionary_size", engine.dictionary_size())?;
+            result.aset("probes", probe.probes)?;
+            result.aset("probes_passed", probe.probes - probe.failures.len())?;
+            result.aset("probe_latency_us", latency_us)?;
+            Ok(result)
+        }
+
+        // Never raises: {"healthy", "error", "loading", "generation"}
+        fn healthcheck_json(&self) -> String {
+            let state = self.state.load_full();
+            let error = state.health_error();
+            let mut health = Map::new();
+
+            health.insert("healthy".into(), error.is_none().into());
+            health.insert("error".into(), error.into());
+            health.insert("loading".into(), self.loading.load(Ordering::Relaxed).into());
+            health.insert("generation".into(), state.generation.into());
+            Json::Object(health).to_string()
+        }
+    }
+
+    #[magnus::init]
+    fn init(ruby: &Ruby) -> Result<(), Error> {
+        let module = define_module("SpellKit")?;
+
+        // Everything the extension raises on its own account; rescue SpellKit::Error catches them all
+        let error = module.define_error("Error", ruby.exception_standard_error())?;
+        module.define_error("NotLoadedError", error)?;
+        module.define_error("DictionaryError", error)?;
+        module.define_error("DictionaryParseError", error)?;
+        module.define_error("InvalidPatternError", error)?;
+        module.define_error("HealthcheckError", error)?;
+        let checker_class = module.define_class("Checker", class::object())?;
+
+        checker_class.define_singleton_method("new", function!(Checker::new, 0))?;
+        checker_class.define_singleton_method("closest_option", function!(Checker::closest_option, 2))?;
+        checker_class.define_singleton_method("_rust_train_from_text", function!(Checker::train_from_text, 3))?;
+        checker_class.define_method("load!", method!(Checker::load_full, 1))?;
+        checker_class.define_method("suggestions", method!(Checker::suggestions, 3))?;
+        checker_class.define_method("suggest_many", method!(Checker::suggest_many, 3))?;
+        checker_class.define_method("correct?", method!(Checker::correct, 1))?;
+        checker_class.define_method("correct", method!(Checker::correct_if_unknown, 2))?;
+        checker_class.define_method("lookup", method!(Checker::lookup, 1))?;
+        checker_class.define_method("frequency", method!(Checker::frequency, 1))?;
+        checker_class.define_method("explain", method!(Checker::explain, 2))?;
+        checker_class.define_method("correct_unique", method!(Checker::correct_unique, 2))?;
+        checker_class.define_method("correct_phrase", method!(Checker::correct_phrase, 1))?;
+        checker_class.define_method("segment", method!(Checker::segment, 2))?;
+        checker_class.define_method("define_set", method!(Checker::define_set, 2))?;
+        checker_class.define_method("add_word!", method!(Checker::add_word, 2))?;
+        checker_class.define_method("remove_word!", method!(Checker::remove_word, 1))?;
+        checker_class.define_method("protect!", method!(Checker::protect, 1))?;
+        checker_class.define_method("unprotect!", method!(Checker::unprotect, 1))?;
+        checker_class.define_method("protected?", method!(Checker::is_protected, 1))?;
+        checker_class.define_method("snapshot", method!(Checker::snapshot, 0))?;
+        checker_class.define_method("correct_tokens", method!(Checker::correct_tokens, 2))?;
+        checker_class.define_method("correct_text", method!(Checker::correct_text, 2))?;
+        checker_class.define_method("apply_changes", method!(Checker::apply_changes, 2))?;
+        checker_class.define_method("correct_lines", method!(Checker::correct_lines, 2))?;
+        checker_class.define_method("tokenize", method!(Checker::tokenize, 2))?;
+        checker_class.define_method("evaluate_corpus", method!(Checker::evaluate_corpus, 3))?;
+        checker_class.define_method("perturb", method!(Checker::perturb, 2))?;
+        checker_class.define_method("sample_words", method!(Checker::sample_words, 2))?;
+        checker_class.define_method("warmup", method!(Checker::warmup, 1))?;
+        checker_class.define_method("save_index", method!(Checker::save_index, 1))?;
+        checker_class.define_method("export_dictionary", method!(Checker::export_dictionary, 1))?;
+        checker_class.define_method("export_user_dictionary", method!(Checker::export_user_dictionary, 1))?;
+        checker_class.define_method("words", method!(Checker::words, 1))?;
+        checker_class.define_method("run_golden", method!(Checker::run_golden, 1))?;
+        checker_class.define_method("behavior_digest", method!(Checker::behavior_digest, 0))?;
+        checker_class.define_method("stats", method!(Checker::stats, 0))?;
+        checker_class.define_method("metrics_text", method!(Checker::metrics_text, 1))?;
+        checker_class.define_method("reset_stats!", method!(Checker::reset_stats, 0))?;
+        checker_class.define_method("reset_metrics!", method!(Checker::reset_metrics, 0))?;
+        checker_class.define_method("keyed", method!(Checker::keyed, 1))?;
+        checker_class.define_method("healthcheck", method!(Checker::healthcheck, 0))?;
+        checker_class.define_method("healthcheck_probe", method!(Checker::healthcheck_probe, 1))?;
+        checker_class.define_method("healthcheck_deep", method!(Checker::healthcheck_deep, 1))?;
+        checker_class.define_method("stats_json", method!(Checker::stats_json, 0))?;
+        checker_class.define_method("healthcheck_json", method!(Checker::healthcheck_json, 0))?;
+
+        let suggestion_class = module.define_class("Suggestion", class::object())?;
+        suggestion_class.undef_default_alloc_func();
+        suggestion_class.define_method("term", method!(RubySuggestion::term, 0))?;
+        suggestion_class.define_method("distance", method!(RubySuggestion::distance, 0))?;
+        suggestion_class.define_method("frequency", method!(RubySuggestion::frequency, 0))?;
+        suggestion_class.define_method("score", method!(RubySuggestion::score, 0))?;
+        suggestion_class.define_method("display_truncated", method!(RubySuggestion::display_truncated, 0))?;
+        suggestion_class.define_method("user?", method!(RubySuggestion::user, 0))?;
+        suggestion_class.define_method("<=>", method!(RubySuggestion::compare, 1))?;
+
         Ok(())
     }
 }
-
-#[magnus::init]
-fn init(_ruby: &Ruby) -> Result<(), Error> {
-    let module = define_module("SpellKit")?;
-    let checker_class = module.define_class("Checker", class::object())?;
-
-    checker_class.define_singleton_method("new", function!(Checker::new, 0))?;
-    checker_class.define_method("load!", method!(Checker::load_full, 1))?;
-    checker_class.define_method("suggestions", method!(Checker::suggestions, 2))?;
-    checker_class.define_method("correct?", method!(Checker::correct, 1))?;
-    checker_class.define_method("correct", method!(Checker::correct_if_unknown, 1))?;
-    checker_class.define_method("correct_tokens", method!(Checker::correct_tokens, 1))?;
-    checker_class.define_method("stats", method!(Checker::stats, 0))?;
-    checker_class.define_method("healthcheck", method!(Checker::healthcheck, 0))?;
-
-    Ok(())
-}
\ No newline at end of file
@@ -0,0 +1,110 @@
+use crate::latency::{Histogram, BUCKETS};
+use std::fmt::Write;
+
+/// Builds Prometheus text exposition format. Every sample carries the same base labels, so one
+/// scrape can hold several checkers told apart by a `checker` label
+pub struct Exposition {
+    out: String,
+    prefix: String,
+    labels: Vec<(String, String)>,
+}
+
+impl Exposition {
+    pub fn new(prefix: &str, labels: &[(&str, &str)]) -> Self {
+        Self {
+            out: String::new(),
+            prefix: prefix.to_string(),
+            labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, value: f64) {
+        self.header(name, help, "gauge");
+        self.sample(name, &[], value);
+    }
+
+    /// One counter with a sample per label set; `name` should end in `_total`
+    pub fn counter(&mut self, name: &str, help: &str, samples: &[(&[(&str, &str)], u64)]) {
+        self.header(name, help, "counter");
+        for (labels, value) in samples {
+            self.sample(name, labels, *value as f64);
+        }
+    }
+
+    /// A latency histogram in seconds, with cumulative buckets at the `Histogram` bounds
+    pub fn histogram(&mut self, name: &str, help: &str, histogram: &Histogram) {
+        self.header(name, help, "histogram");
+        let counts = histogram.counts();
+        let bucket_name = format!("{}_bucket", name);
+        let mut cumulative = 0;
+        for (bound, count) in Histogram::upper_bounds().iter().zip(counts.iter()) {
+            cumulative += count;
+            let le = (*bound as f64 / 1e6).to_string();
+            self.sample(&bucket_name, &[("le", &le)], cumulative as f64);
+        }
+        cumulative += counts[BUCKETS - 1];
+        self.sample(&bucket_name, &[("le", "+Inf")], cumulative as f64);
+        self.sample(&format!("{}_sum", name), &[], histogram.sum_micros() as f64 / 1e6);
+        self.sample(&format!("{}_count", name), &[], cumulative as f64);
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn header(&mut self, name: &str, help: &str, kind: &str) {
+        let _ = writeln!(self.out, "# HELP {}_{} {}", self.prefix, name, help);
+        let _ = writeln!(self.out, "# TYPE {}_{} {}", self.prefix, name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let _ = write!(self.out, "{}_{}", self.prefix, name);
+        let all: Vec<(&str, &str)> = self
+            .labels
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .chain(labels.iter().copied())
+            .collect();
+        if !all.is_empty() {
+            let pairs: Vec<String> = all.iter().map(|(k, v)| format!("{}=\"{}\"", k, escape(v))).collect();
+            let _ = write!(self.out, "{{{}}}", pairs.join(","));
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+}
+
+// Label values escape backslash, double quote, and newline
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_exposition_format() {
+        let mut exposition = Exposition::new("spellkit", &[("checker", "a\"b")]);
+        exposition.gauge("dictionary_size", "Words in the dictionary", 20.0);
+        exposition.counter("protected_total", "Protected tokens", &[(&[("guard", "set")], 3)]);
+
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_micros(3));
+        histogram.record(Duration::from_secs(3600));
+        exposition.histogram("suggest_seconds", "Suggest latency", &histogram);
+
+        let text = exposition.finish();
+        assert!(text.contains("# TYPE spellkit_dictionary_size gauge\nspellkit_dictionary_size{checker=\"a\\\"b\"} 20\n"));
+        assert!(text.contains("spellkit_protected_total{checker=\"a\\\"b\",guard=\"set\"} 3\n"));
+        assert!(text.contains("spellkit_suggest_seconds_bucket{checker=\"a\\\"b\",le=\"0.000002\"} 0\n"));
+        assert!(text.contains("spellkit_suggest_seconds_bucket{checker=\"a\\\"b\",le=\"0.000004\"} 1\n"));
+        assert!(text.contains("spellkit_suggest_seconds_bucket{checker=\"a\\\"b\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("spellkit_suggest_seconds_sum{checker=\"a\\\"b\"} 3600.000003\n"));
+        assert!(text.contains("spellkit_suggest_seconds_count{checker=\"a\\\"b\"} 2\n"));
+
+        let mut bare = Exposition::new("app", &[]);
+        bare.gauge("loaded", "Loaded", 1.0);
+        assert_eq!(bare.finish(), "# HELP app_loaded Loaded\n# TYPE app_loaded gauge\napp_loaded 1\n");
+    }
+}
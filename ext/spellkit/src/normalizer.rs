@@ -0,0 +1,191 @@
+use std::fmt::{self, Write};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use crate::tokenizer::STRIPPED_PUNCTUATION;
+
+// Unicode normalization form applied first. The compatibility forms also fold ligatures, full-width
+// letters, and the like ("ﬁ" -> "fi")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalForm {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "nfc" => Some(Self::Nfc),
+            "nfd" => Some(Self::Nfd),
+            "nfkc" => Some(Self::Nfkc),
+            "nfkd" => Some(Self::Nfkd),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Nfc => "nfc",
+            Self::Nfd => "nfd",
+            Self::Nfkc => "nfkc",
+            Self::Nfkd => "nfkd",
+        }
+    }
+
+    fn apply(self, word: &str) -> String {
+        match self {
+            Self::Nfc => word.nfc().collect(),
+            Self::Nfd => word.nfd().collect(),
+            Self::Nfkc => word.nfkc().collect(),
+            Self::Nfkd => word.nfkd().collect(),
+        }
+    }
+
+    fn is_compatibility(self) -> bool {
+        matches!(self, Self::Nfkc | Self::Nfkd)
+    }
+
+    fn is_composed(self) -> bool {
+        matches!(self, Self::Nfc | Self::Nfkc)
+    }
+}
+
+/// How words become keys, for dictionary entries and queries alike. One load uses one
+/// Normalizer everywhere: the index, guards, boosts, aliases, and `within:` sets. The default
+/// is NFKD with controls and whitespace dropped, lowercased
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalizer {
+    pub form: NormalForm,
+    pub lowercase: bool,
+    /// Drop combining marks, so "café" and "cafe" are the same key
+    pub strip_accents: bool,
+    /// Drop ASCII punctuation and the quotes, brackets, and dashes tokens are trimmed of
+    pub strip_punctuation: bool,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self {
+            form: NormalForm::Nfkd,
+            lowercase: true,
+            strip_accents: false,
+            strip_punctuation: false,
+        }
+    }
+}
+
+impl Normalizer {
+    pub fn normalize(&self, word: &str) -> String {
+        // Marks only come apart from their letters in a decomposed form
+        let decomposed = match (self.strip_accents, self.form.is_compatibility()) {
+            (true, true) => NormalForm::Nfkd.apply(word),
+            (true, false) => NormalForm::Nfd.apply(word),
+            (false, _) => self.form.apply(word),
+        };
+        let mut key: String = decomposed
+            .chars()
+            .filter(|&c| !c.is_control() && !c.is_whitespace())
+            .filter(|&c| !(self.strip_accents && is_combining_mark(c)))
+            .filter(|&c| !(self.strip_punctuation && is_punctuation(c)))
+            .collect();
+        if self.lowercase {
+            key = key.to_lowercase();
+        }
+        // Lowercasing can bring marks back ("İ" is "i" and a combining dot)
+        if self.strip_accents && key.chars().any(is_combining_mark) {
+            key.retain(|c| !is_combining_mark(c));
+        }
+        if self.strip_accents && self.form.is_composed() {
+            key = key.nfc().collect();
+        }
+        key
+    }
+
+    // The settings that differ from `other`'s, as "name: value" for messages
+    pub fn differences(&self, other: &Normalizer) -> String {
+        let mut out = String::new();
+        for (name, value, other_value) in [
+            ("form", self.form.name().to_string(), other.form.name().to_string()),
+            ("lowercase", self.lowercase.to_string(), other.lowercase.to_string()),
+            ("strip_accents", self.strip_accents.to_string(), other.strip_accents.to_string()),
+            ("strip_punctuation", self.strip_punctuation.to_string(), other.strip_punctuation.to_string()),
+        ] {
+            if value != other_value {
+                let _ = write!(out, "{}{}: {}", if out.is_empty() { "" } else { ", " }, name, value);
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for Normalizer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "form={} lowercase={} strip_accents={} strip_punctuation={}",
+            self.form.name(),
+            self.lowercase,
+            self.strip_accents,
+            self.strip_punctuation
+        )
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || STRIPPED_PUNCTUATION.contains(&c) || matches!(c, '\u{2010}'..='\u{2015}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_nfkd_lowercase() {
+        // The pipeline every key went through before normalization was configurable
+        let previous = |word: &str| -> String {
+            word.nfkd().filter(|c| !c.is_control() && !c.is_whitespace()).collect::<String>().to_lowercase()
+        };
+        let words = [
+            "Hello", "HELLO", "New York", "caf\u{e9}", "cafe\u{301}", "\u{fb01}ance", "\u{212b}ngstr\u{f6}m", "\u{130}stanbul",
+            "STRASSE", "stra\u{df}e", "\u{ff28}\u{ff25}\u{ff2c}\u{ff2c}\u{ff2f}", "tab\there", "e-mail", "rock'n'roll", "\u{2460}", "日本", "",
+        ];
+
+        let normalizer = Normalizer::default();
+        for word in words {
+            assert_eq!(normalizer.normalize(word), previous(word), "{:?}", word);
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let nfc = Normalizer { form: NormalForm::Nfc, lowercase: false, ..Normalizer::default() };
+        assert_eq!(nfc.normalize("Cafe\u{301}"), "Caf\u{e9}");
+        assert_eq!(nfc.normalize("\u{fb01}"), "\u{fb01}");
+        assert_eq!(Normalizer { form: NormalForm::Nfkc, ..nfc }.normalize("\u{fb01}"), "fi");
+
+        // Composed forms come back composed where no mark was stripped
+        let accentless = Normalizer { strip_accents: true, ..nfc };
+        assert_eq!(accentless.normalize("Caf\u{e9}"), "Cafe");
+        assert_eq!(accentless.normalize("\u{1100}\u{1161}"), "\u{ac00}");
+        assert_eq!(Normalizer { strip_accents: true, ..Normalizer::default() }.normalize("\u{130}STANBUL"), "istanbul");
+
+        let unpunctuated = Normalizer { strip_punctuation: true, ..Normalizer::default() };
+        assert_eq!(unpunctuated.normalize("E-Mail"), "email");
+        assert_eq!(unpunctuated.normalize("\u{201c}rock\u{2019}n\u{2019}roll\u{201d}"), "rocknroll");
+        assert_eq!(unpunctuated.normalize("a\u{2014}b"), "ab");
+    }
+
+    #[test]
+    fn test_differences() {
+        let default = Normalizer::default();
+        let other = Normalizer { form: NormalForm::Nfc, strip_punctuation: true, ..default };
+
+        assert_eq!(default.differences(&default), "");
+        assert_eq!(default.differences(&other), "form: nfkd, strip_punctuation: false");
+        assert_eq!(other.differences(&default), "form: nfc, strip_punctuation: true");
+        assert_eq!(default.to_string(), "form=nfkd lowercase=true strip_accents=false strip_punctuation=false");
+        assert!(["nfc", "nfd", "nfkc", "nfkd"].iter().all(|name| NormalForm::parse(name).map(NormalForm::name) == Some(*name)));
+        assert_eq!(NormalForm::parse("NFC"), None);
+    }
+}
@@ -1,18 +1,394 @@
-use hashbrown::{HashMap, HashSet};
+use crate::binary::{invalid, read_string, read_u64, read_u8, read_usize, write_str, write_u64, write_u8};
+use hashbrown::hash_table::HashTable;
+use hashbrown::{DefaultHashBuilder, HashMap, HashSet};
 use std::cmp::Ordering;
-use unicode_normalization::UnicodeNormalization;
+use std::hash::BuildHasher;
+use std::io::{self, Read, Write};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use crate::normalizer::{NormalForm, Normalizer};
 
+// Where a word's count came from. Layers are counted separately, so replacing one (reloading
+// the base dictionary) leaves the counts in the others alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layer {
+    Base,
+    User,
+    Trained,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 3] = [Layer::Base, Layer::User, Layer::Trained];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// How a count added to a word's layer combines with the count the layer already has
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Merge {
+    Sum,
+    Max,
+}
+
+// How an entry's layer counts combine into the one frequency used for ranking and thresholds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combine {
+    Sum,
+    Max,
+    BaseOnly,
+}
+
+impl Combine {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Self::Sum),
+            "max" => Some(Self::Max),
+            "base_only" => Some(Self::BaseOnly),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Max => "max",
+            Self::BaseOnly => "base_only",
+        }
+    }
+
+    fn apply(self, layers: [u64; 3]) -> u64 {
+        match self {
+            Self::Sum => layers.iter().fold(0u64, |total, &count| total.saturating_add(count)),
+            Self::Max => layers.into_iter().max().unwrap_or(0),
+            Self::BaseOnly => layers[Layer::Base.index()],
+        }
+    }
+}
+
+// How candidates are measured against the query. Damerau counts swapping two adjacent characters
+// as one edit ("teh" -> "the"), as optimal string alignment: no substring is edited twice
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceMetric {
+    Levenshtein,
+    Damerau,
+}
+
+impl DistanceMetric {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "levenshtein" => Some(Self::Levenshtein),
+            "damerau" => Some(Self::Damerau),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Levenshtein => "levenshtein",
+            Self::Damerau => "damerau",
+        }
+    }
+}
+
+// Text split into words by `segment`, with the natural log of the split's probability
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segmentation {
+    pub segments: Vec<String>,
+    pub log_probability: f64,
+}
+
+// A whitespace-separated term of a phrase for `lookup_compound`. Fixed terms are kept as
+// written; `joins_next` allows merging the term with the one after it
+#[derive(Debug, Clone)]
+pub struct PhraseTerm<'a> {
+    pub text: &'a str,
+    pub fixed: bool,
+    pub joins_next: bool,
+}
+
+// The output for `terms` consecutive input terms (2 for a merge, 1 otherwise). `distance` is the
+// edit distance of the words plus one for each space added or removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhraseSegment {
+    pub terms: usize,
+    pub output: String,
+    pub distance: usize,
+}
+
+// One reading of a term, as a correction or a split into two words, scored like `Split`
+struct Reading {
+    output: String,
+    distance: usize,
+    score: f64,
+}
+
+// `frequency` is the combined count. Per-layer counts are only kept once a layer other than the
+// base has one; until then the frequency is the base count under every combination
 #[derive(Debug, Clone)]
 pub struct WordEntry {
     pub canonical: String,
     pub frequency: u64,
+    layers: Option<Box<[u64; 3]>>,
+}
+
+// Compact-mode entry: counts divided by the store's scale and saturated at u32::MAX, and the
+// canonical form only kept when it differs from the normalized key
+#[derive(Debug, Clone)]
+struct CompactEntry {
+    canonical: Option<Box<str>>,
+    frequency: u32,
+    layers: Option<Box<[u32; 3]>>,
+}
+
+// (was the key new, its id, combined frequency before, combined frequency after) of one layer
+// update
+struct Added {
+    new: bool,
+    id: u32,
+    before: u64,
+    after: u64,
+}
+
+#[derive(Clone)]
+enum Entries {
+    Full(Vec<WordEntry>),
+    Compact { entries: Vec<CompactEntry>, scale: u64 },
+}
+
+// Each normalized key is stored once, in `keys`. A word's id is its position there and in
+// `entries`, and is what the deletes map's buckets hold; `ids` finds the id of a key
+#[derive(Clone)]
+struct WordStore {
+    keys: Vec<Box<str>>,
+    ids: HashTable<u32>,
+    hasher: DefaultHashBuilder,
+    entries: Entries,
+}
+
+impl WordStore {
+    fn new(entries: Entries) -> Self {
+        Self {
+            keys: Vec::new(),
+            ids: HashTable::new(),
+            hasher: DefaultHashBuilder::default(),
+            entries,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn id(&self, key: &str) -> Option<u32> {
+        let hash = self.hasher.hash_one(key);
+        self.ids.find(hash, |&id| &*self.keys[id as usize] == key).copied()
+    }
+
+    fn key(&self, id: u32) -> &str {
+        &self.keys[id as usize]
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.id(key).is_some()
+    }
+
+    // (canonical, frequency) as seen by callers; compact frequencies are scaled back up
+    fn get(&self, key: &str) -> Option<(&str, u64)> {
+        self.id(key).map(|id| self.entry(id))
+    }
+
+    fn entry(&self, id: u32) -> (&str, u64) {
+        let i = id as usize;
+        match self.entries {
+            Entries::Full(ref entries) => (entries[i].canonical.as_str(), entries[i].frequency),
+            Entries::Compact { ref entries, scale } => (
+                entries[i].canonical.as_deref().unwrap_or(&self.keys[i]),
+                entries[i].frequency as u64 * scale,
+            ),
+        }
+    }
+
+    // Heap and inline bytes of the keys, their ids, and the entries, with the id table's unused
+    // slots and control bytes
+    fn estimated_bytes(&self) -> usize {
+        let keys: usize = self.keys.iter().map(|k| size_of::<Box<str>>() + k.len()).sum::<usize>()
+            + self.ids.capacity() * (size_of::<u32>() + 1);
+        let entries: usize = match self.entries {
+            Entries::Full(ref entries) => entries
+                .iter()
+                .map(|e| {
+                    size_of::<WordEntry>()
+                        + e.canonical.capacity()
+                        + e.layers.as_ref().map_or(0, |_| size_of::<[u64; 3]>())
+                })
+                .sum(),
+            Entries::Compact { ref entries, .. } => entries
+                .iter()
+                .map(|e| {
+                    size_of::<CompactEntry>()
+                        + e.canonical.as_ref().map_or(0, |c| c.len())
+                        + e.layers.as_ref().map_or(0, |_| size_of::<[u32; 3]>())
+                })
+                .sum(),
+        };
+        keys + entries
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    // Per-layer counts as seen by callers, scaled back up in compact mode
+    fn layers(&self, key: &str) -> Option<[u64; 3]> {
+        let i = self.id(key)? as usize;
+        Some(match self.entries {
+            Entries::Full(ref entries) => {
+                let e = &entries[i];
+                e.layers.as_deref().copied().unwrap_or([e.frequency, 0, 0])
+            }
+            Entries::Compact { ref entries, scale } => {
+                let e = &entries[i];
+                e.layers
+                    .as_deref()
+                    .copied()
+                    .unwrap_or([e.frequency, 0, 0])
+                    .map(|count| count as u64 * scale)
+            }
+        })
+    }
+
+    // Whether the entry has a count in the user layer. A count that compact mode scales down to
+    // zero doesn't count
+    fn in_user_layer(&self, id: u32) -> bool {
+        let i = id as usize;
+        let user = Layer::User.index();
+        match self.entries {
+            Entries::Full(ref entries) => entries[i].layers.as_ref().is_some_and(|layers| layers[user] > 0),
+            Entries::Compact { ref entries, .. } => entries[i].layers.as_ref().is_some_and(|layers| layers[user] > 0),
+        }
+    }
+
+    // Gives a new key the next id. Ids only ever grow, so buckets that push them stay sorted
+    fn push_key(&mut self, key: &str) -> u32 {
+        let id = u32::try_from(self.keys.len()).expect("more than u32::MAX dictionary words");
+        let Self { keys, ids, hasher, .. } = self;
+        ids.insert_unique(hasher.hash_one(key), id, |&id| hasher.hash_one(&*keys[id as usize]));
+        keys.push(key.into());
+        id
+    }
+
+    // Removes a word's key and entry. The last word moves into the freed id; returns that word's
+    // key and the id it had, unless the removed word was the last
+    fn swap_remove(&mut self, id: u32) -> Option<(String, u32)> {
+        let hash = self.hasher.hash_one(&*self.keys[id as usize]);
+        if let Ok(entry) = self.ids.find_entry(hash, |&other| other == id) {
+            entry.remove();
+        }
+        let last = (self.keys.len() - 1) as u32;
+        self.keys.swap_remove(id as usize);
+        match self.entries {
+            Entries::Full(ref mut entries) => drop(entries.swap_remove(id as usize)),
+            Entries::Compact { ref mut entries, .. } => drop(entries.swap_remove(id as usize)),
+        }
+        if id == last {
+            return None;
+        }
+
+        let moved = &self.keys[id as usize];
+        if let Some(slot) = self.ids.find_mut(self.hasher.hash_one(&**moved), |&other| other == last) {
+            *slot = id;
+        }
+        Some((moved.to_string(), last))
+    }
+
+    // Adds `count` to one layer of an entry, inserting it if needed, and recombines its
+    // frequency. Duplicates within a layer keep the canonical form of the higher-count variant
+    fn add(&mut self, layer: Layer, normalized: &str, canonical: &str, count: u64, combine: Combine, merge: Merge) -> Added {
+        let i = layer.index();
+        let existing = self.id(normalized);
+        let added = match self.entries {
+            Entries::Full(ref mut entries) => {
+                if let Some(id) = existing {
+                    let existing = &mut entries[id as usize];
+                    let before = existing.frequency;
+                    let mut layers = existing.layers.as_deref().copied().unwrap_or([before, 0, 0]);
+
+                    if count > layers[i] {
+                        existing.canonical = canonical.to_string();
+                    }
+                    layers[i] = match merge {
+                        Merge::Sum => layers[i].saturating_add(count),
+                        Merge::Max => layers[i].max(count),
+                    };
+                    existing.frequency = combine.apply(layers);
+                    existing.layers = (layers[1..] != [0, 0]).then(|| Box::new(layers));
+                    Added { new: false, id, before, after: existing.frequency }
+                } else {
+                    let mut layers = [0; 3];
+                    layers[i] = count;
+                    let frequency = combine.apply(layers);
+                    entries.push(WordEntry {
+                        canonical: canonical.to_string(),
+                        frequency,
+                        layers: (i != 0).then(|| Box::new(layers)),
+                    });
+                    Added { new: true, id: 0, before: 0, after: frequency }
+                }
+            }
+            Entries::Compact { ref mut entries, scale } => {
+                let scaled = u32::try_from(count / scale).unwrap_or(u32::MAX);
+                let stored_canonical = (canonical != normalized).then(|| canonical.into());
+                let combined = |layers: [u32; 3]| {
+                    u32::try_from(combine.apply(layers.map(u64::from))).unwrap_or(u32::MAX)
+                };
+
+                if let Some(id) = existing {
+                    let existing = &mut entries[id as usize];
+                    let before = existing.frequency as u64 * scale;
+                    let mut layers = existing.layers.as_deref().copied().unwrap_or([existing.frequency, 0, 0]);
+
+                    if scaled > layers[i] {
+                        existing.canonical = stored_canonical;
+                    }
+                    layers[i] = match merge {
+                        Merge::Sum => layers[i].saturating_add(scaled),
+                        Merge::Max => layers[i].max(scaled),
+                    };
+                    existing.frequency = combined(layers);
+                    existing.layers = (layers[1..] != [0, 0]).then(|| Box::new(layers));
+                    Added { new: false, id, before, after: existing.frequency as u64 * scale }
+                } else {
+                    let mut layers = [0; 3];
+                    layers[i] = scaled;
+                    let frequency = combined(layers);
+                    entries.push(CompactEntry {
+                        canonical: stored_canonical,
+                        frequency,
+                        layers: (i != 0).then(|| Box::new(layers)),
+                    });
+                    Added { new: true, id: 0, before: 0, after: frequency as u64 * scale }
+                }
+            }
+        };
+
+        if added.new {
+            Added { id: self.push_key(normalized), ..added }
+        } else {
+            added
+        }
+    }
 }
 
+// `user` marks a word with a count in the user layer, which outranks every word without one at
+// the same distance, whatever their frequencies
 #[derive(Debug, Clone)]
 pub struct Suggestion {
     pub term: String,
     pub distance: usize,
     pub frequency: u64,
+    pub user: bool,
 }
 
 impl Suggestion {
@@ -21,6 +397,7 @@ impl Suggestion {
             term,
             distance,
             frequency,
+            user: false,
         }
     }
 }
@@ -29,6 +406,7 @@ impl Ord for Suggestion {
     fn cmp(&self, other: &Self) -> Ordering {
         self.distance
             .cmp(&other.distance)
+            .then_with(|| other.user.cmp(&self.user))
             .then_with(|| other.frequency.cmp(&self.frequency))
             .then_with(|| self.term.cmp(&other.term))
     }
@@ -42,74 +420,382 @@ impl PartialOrd for Suggestion {
 
 impl PartialEq for Suggestion {
     fn eq(&self, other: &Self) -> bool {
-        self.term == other.term && self.distance == other.distance && self.frequency == other.frequency
+        self.term == other.term && self.distance == other.distance && self.frequency == other.frequency && self.user == other.user
     }
 }
 
 impl Eq for Suggestion {}
 
+// SplitMix64: small seeded generator so perturb() output is reproducible across platforms
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// One random deletion, insertion, substitution, or transposition
+fn random_edit(chars: &mut Vec<char>, alphabet: &[char], rng: &mut SplitMix64) {
+    match rng.below(4) {
+        0 if !chars.is_empty() => {
+            chars.remove(rng.below(chars.len()));
+        }
+        2 if !chars.is_empty() => {
+            let i = rng.below(chars.len());
+            let replacement = alphabet[rng.below(alphabet.len())];
+            chars[i] = replacement;
+        }
+        3 if chars.len() >= 2 => {
+            let i = rng.below(chars.len() - 1);
+            chars.swap(i, i + 1);
+        }
+        _ => {
+            let c = alphabet[rng.below(alphabet.len())];
+            chars.insert(rng.below(chars.len() + 1), c);
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct SymSpell {
-    deletes: HashMap<String, HashSet<String>>,
-    words: HashMap<String, WordEntry>,
+    deletes: HashMap<String, Vec<u32>>,
+    words: WordStore,
     max_edit_distance: usize,
+    // Deletes are indexed up to this distance. Below max_edit_distance (the query-expanded
+    // strategy) queries make up the difference with insertions over `alphabet`
+    index_edit_distance: usize,
+    // Every character of every indexed key, only kept for a query-expanded index
+    alphabet: Vec<char>,
+    // Deletes are generated from at most this many leading characters of a word, and a longer
+    // word is also indexed under that prefix itself. None indexes whole words
+    prefix_length: Option<usize>,
+    // Sorted normalized keys for prefix completion and sampling, built on first use
+    completion_keys: OnceLock<Vec<String>>,
+    // Running frequency totals over completion_keys for weighted sampling, built on first use
+    cumulative_frequencies: OnceLock<Vec<u64>>,
+    // 1-based rank of each normalized key by frequency, built on first use
+    ranks: OnceLock<HashMap<String, usize>>,
+    // Sum of all combined frequencies, for unigram probabilities
+    total_frequency: u64,
+    combine: Combine,
+    metric: DistanceMetric,
+    // How words become keys. Callers index words under `normalize` of their term, and every
+    // query goes through it
+    normalizer: Normalizer,
+}
+
+// A dictionary entry found by `lookup`. Rank 1 is the most frequent word; equal frequencies
+// are ranked by normalized key, so ranks are unique and stable across loads
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup {
+    pub canonical: String,
+    pub frequency: u64,
+    pub rank: usize,
+}
+
+// What a warm-up run did: how many queries it ran and how many distinct deletes-map entries
+// they probed, a rough measure of how much of the index was paged in
+#[derive(Debug, Clone, Copy)]
+pub struct Warmup {
+    pub elapsed: Duration,
+    pub queries: usize,
+    pub entries_touched: usize,
+}
+
+// One step of an incremental index check: how many entries it checked, which of them failed,
+// and how many entries there are in all
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub checked: usize,
+    pub failures: Vec<String>,
+    pub total: usize,
+}
+
+// What a deep health probe found: how many sampled words it queried, the ones suggestions
+// didn't find again, and the time spent in those queries
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pub probes: usize,
+    pub failures: Vec<String>,
+    pub elapsed: Duration,
+}
+
+// A token split into two dictionary words. `score` is comparable to a single word's frequency:
+// the expected count of the pair under independent unigram probabilities
+#[derive(Debug, Clone)]
+pub struct Split {
+    pub left: String,
+    pub right: String,
+    pub score: f64,
 }
 
 impl SymSpell {
     pub fn new(max_edit_distance: usize) -> Self {
         Self {
             deletes: HashMap::new(),
-            words: HashMap::new(),
+            words: WordStore::new(Entries::Full(Vec::new())),
+            max_edit_distance,
+            index_edit_distance: max_edit_distance,
+            alphabet: Vec::new(),
+            prefix_length: None,
+            completion_keys: OnceLock::new(),
+            cumulative_frequencies: OnceLock::new(),
+            ranks: OnceLock::new(),
+            total_frequency: 0,
+            combine: Combine::Sum,
+            metric: DistanceMetric::Levenshtein,
+            normalizer: Normalizer::default(),
+        }
+    }
+
+    // Lower-memory index: frequencies are divided by `frequency_scale` and stored as saturating
+    // u32, then multiplied back on output, so they come back rounded down to a multiple of the
+    // scale and capped at u32::MAX * scale
+    pub fn new_compact(max_edit_distance: usize, frequency_scale: u64) -> Self {
+        Self {
+            deletes: HashMap::new(),
+            words: WordStore::new(Entries::Compact {
+                entries: Vec::new(),
+                scale: frequency_scale.max(1),
+            }),
             max_edit_distance,
+            index_edit_distance: max_edit_distance,
+            alphabet: Vec::new(),
+            prefix_length: None,
+            completion_keys: OnceLock::new(),
+            cumulative_frequencies: OnceLock::new(),
+            ranks: OnceLock::new(),
+            total_frequency: 0,
+            combine: Combine::Sum,
+            metric: DistanceMetric::Levenshtein,
+            normalizer: Normalizer::default(),
+        }
+    }
+
+    // Indexes deletes one level shallower than max_edit_distance, for roughly half the memory
+    // at distance 2; queries generate the missing level themselves and return the same results.
+    // Call before adding words. A no-op at distance 1, where there is no shallower level
+    pub fn query_expanded(mut self) -> Self {
+        if self.max_edit_distance >= 2 {
+            self.index_edit_distance = self.max_edit_distance - 1;
+        }
+        self
+    }
+
+    // Indexes only the first `prefix_length` characters of longer words: far fewer deletes for
+    // long terms, more candidates per bucket to verify. Every candidate is still verified against
+    // the whole query, so results are the same as for a whole-word index. Should be above
+    // max_edit_distance, or short prefixes put most words in the same few buckets. Call before
+    // adding words
+    pub fn with_prefix_length(mut self, prefix_length: Option<usize>) -> Self {
+        self.prefix_length = prefix_length;
+        self
+    }
+
+    pub fn prefix_length(&self) -> Option<usize> {
+        self.prefix_length
+    }
+
+    // The part of a normalized word its deletes are generated from
+    fn prefix<'a>(&self, word: &'a str) -> &'a str {
+        match self.prefix_length.and_then(|length| word.char_indices().nth(length)) {
+            Some((end, _)) => &word[..end],
+            None => word,
+        }
+    }
+
+    // Keys a word is indexed under: the deletes of its prefix, and the prefix itself when it's
+    // shorter than the word
+    fn index_keys(&self, word: &str) -> HashSet<String> {
+        let prefix = self.prefix(word);
+        let mut keys = self.get_deletes(prefix, self.index_edit_distance);
+        if prefix.len() < word.len() {
+            keys.insert(prefix.to_string());
+        }
+        keys
+    }
+
+    // How layer counts combine into frequencies (sum by default). Call before adding words
+    pub fn with_combine(mut self, combine: Combine) -> Self {
+        self.combine = combine;
+        self
+    }
+
+    pub fn combine(&self) -> Combine {
+        self.combine
+    }
+
+    // The distance candidates are verified and reported with. A transposed pair shares a
+    // one-character delete with the original, so the deletes index needs no change
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    pub fn metric(&self) -> DistanceMetric {
+        self.metric
+    }
+
+    // How words become keys (see `Normalizer`). Canonical forms are stored as given. Call
+    // before adding words
+    pub fn with_normalizer(mut self, normalizer: Normalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    pub fn normalizer(&self) -> &Normalizer {
+        &self.normalizer
+    }
+
+    pub fn is_query_expanded(&self) -> bool {
+        self.index_edit_distance < self.max_edit_distance
+    }
+
+    pub fn is_compact(&self) -> bool {
+        matches!(self.words.entries, Entries::Compact { .. })
+    }
+
+    pub fn max_edit_distance(&self) -> usize {
+        self.max_edit_distance
+    }
+
+    // 1 unless compact
+    pub fn frequency_scale(&self) -> u64 {
+        match self.words.entries {
+            Entries::Full(_) => 1,
+            Entries::Compact { scale, .. } => scale,
         }
     }
 
-    pub fn normalize_word(word: &str) -> String {
-        word.nfkd()
-            .filter(|c| !c.is_control() && !c.is_whitespace())
-            .collect::<String>()
-            .to_lowercase()
+    // Rough size of the index in bytes: words and the deletes map, counting the deletes map's
+    // unused slots and control bytes but not the lazily built completion and rank tables or
+    // allocator overhead. Walks the whole index
+    pub fn estimated_bytes(&self) -> usize {
+        let slack = (self.deletes.capacity() - self.deletes.len()) * size_of::<(String, Vec<u32>)>() + self.deletes.capacity();
+        self.deletes_bytes() + slack + self.words.estimated_bytes()
+    }
+
+    // Rough size of the deletes map: its keys and their buckets of word ids
+    pub fn deletes_bytes(&self) -> usize {
+        self.deletes
+            .iter()
+            .map(|(delete, bucket)| {
+                size_of::<(String, Vec<u32>)>() + delete.capacity() + bucket.capacity() * size_of::<u32>()
+            })
+            .sum()
+    }
+
+    // What the deletes map would take with each bucket a set of owned word strings instead of
+    // ids, for comparison with `deletes_bytes`
+    pub fn string_deletes_bytes(&self) -> usize {
+        self.deletes
+            .iter()
+            .map(|(delete, bucket)| {
+                size_of::<(String, HashSet<String>)>()
+                    + delete.capacity()
+                    + bucket.iter().map(|&id| size_of::<String>() + self.words.key(id).len()).sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// The key `word` is indexed and looked up under, by this index's normalizer
+    pub fn normalize(&self, word: &str) -> String {
+        self.normalizer.normalize(word)
     }
 
+    // Adds a base dictionary count under `normalized`, which is `normalize` of the canonical
+    // term; returns true if the word was new
     pub fn add_word(&mut self, normalized: &str, canonical: &str, frequency: u64) -> bool {
-        let normalized_key = normalized.to_string();
+        self.add_to_layer(Layer::Base, normalized, canonical, frequency)
+    }
 
-        let was_new = if let Some(existing) = self.words.get_mut(&normalized_key) {
-            // Duplicate: sum frequencies and keep highest-frequency canonical form
-            let new_total_freq = existing.frequency + frequency;
+    // `add_word` for a dictionary entry, with `merge` deciding what a repeat of a word does to
+    // its base count
+    pub fn merge_word(&mut self, normalized: &str, canonical: &str, frequency: u64, merge: Merge) -> bool {
+        self.insert(Layer::Base, normalized, canonical, frequency, merge)
+    }
 
-            // Keep the canonical form from the higher-frequency variant
-            if frequency > existing.frequency {
-                existing.canonical = canonical.to_string();
-            }
+    pub fn add_to_layer(&mut self, layer: Layer, normalized: &str, canonical: &str, count: u64) -> bool {
+        self.insert(layer, normalized, canonical, count, Merge::Sum)
+    }
 
-            existing.frequency = new_total_freq;
-            false
-        } else {
-            // New entry
-            self.words.insert(
-                normalized_key.clone(),
-                WordEntry {
-                    canonical: canonical.to_string(),
-                    frequency,
-                },
-            );
-            true
-        };
+    fn insert(&mut self, layer: Layer, normalized: &str, canonical: &str, count: u64, merge: Merge) -> bool {
+        let added = self.words.add(layer, normalized, canonical, count, self.combine, merge);
+        let was_new = added.new;
+        self.total_frequency = self.total_frequency.saturating_add(added.after).saturating_sub(added.before);
+        // Duplicates change frequencies too
+        self.cumulative_frequencies = OnceLock::new();
+        self.ranks = OnceLock::new();
 
         // Only generate deletes for new entries (avoid redundant work)
         if was_new {
-            let deletes = self.get_deletes(normalized, self.max_edit_distance);
-            for delete in deletes {
-                self.deletes
-                    .entry(delete)
-                    .or_insert_with(HashSet::new)
-                    .insert(normalized_key.clone());
+            self.completion_keys = OnceLock::new();
+
+            if self.is_query_expanded() {
+                for c in normalized.chars() {
+                    if !self.alphabet.contains(&c) {
+                        self.alphabet.push(c);
+                    }
+                }
+            }
+
+            for delete in self.index_keys(normalized) {
+                self.deletes.entry(delete).or_default().push(added.id);
             }
         }
 
         was_new
     }
 
+    // Removes a normalized word with all its layer counts and takes it out of its buckets; false
+    // if it isn't in the index. The alphabet keeps its characters, which only costs probes
+    pub fn remove_word(&mut self, normalized: &str) -> bool {
+        let Some(id) = self.words.id(normalized) else {
+            return false;
+        };
+        let (_, frequency) = self.words.entry(id);
+        self.total_frequency = self.total_frequency.saturating_sub(frequency);
+
+        for key in self.index_keys(normalized) {
+            if let Some(bucket) = self.deletes.get_mut(&key) {
+                if let Ok(i) = bucket.binary_search(&id) {
+                    bucket.remove(i);
+                }
+                if bucket.is_empty() {
+                    self.deletes.remove(&key);
+                }
+            }
+        }
+
+        // The word that took over the id is renumbered in its buckets, which stay sorted
+        if let Some((moved, from)) = self.words.swap_remove(id) {
+            for key in self.index_keys(&moved) {
+                if let Some(bucket) = self.deletes.get_mut(&key) {
+                    if let Ok(i) = bucket.binary_search(&from) {
+                        bucket.remove(i);
+                    }
+                    if let Err(i) = bucket.binary_search(&id) {
+                        bucket.insert(i, id);
+                    }
+                }
+            }
+        }
+
+        self.completion_keys = OnceLock::new();
+        self.cumulative_frequencies = OnceLock::new();
+        self.ranks = OnceLock::new();
+        true
+    }
+
     fn get_deletes(&self, word: &str, edit_distance: usize) -> HashSet<String> {
         let mut deletes = HashSet::new();
         if edit_distance == 0 {
@@ -160,68 +846,121 @@ impl SymSpell {
     }
 
     pub fn contains(&self, word: &str) -> bool {
-        let normalized = Self::normalize_word(word);
+        let normalized = self.normalize(word);
         self.words.contains_key(&normalized)
     }
 
+    // Counts per layer, in `Layer::ALL` order
+    pub fn layers(&self, word: &str) -> Option<[u64; 3]> {
+        self.words.layers(&self.normalize(word))
+    }
+
+    // (normalized, canonical, layer counts) of every entry with a count outside the base layer,
+    // to carry over into an index rebuilt from a new base dictionary
+    pub fn layered_entries(&self) -> Vec<(String, String, [u64; 3])> {
+        self.sorted_entries()
+            .filter_map(|(key, canonical, _)| {
+                let layers = self.words.layers(key)?;
+                (layers[1..] != [0, 0]).then(|| (key.to_string(), canonical.to_string(), layers))
+            })
+            .collect()
+    }
+
+    // Keys in the deletes index
+    pub fn deletes_len(&self) -> usize {
+        self.deletes.len()
+    }
+
+    // Word ids across all buckets of the deletes index
+    pub fn delete_entries(&self) -> usize {
+        self.deletes.values().map(Vec::len).sum()
+    }
+
+    pub fn canonical(&self, word: &str) -> Option<&str> {
+        let normalized = self.normalize(word);
+        self.words.get(&normalized).map(|(canonical, _)| canonical)
+    }
+
     pub fn get_frequency(&self, word: &str) -> Option<u64> {
-        let normalized = Self::normalize_word(word);
-        self.words.get(&normalized).map(|entry| entry.frequency)
+        let normalized = self.normalize(word);
+        self.words.get(&normalized).map(|(_, frequency)| frequency)
+    }
+
+    pub fn lookup(&self, word: &str) -> Option<Lookup> {
+        let normalized = self.normalize(word);
+        let (canonical, frequency) = self.words.get(&normalized)?;
+        let rank = self.ranks.get_or_init(|| {
+            self.ranked_entries().iter().enumerate().map(|(i, (key, _, _))| (key.to_string(), i + 1)).collect()
+        })[&normalized];
+
+        Some(Lookup {
+            canonical: canonical.to_string(),
+            frequency,
+            rank,
+        })
     }
 
     pub fn suggestions(&self, word: &str, max_suggestions: usize) -> Vec<Suggestion> {
-        let normalized = Self::normalize_word(word);
+        self.suggestions_within(word, max_suggestions, self.max_edit_distance, false)
+    }
+
+    // Like `suggestions`, but only verifies candidates up to `max_distance` (clamped to the
+    // distance the index was built with). Query deletes are generated at the lower distance too.
+    // With `exclude_exact`, the entry stored under the query's normalized key is never returned,
+    // whatever casing or composition its canonical form has
+    pub fn suggestions_within(
+        &self,
+        word: &str,
+        max_suggestions: usize,
+        max_distance: usize,
+        exclude_exact: bool,
+    ) -> Vec<Suggestion> {
+        let max_distance = max_distance.min(self.max_edit_distance);
+        let normalized = self.normalize(word);
         let mut suggestions = Vec::new();
         let mut seen = HashSet::new();
 
-        if let Some(entry) = self.words.get(&normalized) {
-            suggestions.push(Suggestion::new(entry.canonical.clone(), 0, entry.frequency));
-            seen.insert(normalized.clone());
+        // Marking the key as seen also keeps the candidate loops from re-adding it
+        if let Some(id) = self.words.id(&normalized) {
+            if !exclude_exact {
+                suggestions.push(self.suggestion(id, 0));
+            }
+            seen.insert(id);
         }
 
-        let input_deletes = self.get_deletes(&normalized, self.max_edit_distance);
+        // Words longer than the prefix length are indexed by their prefix, so the query is
+        // matched by its own prefix. A prefix that is itself a word is checked like a delete
+        let query_prefix = self.prefix(&normalized).to_string();
+        let mut input_deletes = self.get_deletes(&query_prefix, max_distance);
+        if query_prefix.len() < normalized.len() {
+            input_deletes.insert(query_prefix.clone());
+        }
 
         for delete in &input_deletes {
             // Check if this delete is itself a dictionary word (important for finding words shorter than input)
-            if !seen.contains(delete) {
-                if let Some(entry) = self.words.get(delete) {
-                    let distance = self.edit_distance(&normalized, delete);
-                    if distance <= self.max_edit_distance {
-                        suggestions.push(Suggestion::new(entry.canonical.clone(), distance, entry.frequency));
-                        seen.insert(delete.clone());
-                    }
+            if let Some(id) = self.words.id(delete).filter(|id| !seen.contains(id)) {
+                let distance = self.edit_distance(&normalized, delete);
+                if distance <= max_distance {
+                    suggestions.push(self.suggestion(id, distance));
+                    seen.insert(id);
                 }
             }
 
             // Check the deletes map for candidates
-            if let Some(candidates) = self.deletes.get(delete) {
-                for candidate in candidates {
-                    if seen.contains(candidate) {
-                        continue;
-                    }
-
-                    let distance = self.edit_distance(&normalized, candidate);
-                    if distance <= self.max_edit_distance {
-                        if let Some(entry) = self.words.get(candidate) {
-                            suggestions.push(Suggestion::new(entry.canonical.clone(), distance, entry.frequency));
-                            seen.insert(candidate.clone());
-                        }
-                    }
-                }
-            }
+            self.add_candidates(delete, &normalized, max_distance, &mut seen, &mut suggestions);
         }
 
-        if let Some(candidates) = self.deletes.get(&normalized) {
-            for candidate in candidates {
-                if seen.contains(candidate) {
-                    continue;
-                }
+        self.add_candidates(&query_prefix, &normalized, max_distance, &mut seen, &mut suggestions);
 
-                let distance = self.edit_distance(&normalized, candidate);
-                if distance <= self.max_edit_distance {
-                    if let Some(entry) = self.words.get(candidate) {
-                        suggestions.push(Suggestion::new(entry.canonical.clone(), distance, entry.frequency));
-                        seen.insert(candidate.clone());
+        // A word whose route to the query takes one delete more than the index holds has its
+        // deepest indexed delete at one insertion from a query delete (or the query itself)
+        if max_distance > self.index_edit_distance {
+            for probe in input_deletes.iter().chain(std::iter::once(&query_prefix)) {
+                let chars: Vec<char> = probe.chars().collect();
+                for i in 0..=chars.len() {
+                    for &c in &self.alphabet {
+                        let expanded: String = chars[..i].iter().chain(std::iter::once(&c)).chain(&chars[i..]).collect();
+                        self.add_candidates(&expanded, &normalized, max_distance, &mut seen, &mut suggestions);
                     }
                 }
             }
@@ -232,52 +971,761 @@ impl SymSpell {
         suggestions
     }
 
-    fn edit_distance(&self, s1: &str, s2: &str) -> usize {
-        let len1 = s1.chars().count();
-        let len2 = s2.chars().count();
+    // Verifies the words indexed under `delete` against the query, adding those within distance
+    fn add_candidates(
+        &self,
+        delete: &str,
+        normalized: &str,
+        max_distance: usize,
+        seen: &mut HashSet<u32>,
+        suggestions: &mut Vec<Suggestion>,
+    ) {
+        let Some(candidates) = self.deletes.get(delete) else {
+            return;
+        };
 
-        if len1 == 0 {
-            return len2;
+        for &id in candidates {
+            if seen.contains(&id) {
+                continue;
+            }
+
+            let distance = self.edit_distance(normalized, self.words.key(id));
+            if distance <= max_distance {
+                suggestions.push(self.suggestion(id, distance));
+                seen.insert(id);
+            }
         }
-        if len2 == 0 {
-            return len1;
+    }
+
+    fn suggestion(&self, id: u32, distance: usize) -> Suggestion {
+        let (canonical, frequency) = self.words.entry(id);
+        Suggestion {
+            user: self.words.in_user_layer(id),
+            ..Suggestion::new(canonical.to_string(), distance, frequency)
         }
+    }
 
-        let s1_chars: Vec<char> = s1.chars().collect();
-        let s2_chars: Vec<char> = s2.chars().collect();
+    // The best split of the normalized word into two dictionary words, scored as
+    // freq(left) * freq(right) / total frequency
+    pub fn best_split(&self, word: &str) -> Option<Split> {
+        let normalized = self.normalize(word);
+        let total = self.total_frequency.max(1) as f64;
+        let mut best: Option<Split> = None;
 
-        let mut prev_row: Vec<usize> = (0..=len2).collect();
-        let mut curr_row = vec![0; len2 + 1];
+        for (i, _) in normalized.char_indices().skip(1) {
+            let (left, right) = normalized.split_at(i);
+            let (Some((left_canonical, left_frequency)), Some((right_canonical, right_frequency))) =
+                (self.words.get(left), self.words.get(right))
+            else {
+                continue;
+            };
 
-        for i in 1..=len1 {
-            curr_row[0] = i;
+            let score = left_frequency as f64 * right_frequency as f64 / total;
+            let better = match best {
+                Some(ref current) => score > current.score,
+                None => true,
+            };
+            if better {
+                best = Some(Split {
+                    left: left_canonical.to_string(),
+                    right: right_canonical.to_string(),
+                    score,
+                });
+            }
+        }
 
-            for j in 1..=len2 {
-                let cost = if s1_chars[i - 1] == s2_chars[j - 1] {
-                    0
-                } else {
-                    1
-                };
+        best
+    }
 
-                curr_row[j] = std::cmp::min(
-                    std::cmp::min(
-                        prev_row[j] + 1,      // deletion
-                        curr_row[j - 1] + 1   // insertion
-                    ),
-                    prev_row[j - 1] + cost    // substitution
-                );
+    // Splits text without spaces into its most probable sequence of dictionary words of at most
+    // `max_length` characters, under independent unigram probabilities (dynamic programming over
+    // every end position). A piece that isn't a word is kept verbatim at a probability of
+    // 10 / (total * 10^length), so unknown text only appears where no words fit; adjacent unknown
+    // pieces come back as one segment. Known pieces come back in canonical form
+    pub fn segment(&self, text: &str, max_length: usize) -> Segmentation {
+        let total = self.total_frequency.max(1) as f64;
+        let bounds: Vec<usize> = text.char_indices().map(|(i, _)| i).chain(std::iter::once(text.len())).collect();
+        let length = bounds.len() - 1;
+
+        // For each prefix length: its best log probability, where its last piece starts, and the
+        // canonical form of that piece if it is a word
+        let mut best: Vec<(f64, usize, Option<&str>)> = vec![(f64::NEG_INFINITY, 0, None); length + 1];
+        best[0].0 = 0.0;
+        for end in 1..=length {
+            for start in end.saturating_sub(max_length.max(1))..end {
+                let piece = &text[bounds[start]..bounds[end]];
+                let (log_probability, canonical) = match self.words.get(&self.normalize(piece)) {
+                    Some((canonical, frequency)) if frequency > 0 => ((frequency as f64 / total).ln(), Some(canonical)),
+                    _ => ((10.0 / total).ln() - (end - start) as f64 * 10f64.ln(), None),
+                };
+                let score = best[start].0 + log_probability;
+                if score > best[end].0 {
+                    best[end] = (score, start, canonical);
+                }
             }
+        }
 
-            std::mem::swap(&mut prev_row, &mut curr_row);
+        let mut pieces = Vec::new();
+        let mut end = length;
+        while end > 0 {
+            let (_, start, canonical) = best[end];
+            pieces.push((&text[bounds[start]..bounds[end]], canonical));
+            end = start;
         }
 
-        prev_row[len2]
+        let mut segments: Vec<String> = Vec::new();
+        let mut after_unknown = false;
+        for (piece, canonical) in pieces.into_iter().rev() {
+            match canonical {
+                Some(canonical) => segments.push(canonical.to_string()),
+                None if after_unknown => segments.last_mut().unwrap().push_str(piece),
+                None => segments.push(piece.to_string()),
+            }
+            after_unknown = canonical.is_none();
+        }
+
+        Segmentation { segments, log_probability: best[length].0 }
     }
-}
+
+    // Corrects a phrase term by term, SymSpell lookup_compound style. Each term also competes
+    // with a merge into the next term ("chec ker" -> "checker") and with splits at every position
+    // ("spellcheker" -> "spell checker"). Fewer edits win, then the higher score; a pair of words
+    // scores freq(left) * freq(right) / total frequency, as in `best_split`. Candidates other than
+    // exact matches need `min_frequency`. Terms without a reading are kept as written
+    pub fn lookup_compound(&self, terms: &[PhraseTerm], min_frequency: f64) -> Vec<PhraseSegment> {
+        let total = self.total_frequency.max(1) as f64;
+        let readings: Vec<Option<Reading>> = terms
+            .iter()
+            .map(|term| if term.fixed { None } else { self.best_reading(term.text, min_frequency, total) })
+            .collect();
+        // When weighing a merge, a term left as written counts one edit past any correction
+        let unresolved = self.max_edit_distance + 1;
+
+        let mut segments = Vec::new();
+        let mut i = 0;
+        while i < terms.len() {
+            let mergeable = !terms[i].fixed && terms[i].joins_next && terms.get(i + 1).is_some_and(|next| !next.fixed);
+            if mergeable {
+                let joined = format!("{}{}", terms[i].text, terms[i + 1].text);
+                if let Some(merged) = self.best_candidate(&joined, min_frequency) {
+                    let (separate_distance, separate_score) = match (&readings[i], &readings[i + 1]) {
+                        (Some(left), Some(right)) => (left.distance + right.distance, left.score * right.score / total),
+                        (Some(reading), None) | (None, Some(reading)) => (reading.distance + unresolved, 0.0),
+                        (None, None) => (2 * unresolved, 0.0),
+                    };
+                    let distance = merged.distance + 1;
+                    if distance < separate_distance
+                        || (distance == separate_distance && merged.frequency as f64 > separate_score)
+                    {
+                        segments.push(PhraseSegment { terms: 2, output: merged.term, distance });
+                        i += 2;
+                        continue;
+                    }
+                }
+            }
+
+            segments.push(match readings[i] {
+                Some(ref reading) => PhraseSegment { terms: 1, output: reading.output.clone(), distance: reading.distance },
+                None => PhraseSegment { terms: 1, output: terms[i].text.to_string(), distance: 0 },
+            });
+            i += 1;
+        }
+
+        segments
+    }
+
+    // The best-ranked candidate within edit distance that is exact or passes `min_frequency`
+    fn best_candidate(&self, word: &str, min_frequency: f64) -> Option<Suggestion> {
+        self.suggestions(word, usize::MAX)
+            .into_iter()
+            .find(|s| s.distance == 0 || s.frequency as f64 >= min_frequency)
+    }
+
+    // A known term reads as itself. Otherwise its best correction competes with every split
+    fn best_reading(&self, term: &str, min_frequency: f64, total: f64) -> Option<Reading> {
+        let correction = self.best_candidate(term, min_frequency).map(|s| Reading {
+            output: s.term,
+            distance: s.distance,
+            score: s.frequency as f64,
+        });
+        if correction.as_ref().is_some_and(|reading| reading.distance == 0) {
+            return correction;
+        }
+
+        let splits = term.char_indices().skip(1).filter_map(|(i, _)| {
+            let left = self.best_candidate(&term[..i], min_frequency)?;
+            let right = self.best_candidate(&term[i..], min_frequency)?;
+            Some(Reading {
+                output: format!("{} {}", left.term, right.term),
+                distance: left.distance + right.distance + 1,
+                score: left.frequency as f64 * right.frequency as f64 / total,
+            })
+        });
+
+        correction.into_iter().chain(splits).min_by(|a, b| {
+            a.distance.cmp(&b.distance).then(b.score.total_cmp(&a.score))
+        })
+    }
+
+    // The most frequent dictionary word that extends the normalized `prefix`, for a token that
+    // is still being typed. The prefix itself is not a completion. The distance reported is
+    // the number of characters added
+    pub fn complete(&self, prefix: &str) -> Option<Suggestion> {
+        self.complete_where(prefix, |_| true)
+    }
+
+    // `complete` among the words whose normalized key passes `allowed`
+    pub fn complete_where(&self, prefix: &str, allowed: impl Fn(&str) -> bool) -> Option<Suggestion> {
+        let prefix = self.normalize(prefix);
+        if prefix.is_empty() {
+            return None;
+        }
+
+        let keys = self.sorted_keys();
+        let start = keys.partition_point(|key| key.as_str() < prefix.as_str());
+        let mut best: Option<Suggestion> = None;
+
+        for key in keys[start..].iter().take_while(|key| key.starts_with(prefix.as_str())) {
+            if *key == prefix || !allowed(key) {
+                continue;
+            }
+
+            if let Some((canonical, frequency)) = self.words.get(key) {
+                // Keys are sorted, so ties go to the alphabetically first completion
+                let better = match best {
+                    Some(ref current) => frequency > current.frequency,
+                    None => true,
+                };
+                if better {
+                    let added = key.chars().count() - prefix.chars().count();
+                    best = Some(Suggestion::new(canonical.to_string(), added, frequency));
+                }
+            }
+        }
+
+        best
+    }
+
+    fn sorted_keys(&self) -> &[String] {
+        self.completion_keys.get_or_init(|| {
+            let mut keys = self.words.keys();
+            keys.sort_unstable();
+            keys
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    // A frequency as a unigram probability: its share of every count in the dictionary
+    pub fn probability(&self, frequency: u64) -> f64 {
+        frequency as f64 / self.total_frequency.max(1) as f64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Writes the whole index: settings, entries with their layer counts, and the deletes map, in
+    // key order so the same index always produces the same bytes. Buckets hold each word's
+    // position in that order, which becomes its id when read back. Lookup caches are rebuilt on use
+    pub fn write_index(&self, out: &mut impl Write) -> io::Result<()> {
+        write_u64(out, self.max_edit_distance as u64)?;
+        write_u64(out, self.index_edit_distance as u64)?;
+        write_str(out, self.combine.name())?;
+        write_str(out, self.metric.name())?;
+        write_u64(out, self.prefix_length.map_or(0, |length| length as u64))?;
+        write_str(out, self.normalizer.form.name())?;
+        for flag in [self.normalizer.lowercase, self.normalizer.strip_accents, self.normalizer.strip_punctuation] {
+            write_u8(out, flag as u8)?;
+        }
+        write_u64(out, self.total_frequency)?;
+        write_u64(out, self.alphabet.len() as u64)?;
+        for &c in &self.alphabet {
+            write_u64(out, c as u64)?;
+        }
+
+        let keys = self.sorted_keys();
+        let ids: Vec<u32> = keys.iter().filter_map(|key| self.words.id(key)).collect();
+        let mut positions = vec![0; ids.len()];
+        for (position, &id) in ids.iter().enumerate() {
+            positions[id as usize] = position as u64;
+        }
+
+        match self.words.entries {
+            Entries::Full(ref entries) => {
+                write_u8(out, 0)?;
+                write_u64(out, keys.len() as u64)?;
+                for (key, &id) in keys.iter().zip(&ids) {
+                    let entry = &entries[id as usize];
+                    write_str(out, key)?;
+                    write_str(out, &entry.canonical)?;
+                    write_u64(out, entry.frequency)?;
+                    write_layers(out, entry.layers.as_deref().copied())?;
+                }
+            }
+            Entries::Compact { ref entries, scale } => {
+                write_u8(out, 1)?;
+                write_u64(out, scale)?;
+                write_u64(out, keys.len() as u64)?;
+                for (key, &id) in keys.iter().zip(&ids) {
+                    let entry = &entries[id as usize];
+                    write_str(out, key)?;
+                    write_str(out, entry.canonical.as_deref().unwrap_or(key))?;
+                    write_u64(out, entry.frequency as u64)?;
+                    write_layers(out, entry.layers.as_deref().map(|layers| layers.map(u64::from)))?;
+                }
+            }
+        }
+
+        let mut deletes: Vec<(&String, &Vec<u32>)> = self.deletes.iter().collect();
+        deletes.sort_unstable_by_key(|&(delete, _)| delete);
+        write_u64(out, deletes.len() as u64)?;
+        for (delete, bucket) in deletes {
+            let mut bucket: Vec<u64> = bucket.iter().map(|&id| positions[id as usize]).collect();
+            bucket.sort_unstable();
+            write_str(out, delete)?;
+            write_u64(out, bucket.len() as u64)?;
+            for position in bucket {
+                write_u64(out, position)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Reads an index written by `write_index`. Counts come from the file, so nothing is allocated
+    // ahead of the items actually read
+    pub fn read_index(input: &mut impl Read) -> io::Result<Self> {
+        let max_edit_distance = read_usize(input)?;
+        let index_edit_distance = read_usize(input)?;
+        let combine = Combine::parse(&read_string(input)?).ok_or_else(|| invalid("unknown frequency_combine"))?;
+        let metric = DistanceMetric::parse(&read_string(input)?).ok_or_else(|| invalid("unknown distance_metric"))?;
+        let prefix_length = Some(read_usize(input)?).filter(|&length| length > 0);
+        let form = NormalForm::parse(&read_string(input)?).ok_or_else(|| invalid("unknown normalization form"))?;
+        let mut flags = [false; 3];
+        for flag in &mut flags {
+            *flag = match read_u8(input)? {
+                0 => false,
+                1 => true,
+                _ => return Err(invalid("invalid normalizer flag")),
+            };
+        }
+        let [lowercase, strip_accents, strip_punctuation] = flags;
+        let total_frequency = read_u64(input)?;
+        let mut alphabet = Vec::new();
+        for _ in 0..read_u64(input)? {
+            let c = u32::try_from(read_u64(input)?).ok().and_then(char::from_u32).ok_or_else(|| invalid("invalid character"))?;
+            alphabet.push(c);
+        }
+
+        let mut keys = Vec::new();
+        let entries = match read_u8(input)? {
+            0 => {
+                let mut entries = Vec::new();
+                for _ in 0..read_u64(input)? {
+                    keys.push(read_string(input)?);
+                    let canonical = read_string(input)?;
+                    let frequency = read_u64(input)?;
+                    let layers = read_layers(input)?.map(Box::new);
+                    entries.push(WordEntry { canonical, frequency, layers });
+                }
+                Entries::Full(entries)
+            }
+            1 => {
+                let scale = read_u64(input)?.max(1);
+                let mut entries = Vec::new();
+                for _ in 0..read_u64(input)? {
+                    let key = read_string(input)?;
+                    let canonical = read_string(input)?;
+                    let frequency = u32::try_from(read_u64(input)?).map_err(|_| invalid("compact frequency out of range"))?;
+                    let layers = match read_layers(input)? {
+                        Some(layers) if layers.iter().all(|&count| count <= u32::MAX as u64) => {
+                            Some(Box::new(layers.map(|count| count as u32)))
+                        }
+                        Some(_) => return Err(invalid("compact frequency out of range")),
+                        None => None,
+                    };
+                    let canonical = (canonical != key).then(|| canonical.into_boxed_str());
+                    entries.push(CompactEntry { canonical, frequency, layers });
+                    keys.push(key);
+                }
+                Entries::Compact { entries, scale }
+            }
+            _ => return Err(invalid("unknown word store")),
+        };
+
+        let mut words = WordStore::new(entries);
+        for key in &keys {
+            if words.contains_key(key) {
+                return Err(invalid("duplicate word"));
+            }
+            words.push_key(key);
+        }
+
+        // Positions must be in range and strictly increasing, as they are in a bucket built by add
+        let mut deletes = HashMap::new();
+        for _ in 0..read_u64(input)? {
+            let delete = read_string(input)?;
+            let mut bucket: Vec<u32> = Vec::new();
+            for _ in 0..read_u64(input)? {
+                let id = u32::try_from(read_u64(input)?)
+                    .ok()
+                    .filter(|&id| (id as usize) < keys.len() && bucket.last().is_none_or(|&last| last < id))
+                    .ok_or_else(|| invalid("invalid word position"))?;
+                bucket.push(id);
+            }
+            deletes.insert(delete, bucket);
+        }
+
+        Ok(Self {
+            deletes,
+            words,
+            max_edit_distance,
+            index_edit_distance,
+            alphabet,
+            prefix_length,
+            completion_keys: OnceLock::new(),
+            cumulative_frequencies: OnceLock::new(),
+            ranks: OnceLock::new(),
+            total_frequency,
+            combine,
+            normalizer: Normalizer { form, lowercase, strip_accents, strip_punctuation },
+            metric,
+        })
+    }
+
+    // (normalized, canonical, frequency) for every entry, ordered by normalized key
+    pub fn sorted_entries(&self) -> impl Iterator<Item = (&str, &str, u64)> {
+        self.sorted_keys().iter().filter_map(|key| {
+            self.words.get(key).map(|(canonical, frequency)| (key.as_str(), canonical, frequency))
+        })
+    }
+
+    // (normalized, canonical, frequency) for every entry in rank order: most frequent first, and
+    // equal frequencies by normalized key
+    pub fn ranked_entries(&self) -> Vec<(&str, &str, u64)> {
+        let mut entries: Vec<(&str, &str, u64)> = self.sorted_entries().collect();
+        entries.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+        entries
+    }
+
+    // (canonical, user count) of every entry with a count in the user layer, most counted first
+    // and equal counts by normalized key
+    pub fn user_entries(&self) -> Vec<(&str, u64)> {
+        let mut entries: Vec<(&str, &str, u64)> = self
+            .sorted_entries()
+            .filter_map(|(key, canonical, _)| {
+                let user = self.words.layers(key)?[Layer::User.index()];
+                (user > 0).then_some((key, canonical, user))
+            })
+            .collect();
+        entries.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+        entries.into_iter().map(|(_, canonical, user)| (canonical, user)).collect()
+    }
+
+    // `count` canonical words drawn with replacement, uniformly or in proportion to frequency.
+    // Draws index the sorted keys, so a seed gives the same sample on every platform. Weighted
+    // sampling never picks zero-frequency words, and falls back to uniform if every word is one
+    pub fn sample(&self, count: usize, seed: u64, weighted: bool) -> Vec<String> {
+        let keys = self.sorted_keys();
+        if keys.is_empty() {
+            return Vec::new();
+        }
+
+        let cumulative = weighted.then(|| {
+            self.cumulative_frequencies.get_or_init(|| {
+                let mut total = 0u64;
+                keys.iter()
+                    .map(|key| {
+                        total = total.saturating_add(self.words.get(key).map_or(0, |(_, frequency)| frequency));
+                        total
+                    })
+                    .collect()
+            })
+        });
+        let total = cumulative.and_then(|c| c.last().copied()).unwrap_or(0);
+
+        let mut rng = SplitMix64(seed);
+        (0..count)
+            .filter_map(|_| {
+                let index = match cumulative {
+                    Some(cumulative) if total > 0 => {
+                        let target = rng.next() % total;
+                        cumulative.partition_point(|&running| running <= target)
+                    }
+                    _ => rng.below(keys.len()),
+                };
+                self.words.get(&keys[index]).map(|(canonical, _)| canonical.to_string())
+            })
+            .collect()
+    }
+
+    // Runs `sample` uniformly drawn dictionary words and one random typo of each through
+    // `suggestions`, so a freshly built index is paged in and cache-warm before it serves
+    // traffic. Uniform draws spread the probes across the deletes map instead of favouring
+    // the few most frequent words
+    pub fn warmup(&self, sample: usize, seed: u64) -> Warmup {
+        let started = Instant::now();
+        let alphabet: Vec<char> = ('a'..='z').collect();
+        let mut rng = SplitMix64(seed ^ 0x5741_524D);
+        let mut touched = HashSet::new();
+        let mut queries = 0;
+
+        for word in self.sample(sample, seed, false) {
+            let normalized = self.normalize(&word);
+            let mut chars: Vec<char> = normalized.chars().collect();
+            random_edit(&mut chars, &alphabet, &mut rng);
+            let typo: String = chars.into_iter().collect();
+
+            for query in [normalized, typo] {
+                std::hint::black_box(self.suggestions(&query, 5));
+                queries += 1;
+
+                let mut probes = self.get_deletes(self.prefix(&query), self.max_edit_distance);
+                probes.insert(self.prefix(&query).to_string());
+                for probe in probes {
+                    if self.deletes.contains_key(&probe) {
+                        touched.insert(probe);
+                    }
+                }
+            }
+        }
+
+        Warmup {
+            elapsed: started.elapsed(),
+            queries,
+            entries_touched: touched.len(),
+        }
+    }
+
+    // Checks up to `max` entries in key order from position `start`, stopping early once
+    // `deadline` passes (but always checking at least one). An entry passes if its key looks
+    // itself up at distance 0 and the deletes map indexes it under each of its prefix's
+    // one-character deletes (and the prefix itself, when shorter than the key), so a damaged
+    // bucket shows up on the entries it should hold
+    pub fn verify(&self, start: usize, max: usize, deadline: Instant) -> Verification {
+        let keys = self.sorted_keys();
+        let mut checked = 0;
+        let mut failures = Vec::new();
+
+        for key in keys.iter().skip(start).take(max) {
+            if checked > 0 && Instant::now() >= deadline {
+                break;
+            }
+            checked += 1;
+
+            let exact = self
+                .suggestions_within(key, 1, 0, false)
+                .first()
+                .is_some_and(|s| s.distance == 0 && self.normalize(&s.term) == *key);
+            let prefix = self.prefix(key);
+            let indexed = self
+                .generate_deletes(prefix)
+                .iter()
+                .chain((prefix.len() < key.len()).then(|| prefix.to_string()).iter())
+                .all(|delete| {
+                    self.deletes
+                        .get(delete)
+                        .is_some_and(|bucket| self.words.id(key).is_some_and(|id| bucket.binary_search(&id).is_ok()))
+                });
+            if !(exact && indexed) {
+                failures.push(key.clone());
+            }
+        }
+
+        Verification { checked, failures, total: keys.len() }
+    }
+
+    // Queries `count` uniformly drawn words, each with a seeded typo one edit away, and checks
+    // that suggestions find the word again. A word whose every typo is another dictionary word
+    // is queried as it is. Only the suggestion queries are timed, so the elapsed time reflects
+    // the index rather than typo generation
+    pub fn probe(&self, count: usize, seed: u64) -> Probe {
+        let mut elapsed = Duration::ZERO;
+        let mut failures = Vec::new();
+        let words = self.sample(count, seed, false);
+
+        for (i, word) in words.iter().enumerate() {
+            let key = self.normalize(word);
+            let query = self.perturb(&key, 1, 1, seed.wrapping_add(i as u64), false).pop().unwrap_or_else(|| key.clone());
+
+            let started = Instant::now();
+            let suggestions = self.suggestions_within(&query, usize::MAX, 1, false);
+            elapsed += started.elapsed();
+
+            if !suggestions.iter().any(|s| self.normalize(&s.term) == key) {
+                failures.push(word.clone());
+            }
+        }
+
+        Probe { probes: words.len(), failures, elapsed }
+    }
+
+    // Generates up to `count` distinct typos of the normalized word at exactly `distance`
+    // edits, checked with the same edit distance used for suggestions. Random edits can cancel
+    // out or overshoot, so candidates that miss the distance are discarded; after a bounded
+    // number of attempts fewer than `count` may be returned. Unless `allow_known`, dictionary
+    // words are excluded
+    pub fn perturb(&self, word: &str, distance: usize, count: usize, seed: u64, allow_known: bool) -> Vec<String> {
+        let normalized = self.normalize(word);
+        let base: Vec<char> = normalized.chars().collect();
+
+        let mut alphabet: Vec<char> = ('a'..='z').collect();
+        for &c in &base {
+            if !alphabet.contains(&c) {
+                alphabet.push(c);
+            }
+        }
+
+        let mut rng = SplitMix64(seed);
+        let mut results = Vec::new();
+        let mut seen = HashSet::new();
+        let max_attempts = count.saturating_mul(200).max(1000);
+
+        for _ in 0..max_attempts {
+            if results.len() >= count {
+                break;
+            }
+
+            let mut chars = base.clone();
+            for _ in 0..distance {
+                random_edit(&mut chars, &alphabet, &mut rng);
+            }
+
+            let candidate: String = chars.into_iter().collect();
+            if candidate.is_empty() || !seen.insert(candidate.clone()) {
+                continue;
+            }
+            if self.edit_distance(&normalized, &candidate) != distance {
+                continue;
+            }
+            if !allow_known && self.words.contains_key(&candidate) {
+                continue;
+            }
+
+            results.push(candidate);
+        }
+
+        results
+    }
+
+    fn edit_distance(&self, s1: &str, s2: &str) -> usize {
+        match self.metric {
+            DistanceMetric::Levenshtein => edit_distance(s1, s2),
+            DistanceMetric::Damerau => damerau_distance(s1, s2),
+        }
+    }
+}
+
+// Levenshtein distance between two strings, in characters
+pub fn edit_distance(s1: &str, s2: &str) -> usize {
+    let len1 = s1.chars().count();
+    let len2 = s2.chars().count();
+
+    if len1 == 0 {
+        return len2;
+    }
+    if len2 == 0 {
+        return len1;
+    }
+
+    let s1_chars: Vec<char> = s1.chars().collect();
+    let s2_chars: Vec<char> = s2.chars().collect();
+
+    let mut prev_row: Vec<usize> = (0..=len2).collect();
+    let mut curr_row = vec![0; len2 + 1];
+
+    for i in 1..=len1 {
+        curr_row[0] = i;
+
+        for j in 1..=len2 {
+            let cost = if s1_chars[i - 1] == s2_chars[j - 1] {
+                0
+            } else {
+                1
+            };
+
+            curr_row[j] = std::cmp::min(
+                std::cmp::min(
+                    prev_row[j] + 1,      // deletion
+                    curr_row[j - 1] + 1   // insertion
+                ),
+                prev_row[j - 1] + cost    // substitution
+            );
+        }
+
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    prev_row[len2]
+}
+
+fn write_layers(out: &mut impl Write, layers: Option<[u64; 3]>) -> io::Result<()> {
+    match layers {
+        Some(layers) => {
+            write_u8(out, 1)?;
+            layers.iter().try_for_each(|&count| write_u64(out, count))
+        }
+        None => write_u8(out, 0),
+    }
+}
+
+fn read_layers(input: &mut impl Read) -> io::Result<Option<[u64; 3]>> {
+    match read_u8(input)? {
+        0 => Ok(None),
+        1 => Ok(Some([read_u64(input)?, read_u64(input)?, read_u64(input)?])),
+        _ => Err(invalid("invalid layer flag")),
+    }
+}
+
+// Optimal string alignment distance: Levenshtein plus transposition of two adjacent characters
+pub fn damerau_distance(s1: &str, s2: &str) -> usize {
+    let a: Vec<char> = s1.chars().collect();
+    let b: Vec<char> = s2.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return a.len().max(b.len());
+    }
+
+    // Three rows: a transposition looks back two
+    let mut before_prev: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    let mut curr_row = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr_row[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr_row[j] = (prev_row[j] + 1).min(curr_row[j - 1] + 1).min(prev_row[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                curr_row[j] = curr_row[j].min(before_prev[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before_prev, &mut prev_row);
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    prev_row[b.len()]
+}
+
+// The candidate closest to `word` by edit distance, if it is close enough to be a likely typo of
+// it: within a third of the word's length, and at least 1. Ties go to the earlier candidate
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (word.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|&(distance, _)| distance <= limit)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use unicode_normalization::char::is_combining_mark;
 
     #[test]
     fn test_edit_distance() {
@@ -288,6 +1736,108 @@ mod tests {
         assert_eq!(symspell.edit_distance("test", "toast"), 2);
     }
 
+    #[test]
+    fn test_damerau_distance() {
+        assert_eq!(damerau_distance("teh", "the"), 1);
+        assert_eq!(damerau_distance("ca", "abc"), 3);
+        assert_eq!(damerau_distance("test", "toast"), 2);
+        assert_eq!(damerau_distance("", "abc"), 3);
+
+        // One swap at every position of words of length 2 to 10
+        let alphabet: Vec<char> = "abcdefghij".chars().collect();
+        for len in 2..=10 {
+            let word: String = alphabet[..len].iter().collect();
+            for i in 0..len - 1 {
+                let mut chars: Vec<char> = word.chars().collect();
+                chars.swap(i, i + 1);
+                let swapped: String = chars.into_iter().collect();
+                assert_eq!(damerau_distance(&word, &swapped), 1, "{} / {}", word, swapped);
+                assert_eq!(edit_distance(&word, &swapped), 2, "{} / {}", word, swapped);
+            }
+        }
+    }
+
+    #[test]
+    fn test_damerau_suggestions() {
+        let words = ["the", "from", "friend", "receive", "separately"];
+        let mut levenshtein = SymSpell::new(1);
+        let mut damerau = SymSpell::new(1).with_metric(DistanceMetric::Damerau);
+        for word in words {
+            levenshtein.add_word(word, word, 100);
+            damerau.add_word(word, word, 100);
+        }
+
+        for (typo, expected) in [("teh", "the"), ("form", "from"), ("freind", "friend"), ("recieve", "receive"), ("separatley", "separately")] {
+            assert!(levenshtein.suggestions(typo, 5).is_empty(), "{}", typo);
+            let suggestions = damerau.suggestions(typo, 5);
+            assert_eq!(suggestions.len(), 1, "{}", typo);
+            assert_eq!(suggestions[0].term, expected);
+            assert_eq!(suggestions[0].distance, 1);
+        }
+    }
+
+    #[test]
+    fn test_closest() {
+        let options = ["frequency_threshold", "frequency_scale", "edit_distance"];
+
+        assert_eq!(closest("frequency_treshold", options), Some("frequency_threshold"));
+        assert_eq!(closest("edit_distnace", options), Some("edit_distance"));
+        assert_eq!(closest("dictionary", options), None);
+    }
+
+    #[test]
+    fn test_segment() {
+        let mut symspell = SymSpell::new(1);
+        for (word, frequency) in [("the", 5000), ("quick", 300), ("brown", 200), ("fox", 100), ("now", 500), ("here", 400), ("no", 300), ("where", 200)] {
+            symspell.add_word(word, word, frequency);
+        }
+
+        let segmented = symspell.segment("TheQuickBrownFox", 20);
+        assert_eq!(segmented.segments, ["the", "quick", "brown", "fox"]);
+        let total = 7000.0f64;
+        let expected: f64 = [5000.0, 300.0, 200.0, 100.0].iter().map(|f: &f64| (f / total).ln()).sum();
+        assert!((segmented.log_probability - expected).abs() < 1e-9);
+
+        // Frequency decides between "now here" and "no where"
+        assert_eq!(symspell.segment("nowhere", 20).segments, ["now", "here"]);
+        symspell.add_word("where", "where", 1000);
+        assert_eq!(symspell.segment("nowhere", 20).segments, ["no", "where"]);
+
+        // Unknown stretches are kept verbatim, as one segment
+        assert_eq!(symspell.segment("thexyzfox", 20).segments, ["the", "xyz", "fox"]);
+        assert_eq!(symspell.segment("", 20), Segmentation { segments: Vec::new(), log_probability: 0.0 });
+
+        // Pieces longer than the limit are never looked up, so "Quick" is left as written
+        assert_eq!(symspell.segment("QuickFox", 3).segments, ["Quick", "fox"]);
+    }
+
+    #[test]
+    fn test_lookup_compound() {
+        let mut symspell = SymSpell::new(2);
+        for (word, frequency) in [("spell", 500), ("checker", 400), ("check", 600), ("for", 10000), ("ruby", 300)] {
+            symspell.add_word(word, word, frequency);
+        }
+        let phrase = |text: &'static str| -> Vec<PhraseTerm<'static>> {
+            text.split(' ').map(|text| PhraseTerm { text, fixed: false, joins_next: true }).collect()
+        };
+        let output = |segments: &[PhraseSegment]| -> (String, usize) {
+            let words: Vec<&str> = segments.iter().map(|s| s.output.as_str()).collect();
+            (words.join(" "), segments.iter().map(|s| s.distance).sum())
+        };
+
+        assert_eq!(output(&symspell.lookup_compound(&phrase("spel cheker for ruby"), 1.0)), ("spell checker for ruby".into(), 2));
+        assert_eq!(output(&symspell.lookup_compound(&phrase("spellcheker"), 1.0)), ("spell checker".into(), 2));
+
+        let merged = symspell.lookup_compound(&phrase("chec ker"), 1.0);
+        assert_eq!(merged, vec![PhraseSegment { terms: 2, output: "checker".into(), distance: 1 }]);
+
+        // Fixed terms are neither corrected nor merged, and candidates need the frequency
+        let mut terms = phrase("chec ker");
+        terms[1].fixed = true;
+        assert_eq!(output(&symspell.lookup_compound(&terms, 1.0)), ("check ker".into(), 1));
+        assert_eq!(output(&symspell.lookup_compound(&phrase("rubyy"), 1000.0)), ("rubyy".into(), 0));
+    }
+
     #[test]
     fn test_suggestions() {
         let mut symspell = SymSpell::new(2);
@@ -301,6 +1851,373 @@ mod tests {
         assert_eq!(suggestions[0].distance, 1);
     }
 
+    #[test]
+    fn test_suggestions_within_limits_distance() {
+        let mut symspell = SymSpell::new(2);
+        symspell.add_word("hello", "hello", 1000);
+        symspell.add_word("help", "help", 750);
+
+        // "hel" is distance 1 from "help" and distance 2 from "hello"
+        let full = symspell.suggestions_within("hel", 5, 2, false);
+        assert_eq!(full.len(), 2);
+
+        let clamped = symspell.suggestions_within("hel", 5, 1, false);
+        assert_eq!(clamped.len(), 1);
+        assert_eq!(clamped[0].term, "help");
+
+        let exact = symspell.suggestions_within("hel", 5, 0, false);
+        assert!(exact.is_empty());
+
+        // Requests above the build distance are clamped rather than searched
+        let over = symspell.suggestions_within("hel", 5, 3, false);
+        assert_eq!(over.len(), full.len());
+    }
+
+    #[test]
+    fn test_compact_suggestions_match_full() {
+        let words = [("Hello", 10000), ("hell", 2500), ("help", 3000), ("NASA", 800), ("world", 8000)];
+        let mut full = SymSpell::new(2);
+        let mut compact = SymSpell::new_compact(2, 1);
+        for (word, freq) in words {
+            full.add_word(&full.normalize(word), word, freq);
+            compact.add_word(&compact.normalize(word), word, freq);
+        }
+
+        assert!(compact.is_compact());
+        for query in ["helo", "hello", "nasa", "wrld", "xyz"] {
+            assert_eq!(full.suggestions(query, 5), compact.suggestions(query, 5), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_query_expanded_matches_full() {
+        let words = [
+            ("hello", 1000), ("help", 750), ("hell", 500), ("shell", 300), ("yellow", 200),
+            ("abcd", 90), ("axyd", 80), ("ab", 70), ("café", 60), ("a", 50), ("hallo", 40),
+        ];
+        let mut full = SymSpell::new(2);
+        let mut expanded = SymSpell::new(2).query_expanded();
+        for (word, frequency) in words {
+            full.add_word(word, word, frequency);
+            expanded.add_word(word, word, frequency);
+        }
+
+        assert!(expanded.is_query_expanded());
+        assert!(expanded.deletes.len() < full.deletes.len());
+
+        let mut queries: Vec<String> = ["helo", "hlelo", "ab", "abcd", "ad", "x", "cafe", "yelow", "shel"]
+            .iter()
+            .map(|q| q.to_string())
+            .collect();
+        for (word, _) in words {
+            queries.extend(full.perturb(word, 1, 3, 1, true));
+            queries.extend(full.perturb(word, 2, 5, 2, true));
+        }
+
+        let terms = |results: Vec<Suggestion>| -> Vec<(String, usize)> {
+            results.into_iter().map(|s| (s.term, s.distance)).collect()
+        };
+        for query in &queries {
+            assert_eq!(
+                terms(expanded.suggestions(query, usize::MAX)),
+                terms(full.suggestions(query, usize::MAX)),
+                "query {:?}",
+                query
+            );
+            assert_eq!(
+                terms(expanded.suggestions_within(query, usize::MAX, 1, false)),
+                terms(full.suggestions_within(query, usize::MAX, 1, false))
+            );
+        }
+
+        assert!(!SymSpell::new(1).query_expanded().is_query_expanded());
+        assert!(expanded.estimated_bytes() < full.estimated_bytes());
+    }
+
+    #[test]
+    fn test_prefix_length_matches_whole_word() {
+        let words = [
+            ("acetylcholinesterase", 900), ("acetylcholine", 800), ("phosphofructokinase", 700),
+            ("phosphorylation", 650), ("immunoglobulin", 600), ("immunology", 550), ("temperature", 500),
+            ("tempera", 450), ("tempering", 400), ("cell", 300), ("cells", 250), ("cello", 200), ("ab", 100),
+        ];
+        let build = |prefix_length: Option<usize>, expanded: bool| {
+            let mut symspell = SymSpell::new(2).with_prefix_length(prefix_length);
+            if expanded {
+                symspell = symspell.query_expanded();
+            }
+            for (word, frequency) in words {
+                symspell.add_word(word, word, frequency);
+            }
+            symspell
+        };
+        let full = build(None, false);
+
+        let mut queries: Vec<String> = ["acetylcholinesterse", "xacetylcholine", "tempratures", "temperatur", "cel", "immunoglobin", "phosph"]
+            .iter()
+            .map(|q| q.to_string())
+            .collect();
+        for (word, _) in words {
+            queries.extend(full.perturb(word, 1, 4, 3, true));
+            queries.extend(full.perturb(word, 2, 6, 4, true));
+        }
+
+        let terms = |results: Vec<Suggestion>| -> Vec<(String, usize)> {
+            results.into_iter().map(|s| (s.term, s.distance)).collect()
+        };
+        for (prefix_length, expanded) in [(Some(7), false), (Some(3), false), (Some(7), true)] {
+            let prefixed = build(prefix_length, expanded);
+            assert!(prefixed.deletes.len() < full.deletes.len());
+            assert!(prefixed.verify(0, usize::MAX, Instant::now() + Duration::from_secs(60)).failures.is_empty());
+
+            for query in &queries {
+                assert_eq!(
+                    terms(prefixed.suggestions(query, usize::MAX)),
+                    terms(full.suggestions(query, usize::MAX)),
+                    "query {:?} with prefix_length {:?}",
+                    query,
+                    prefix_length
+                );
+                assert_eq!(
+                    terms(prefixed.suggestions_within(query, usize::MAX, 1, false)),
+                    terms(full.suggestions_within(query, usize::MAX, 1, false))
+                );
+            }
+        }
+
+        // Words no longer than the prefix are indexed exactly as before
+        let short = build(Some(32), false);
+        assert_eq!(short.deletes.len(), full.deletes.len());
+    }
+
+    #[test]
+    fn test_remove_word() {
+        let words = [("hello", 1000), ("help", 800), ("hell", 600), ("shell", 400), ("world", 300)];
+        let build = |skip: Option<&str>| {
+            let mut symspell = SymSpell::new(2).with_prefix_length(Some(4));
+            for (word, frequency) in words.iter().filter(|(word, _)| Some(*word) != skip) {
+                symspell.add_word(word, word, *frequency);
+            }
+            symspell
+        };
+
+        // "help" is in the middle, so the last word moves into its id
+        let mut symspell = build(None);
+        assert!(symspell.remove_word("help"));
+        assert!(!symspell.remove_word("help"));
+        assert!(!symspell.remove_word("absent"));
+
+        let expected = build(Some("help"));
+        assert_eq!(symspell.len(), 4);
+        assert!(!symspell.contains("help"));
+        assert_eq!(symspell.total_frequency, expected.total_frequency);
+        assert_eq!(symspell.deletes.len(), expected.deletes.len());
+        for bucket in symspell.deletes.values() {
+            assert!(bucket.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        for query in ["helo", "hel", "shel", "wrld", "help", "helpp"] {
+            let terms = |symspell: &SymSpell| -> Vec<(String, usize)> {
+                symspell.suggestions(query, 10).into_iter().map(|s| (s.term, s.distance)).collect()
+            };
+            assert_eq!(terms(&symspell), terms(&expected), "query {:?}", query);
+        }
+        assert!(symspell.verify(0, usize::MAX, Instant::now() + Duration::from_secs(60)).failures.is_empty());
+
+        // Removing the last word moves nothing
+        assert!(symspell.remove_word("shell"));
+        assert!(symspell.suggestions("shel", 10).iter().all(|s| s.term != "shell"));
+        assert_eq!(symspell.suggestions("wrld", 1)[0].term, "world");
+    }
+
+    #[test]
+    fn test_deletes_hold_sorted_word_ids() {
+        let mut symspell = SymSpell::new(2);
+        for word in ["hello", "help", "hell", "shell", "hello"] {
+            symspell.add_word(word, word, 10);
+        }
+
+        assert_eq!(symspell.len(), 4);
+        for bucket in symspell.deletes.values() {
+            assert!(bucket.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        let hel: Vec<&str> = symspell.deletes["hel"].iter().map(|&id| symspell.words.key(id)).collect();
+        assert_eq!(hel, ["hello", "help", "hell", "shell"]);
+        assert!(symspell.deletes_bytes() < symspell.string_deletes_bytes());
+
+        // Ids are renumbered in key order by a save and load, and buckets stay sorted
+        let mut bytes = Vec::new();
+        symspell.write_index(&mut bytes).unwrap();
+        let loaded = SymSpell::read_index(&mut bytes.as_slice()).unwrap();
+        for (delete, bucket) in &loaded.deletes {
+            assert!(bucket.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(bucket.len(), symspell.deletes[delete].len());
+        }
+        assert_eq!(loaded.suggestions("helo", 10).len(), symspell.suggestions("helo", 10).len());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word("hello", "hello", 1000);
+        symspell.add_word("nasa", "NASA", 500);
+        symspell.add_word("apple", "apple", 500);
+        symspell.add_word("world", "world", 800);
+
+        let nasa = symspell.lookup("Nasa").unwrap();
+        assert_eq!(nasa, Lookup { canonical: "NASA".into(), frequency: 500, rank: 4 });
+        assert_eq!(symspell.lookup("NASA"), Some(nasa));
+        assert_eq!(symspell.lookup("apple").unwrap().rank, 3);
+        assert_eq!(symspell.lookup("HELLO").unwrap().rank, 1);
+        assert_eq!(symspell.lookup("helo"), None);
+
+        // A duplicate can change the order, so ranks are rebuilt
+        symspell.add_word("apple", "apple", 1000);
+        assert_eq!(symspell.lookup("apple").unwrap().rank, 1);
+        assert_eq!(symspell.lookup("hello").unwrap().rank, 2);
+    }
+
+    #[test]
+    fn test_probability_follows_total_frequency() {
+        let mut symspell = SymSpell::new(1);
+        assert_eq!(symspell.probability(0), 0.0);
+
+        symspell.add_word("hello", "hello", 750);
+        symspell.add_word("help", "help", 250);
+        assert_eq!(symspell.probability(750), 0.75);
+
+        symspell.remove_word("help");
+        assert_eq!(symspell.probability(750), 1.0);
+    }
+
+    #[test]
+    fn test_layer_combination() {
+        let build = |combine: Combine, compact: bool| {
+            let symspell = if compact { SymSpell::new_compact(1, 1) } else { SymSpell::new(1) };
+            let mut symspell = symspell.with_combine(combine);
+            symspell.add_word("hello", "hello", 100);
+            symspell.add_to_layer(Layer::User, "hello", "hello", 300);
+            symspell.add_to_layer(Layer::Trained, "hello", "hello", 50);
+            symspell.add_to_layer(Layer::Trained, "zeta", "zeta", 7);
+            symspell
+        };
+
+        for compact in [false, true] {
+            let sum = build(Combine::Sum, compact);
+            assert_eq!(sum.get_frequency("hello"), Some(450));
+            assert_eq!(sum.layers("hello"), Some([100, 300, 50]));
+            assert_eq!(sum.get_frequency("zeta"), Some(7));
+            assert_eq!(sum.total_frequency, 457);
+
+            assert_eq!(build(Combine::Max, compact).get_frequency("hello"), Some(300));
+
+            let base_only = build(Combine::BaseOnly, compact);
+            assert_eq!(base_only.get_frequency("hello"), Some(100));
+            assert_eq!(base_only.get_frequency("zeta"), Some(0));
+            assert!(base_only.contains("zeta"));
+        }
+
+        let sum = build(Combine::Sum, false);
+        let layered: Vec<String> = sum.layered_entries().into_iter().map(|(key, _, _)| key).collect();
+        assert_eq!(layered, ["hello", "zeta"]);
+
+        // Base-only entries keep no per-layer counts
+        let mut plain = SymSpell::new(1);
+        plain.add_word("world", "world", 10);
+        plain.add_word("world", "world", 5);
+        assert_eq!(plain.layers("world"), Some([15, 0, 0]));
+        assert!(plain.layered_entries().is_empty());
+    }
+
+    #[test]
+    fn test_compact_canonical_fallback() {
+        let mut compact = SymSpell::new_compact(1, 1);
+        compact.add_word("hello", "hello", 100);
+        compact.add_word("nasa", "NASA", 100);
+
+        assert_eq!(compact.suggestions("hello", 1)[0].term, "hello");
+        assert_eq!(compact.suggestions("nasa", 1)[0].term, "NASA");
+
+        // A higher-frequency duplicate whose canonical equals the key replaces the stored form
+        compact.add_word("nasa", "nasa", 500);
+        assert_eq!(compact.suggestions("NASA", 1)[0].term, "nasa");
+        assert_eq!(compact.get_frequency("nasa"), Some(600));
+    }
+
+    #[test]
+    fn test_compact_frequency_saturation_and_scale() {
+        let mut compact = SymSpell::new_compact(1, 1);
+        compact.add_word("big", "big", u64::MAX);
+        compact.add_word("mid", "mid", u32::MAX as u64 - 1);
+        compact.add_word("mid", "mid", 10);
+        assert_eq!(compact.get_frequency("big"), Some(u32::MAX as u64));
+        assert_eq!(compact.get_frequency("mid"), Some(u32::MAX as u64));
+
+        let mut scaled = SymSpell::new_compact(1, 1000);
+        scaled.add_word("word", "word", 123_456);
+        scaled.add_word("huge", "huge", 10_000_000_000_000);
+        // Rounded down to a multiple of the scale on the way back out
+        assert_eq!(scaled.get_frequency("word"), Some(123_000));
+        assert_eq!(scaled.get_frequency("huge"), Some(u32::MAX as u64 * 1000));
+    }
+
+    #[test]
+    fn test_perturb_distance_count_and_determinism() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word("hello", "hello", 1000);
+
+        for distance in 1..=3 {
+            let typos = symspell.perturb("Hello", distance, 10, 42, false);
+            assert_eq!(typos.len(), 10);
+            assert!(typos.iter().all(|t| symspell.edit_distance("hello", t) == distance));
+
+            let unique: HashSet<&String> = typos.iter().collect();
+            assert_eq!(unique.len(), typos.len());
+
+            assert_eq!(typos, symspell.perturb("Hello", distance, 10, 42, false));
+        }
+
+        assert_ne!(symspell.perturb("hello", 1, 10, 1, false), symspell.perturb("hello", 1, 10, 2, false));
+    }
+
+    #[test]
+    fn test_perturb_excludes_known_words() {
+        let mut symspell = SymSpell::new(1);
+        // Every single deletion of "ab" is a dictionary word
+        symspell.add_word("ab", "ab", 100);
+        symspell.add_word("a", "a", 100);
+        symspell.add_word("b", "b", 100);
+
+        let typos = symspell.perturb("ab", 1, 200, 7, false);
+        assert!(typos.iter().all(|t| t != "a" && t != "b"));
+
+        let with_known = symspell.perturb("ab", 1, 200, 7, true);
+        assert!(with_known.iter().any(|t| t == "a" || t == "b"));
+    }
+
+    #[test]
+    fn test_exclude_exact_uses_normalized_key() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word(&symspell.normalize("Hello"), "Hello", 1000);
+        symspell.add_word("hell", "hell", 500);
+        // Precomposed é; queries below use the decomposed form
+        symspell.add_word(&symspell.normalize("caf\u{e9}"), "caf\u{e9}", 800);
+
+        let with_exact = symspell.suggestions_within("hello", 5, 1, false);
+        assert_eq!(with_exact[0].term, "Hello");
+        assert_eq!(with_exact[0].distance, 0);
+
+        for query in ["hello", "HELLO", "Hello"] {
+            let alternatives = symspell.suggestions_within(query, 5, 1, true);
+            assert!(alternatives.iter().all(|s| s.distance > 0), "{} returned a distance-0 entry", query);
+            assert!(alternatives.iter().all(|s| s.term != "Hello"));
+            assert_eq!(alternatives[0].term, "hell");
+        }
+
+        let decomposed = "cafe\u{301}";
+        assert_eq!(symspell.suggestions_within(decomposed, 5, 1, false)[0].term, "caf\u{e9}");
+        assert!(symspell.suggestions_within(decomposed, 5, 1, true).is_empty());
+    }
+
     #[test]
     fn test_single_character_corrections() {
         let mut symspell = SymSpell::new(1);
@@ -346,4 +2263,167 @@ mod tests {
         assert_eq!(suggestions2[0].term, "world", "Should update to high-frequency 'world' canonical");
         assert_eq!(suggestions2[0].frequency, 10100, "Should sum frequencies");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_complete_picks_most_frequent_extension() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word("prot", "prot", 50);
+        symspell.add_word("protein", "protein", 1200);
+        symspell.add_word("protocol", "Protocol", 900);
+        symspell.add_word("proton", "proton", 300);
+
+        let completion = symspell.complete("PROT").unwrap();
+        assert_eq!(completion.term, "protein");
+        assert_eq!(completion.distance, 3);
+        assert!(symspell.complete("protx").is_none());
+
+        // New words invalidate the sorted key list
+        symspell.add_word("protest", "protest", 5000);
+        assert_eq!(symspell.complete("prot").unwrap().term, "protest");
+    }
+
+    #[test]
+    fn test_best_split_prefers_the_higher_scoring_boundary() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word("a", "a", 50000);
+        symspell.add_word("lot", "lot", 3000);
+        symspell.add_word("al", "AL", 10);
+        symspell.add_word("ot", "ot", 5);
+        symspell.add_word("allot", "allot", 200);
+
+        let split = symspell.best_split("Alot").unwrap();
+        assert_eq!((split.left.as_str(), split.right.as_str()), ("a", "lot"));
+        assert!((split.score - 50000.0 * 3000.0 / 53215.0).abs() < 1e-6);
+        assert!(symspell.best_split("lots").is_none());
+    }
+
+    #[test]
+    fn test_sample_is_reproducible_and_weighted() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word("common", "Common", 9900);
+        symspell.add_word("rare", "rare", 100);
+        symspell.add_word("never", "never", 0);
+
+        let sample = symspell.sample(1000, 7, true);
+        assert_eq!(sample, symspell.sample(1000, 7, true));
+        assert_ne!(sample, symspell.sample(1000, 8, true));
+
+        // Expected 990 of 1000
+        let common = sample.iter().filter(|w| *w == "Common").count();
+        assert!((950..=1000).contains(&common), "{} common draws", common);
+        assert!(!sample.iter().any(|w| w == "never"));
+
+        let uniform = symspell.sample(3000, 7, false);
+        assert!(uniform.iter().filter(|w| *w == "never").count() > 800);
+    }
+
+    #[test]
+    fn test_warmup_counts_queries_and_touched_entries() {
+        let mut symspell = SymSpell::new(1);
+        for (word, frequency) in [("hello", 100), ("help", 50), ("world", 80)] {
+            symspell.add_word(word, word, frequency);
+        }
+
+        let warmup = symspell.warmup(20, 7);
+        assert_eq!(warmup.queries, 40);
+        assert!(warmup.entries_touched > 0);
+        assert!(warmup.entries_touched <= symspell.deletes.len());
+        assert_eq!(symspell.warmup(20, 7).entries_touched, warmup.entries_touched);
+
+        let empty = SymSpell::new(1).warmup(20, 7);
+        assert_eq!((empty.queries, empty.entries_touched), (0, 0));
+    }
+
+    #[test]
+    fn test_verify_finds_a_missing_deletes_bucket() {
+        let mut symspell = SymSpell::new(1);
+        for (word, frequency) in [("hello", 100), ("help", 50), ("world", 80), ("word", 40)] {
+            symspell.add_word(word, word, frequency);
+        }
+        let later = Instant::now() + Duration::from_secs(60);
+
+        let healthy = symspell.verify(0, 10, later);
+        assert_eq!((healthy.checked, healthy.total), (4, 4));
+        assert!(healthy.failures.is_empty());
+
+        // "hell" holds only "hello"; key order is hello, help, word, world
+        symspell.deletes.remove("hell");
+        let first = symspell.verify(0, 1, later);
+        assert_eq!((first.checked, first.failures), (1, vec!["hello".to_string()]));
+        assert!(symspell.verify(1, 10, later).failures.is_empty());
+
+        // Past the deadline one entry is still checked, so probes always make progress
+        assert_eq!(symspell.verify(0, 10, Instant::now()).checked, 1);
+    }
+
+    #[test]
+    fn test_probe_finds_words_from_typos() {
+        let mut symspell = SymSpell::new(2);
+        for (word, frequency) in [("hello", 100), ("help", 50), ("world", 80), ("word", 40), ("a", 10)] {
+            symspell.add_word(word, word, frequency);
+        }
+
+        let healthy = symspell.probe(20, 7);
+        assert_eq!(healthy.probes, 20);
+        assert!(healthy.failures.is_empty(), "{:?}", healthy.failures);
+        assert_eq!(symspell.probe(20, 7).failures, healthy.failures);
+
+        // Without the deletes map only typos that insert a character still find their word
+        symspell.deletes.clear();
+        let damaged = symspell.probe(20, 7);
+        assert_eq!(damaged.probes, 20);
+        assert!(damaged.failures.len() > damaged.probes / 2, "{:?}", damaged.failures);
+
+        assert_eq!(SymSpell::new(1).probe(5, 1).probes, 0);
+    }
+
+    #[test]
+    fn test_sample_cache_is_rebuilt_after_add_word() {
+        let mut symspell = SymSpell::new(1);
+        symspell.add_word("alpha", "alpha", 100);
+        assert_eq!(symspell.sample(3, 1, true), vec!["alpha"; 3]);
+
+        symspell.add_word("beta", "beta", 1);
+        assert!(symspell.sample(100, 1, true).iter().filter(|w| *w == "alpha").count() > 90);
+
+        // A duplicate shifts the weights without adding a key
+        symspell.add_word("beta", "beta", 1_000_000);
+        let sample = symspell.sample(100, 1, true);
+        assert!(sample.iter().filter(|w| *w == "beta").count() > 95);
+    }
+
+    #[test]
+    fn test_strip_accents() {
+        let accentless = Normalizer { strip_accents: true, ..Normalizer::default() };
+        let mut symspell = SymSpell::new(1).with_normalizer(accentless);
+        for (word, frequency) in [("caf\u{e9}", 800), ("na\u{ef}ve", 500), ("M\u{fc}ller", 300), ("resume", 100)] {
+            symspell.add_word(&symspell.normalize(word), word, frequency);
+        }
+
+        // Found at distance 0 without the accents, and reported with them
+        for (query, canonical) in [("cafe", "caf\u{e9}"), ("NAIVE", "na\u{ef}ve"), ("Muller", "M\u{fc}ller"), ("r\u{e9}sum\u{e9}", "resume")] {
+            let suggestions = symspell.suggestions(query, 1);
+            assert_eq!((suggestions[0].term.as_str(), suggestions[0].distance), (canonical, 0), "{}", query);
+            assert_eq!(symspell.canonical(query), Some(canonical));
+        }
+        assert_eq!(symspell.suggestions("cafes", 1)[0].term, "caf\u{e9}");
+        assert!(!SymSpell::new(1).contains("cafe"));
+
+        // Every spelling of a word is indexed under the key its queries are normalized to:
+        // precomposed, decomposed, stacked marks, compatibility forms, and text with no marks
+        let words = ["e\u{301}cole", "\u{e9}cole", "\u{1ec7}", "e\u{323}\u{302}", "\u{fb01}anc\u{e9}", "\u{212b}ngstr\u{f6}m", "ΆΈΉ", "日本", "plain"];
+        for word in words {
+            let mut index = SymSpell::new(1).with_normalizer(accentless);
+            index.add_word(&index.normalize(word), word, 10);
+            assert!(index.contains(word), "{:?}", word);
+            assert_eq!(index.canonical(&index.normalize(word)), Some(word), "{:?}", word);
+            assert!(!index.normalize(word).chars().any(is_combining_mark), "{:?}", word);
+        }
+
+        let mut bytes = Vec::new();
+        symspell.write_index(&mut bytes).unwrap();
+        let loaded = SymSpell::read_index(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.normalizer(), &accentless);
+        assert_eq!(loaded.canonical("cafe"), Some("caf\u{e9}"));
+    }
+}
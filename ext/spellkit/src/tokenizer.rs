@@ -0,0 +1,352 @@
+use regex::Regex;
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone)]
+pub enum Tokenizer {
+    // UAX-29 word boundaries; only segments containing a letter or digit are tokens
+    Unicode,
+    // Split on whitespace only, punctuation stays attached to the token
+    Whitespace,
+    // Every match of the pattern is a token
+    Pattern(Regex),
+}
+
+impl Tokenizer {
+    pub fn from_config(kind: &str, pattern: Option<&str>) -> Result<Self, String> {
+        match kind {
+            "unicode" => Ok(Tokenizer::Unicode),
+            "whitespace" => Ok(Tokenizer::Whitespace),
+            "pattern" => {
+                let source = pattern.ok_or_else(|| "tokenizer pattern is required".to_string())?;
+                Regex::new(source)
+                    .map(Tokenizer::Pattern)
+                    .map_err(|e| format!("Invalid tokenizer pattern: {}", e))
+            }
+            other => Err(format!(
+                "tokenizer must be \"unicode\", \"whitespace\", or a pattern, got: {}",
+                other
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tokenizer::Unicode => "unicode",
+            Tokenizer::Whitespace => "whitespace",
+            Tokenizer::Pattern(_) => "pattern",
+        }
+    }
+
+    // Returns (byte offset, token) pairs in input order
+    pub fn tokens<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+        match self {
+            Tokenizer::Unicode => text
+                .split_word_bound_indices()
+                .filter(|(_, segment)| segment.chars().any(char::is_alphanumeric))
+                .collect(),
+            Tokenizer::Whitespace => {
+                let mut tokens = Vec::new();
+                let mut start = None;
+                for (i, c) in text.char_indices() {
+                    if c.is_whitespace() {
+                        if let Some(s) = start.take() {
+                            tokens.push((s, &text[s..i]));
+                        }
+                    } else if start.is_none() {
+                        start = Some(i);
+                    }
+                }
+                if let Some(s) = start {
+                    tokens.push((s, &text[s..]));
+                }
+                tokens
+            }
+            Tokenizer::Pattern(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.as_str().is_empty())
+                .map(|m| (m.start(), m.as_str()))
+                .collect(),
+        }
+    }
+}
+
+// Whether text[start..end] is one part of a hyphenated word ("state-of-the-art", "5-FU"), which
+// UAX-29 splits at every hyphen. The text pipeline passes such words through whole rather than
+// correcting their parts one at a time
+pub fn in_hyphenated_word(text: &str, start: usize, end: usize) -> bool {
+    let is_hyphen = |c: char| c == '-' || c == '\u{2010}';
+    let mut before = text[..start].chars().rev();
+    let mut after = text[end..].chars();
+
+    before.next().is_some_and(is_hyphen) && before.next().is_some_and(char::is_alphanumeric)
+        || after.next().is_some_and(is_hyphen) && after.next().is_some_and(char::is_alphanumeric)
+}
+
+// Scripts written without spaces that UAX-29 can't split into words: Han, Hiragana, Katakana,
+// and Thai. Tokens containing them are passed through by the text pipeline, never corrected
+pub fn is_unsegmented_script(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0E7F}'        // Thai
+        | '\u{3005}' | '\u{3007}'      // Ideographic iteration mark and zero
+        | '\u{3040}'..='\u{30FF}'      // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'      // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'      // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'      // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'      // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9D}'      // Halfwidth Katakana
+        | '\u{20000}'..='\u{3134F}'    // CJK extensions B-G and compatibility supplement
+    )
+}
+
+// Byte ranges of maximal runs of unsegmented-script characters, for reporting them
+pub fn unsegmented_runs(text: &str) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        match (start, is_unsegmented_script(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                runs.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        runs.push((s, text.len()));
+    }
+    runs
+}
+
+// Punctuation peeled off the edges of a token before it is guarded and corrected: quotes,
+// brackets, sentence punctuation, and dashes. Hyphens, apostrophes inside a word, and symbols
+// such as "+" or "#" are kept
+pub const STRIPPED_PUNCTUATION: &[char] = &[
+    '"', '\'', '‘', '’', '“', '”', '«', '»', '‹', '›', '(', ')', '[', ']', '{', '}', '<', '>', '.', ',', ';',
+    ':', '!', '?', '¡', '¿', '…', '–', '—',
+];
+
+// (leading, core, trailing) of a token around its edge punctuation. All-punctuation tokens
+// come back as all leading with an empty core
+pub fn strip_punctuation(token: &str) -> (&str, &str, &str) {
+    let rest = token.trim_start_matches(STRIPPED_PUNCTUATION);
+    let core = rest.trim_end_matches(STRIPPED_PUNCTUATION);
+    let lead = &token[..token.len() - rest.len()];
+    (lead, core, &rest[core.len()..])
+}
+
+// Default-ignorable code points (Unicode's Default_Ignorable_Code_Point) that survive
+// `normalize_word` because they are format characters, not controls or whitespace: zero-width
+// spaces and joiners, bidi marks and embeddings, the soft hyphen, the BOM, and variation selectors
+pub fn is_default_ignorable(c: char) -> bool {
+    matches!(c,
+        '\u{00AD}' | '\u{034F}' | '\u{061C}' | '\u{115F}' | '\u{1160}' | '\u{17B4}' | '\u{17B5}'
+        | '\u{180B}'..='\u{180F}'
+        | '\u{200B}'..='\u{200F}'    // ZWSP, ZWNJ, ZWJ, LRM, RLM
+        | '\u{202A}'..='\u{202E}'    // Bidi embeddings and overrides
+        | '\u{2060}'..='\u{206F}'    // Word joiner, invisible operators, bidi isolates
+        | '\u{3164}' | '\u{FEFF}' | '\u{FFA0}'
+        | '\u{FE00}'..='\u{FE0F}'    // Variation selectors
+        | '\u{FFF0}'..='\u{FFF8}'
+        | '\u{1BCA0}'..='\u{1BCA3}'
+        | '\u{1D173}'..='\u{1D17A}'
+        | '\u{E0000}'..='\u{E0FFF}'
+    )
+}
+
+fn is_pictographic(c: char) -> bool {
+    matches!(c, '\u{2600}'..='\u{27BF}' | '\u{1F000}'..='\u{1FAFF}')
+}
+
+// `text` without default-ignorable code points. A ZWJ or variation selector directly after a
+// pictograph (or after another one of those) belongs to an emoji sequence and is kept
+pub fn strip_ignorable(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_default_ignorable) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut in_emoji = false;
+    for c in text.chars() {
+        let joins_emoji = in_emoji && matches!(c, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}');
+        if is_default_ignorable(c) && !joins_emoji {
+            in_emoji = false;
+            continue;
+        }
+        in_emoji = joins_emoji || is_pictographic(c);
+        out.push(c);
+    }
+    Cow::Owned(out)
+}
+
+// Terminal cells a grapheme cluster takes up: 2 for East Asian wide and fullwidth characters and
+// for emoji, 1 otherwise. An approximation of UAX-11 that covers the scripts in our dictionaries
+fn grapheme_width(grapheme: &str) -> usize {
+    let wide = grapheme.chars().any(|c| {
+        matches!(c,
+            '\u{1100}'..='\u{115F}'        // Hangul Jamo initials
+            | '\u{2E80}'..='\u{303E}'      // CJK radicals, punctuation
+            | '\u{3041}'..='\u{A4CF}'      // Kana through Yi
+            | '\u{AC00}'..='\u{D7A3}'      // Hangul syllables
+            | '\u{F900}'..='\u{FAFF}'      // CJK compatibility ideographs
+            | '\u{FE30}'..='\u{FE4F}'      // CJK compatibility forms
+            | '\u{FF00}'..='\u{FF60}'      // Fullwidth forms
+            | '\u{FFE0}'..='\u{FFE6}'
+            | '\u{FE0F}'                   // Emoji presentation selector
+            | '\u{1F300}'..='\u{1F64F}'    // Pictographs, emoticons
+            | '\u{1F680}'..='\u{1F6FF}'    // Transport and map symbols
+            | '\u{1F900}'..='\u{1FAFF}'    // Supplemental pictographs
+            | '\u{20000}'..='\u{3FFFD}'    // CJK extensions
+        )
+    });
+    if wide { 2 } else { 1 }
+}
+
+// `text` cut to at most `cells` display cells on grapheme boundaries, ending in "…" when
+// anything was cut, so combining marks and emoji sequences are never split. Text that fits
+// is returned unchanged
+pub fn truncate_display(text: &str, cells: usize) -> String {
+    let total: usize = text.graphemes(true).map(grapheme_width).sum();
+    if total <= cells {
+        return text.to_string();
+    }
+    if cells == 0 {
+        return String::new();
+    }
+
+    // One cell goes to the ellipsis
+    let mut out = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let width = grapheme_width(grapheme);
+        if used + width > cells - 1 {
+            break;
+        }
+        out.push_str(grapheme);
+        used += width;
+    }
+    out.push('…');
+    out
+}
+
+// One replacement of `original` at byte offsets [start, end) of a text, as a dry run proposes it
+pub struct Change<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub original: &'a str,
+    pub proposed: &'a str,
+}
+
+// `text` with `changes` applied, in offset order whatever order they come in. Each change has to
+// describe the text as it is: in bounds, on character boundaries, and covering exactly its
+// `original`. Overlapping changes are rejected
+pub fn apply_changes(text: &str, mut changes: Vec<Change>) -> Result<String, String> {
+    changes.sort_by_key(|change| (change.start, change.end));
+
+    let mut out = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for change in &changes {
+        let (start, end) = (change.start, change.end);
+        if start > end || end > text.len() {
+            return Err(format!("change at {}..{} is out of bounds for a text of {} bytes", start, end, text.len()));
+        }
+        if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            return Err(format!("change at {}..{} doesn't fall on character boundaries", start, end));
+        }
+        if &text[start..end] != change.original {
+            return Err(format!(
+                "change at {}..{} expects {:?}, but the text has {:?}",
+                start, end, change.original, &text[start..end]
+            ));
+        }
+        if start < last_end {
+            return Err(format!("change at {}..{} overlaps the change before it", start, end));
+        }
+
+        out.push_str(&text[last_end..start]);
+        out.push_str(change.proposed);
+        last_end = end;
+    }
+    out.push_str(&text[last_end..]);
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_punctuation() {
+        assert_eq!(strip_punctuation("(COVID-19),"), ("(", "COVID-19", "),"));
+        assert_eq!(strip_punctuation("“hello”"), ("“", "hello", "”"));
+        assert_eq!(strip_punctuation("¿qué?"), ("¿", "qué", "?"));
+        assert_eq!(strip_punctuation("don't"), ("", "don't", ""));
+        assert_eq!(strip_punctuation("C++"), ("", "C++", ""));
+        assert_eq!(strip_punctuation("..."), ("...", "", ""));
+    }
+
+    #[test]
+    fn test_in_hyphenated_word() {
+        let text = "a well-known, state\u{2010}of café-au-lait don't - dash 5-FU";
+        let hyphenated: Vec<&str> = Tokenizer::Unicode
+            .tokens(text)
+            .into_iter()
+            .filter(|&(start, token)| in_hyphenated_word(text, start, start + token.len()))
+            .map(|(_, token)| token)
+            .collect();
+
+        assert_eq!(hyphenated, ["well", "known", "state", "of", "café", "au", "lait", "5", "FU"]);
+        assert!(Tokenizer::Unicode.tokens(text).iter().any(|&(_, token)| token == "don't"));
+    }
+
+    #[test]
+    fn test_strip_ignorable() {
+        assert!(matches!(strip_ignorable("hello"), Cow::Borrowed("hello")));
+        assert_eq!(strip_ignorable("hel\u{200B}lo"), "hello");
+        assert_eq!(strip_ignorable("\u{200E}hello\u{200F}"), "hello");
+        assert_eq!(strip_ignorable("\u{202B}co\u{00AD}op\u{202C}\u{FEFF}"), "coop");
+        assert_eq!(strip_ignorable("a\u{200D}b\u{200C}c"), "abc");
+
+        // Emoji sequences keep their joiners and presentation selectors
+        assert_eq!(strip_ignorable("👩\u{200D}💻"), "👩\u{200D}💻");
+        assert_eq!(strip_ignorable("❤\u{FE0F}\u{200B}"), "❤\u{FE0F}");
+    }
+
+    #[test]
+    fn test_truncate_display() {
+        assert_eq!(truncate_display("hello", 5), "hello");
+        assert_eq!(truncate_display("hello world", 6), "hello…");
+        assert_eq!(truncate_display("hello", 0), "");
+
+        // e + combining acute is one cell and never split from its mark
+        assert_eq!(truncate_display("cafe\u{301}s", 5), "cafe\u{301}s");
+        assert_eq!(truncate_display("cafe\u{301}ine", 5), "cafe\u{301}…");
+        assert_eq!(truncate_display("cafe\u{301}ine", 4), "caf…");
+
+        // A two-cell emoji that would straddle the limit is left out whole, ZWJ sequences included
+        assert_eq!(truncate_display("ab👍cd", 4), "ab…");
+        assert_eq!(truncate_display("ab👍cd", 5), "ab👍…");
+        assert_eq!(truncate_display("a👩\u{200D}💻bcd", 4), "a👩\u{200D}💻…");
+        assert_eq!(truncate_display("東京都庁", 5), "東京…");
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let text = "teh helo wrld";
+        let change = |start, end, original, proposed| Change { start, end, original, proposed };
+
+        // Out of order, and a subset
+        let applied = apply_changes(text, vec![change(9, 13, "wrld", "world"), change(0, 3, "teh", "the")]);
+        assert_eq!(applied.unwrap(), "the helo world");
+        assert_eq!(apply_changes(text, vec![]).unwrap(), text);
+
+        assert!(apply_changes(text, vec![change(4, 8, "hell", "hello")]).unwrap_err().contains("expects"));
+        assert!(apply_changes(text, vec![change(9, 20, "wrld", "world")]).unwrap_err().contains("out of bounds"));
+        assert!(apply_changes("café", vec![change(0, 4, "caf\u{e9}", "cafe")]).unwrap_err().contains("boundaries"));
+        let overlapping = vec![change(4, 8, "helo", "hello"), change(6, 13, "lo wrld", "lo world")];
+        assert!(apply_changes(text, overlapping).unwrap_err().contains("overlaps"));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::normalizer::Normalizer;
+use crate::tokenizer::Tokenizer;
+use hashbrown::HashMap;
+use std::io::{self, BufRead, Write};
+use unicode_normalization::UnicodeNormalization;
+
+/// What a training run read and wrote: corpus lines and word tokens counted, distinct words
+/// among them, and the words written with at least `min_count` occurrences
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Training {
+    pub lines: usize,
+    pub tokens: usize,
+    pub distinct: usize,
+    pub written: usize,
+}
+
+/// Counts the words of a raw text corpus and writes those seen at least `min_count` times as
+/// a word<TAB>count dictionary, most frequent first and ties in word order. The corpus is read
+/// a line at a time, so memory grows with its vocabulary rather than its size. Tokens are UAX-29
+/// words with a letter in them, counted under the default normalization `load!` uses and
+/// written recomposed (NFC), so a later load keys every word the way it was counted. Lines that
+/// aren't valid UTF-8 are read lossily rather than failing a multi-gigabyte run
+pub fn train<R: BufRead, W: Write>(mut input: R, mut output: W, min_count: u64) -> io::Result<Training> {
+    let normalizer = Normalizer::default();
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    let mut training = Training::default();
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        training.lines += 1;
+
+        let text = String::from_utf8_lossy(&line);
+        for (_, token) in Tokenizer::Unicode.tokens(&text) {
+            if !token.chars().any(char::is_alphabetic) {
+                continue;
+            }
+            let key = normalizer.normalize(token);
+            if key.is_empty() {
+                continue;
+            }
+            training.tokens += 1;
+            match counts.get_mut(&key) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.insert(key, 1);
+                }
+            }
+        }
+    }
+    training.distinct = counts.len();
+
+    let mut words: Vec<(String, u64)> = counts.into_iter().filter(|&(_, count)| count >= min_count).collect();
+    words.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (key, count) in &words {
+        writeln!(output, "{}\t{}", key.nfc().collect::<String>(), count)?;
+    }
+    output.flush()?;
+    training.written = words.len();
+
+    Ok(training)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Engine, LoadOptions};
+
+    #[test]
+    fn test_counts_normalized_words_above_min_count() {
+        let corpus = "The printer won't print.\nthe PRINTER jams; the toner is low\n\n42 tickets, café CAFÉ cafe\u{301}\n";
+        let mut output = Vec::new();
+
+        let training = train(corpus.as_bytes(), &mut output, 2).unwrap();
+        assert_eq!(training, Training { lines: 4, tokens: 15, distinct: 10, written: 3 });
+        assert_eq!(String::from_utf8(output).unwrap(), "café\t3\nthe\t3\nprinter\t2\n");
+
+        let mut output = Vec::new();
+        assert_eq!(train("won't won't\n".as_bytes(), &mut output, 1).unwrap().written, 1);
+        assert_eq!(String::from_utf8(output).unwrap(), "won't\t2\n");
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_read_lossily() {
+        let mut output = Vec::new();
+        let training = train(&b"ticket \xff\xfe ticket\nticket"[..], &mut output, 1).unwrap();
+        assert_eq!((training.lines, training.tokens), (2, 3));
+        assert_eq!(String::from_utf8(output).unwrap(), "ticket\t3\n");
+    }
+
+    #[test]
+    fn test_trained_dictionary_loads() {
+        let dir = std::env::temp_dir().join(format!("spellkit-train-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trained.tsv");
+
+        let corpus = "Reset the router, then reset the modem.\nThe router lights blink.\nRouter firmware update\n";
+        let file = std::fs::File::create(&path).unwrap();
+        train(corpus.as_bytes(), std::io::BufWriter::new(file), 2).unwrap();
+
+        let mut options = LoadOptions::new(path.to_str().unwrap());
+        options.strict = true;
+        let engine = Engine::load(&options).unwrap();
+        assert_eq!(engine.dictionary_size(), 3);
+        assert_eq!(engine.symspell().get_frequency("router"), Some(3));
+        assert_eq!(engine.suggest_within("routr", 1, 1, false).first().map(|s| s.term.as_str()), Some("router"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use hashbrown::HashMap;
+use std::borrow::Cow;
+
+// Units recognised after a number unless load! is given its own list. Each is matched
+// case-insensitively and written back in the form listed here
+pub const DEFAULT_UNITS: &[&str] = &[
+    "mg", "g", "kg", "mcg", "µg", "ng", "ml", "l", "dl", "cl", "mm", "cm", "m", "km", "in", "ft", "lb", "oz",
+    "mmol", "mol", "iu", "meq", "kcal", "cal", "h", "hr", "min", "s", "ms", "%", "°C", "°F", "mmHg",
+];
+
+/// What the pipeline does with a number, optionally followed by a unit from the unit list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitMode {
+    /// No special handling: the token goes through guards and correction like any other
+    Off,
+    /// Left exactly as written, and counted as correct
+    Skip,
+    /// As `Skip`, but the unit is rewritten in its listed form right after the number
+    Normalize,
+}
+
+impl UnitMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(Self::Off),
+            "skip" => Some(Self::Skip),
+            "normalize" => Some(Self::Normalize),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Skip => "skip",
+            Self::Normalize => "normalize",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Units {
+    mode: UnitMode,
+    // Lowercased unit -> its listed form
+    canonical: HashMap<String, String>,
+}
+
+impl Units {
+    pub fn new(mode: UnitMode, list: &[String]) -> Self {
+        let canonical = list.iter().map(|unit| (unit.to_lowercase(), unit.clone())).collect();
+        Self { mode, canonical }
+    }
+
+    pub fn mode(&self) -> UnitMode {
+        self.mode
+    }
+
+    // Listed forms in sorted order, for the behavior material
+    pub fn sorted(&self) -> Vec<&str> {
+        let mut units: Vec<&str> = self.canonical.values().map(String::as_str).collect();
+        units.sort_unstable();
+        units
+    }
+
+    /// The output for a numeric token ("37.5", "500MG", "10 ml"), or None if the token isn't
+    /// one or the mode is `Off`. A token has to start with a digit to be considered at all, so
+    /// every other token costs one byte comparison. A number followed by anything but a listed
+    /// unit isn't numeric
+    pub fn apply<'a>(&self, token: &'a str) -> Option<Cow<'a, str>> {
+        if self.mode == UnitMode::Off || !token.as_bytes().first().is_some_and(u8::is_ascii_digit) {
+            return None;
+        }
+
+        let (number, unit) = split_number(token);
+        if unit.is_empty() {
+            return Some(Cow::Borrowed(token));
+        }
+
+        let canonical = self.canonical.get(&unit.to_lowercase())?;
+        match self.mode {
+            UnitMode::Normalize => Some(Cow::Owned(format!("{}{}", number, canonical))),
+            _ => Some(Cow::Borrowed(token)),
+        }
+    }
+}
+
+// (number, unit) of a token starting with a digit: digits with "." or "," separators between
+// them ("1,000.5"), then whatever follows after optional spaces
+fn split_number(token: &str) -> (&str, &str) {
+    let bytes = token.as_bytes();
+    let mut end = 0;
+    while end < bytes.len() {
+        let separator = matches!(bytes[end], b'.' | b',') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit);
+        if !bytes[end].is_ascii_digit() && !separator {
+            break;
+        }
+        end += 1;
+    }
+
+    (&token[..end], token[end..].trim_start_matches(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units(mode: UnitMode) -> Units {
+        Units::new(mode, &DEFAULT_UNITS.iter().map(|unit| unit.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_split_number() {
+        assert_eq!(split_number("500mg"), ("500", "mg"));
+        assert_eq!(split_number("1,000.5 ML"), ("1,000.5", "ML"));
+        assert_eq!(split_number("37.5\u{b0}C"), ("37.5", "\u{b0}C"));
+        assert_eq!(split_number("5."), ("5", "."));
+    }
+
+    #[test]
+    fn test_apply() {
+        let skip = units(UnitMode::Skip);
+        let normalize = units(UnitMode::Normalize);
+
+        assert_eq!(skip.apply("500MG").as_deref(), Some("500MG"));
+        assert_eq!(normalize.apply("500MG").as_deref(), Some("500mg"));
+        assert_eq!(normalize.apply("10 ML").as_deref(), Some("10ml"));
+        assert_eq!(normalize.apply("37.5\u{b0}c").as_deref(), Some("37.5\u{b0}C"));
+        assert_eq!(normalize.apply("42").as_deref(), Some("42"));
+
+        // Unknown units and words are left to the rest of the pipeline
+        assert_eq!(normalize.apply("500zz"), None);
+        assert_eq!(normalize.apply("mg500"), None);
+        assert_eq!(units(UnitMode::Off).apply("500mg"), None);
+    }
+}
@@ -3,6 +3,9 @@ require "uri"
 require "net/http"
 require "openssl"
 require "fileutils"
+require "tempfile"
+require "forwardable"
+require "digest"
 
 # Load the compiled Rust extension. Precompiled (platform) gems install it into a
 # Ruby-ABI-versioned subdir (lib/spellkit/<major.minor>/spellkit.{so,bundle}) so a
@@ -18,8 +21,10 @@ rescue LoadError
 end
 
 module SpellKit
-  class Error < StandardError; end
-  class NotLoadedError < Error; end
+  # The extension defines SpellKit::Error and the subclasses it raises: NotLoadedError,
+  # DictionaryError (a dictionary over max_dictionary_bytes or max_words), DictionaryParseError
+  # (an entry rejected under strict: or on_duplicate: "error"), InvalidPatternError, and
+  # HealthcheckError (a failed healthcheck(deep: true) probe)
   class FileNotFoundError < Error; end
   class InvalidArgumentError < Error; end
   class DownloadError < Error; end
@@ -28,23 +33,153 @@ module SpellKit
   DEFAULT_DICTIONARY_URL = "https://raw.githubusercontent.com/wolfgarbe/SymSpell/master/SymSpell.FrequencyDictionary/en-80k.txt"
 
   class Configuration
-    attr_accessor :dictionary, :protected_path, :protected_patterns, :edit_distance, :frequency_threshold
+    attr_accessor :dictionary, :user_dictionary_path, :protected_path, :protected_patterns, :protected_prefixes, :edit_distance,
+      :max_suggestions, :skip_tokens_with_digits, :min_word_length, :skip_all_caps,
+      :tokenizer, :protected_as_known, :protect_only_unknown, :confident_frequency,
+      :autocorrect_known_rare, :aliases_path, :denylist_path, :compact, :frequency_scale, :boosts, :max_boost,
+      :segmentation, :prefer_segmentation_over_distance, :protected_case_sensitive, :max_line_length,
+      :max_word_length, :strict, :structural_match, :latency_stats, :warmup, :index_strategy, :frequency_combine,
+      :strip_ignorable, :tie_break, :units, :unit_list, :max_dictionary_bytes, :max_words,
+      :distance_metric, :index_path, :prefix_length, :format, :default_frequency, :separator, :term_index,
+      :count_index, :compression, :on_duplicate, :preserve_case, :skip, :strip_accents, :normalize, :case_sensitive,
+      :symbolize_keys
+    attr_reader :frequency_threshold, :min_suggestion_frequency, :frequency_ratio
 
     def initialize
       @dictionary = DEFAULT_DICTIONARY_URL
+      @user_dictionary_path = nil
       @protected_path = nil
       @protected_patterns = []
+      @protected_prefixes = []
       @edit_distance = 1
       @frequency_threshold = 10.0
+      @min_suggestion_frequency = nil
+      @frequency_ratio = nil
+      @tokenizer = "unicode"
+      @protected_as_known = false
+      @protect_only_unknown = false
+      @confident_frequency = 1000
+      @autocorrect_known_rare = nil
+      @aliases_path = nil
+      @denylist_path = nil
+      @compact = false
+      @frequency_scale = nil
+      @boosts = []
+      @max_boost = 100.0
+      @segmentation = false
+      @prefer_segmentation_over_distance = nil
+      @protected_case_sensitive = false
+      @max_line_length = 1024
+      @max_word_length = 100
+      @strict = false
+      @structural_match = true
+      @latency_stats = true
+      @warmup = false
+      @index_strategy = "full"
+      @frequency_combine = "sum"
+      @strip_ignorable = true
+      @tie_break = "lexicographic"
+      @units = "off"
+      @unit_list = nil
+      @max_dictionary_bytes = SpellKit::Checker::MAX_DICTIONARY_BYTES
+      @max_words = SpellKit::Checker::MAX_WORDS
+      @distance_metric = "levenshtein"
+      @index_path = nil
+      @prefix_length = SpellKit::Checker::PREFIX_LENGTH
+      @max_suggestions = SpellKit::Checker::MAX_SUGGESTIONS
+      @skip_tokens_with_digits = false
+      @min_word_length = 0
+      @skip_all_caps = false
+      @format = "counts"
+      @default_frequency = 1
+      @separator = nil
+      @term_index = 0
+      @count_index = 1
+      @compression = "auto"
+      @on_duplicate = "max"
+      @preserve_case = false
+      @skip = []
+      @strip_accents = false
+      @normalize = {}
+      @case_sensitive = false
+      @symbolize_keys = false
+    end
+
+    # Checked on assignment, so a bad value fails where it's set rather than at load!
+    def frequency_threshold=(value)
+      SpellKit::Checker.validate_frequency_threshold!(value)
+      @frequency_threshold = value
+    end
+
+    def min_suggestion_frequency=(value)
+      SpellKit::Checker.validate_frequency_threshold!(value, "min_suggestion_frequency") unless value.nil?
+      @min_suggestion_frequency = value
+    end
+
+    def frequency_ratio=(value)
+      SpellKit::Checker.validate_frequency_threshold!(value, "frequency_ratio") unless value.nil?
+      @frequency_ratio = value
     end
 
     def to_h
       {
         dictionary: @dictionary,
+        user_dictionary_path: @user_dictionary_path,
         protected_path: @protected_path,
         protected_patterns: @protected_patterns,
+        protected_prefixes: @protected_prefixes,
         edit_distance: @edit_distance,
-        frequency_threshold: @frequency_threshold
+        frequency_threshold: @frequency_threshold,
+        min_suggestion_frequency: @min_suggestion_frequency,
+        frequency_ratio: @frequency_ratio,
+        tokenizer: @tokenizer,
+        protected_as_known: @protected_as_known,
+        protect_only_unknown: @protect_only_unknown,
+        confident_frequency: @confident_frequency,
+        autocorrect_known_rare: @autocorrect_known_rare,
+        aliases_path: @aliases_path,
+        denylist_path: @denylist_path,
+        compact: @compact,
+        frequency_scale: @frequency_scale,
+        boosts: @boosts,
+        max_boost: @max_boost,
+        segmentation: @segmentation,
+        prefer_segmentation_over_distance: @prefer_segmentation_over_distance,
+        protected_case_sensitive: @protected_case_sensitive,
+        max_line_length: @max_line_length,
+        max_word_length: @max_word_length,
+        strict: @strict,
+        structural_match: @structural_match,
+        latency_stats: @latency_stats,
+        warmup: @warmup,
+        index_strategy: @index_strategy,
+        frequency_combine: @frequency_combine,
+        strip_ignorable: @strip_ignorable,
+        tie_break: @tie_break,
+        units: @units,
+        unit_list: @unit_list,
+        max_dictionary_bytes: @max_dictionary_bytes,
+        max_words: @max_words,
+        distance_metric: @distance_metric,
+        index_path: @index_path,
+        prefix_length: @prefix_length,
+        max_suggestions: @max_suggestions,
+        skip_tokens_with_digits: @skip_tokens_with_digits,
+        min_word_length: @min_word_length,
+        skip_all_caps: @skip_all_caps,
+        format: @format,
+        default_frequency: @default_frequency,
+        separator: @separator,
+        term_index: @term_index,
+        count_index: @count_index,
+        compression: @compression,
+        on_duplicate: @on_duplicate,
+        preserve_case: @preserve_case,
+        skip: @skip,
+        strip_accents: @strip_accents,
+        normalize: @normalize,
+        case_sensitive: @case_sensitive,
+        symbolize_keys: @symbolize_keys
       }
     end
   end
@@ -55,8 +190,7 @@ module SpellKit
     def configure
       config = Configuration.new
       yield(config)
-      @default = Checker.new
-      @default.load!(**config.to_h)
+      load!(**config.to_h)
       @default
     end
 
@@ -69,66 +203,408 @@ module SpellKit
     end
 
     # Delegation methods
+    # The previous default keeps serving if the load fails
     def load!(**options)
-      @default = Checker.new
-      @default.load!(**options)
-      @default
+      checker = Checker.new
+      report = checker.load!(**options)
+      @default = checker
+      report
+    end
+
+    def train_from_text(path_or_io, output_path, **options)
+      Checker.train_from_text(path_or_io, output_path, **options)
+    end
+
+    def reload!
+      raise SpellKit::NotLoadedError, "reload! needs a previous successful load!; call load! first" unless @default
+
+      @default.reload!
     end
 
-    def suggestions(word, max = 5)
-      default.suggestions(word, max)
+    def suggestions(word, max = nil, **options)
+      default.suggestions(word, max, **options)
+    end
+
+    def suggest_many(words, options = nil, **kwargs)
+      default.suggest_many(words, options, **kwargs)
     end
 
     def correct?(word)
       default.correct?(word)
     end
 
-    def correct(word)
-      default.correct(word)
+    def lookup(word)
+      default.lookup(word)
+    end
+
+    def frequency(word)
+      default.frequency(word)
+    end
+
+    def explain(word, **options)
+      default.explain(word, **options)
+    end
+
+    def correct(word, **options)
+      default.correct(word, **options)
+    end
+
+    def correct_unique(counts, **options)
+      default.correct_unique(counts, **options)
+    end
+
+    def correct_phrase(phrase)
+      default.correct_phrase(phrase)
+    end
+
+    def segment(text, **options)
+      default.segment(text, **options)
+    end
+
+    def define_set(name, terms)
+      default.define_set(name, terms)
+    end
+
+    def add_word!(word, frequency)
+      default.add_word!(word, frequency)
+    end
+
+    def remove_word!(word)
+      default.remove_word!(word)
+    end
+
+    def protect!(terms)
+      default.protect!(terms)
+    end
+
+    def unprotect!(term)
+      default.unprotect!(term)
+    end
+
+    def protected?(word)
+      default.protected?(word)
+    end
+
+    def snapshot
+      default.snapshot
+    end
+
+    def correct_tokens(tokens, **options)
+      default.correct_tokens(tokens, **options)
+    end
+
+    def correct_tokens_with_info(tokens, **options)
+      default.correct_tokens_with_info(tokens, **options)
+    end
+
+    def correct_text(text, **options)
+      default.correct_text(text, **options)
+    end
+
+    def apply_changes(input, changes)
+      default.apply_changes(input, changes)
+    end
+
+    def correct_lines(input, **options)
+      default.correct_lines(input, **options)
+    end
+
+    def tokenize(text, **options)
+      default.tokenize(text, **options)
+    end
+
+    def evaluate_corpus(path_or_io, **options)
+      default.evaluate_corpus(path_or_io, **options)
+    end
+
+    def perturb(word, **options)
+      default.perturb(word, **options)
+    end
+
+    def sample_words(count, **options)
+      default.sample_words(count, **options)
+    end
+
+    def warmup(**options)
+      default.warmup(**options)
+    end
+
+    def save_index(path)
+      default.save_index(path)
+    end
+
+    def export_dictionary(path, **options)
+      default.export_dictionary(path, **options)
+    end
+
+    def export_user_dictionary(path)
+      default.export_user_dictionary(path)
+    end
+
+    def words(**options)
+      default.words(**options)
+    end
+
+    def run_golden(pairs)
+      default.run_golden(pairs)
     end
 
-    def correct_tokens(tokens)
-      default.correct_tokens(tokens)
+    def behavior_digest
+      default.behavior_digest
     end
 
     def stats
       default.stats
     end
 
-    def healthcheck
-      default.healthcheck
+    def reset_stats!
+      default.reset_stats!
+    end
+
+    def reset_metrics!
+      default.reset_metrics!
+    end
+
+    def healthcheck(**options)
+      default.healthcheck(**options)
+    end
+
+    def stats_json
+      default.stats_json
+    end
+
+    def healthcheck_json
+      default.healthcheck_json
+    end
+
+    def metrics_text(**options)
+      default.metrics_text(**options)
     end
   end
 end
 
 # Reopen Rust-defined Checker class to add Ruby wrappers
 class SpellKit::Checker
+  # Shared by load! and the Configuration setters for the three thresholds
+  def self.validate_frequency_threshold!(value, name = "frequency_threshold")
+    unless value.is_a?(Numeric)
+      raise SpellKit::InvalidArgumentError, "#{name} must be a number, got: #{value.class}"
+    end
+
+    unless value.finite?
+      raise SpellKit::InvalidArgumentError, "#{name} must be finite (got NaN or Infinity)"
+    end
+
+    if value < 0
+      raise SpellKit::InvalidArgumentError, "#{name} must be non-negative, got: #{value}"
+    end
+  end
+
+  # Counts the words of a raw text corpus (a path or an IO) and writes the ones seen at least
+  # `min_count` times to `output_path` as a word<TAB>count dictionary for load!. Streams the
+  # corpus line by line; returns {"lines", "tokens", "distinct", "written"}
+  def self.train_from_text(path_or_io, output_path, min_count: 2)
+    raise SpellKit::InvalidArgumentError, "output_path is required" if output_path.nil?
+
+    unless min_count.is_a?(Integer) && min_count.positive?
+      raise SpellKit::InvalidArgumentError, "min_count must be a positive Integer, got: #{min_count.inspect}"
+    end
+
+    if path_or_io.respond_to?(:read)
+      # Spool IO input to disk so the extension can stream it line by line
+      Tempfile.create(["spellkit_corpus", ".txt"]) do |spool|
+        IO.copy_stream(path_or_io, spool)
+        spool.flush
+        _rust_train_from_text(spool.path, output_path.to_s, min_count)
+      end
+    else
+      path = path_or_io.to_s
+      raise SpellKit::FileNotFoundError, "Corpus file not found: #{path}" unless File.exist?(path)
+
+      _rust_train_from_text(path, output_path.to_s, min_count)
+    end
+  end
+
+  # Raised by the option check every method with keyword options runs first (see OPTION_METHODS)
+  def self.check_options!(method_name, unknown, accepted)
+    names = accepted.map(&:to_s)
+    hints = unknown.map do |key|
+      closest = closest_option(key.to_s, names)
+      closest ? "#{key} (did you mean #{closest}?)" : key.to_s
+    end
+
+    raise SpellKit::InvalidArgumentError,
+      "unknown option#{"s" if unknown.size > 1} for #{method_name}: #{hints.join(", ")}. " \
+      "Accepted options: #{names.join(", ")}"
+  end
+
   # Save original Rust methods
   alias_method :_rust_load!, :load!
   alias_method :_rust_suggestions, :suggestions
+  alias_method :_rust_suggest_many, :suggest_many
   alias_method :_rust_correct?, :correct?
+  alias_method :_rust_lookup, :lookup
+  alias_method :_rust_frequency, :frequency
+  alias_method :_rust_explain, :explain
   alias_method :_rust_correct, :correct
+  alias_method :_rust_correct_unique, :correct_unique
+  alias_method :_rust_correct_phrase, :correct_phrase
+  alias_method :_rust_segment, :segment
+  alias_method :_rust_define_set, :define_set
+  alias_method :_rust_add_word!, :add_word!
+  alias_method :_rust_remove_word!, :remove_word!
+  alias_method :_rust_protect!, :protect!
+  alias_method :_rust_unprotect!, :unprotect!
+  alias_method :_rust_protected?, :protected?
+  alias_method :_rust_snapshot, :snapshot
   alias_method :_rust_correct_tokens, :correct_tokens
+  alias_method :_rust_correct_text, :correct_text
+  alias_method :_rust_apply_changes, :apply_changes
+  alias_method :_rust_correct_lines, :correct_lines
+  alias_method :_rust_tokenize, :tokenize
+  alias_method :_rust_evaluate_corpus, :evaluate_corpus
+  alias_method :_rust_perturb, :perturb
+  alias_method :_rust_sample_words, :sample_words
+  alias_method :_rust_warmup, :warmup
+  alias_method :_rust_save_index, :save_index
+  alias_method :_rust_export_dictionary, :export_dictionary
+  alias_method :_rust_export_user_dictionary, :export_user_dictionary
+  alias_method :_rust_words, :words
+  alias_method :_rust_run_golden, :run_golden
+  alias_method :_rust_behavior_digest, :behavior_digest
   alias_method :_rust_stats, :stats
+  alias_method :_rust_reset_stats!, :reset_stats!
+  alias_method :_rust_reset_metrics!, :reset_metrics!
   alias_method :_rust_healthcheck, :healthcheck
+  alias_method :_rust_keyed, :keyed
+  alias_method :_rust_stats_json, :stats_json
+  alias_method :_rust_healthcheck_json, :healthcheck_json
+  alias_method :_rust_healthcheck_probe, :healthcheck_probe
+  alias_method :_rust_healthcheck_deep, :healthcheck_deep
+  alias_method :_rust_metrics_text, :metrics_text
+
+  INDEX_STRATEGIES = %w[full query_expanded].freeze
+  FREQUENCY_COMBINES = %w[sum max base_only].freeze
+  DISTANCE_METRICS = %w[levenshtein damerau].freeze
+  TIE_BREAKS = %w[lexicographic none].freeze
+  UNIT_MODES = %w[off skip normalize].freeze
+  DICTIONARY_FORMATS = %w[counts wordlist auto].freeze
+  COMPRESSIONS = %w[auto gzip none].freeze
+  ON_DUPLICATES = %w[sum max error].freeze
+  NORMALIZATION_FORMS = %w[nfc nfd nfkc nfkd].freeze
+  MAX_DICTIONARY_BYTES = 1 << 30
+  MAX_WORDS = 10_000_000
+  PREFIX_LENGTH = 7
+  MAX_SUGGESTIONS = 5
 
-  def load!(dictionary: nil, protected_path: nil, protected_patterns: [],
-            edit_distance: 1, frequency_threshold: 10.0,
+  # Built-in protected patterns for load!(skip: [...]), in the order they're checked
+  SKIP_PRESETS = {
+    # http://, https://, and www. URLs
+    "urls" => [/^https?:\/\/[^\s]+$/i, /^www\.[^\s]+$/i],
+    # user@domain.com, user+tag@domain.co.uk
+    "emails" => [/^[\w.+-]+@[\w.-]+\.\w+$/i],
+    # example.com, sub.example.com, my-site.co.uk: at least one dot
+    "hostnames" => [/^[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)+$/i],
+    "code_patterns" => [
+      # camelCase: starts lowercase, has uppercase (arrayMap, getElementById)
+      /^[a-z]+[A-Z][a-zA-Z0-9]*$/,
+      # PascalCase: starts uppercase, has mixed case (ArrayList, MyClass)
+      /^[A-Z][a-z]+[A-Z][a-zA-Z0-9]*$/,
+      # snake_case: lowercase with underscores (my_function, API_KEY)
+      /^[a-z]+_[a-z0-9_]+$/i,
+      # SCREAMING_SNAKE_CASE: uppercase with underscores
+      /^[A-Z]+_[A-Z0-9_]+$/,
+      # dotted.paths: identifier.identifier (Array.map, config.yml)
+      /^[a-zA-Z_][a-zA-Z0-9_]*\.[a-zA-Z_][a-zA-Z0-9_.]*$/
+    ],
+    "numbers" => [
+      # Versions: 1.0, 1.2.3, 1.2.3.4, v1.2.3
+      /^v?\d+\.\d+(\.\d+)?(\.\d+)?$/i,
+      # Issue numbers and IDs: #123, #4567
+      /^#\d+$/,
+      # Measurements: weight (kg, g, mg, lb, oz), distance (km, m, cm, mm, mi, ft, in),
+      # data (gb, mb, kb, tb, pb), and screen units (px, pt, em, rem)
+      /^\d+(\.\d+)?(kg|g|mg|lb|oz|km|m|cm|mm|mi|ft|in|gb|mb|kb|tb|pb|px|pt|em|rem)$/i,
+      # Anything starting with a digit (5kg, 123abc)
+      /^\d/
+    ],
+    "hex" => [
+      # 0x literals: 0xFF, 0xdeadbeef
+      /^0x[0-9a-f]+$/i,
+      # CSS colors: #fff, #ffffff, #ffffff80
+      /^#([0-9a-f]{3,4}|[0-9a-f]{6}|[0-9a-f]{8})$/i,
+      # Digests and commit hashes mixing digits and hex letters (9fceb02, a1b2c3); words
+      # spelled only with a-f ("decade", "faced") never match
+      /^([0-9a-f]*[0-9][0-9a-f]*[a-f]|[a-f][0-9a-f]*[0-9])[0-9a-f]*$/i
+    ],
+    # 123e4567-e89b-12d3-a456-426614174000
+    "uuids" => [/^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i],
+    # @user, @some-team
+    "mentions" => [/^@\w[\w-]*$/],
+    # #rubyconf, #day_2
+    "hashtags" => [/^#\w+$/]
+  }.freeze
+
+  def load!(dictionary: nil, user_dictionary_path: nil, protected_path: nil, protected_patterns: [], protected_prefixes: [],
+            edit_distance: 1, frequency_threshold: 10.0, min_suggestion_frequency: nil, frequency_ratio: nil,
             skip_urls: false, skip_emails: false, skip_hostnames: false,
-            skip_code_patterns: false, skip_numbers: false, **_options)
+            skip_code_patterns: false, skip_numbers: false, tokenizer: "unicode", progress: nil,
+            protected_as_known: false, protect_only_unknown: false, confident_frequency: 1000,
+            protected_case_sensitive: false, autocorrect_known_rare: nil, aliases_path: nil, denylist_path: nil, compact: false, frequency_scale: nil,
+            boosts: [], max_boost: 100.0, segmentation: false, prefer_segmentation_over_distance: nil,
+            max_line_length: 1024, max_word_length: 100, strict: false, structural_match: true, latency_stats: true,
+            warmup: false, index_strategy: "full", frequency_combine: "sum", strip_ignorable: true,
+            tie_break: "lexicographic", units: "off", unit_list: nil, max_dictionary_bytes: MAX_DICTIONARY_BYTES,
+            max_words: MAX_WORDS, distance_metric: "levenshtein", index_path: nil, prefix_length: PREFIX_LENGTH, max_suggestions: MAX_SUGGESTIONS,
+            skip_tokens_with_digits: false, min_word_length: 0, skip_all_caps: false, format: "counts", default_frequency: 1, separator: nil, term_index: 0, count_index: 1,
+            compression: "auto", on_duplicate: "max", preserve_case: false, skip: [], strip_accents: false,
+            normalize: {}, case_sensitive: false, symbolize_keys: false)
+
+    # Validate dictionary parameter; a prebuilt index replaces the dictionary
+    if dictionary.is_a?(Hash) || dictionary.is_a?(Array)
+      raise SpellKit::InvalidArgumentError, "pass either index_path or an in-memory dictionary, not both" if index_path
 
-    # Validate dictionary parameter
-    raise SpellKit::InvalidArgumentError, "dictionary parameter is required" if dictionary.nil?
+      entries = dictionary_entries(dictionary)
+    elsif index_path.nil?
+      raise SpellKit::InvalidArgumentError, "dictionary parameter is required" if dictionary.nil?
 
-    # Auto-detect URL vs path
-    dictionary_path = if dictionary.to_s.start_with?("http://", "https://")
-      download_dictionary(dictionary)
+      # Auto-detect URL vs path
+      from_url = dictionary.to_s.start_with?("http://", "https://")
+      dictionary_path = if from_url
+        download_dictionary(dictionary)
+      else
+        dictionary.to_s
+      end
+
+      # Validate file exists
+      raise SpellKit::FileNotFoundError, "Dictionary file not found: #{dictionary_path}" unless File.exist?(dictionary_path)
     else
-      dictionary.to_s
+      raise SpellKit::FileNotFoundError, "Index file not found: #{index_path}" unless File.exist?(index_path.to_s)
+    end
+
+    {max_dictionary_bytes: max_dictionary_bytes, max_words: max_words}.each do |name, value|
+      unless value.nil? || (value.is_a?(Integer) && value >= 1)
+        raise SpellKit::InvalidArgumentError, "#{name} must be a positive Integer or nil, got: #{value.inspect}"
+      end
     end
 
-    # Validate file exists
-    raise SpellKit::FileNotFoundError, "Dictionary file not found: #{dictionary_path}" unless File.exist?(dictionary_path)
+    # Before the file is hashed for the source digest, which would read all of it
+    if dictionary_path && max_dictionary_bytes && File.size(dictionary_path) > max_dictionary_bytes
+      raise SpellKit::DictionaryError,
+        "Dictionary file is #{File.size(dictionary_path)} bytes, over max_dictionary_bytes (#{max_dictionary_bytes}); nothing was read"
+    end
+
+    if user_dictionary_path && !File.exist?(user_dictionary_path.to_s)
+      raise SpellKit::FileNotFoundError, "User dictionary file not found: #{user_dictionary_path}"
+    end
+
+    if aliases_path && !File.exist?(aliases_path.to_s)
+      raise SpellKit::FileNotFoundError, "Aliases file not found: #{aliases_path}"
+    end
+
+    if denylist_path && !File.exist?(denylist_path.to_s)
+      raise SpellKit::FileNotFoundError, "Denylist file not found: #{denylist_path}"
+    end
 
     # Validate edit distance
     unless [1, 2].include?(edit_distance)
@@ -140,172 +616,1219 @@ class SpellKit::Checker
       raise SpellKit::InvalidArgumentError, "protected_patterns must be an Array"
     end
 
-    # Validate frequency_threshold
-    unless frequency_threshold.is_a?(Numeric)
-      raise SpellKit::InvalidArgumentError, "frequency_threshold must be a number, got: #{frequency_threshold.class}"
+    # Validate protected_prefixes is an array of strings
+    unless protected_prefixes.is_a?(Array) && protected_prefixes.all? { |prefix| prefix.is_a?(String) }
+      raise SpellKit::InvalidArgumentError, "protected_prefixes must be an Array of Strings"
     end
 
-    unless frequency_threshold.finite?
-      raise SpellKit::InvalidArgumentError, "frequency_threshold must be finite (got NaN or Infinity)"
+    # frequency_threshold is the deprecated single knob; it fills in whichever of the two is unset
+    self.class.validate_frequency_threshold!(frequency_threshold)
+    self.class.validate_frequency_threshold!(min_suggestion_frequency, "min_suggestion_frequency") unless min_suggestion_frequency.nil?
+    self.class.validate_frequency_threshold!(frequency_ratio, "frequency_ratio") unless frequency_ratio.nil?
+    validate_max_suggestions!(max_suggestions)
+    validate_min_word_length!(min_word_length)
+
+    unless confident_frequency.is_a?(Integer) && confident_frequency >= 0
+      raise SpellKit::InvalidArgumentError, "confident_frequency must be a non-negative Integer, got: #{confident_frequency.inspect}"
     end
 
-    if frequency_threshold < 0
-      raise SpellKit::InvalidArgumentError, "frequency_threshold must be non-negative, got: #{frequency_threshold}"
+    unless autocorrect_known_rare.nil? || (autocorrect_known_rare.is_a?(Integer) && autocorrect_known_rare > 0)
+      raise SpellKit::InvalidArgumentError, "autocorrect_known_rare must be a positive Integer, got: #{autocorrect_known_rare.inspect}"
     end
 
-    # Build skip patterns from convenience flags
-    skip_patterns = build_skip_patterns(
-      skip_urls: skip_urls,
-      skip_emails: skip_emails,
-      skip_hostnames: skip_hostnames,
-      skip_code_patterns: skip_code_patterns,
-      skip_numbers: skip_numbers
-    )
+    unless frequency_scale.nil?
+      raise SpellKit::InvalidArgumentError, "frequency_scale requires compact: true" unless compact
+
+      unless frequency_scale.is_a?(Integer) && frequency_scale >= 1
+        raise SpellKit::InvalidArgumentError, "frequency_scale must be a positive Integer, got: #{frequency_scale.inspect}"
+      end
+    end
+
+    {max_line_length: max_line_length, max_word_length: max_word_length}.each do |name, value|
+      unless value.is_a?(Integer) && value >= 1
+        raise SpellKit::InvalidArgumentError, "#{name} must be a positive Integer, got: #{value.inspect}"
+      end
+    end
+
+    unless max_boost.is_a?(Numeric) && max_boost.finite? && max_boost > 0
+      raise SpellKit::InvalidArgumentError, "max_boost must be a positive number, got: #{max_boost.inspect}"
+    end
 
-    # Merge skip patterns with user-provided patterns
-    all_patterns = skip_patterns + protected_patterns
+    raise SpellKit::InvalidArgumentError, "boosts must be an Array" unless boosts.is_a?(Array)
+    boost_objects = boosts.map { |boost| boost_config(boost) }
+
+    unless [nil, true, false].include?(prefer_segmentation_over_distance)
+      raise SpellKit::InvalidArgumentError,
+        "prefer_segmentation_over_distance must be true, false, or nil, got: #{prefer_segmentation_over_distance.inspect}"
+    end
+
+    unless [true, false, nil].include?(warmup) || (warmup.is_a?(Integer) && warmup >= 0)
+      raise SpellKit::InvalidArgumentError, "warmup must be true, false, or a non-negative Integer, got: #{warmup.inspect}"
+    end
+
+    unless INDEX_STRATEGIES.include?(index_strategy.to_s)
+      raise SpellKit::InvalidArgumentError,
+        "index_strategy must be one of #{INDEX_STRATEGIES.join(", ")}, got: #{index_strategy.inspect}"
+    end
+
+    unless prefix_length.nil? || (prefix_length.is_a?(Integer) && prefix_length > edit_distance)
+      raise SpellKit::InvalidArgumentError,
+        "prefix_length must be an Integer greater than edit_distance (#{edit_distance}) or nil, got: #{prefix_length.inspect}"
+    end
+
+    unless FREQUENCY_COMBINES.include?(frequency_combine.to_s)
+      raise SpellKit::InvalidArgumentError,
+        "frequency_combine must be one of #{FREQUENCY_COMBINES.join(", ")}, got: #{frequency_combine.inspect}"
+    end
+
+    unless DISTANCE_METRICS.include?(distance_metric.to_s)
+      raise SpellKit::InvalidArgumentError,
+        "distance_metric must be one of #{DISTANCE_METRICS.join(", ")}, got: #{distance_metric.inspect}"
+    end
+
+    unless TIE_BREAKS.include?(tie_break.to_s)
+      raise SpellKit::InvalidArgumentError, "tie_break must be one of #{TIE_BREAKS.join(", ")}, got: #{tie_break.inspect}"
+    end
+
+    unless DICTIONARY_FORMATS.include?(format.to_s)
+      raise SpellKit::InvalidArgumentError, "format must be one of #{DICTIONARY_FORMATS.join(", ")}, got: #{format.inspect}"
+    end
+
+    unless default_frequency.is_a?(Integer) && default_frequency >= 1
+      raise SpellKit::InvalidArgumentError, "default_frequency must be a positive Integer, got: #{default_frequency.inspect}"
+    end
+
+    unless ON_DUPLICATES.include?(on_duplicate.to_s)
+      raise SpellKit::InvalidArgumentError, "on_duplicate must be one of #{ON_DUPLICATES.join(", ")}, got: #{on_duplicate.inspect}"
+    end
+
+    unless COMPRESSIONS.include?(compression.to_s)
+      raise SpellKit::InvalidArgumentError, "compression must be one of #{COMPRESSIONS.join(", ")}, got: #{compression.inspect}"
+    end
+
+    unless separator.nil? || (separator.is_a?(String) && !separator.empty?)
+      raise SpellKit::InvalidArgumentError, "separator must be a non-empty String or nil, got: #{separator.inspect}"
+    end
+
+    {term_index: term_index, count_index: count_index}.each do |name, value|
+      unless value.is_a?(Integer) && value >= 0
+        raise SpellKit::InvalidArgumentError, "#{name} must be a non-negative Integer, got: #{value.inspect}"
+      end
+    end
+    if term_index == count_index
+      raise SpellKit::InvalidArgumentError, "term_index and count_index must be different columns, both are #{term_index}"
+    end
+
+    unless UNIT_MODES.include?(units.to_s)
+      raise SpellKit::InvalidArgumentError, "units must be one of #{UNIT_MODES.join(", ")}, got: #{units.inspect}"
+    end
+
+    unless unit_list.nil? || (unit_list.is_a?(Array) && unit_list.all? { |unit| unit.is_a?(String) && !unit.empty? })
+      raise SpellKit::InvalidArgumentError, "unit_list must be an Array of non-empty Strings"
+    end
+
+    # Validate progress callback
+    if progress && !progress.respond_to?(:call)
+      raise SpellKit::InvalidArgumentError, "progress must respond to #call"
+    end
+
+    # The skip_* flags are shorthands for their presets
+    skip_presets = skip_preset_names(skip)
+    {"urls" => skip_urls, "emails" => skip_emails, "hostnames" => skip_hostnames,
+     "code_patterns" => skip_code_patterns, "numbers" => skip_numbers}.each do |name, flag|
+      skip_presets << name if flag
+    end
+    skip_presets = SKIP_PRESETS.keys & skip_presets
+
+    # User patterns come first, so errors Rust reports by index point into protected_patterns
+    pattern_objects = protected_patterns.each_with_index.map { |pattern, index| protected_pattern_config(pattern, index) }
+    pattern_objects += skip_presets.flat_map do |name|
+      SKIP_PRESETS[name].map { |pattern| pattern_config(pattern).merge("name" => name) }
+    end
 
     config = {
-      "dictionary_path" => dictionary_path,
       "edit_distance" => edit_distance,
-      "frequency_threshold" => frequency_threshold
+      "frequency_threshold" => frequency_threshold,
+      "min_suggestion_frequency" => min_suggestion_frequency,
+      "frequency_ratio" => frequency_ratio,
+      "max_suggestions" => max_suggestions,
+      "skip_tokens_with_digits" => skip_tokens_with_digits ? true : false,
+      "min_word_length" => min_word_length,
+      "skip_all_caps" => skip_all_caps ? true : false
     }
+    if entries
+      config["dictionary"] = entries
+    else
+      source_path = index_path ? index_path.to_s : dictionary_path
+      config["dictionary_path"] = source_path
+      config["source"] = {
+        "kind" => index_path ? "index" : (from_url ? "url" : "file"),
+        "path_or_url" => index_path ? index_path.to_s : dictionary.to_s,
+        "sha256" => Digest::SHA256.file(source_path).hexdigest
+      }
+    end
+
+    config["index_path"] = index_path.to_s if index_path
 
+    config["user_dictionary_path"] = user_dictionary_path.to_s if user_dictionary_path
     config["protected_path"] = protected_path.to_s if protected_path
+    config["protected_prefixes"] = protected_prefixes if protected_prefixes.any?
+    config["protected_as_known"] = protected_as_known ? true : false
+    config["protect_only_unknown"] = protect_only_unknown ? true : false
+    config["protected_case_sensitive"] = protected_case_sensitive ? true : false
+    config["confident_frequency"] = confident_frequency
+    config["autocorrect_known_rare"] = autocorrect_known_rare if autocorrect_known_rare
+    config["aliases_path"] = aliases_path.to_s if aliases_path
+    config["denylist_path"] = denylist_path.to_s if denylist_path
+    config["compact"] = compact ? true : false
+    config["index_strategy"] = index_strategy.to_s
+    config["prefix_length"] = prefix_length
+    config["frequency_combine"] = frequency_combine.to_s
+    config["distance_metric"] = distance_metric.to_s
+    config["frequency_scale"] = frequency_scale if frequency_scale
+    config["boosts"] = boost_objects if boost_objects.any?
+    config["max_boost"] = max_boost.to_f
+    config["segmentation"] = segmentation ? true : false
+    config["prefer_segmentation_over_distance"] = prefer_segmentation_over_distance unless prefer_segmentation_over_distance.nil?
+    config["max_line_length"] = max_line_length
+    config["max_word_length"] = max_word_length
+    config["format"] = format.to_s
+    config["default_frequency"] = default_frequency
+    config["separator"] = separator if separator
+    config["compression"] = compression.to_s
+    config["on_duplicate"] = on_duplicate.to_s
+    config["term_index"] = term_index
+    config["count_index"] = count_index
+    config["strict"] = strict ? true : false
+    config["structural_match"] = structural_match ? true : false
+    config["preserve_case"] = preserve_case ? true : false
+    config["symbolize_keys"] = symbolize_keys ? true : false
+    config["normalize"] = normalize_config(normalize, strip_accents: strip_accents, case_sensitive: case_sensitive)
+    config["skip_presets"] = skip_presets if skip_presets.any?
+    config["strip_ignorable"] = strip_ignorable ? true : false
+    config["tie_break"] = tie_break.to_s
+    config["units"] = units.to_s
+    config["unit_list"] = unit_list if unit_list
+    config["max_dictionary_bytes"] = max_dictionary_bytes
+    config["max_words"] = max_words
+    config["latency_stats"] = latency_stats ? true : false
+    config["warmup"] = warmup == true ? WARMUP_SAMPLE : warmup if warmup
+    config.merge!(tokenizer_config(tokenizer))
+    config["progress"] = progress.is_a?(Proc) ? progress : progress.method(:call).to_proc if progress
 
-    # Convert Ruby Regex objects to hashes with flags for Rust
-    if all_patterns.any?
-      pattern_objects = all_patterns.map do |pattern|
-        if pattern.is_a?(Regexp)
-          # Extract flags from Regexp.options bitmask
-          options = pattern.options
-          {
-            "source" => pattern.source,
-            "case_insensitive" => (options & Regexp::IGNORECASE) != 0,
-            "multiline" => (options & Regexp::MULTILINE) != 0,
-            "extended" => (options & Regexp::EXTENDED) != 0
-          }
-        elsif pattern.is_a?(String)
-          # Plain strings default to case-sensitive
-          {
-            "source" => pattern,
-            "case_insensitive" => false,
-            "multiline" => false,
-            "extended" => false
-          }
-        else
-          raise SpellKit::InvalidArgumentError, "protected_patterns must contain Regexp or String objects"
-        end
+    config["protected_patterns"] = pattern_objects if pattern_objects.any?
+
+    report = keyed(_rust_load!(config))
+    @load_options = LOAD_OPTIONS.to_h { |name| [name, binding.local_variable_get(name)] }
+    warn_zero_threshold(
+      min_suggestion_frequency.nil? ? ["frequency_threshold", frequency_threshold] : ["min_suggestion_frequency", min_suggestion_frequency],
+      frequency_ratio.nil? ? ["frequency_threshold", frequency_threshold] : ["frequency_ratio", frequency_ratio],
+      autocorrect_known_rare
+    )
+    warn_protected_overlap(confident_frequency)
+    warn_skipped_long(max_line_length, max_word_length)
+    report
+  end
+
+  LOAD_OPTIONS = instance_method(:load!).parameters.map(&:last).freeze
+
+  # Repeats the last successful load! with the same options, re-reading its files from disk.
+  # The current dictionary keeps serving while the new one is built, and stays if the reload fails
+  def reload!
+    raise SpellKit::NotLoadedError, "reload! needs a previous successful load!; call load! first" unless @load_options
+
+    load!(**@load_options)
+  end
+
+  SUGGESTION_FORMATS = %w[objects hash].freeze
+
+  # Ranked SpellKit::Suggestion objects, or with format: :hash the {"term", "distance", "freq"}
+  # hashes of earlier versions. With exclude_exact: true the dictionary entry matching `word`
+  # itself (ignoring case and Unicode composition) is left out, so only alternatives are
+  # returned. structural_match: true applies the same digit and hyphen/apostrophe filter that
+  # corrections use. truncate_display: n adds "display_truncated", the term cut to n display cells
+  # on grapheme boundaries. max_distance searches closer than the loaded edit_distance (0 is an
+  # exact lookup) and may not exceed it. `max` defaults to load!'s max_suggestions; 0 returns []
+  # without a lookup
+  def suggestions(word, max = nil, exclude_exact: false, structural_match: false, truncate_display: nil, max_distance: nil, format: :objects)
+    validate_word!(word)
+    validate_suggestion_format!(format)
+    suggest_options(max: max, max_distance: max_distance)
+    {exclude_exact: exclude_exact, structural_match: structural_match}.each do |name, value|
+      unless [true, false].include?(value)
+        raise SpellKit::InvalidArgumentError, "#{name} must be true or false, got: #{value.inspect}"
       end
-      config["protected_patterns"] = pattern_objects
+    end
+    unless truncate_display.nil? || (truncate_display.is_a?(Integer) && truncate_display >= 1)
+      raise SpellKit::InvalidArgumentError, "truncate_display must be a positive Integer, got: #{truncate_display.inspect}"
     end
 
-    _rust_load!(config)
-    self
+    options = {"exclude_exact" => exclude_exact, "structural_match" => structural_match}
+    options["truncate_display"] = truncate_display if truncate_display
+    options["max_distance"] = max_distance unless max_distance.nil?
+    options["hashes"] = true if format.to_s == "hash"
+    keyed(_rust_suggestions(word, max, options))
   end
 
-  def suggestions(word, max = 5)
-    raise SpellKit::InvalidArgumentError, "word cannot be nil" if word.nil?
-    raise SpellKit::InvalidArgumentError, "word cannot be empty" if word.to_s.empty?
+  SUGGEST_OPTION_KEYS = %i[max max_distance].freeze
 
-    _rust_suggestions(word, max)
+  ON_ERROR_MODES = %w[raise partial].freeze
+
+  # Suggestions for many words under a single lock. `options` is either an Integer max
+  # applied to every word (load!'s max_suggestions when nil), or an Array of {max:, max_distance:}
+  # hashes aligned with `words`.
+  # on_error: "partial" returns {"error" => message} at the position of each word that isn't
+  # a non-empty String instead of raising, keeping the other results. format: as for suggestions
+  def suggest_many(words, options = nil, on_error: "raise", format: :objects)
+    raise SpellKit::InvalidArgumentError, "words must be an Array" unless words.is_a?(Array)
+    validate_on_error!(on_error)
+    validate_suggestion_format!(format)
+    words.each { |word| validate_word!(word) } if on_error.to_s == "raise"
+
+    per_word = case options
+    when Integer, nil
+      Array.new(words.length) { suggest_options(max: options) }
+    when Array
+      unless options.length == words.length
+        raise SpellKit::InvalidArgumentError,
+          "per-word options must align with words (got #{options.length} for #{words.length} words)"
+      end
+
+      options.map do |word_options|
+        raise SpellKit::InvalidArgumentError, "per-word options must be Hashes" unless word_options.is_a?(Hash)
+
+        word_options = word_options.transform_keys(&:to_sym)
+        unknown = word_options.keys - SUGGEST_OPTION_KEYS
+        raise SpellKit::InvalidArgumentError, "unknown suggest options: #{unknown.join(", ")}" if unknown.any?
+
+        suggest_options(**word_options)
+      end
+    else
+      raise SpellKit::InvalidArgumentError, "options must be an Integer or an Array of Hashes"
+    end
+
+    keyed(_rust_suggest_many(words, per_word, {"on_error" => on_error.to_s, "hashes" => format.to_s == "hash"}))
   end
 
   def correct?(word)
-    raise SpellKit::InvalidArgumentError, "word cannot be nil" if word.nil?
-    raise SpellKit::InvalidArgumentError, "word cannot be empty" if word.to_s.empty?
+    validate_word!(word)
 
     _rust_correct?(word)
   end
 
-  def correct(word)
-    raise SpellKit::InvalidArgumentError, "word cannot be nil" if word.nil?
-    raise SpellKit::InvalidArgumentError, "word cannot be empty" if word.to_s.empty?
+  # The dictionary entry behind a known word as {term:, canonical:, frequency:, rank:, metadata:},
+  # or nil for unknown words. A plain dictionary lookup: protected terms are not consulted
+  def lookup(word)
+    validate_word!(word)
+
+    _rust_lookup(word)&.transform_keys(&:to_sym)
+  end
+
+  # lookup(word)[:frequency] without building the entry
+  def frequency(word)
+    validate_word!(word)
+
+    _rust_frequency(word)
+  end
+
+  # Why correct(word) returns what it does, for tuning thresholds and guards. Returns a Hash with
+  # the cleaned "term" and its "normalized" form, "in_dictionary" and "frequency", the "guard"
+  # that protects it ({"kind", "matched"} or nil), the "candidates" within edit_distance, the
+  # "required_frequency" a correction had to reach, and the "decision" as correct_tokens
+  # details: true reports it. guard: false skips the guards, showing what would happen to a
+  # protected word otherwise
+  def explain(word, guard: true)
+    validate_word!(word)
+
+    keyed(_rust_explain(word.to_s, guard ? true : false))
+  end
+
+  # With resolve_aliases: true, the corrected word is mapped through the aliases_path
+  # table (one hop; protected words are never aliased).
+  # With within:, corrections only land on terms in that closed set: an Array of terms, or the
+  # name of a set registered with define_set.
+  # frequency_threshold:, min_suggestion_frequency:, frequency_ratio:, and max_suggestions: (how
+  # many of the best candidates are tried) override the load! values for this call only, as do
+  # skip_tokens_with_digits:, min_word_length:, and skip_all_caps: (which words are left as written)
+  # preserve_case: true carries the input's casing over ("Teh" -> "The"); nil uses the load! default
+  def correct(word, resolve_aliases: false, within: nil, frequency_threshold: nil, min_suggestion_frequency: nil,
+              frequency_ratio: nil, max_suggestions: nil, skip_tokens_with_digits: nil, min_word_length: nil,
+              skip_all_caps: nil, preserve_case: nil)
+    validate_word!(word)
+
+    options = {"resolve_aliases" => resolve_aliases ? true : false}
+    options["preserve_case"] = preserve_case ? true : false unless preserve_case.nil?
+    options.merge!(threshold_overrides(frequency_threshold, min_suggestion_frequency, frequency_ratio, max_suggestions))
+    options.merge!(skip_overrides(skip_tokens_with_digits, min_word_length, skip_all_caps))
+    case within
+    when nil
+    when Symbol, String
+      options["within_set"] = within.to_s
+    when Array
+      raise SpellKit::InvalidArgumentError, "within must contain only Strings" unless within.all?(String)
+      options["within"] = within
+    else
+      raise SpellKit::InvalidArgumentError, "within must be an Array of terms or a set name, got: #{within.class}"
+    end
+
+    _rust_correct(word, options)
+  end
+
+  # Corrects each key of a word => count Hash once, for frequency tables of tokens. Returns
+  # {word => {"corrected" => String, "count" => count}} in the input's order; counts are only
+  # echoed back. changed_only: true leaves out words that came back unchanged. threads: splits
+  # the words across that many threads
+  def correct_unique(counts, changed_only: false, threads: 1)
+    unless counts.is_a?(Hash) && counts.each_key.all?(String)
+      raise SpellKit::InvalidArgumentError, "counts must be a Hash with String keys"
+    end
+
+    unless threads.is_a?(Integer) && threads >= 1
+      raise SpellKit::InvalidArgumentError, "threads must be a positive Integer, got: #{threads.inspect}"
+    end
+
+    words = counts.keys
+    corrected = _rust_correct_unique(words, {"threads" => threads})
+
+    result = {}
+    words.each_with_index do |word, i|
+      next if changed_only && corrected[i] == word
+
+      result[word] = keyed({"corrected" => corrected[i], "count" => counts[word]})
+    end
+    result
+  end
+
+  # Corrects a whole phrase, merging words split by a stray space and splitting run-on words.
+  # Returns {"output" => String, "distance" => Integer}; the output's words are joined by single
+  # spaces
+  def correct_phrase(phrase)
+    raise SpellKit::InvalidArgumentError, "phrase must be a String, got: #{phrase.class}" unless phrase.is_a?(String)
+
+    keyed(_rust_correct_phrase(phrase))
+  end
+
+  # Splits text without spaces ("thequickbrownfox", hashtags, domain names) into its most probable
+  # sequence of dictionary words. Returns {"segments" => [String], "score" => Float}, the score
+  # being the split's natural log probability. Stretches that aren't words are kept verbatim
+  def segment(text, max_segment_length: 24)
+    validate_word!(text)
+    raise SpellKit::InvalidArgumentError, "text must not contain whitespace; use correct_phrase for phrases" if text.to_s.match?(/\s/)
+
+    unless max_segment_length.is_a?(Integer) && max_segment_length >= 1
+      raise SpellKit::InvalidArgumentError, "max_segment_length must be a positive Integer, got: #{max_segment_length.inspect}"
+    end
+
+    keyed(_rust_segment(text.to_s, {"max_segment_length" => max_segment_length}))
+  end
+
+  # Registers a named closed set of terms for correct(word, within: name)
+  def define_set(name, terms)
+    unless name.is_a?(Symbol) || name.is_a?(String)
+      raise SpellKit::InvalidArgumentError, "set name must be a Symbol or String, got: #{name.class}"
+    end
+
+    unless terms.is_a?(Array) && terms.all?(String)
+      raise SpellKit::InvalidArgumentError, "terms must be an Array of Strings"
+    end
+
+    _rust_define_set(name.to_s, terms)
+    self
+  end
+
+  # Adds `frequency` to a word's user count without a reload, indexing it if it's new. Returns
+  # true for a new word. User counts survive reload! and later load!s into this checker, rank
+  # the word like a user_dictionary_path entry, and are written by export_user_dictionary
+  def add_word!(word, frequency)
+    validate_word!(word)
+    raise SpellKit::InvalidArgumentError, "word must be a single word, got: #{word.inspect}" if word.to_s.match?(/\s/)
+
+    unless frequency.is_a?(Integer) && frequency >= 1
+      raise SpellKit::InvalidArgumentError, "frequency must be a positive Integer, got: #{frequency.inspect}"
+    end
+
+    _rust_add_word!(word.to_s, frequency)
+  end
+
+  # Removes a word and all its counts from the loaded dictionary. Returns false if it wasn't there
+  def remove_word!(word)
+    validate_word!(word)
+
+    _rust_remove_word!(word.to_s)
+  end
+
+  # Protects a term, or an Array of terms, without a reload: stored like the lines of
+  # protected_path and honored by every call that starts after it returns. Lasts until the next
+  # load! or reload!; returns self
+  def protect!(terms)
+    terms = Array(terms)
+    unless terms.all? { |term| term.is_a?(String) && !term.strip.empty? }
+      raise SpellKit::InvalidArgumentError, "protect! takes a non-empty String or an Array of them, got: #{terms.inspect}"
+    end
+
+    _rust_protect!(terms)
+    self
+  end
+
+  # Takes a protected term out, whether it came from protected_path or protect!. Returns false
+  # if it wasn't protected. Prefixes and patterns that match it still protect it
+  def unprotect!(term)
+    validate_word!(term)
+
+    _rust_unprotect!(term.to_s)
+  end
+
+  # Whether a guard (term, prefix, or pattern) protects `word`, as correct would check it.
+  # Doesn't count toward stats["guard_hits"]
+  def protected?(word)
+    validate_word!(word)
 
-    _rust_correct(word)
+    _rust_protected?(word.to_s)
   end
 
-  def correct_tokens(tokens)
+  # Pins the current dictionary generation: every call through the returned SpellKit::Snapshot
+  # sees the same dictionary, even if load! runs in between
+  def snapshot
+    SpellKit::Snapshot.new(_rust_snapshot)
+  end
+
+  OUTPUT_MODES = %w[canonical normalized preserve_case].freeze
+  LAST_TOKEN_MODES = %w[normal skip complete].freeze
+  UNKNOWN_MODES = %w[keep remove].freeze
+
+  # With details: true each token comes back as a Hash describing the decision instead of a String.
+  # last_token: "skip" leaves the final token alone and "complete" completes it as a prefix still
+  # being typed instead of correcting it. on_error works as in suggest_many.
+  # unknown: "remove" drops tokens that could not be corrected and expand: true splits multi-word
+  # outputs into one token per word; alignment: true returns {"tokens", "alignment"} where
+  # alignment[i] is the Range of output indexes input token i produced. threads: above 1 corrects
+  # the tokens across that many threads with the GVL released, with the same output in the same order.
+  # skip_tokens_with_digits:, min_word_length:, and skip_all_caps: override load!'s for this call
+  def correct_tokens(tokens, output: nil, resolve_aliases: false, details: false, last_token: "normal",
+                     on_error: "raise", unknown: "keep", expand: false, alignment: false, dry_run: false, threads: 1,
+                     frequency_threshold: nil, min_suggestion_frequency: nil, frequency_ratio: nil, max_suggestions: nil,
+                     skip_tokens_with_digits: nil, min_word_length: nil, skip_all_caps: nil, preserve_case: nil)
     raise SpellKit::InvalidArgumentError, "tokens must be an Array" unless tokens.is_a?(Array)
 
-    _rust_correct_tokens(tokens)
+    unless threads.is_a?(Integer) && threads >= 1
+      raise SpellKit::InvalidArgumentError, "threads must be a positive Integer, got: #{threads.inspect}"
+    end
+
+    unless output.nil? || OUTPUT_MODES.include?(output.to_s)
+      raise SpellKit::InvalidArgumentError, "output must be one of #{OUTPUT_MODES.join(", ")}, got: #{output.inspect}"
+    end
+
+    # Without output:, preserve_case: (or the load! default) picks preserve_case or canonical
+    if preserve_case && output && output.to_s != "preserve_case"
+      raise SpellKit::InvalidArgumentError, "preserve_case: true can't be combined with output: #{output.inspect}"
+    end
+    validate_last_token!(last_token)
+    validate_on_error!(on_error)
+
+    unless UNKNOWN_MODES.include?(unknown.to_s)
+      raise SpellKit::InvalidArgumentError, "unknown must be one of #{UNKNOWN_MODES.join(", ")}, got: #{unknown.inspect}"
+    end
+
+    if details && (unknown.to_s == "remove" || expand)
+      raise SpellKit::InvalidArgumentError, "unknown: \"remove\" and expand: true only apply without details"
+    end
+
+    if dry_run && (details || unknown.to_s == "remove" || expand || alignment)
+      raise SpellKit::InvalidArgumentError,
+        "dry_run: true can't be combined with details, unknown: \"remove\", expand, or alignment"
+    end
+
+    result = _rust_correct_tokens(tokens, {
+      "output" => output&.to_s,
+      "preserve_case" => preserve_case.nil? ? nil : (preserve_case ? true : false),
+      "resolve_aliases" => resolve_aliases ? true : false,
+      "details" => details ? true : false,
+      "last_token" => last_token.to_s,
+      "on_error" => on_error.to_s,
+      "unknown" => unknown.to_s,
+      "expand" => expand ? true : false,
+      "alignment" => alignment ? true : false,
+      "dry_run" => dry_run ? true : false,
+      "threads" => threads,
+      **threshold_overrides(frequency_threshold, min_suggestion_frequency, frequency_ratio, max_suggestions),
+      **skip_overrides(skip_tokens_with_digits, min_word_length, skip_all_caps)
+    }.compact)
+    result["alignment"] = result["alignment"].map { |start, stop| start...stop } if alignment
+    keyed(result)
+  end
+
+  # One Hash per token for audit logs: {token:, corrected:, changed:, distance:, frequency:,
+  # reason:, guard:}. reason is "exact", "protected", "corrected", "below_threshold",
+  # "no_candidates", or, for a token left as written before any lookup, "has_digits",
+  # "too_short", or "all_caps" in the standard pipeline (see correct_tokens details: true for the rest);
+  # distance and frequency describe the corrected term and are nil when it didn't come from the
+  # dictionary. guard is {kind:, matched:} plus {index:, name:} for a pattern or {form:} for a
+  # protected term, and nil unless reason is "protected"
+  def correct_tokens_with_info(tokens, output: nil, resolve_aliases: false, last_token: "normal", threads: 1,
+                               frequency_threshold: nil, min_suggestion_frequency: nil, frequency_ratio: nil,
+                               max_suggestions: nil, skip_tokens_with_digits: nil, min_word_length: nil, skip_all_caps: nil,
+                               preserve_case: nil)
+    details = correct_tokens(tokens, output: output, resolve_aliases: resolve_aliases, details: true,
+      last_token: last_token, threads: threads, frequency_threshold: frequency_threshold,
+      min_suggestion_frequency: min_suggestion_frequency, frequency_ratio: frequency_ratio,
+      max_suggestions: max_suggestions, skip_tokens_with_digits: skip_tokens_with_digits,
+      min_word_length: min_word_length, skip_all_caps: skip_all_caps, preserve_case: preserve_case)
+
+    details.map do |detail|
+      detail = detail.transform_keys(&:to_s)
+      {
+        token: detail["token"],
+        corrected: detail["output"],
+        changed: detail["output"] != detail["token"],
+        distance: detail["distance"],
+        frequency: detail["freq"],
+        reason: detail["reason"],
+        guard: detail["guard"]&.transform_keys(&:to_sym)
+      }
+    end
+  end
+
+  TEXT_MODES = %w[text html].freeze
+  CJK_MODES = %w[passthrough flag].freeze
+
+  # mode: "html" leaves tags, attributes, and <code>/<pre>/<script> contents untouched and
+  # corrects only text content, decoding and re-encoding entities around replacements.
+  # last_token applies to the final word token, as in correct_tokens (text mode only).
+  # Han, Hiragana, Katakana, and Thai text is never corrected; cjk: "flag" also reports its runs.
+  # dry_run: true returns only the proposed changes, for apply_changes. Hyphenated words split by the
+  # unicode tokenizer are left whole. guard: false corrects protected words too
+  def correct_text(text, tokenizer: nil, mode: "text", last_token: "normal", cjk: "passthrough", dry_run: false, guard: true)
+    raise SpellKit::InvalidArgumentError, "text cannot be nil" if text.nil?
+
+    unless TEXT_MODES.include?(mode.to_s)
+      raise SpellKit::InvalidArgumentError, "mode must be one of #{TEXT_MODES.join(", ")}, got: #{mode.inspect}"
+    end
+    validate_last_token!(last_token)
+
+    unless CJK_MODES.include?(cjk.to_s)
+      raise SpellKit::InvalidArgumentError, "cjk must be one of #{CJK_MODES.join(", ")}, got: #{cjk.inspect}"
+    end
+
+    if mode.to_s == "html" && last_token.to_s != "normal"
+      raise SpellKit::InvalidArgumentError, "last_token is only supported in text mode"
+    end
+
+    options = tokenizer.nil? ? {} : tokenizer_config(tokenizer)
+    options["mode"] = mode.to_s
+    options["last_token"] = last_token.to_s
+    options["cjk"] = cjk.to_s
+    options["dry_run"] = dry_run ? true : false
+    options["guard"] = guard ? true : false
+    keyed(_rust_correct_text(text.to_s, options))
+  end
+
+  # Applies an accepted subset of dry-run changes: correct_text changes to the String they were
+  # proposed for, correct_tokens changes to the Array. Every change is checked against the input,
+  # so one made for a different or edited text raises instead of corrupting it
+  def apply_changes(input, changes)
+    raise SpellKit::InvalidArgumentError, "changes must be an Array" unless changes.is_a?(Array)
+
+    changes = changes.map do |change|
+      raise SpellKit::InvalidArgumentError, "each change must be a Hash, got: #{change.inspect}" unless change.is_a?(Hash)
+
+      change.transform_keys(&:to_s)
+    end
+
+    case input
+    when String then apply_text_changes(input, changes)
+    when Array then apply_token_changes(input, changes)
+    else raise SpellKit::InvalidArgumentError, "input must be a String or an Array, got: #{input.class}"
+    end
+  end
+
+  LINE_MODES = %w[token text].freeze
+
+  # Corrects each separator-delimited field of one big string and returns one joined string.
+  # mode: "token" corrects each field as a single word, "text" runs the correct_text pipeline
+  def correct_lines(input, separator: "\n", mode: "token")
+    raise SpellKit::InvalidArgumentError, "input must be a String" unless input.is_a?(String)
+
+    unless separator.is_a?(String) && !separator.empty?
+      raise SpellKit::InvalidArgumentError, "separator must be a non-empty String, got: #{separator.inspect}"
+    end
+
+    unless LINE_MODES.include?(mode.to_s)
+      raise SpellKit::InvalidArgumentError, "mode must be one of #{LINE_MODES.join(", ")}, got: #{mode.inspect}"
+    end
+
+    _rust_correct_lines(input, {"separator" => separator, "mode" => mode.to_s})
+  end
+
+  def tokenize(text, tokenizer: nil)
+    raise SpellKit::InvalidArgumentError, "text cannot be nil" if text.nil?
+
+    _rust_tokenize(text.to_s, tokenizer.nil? ? {} : tokenizer_config(tokenizer))
+  end
+
+  # Replays a corpus file (or IO) through the correction pipeline and writes one
+  # original<TAB>corrected<TAB>distance<TAB>freq<TAB>reason row per corrected token.
+  # Returns summary counts; neither the corpus nor the report is held in memory
+  def evaluate_corpus(path_or_io, output_path:, format: "tsv", tokenizer: nil)
+    raise SpellKit::InvalidArgumentError, "output_path is required" if output_path.nil?
+    raise SpellKit::InvalidArgumentError, "format must be \"tsv\", got: #{format.inspect}" unless format.to_s == "tsv"
+
+    options = tokenizer.nil? ? {} : tokenizer_config(tokenizer)
+
+    if path_or_io.respond_to?(:read)
+      # Spool IO input to disk so the extension can stream it line by line
+      Tempfile.create(["spellkit_corpus", ".txt"]) do |spool|
+        IO.copy_stream(path_or_io, spool)
+        spool.flush
+        keyed(_rust_evaluate_corpus(spool.path, output_path.to_s, options))
+      end
+    else
+      path = path_or_io.to_s
+      raise SpellKit::FileNotFoundError, "Corpus file not found: #{path}" unless File.exist?(path)
+
+      keyed(_rust_evaluate_corpus(path, output_path.to_s, options))
+    end
+  end
+
+  # Generates distinct typos of `word` at exactly `distance` edits for test data. The same seed
+  # always yields the same typos; dictionary words are excluded unless allow_known: true
+  def perturb(word, distance: 1, count: 5, seed: nil, allow_known: false)
+    validate_word!(word)
+
+    unless distance.is_a?(Integer) && distance >= 1
+      raise SpellKit::InvalidArgumentError, "distance must be a positive Integer, got: #{distance.inspect}"
+    end
+
+    unless count.is_a?(Integer) && count >= 0
+      raise SpellKit::InvalidArgumentError, "count must be a non-negative Integer, got: #{count.inspect}"
+    end
+
+    unless seed.nil? || (seed.is_a?(Integer) && seed >= 0)
+      raise SpellKit::InvalidArgumentError, "seed must be a non-negative Integer, got: #{seed.inspect}"
+    end
+
+    _rust_perturb(word, {
+      "distance" => distance,
+      "count" => count,
+      "seed" => (seed || Random.new_seed) & 0xFFFF_FFFF_FFFF_FFFF,
+      "allow_known" => allow_known ? true : false
+    })
+  end
+
+  # Draws `count` dictionary words (canonical forms, with replacement) in proportion to their
+  # frequency, or uniformly with weighted: false. Reproducible for a given seed and dictionary
+  def sample_words(count, seed: nil, weighted: true)
+    unless count.is_a?(Integer) && count >= 0
+      raise SpellKit::InvalidArgumentError, "count must be a non-negative Integer, got: #{count.inspect}"
+    end
+
+    unless seed.nil? || (seed.is_a?(Integer) && seed >= 0)
+      raise SpellKit::InvalidArgumentError, "seed must be a non-negative Integer, got: #{seed.inspect}"
+    end
+
+    _rust_sample_words(count, {
+      "seed" => (seed || Random.new_seed) & 0xFFFF_FFFF_FFFF_FFFF,
+      "weighted" => weighted ? true : false
+    })
+  end
+
+  WARMUP_SAMPLE = 10_000
+
+  # Runs `sample` seeded dictionary words and a typo of each through suggestions to page in and
+  # cache-warm the index, e.g. after a load! without warmup: true. Latency histograms are not
+  # touched. Returns {"elapsed_ms" => Float, "queries" => Integer, "entries_touched" => Integer}
+  def warmup(sample: WARMUP_SAMPLE, seed: nil)
+    unless sample.is_a?(Integer) && sample >= 0
+      raise SpellKit::InvalidArgumentError, "sample must be a non-negative Integer, got: #{sample.inspect}"
+    end
+
+    unless seed.nil? || (seed.is_a?(Integer) && seed >= 0)
+      raise SpellKit::InvalidArgumentError, "seed must be a non-negative Integer, got: #{seed.inspect}"
+    end
+
+    keyed(_rust_warmup({
+      "sample" => sample,
+      "seed" => (seed || Random.new_seed) & 0xFFFF_FFFF_FFFF_FFFF
+    }))
+  end
+
+  # Writes the loaded dictionary's index, with added and trained counts, to `path`, so a later
+  # load!(index_path: path) skips parsing and indexing. The load must ask for the same
+  # edit_distance, index_strategy, prefix_length, compact, frequency_scale, and frequency_combine
+  def save_index(path)
+    raise SpellKit::InvalidArgumentError, "path must be a String, got: #{path.inspect}" unless path.is_a?(String)
+
+    _rust_save_index(path)
+  end
+
+  EXPORT_FORMATS = %w[tsv].freeze
+
+  # Writes every word the checker knows, runtime additions and merged duplicates included, to
+  # `path` as canonical<TAB>frequency lines, most frequent first, for a later load!
+  def export_dictionary(path, format: :tsv)
+    raise SpellKit::InvalidArgumentError, "path must be a String, got: #{path.inspect}" unless path.is_a?(String)
+
+    unless EXPORT_FORMATS.include?(format.to_s)
+      raise SpellKit::InvalidArgumentError, "format must be one of #{EXPORT_FORMATS.join(", ")}, got: #{format.inspect}"
+    end
+
+    _rust_export_dictionary(path)
+  end
+
+  # Writes only the user layer to `path` as canonical<TAB>count lines, most counted first: the
+  # words of user_dictionary_path and add_word!, without their shared counts. Pass the file as
+  # user_dictionary_path to restore them
+  def export_user_dictionary(path)
+    raise SpellKit::InvalidArgumentError, "path must be a String, got: #{path.inspect}" unless path.is_a?(String)
+
+    _rust_export_user_dictionary(path)
+  end
+
+  # [canonical, frequency] pairs, most frequent first; all of them unless `limit` is given
+  def words(limit: nil)
+    unless limit.nil? || (limit.is_a?(Integer) && limit >= 0)
+      raise SpellKit::InvalidArgumentError, "limit must be a non-negative Integer or nil, got: #{limit.inspect}"
+    end
+
+    _rust_words(limit)
+  end
+
+  # Checks golden [input, expected] pairs against the full correction pipeline.
+  # Returns {"passed" => count, "failed" => [{"input", "expected", "got", "reason"}, ...]}
+  def run_golden(pairs)
+    unless pairs.is_a?(Array) && pairs.all? { |pair| pair.is_a?(Array) && pair.size == 2 && pair.all?(String) }
+      raise SpellKit::InvalidArgumentError, "pairs must be an Array of [input, expected] String pairs"
+    end
+
+    keyed(_rust_run_golden(pairs))
+  end
+
+  # Hex SHA-256 over the effective configuration and the sorted dictionary entries: it changes
+  # exactly when corrections could change, whatever order the dictionary lines were in.
+  # Computed once per loaded dictionary
+  def behavior_digest
+    _rust_behavior_digest
   end
 
   def stats
-    _rust_stats
+    keyed(_rust_stats)
+  end
+
+  # Zeroes the guard hit counts and latency histograms in `stats`; returns self
+  def reset_stats!
+    _rust_reset_stats!
+    self
+  end
+
+  # Zeroes the call and token counts under "metrics" in `stats`; returns self
+  def reset_metrics!
+    _rust_reset_metrics!
+    self
   end
 
-  def healthcheck
+  # Raises unless a dictionary is loaded. incremental: true also verifies the next `batch`
+  # dictionary entries against the index, for at most about `time_budget` seconds, and returns
+  # the progress of these probes through the whole dictionary (see README). deep: true instead
+  # suggests for a typo of `probes` sampled words, raising HealthcheckError unless each comes
+  # back, and returns {"status", "dictionary_size", "probes", "probes_passed", "probe_latency_us"}
+  def healthcheck(incremental: false, batch: 100, time_budget: 0.01, deep: false, probes: 10, seed: nil)
+    if deep
+      raise SpellKit::InvalidArgumentError, "deep: true can't be combined with incremental: true" if incremental
+      raise SpellKit::InvalidArgumentError, "probes must be a positive Integer, got: #{probes.inspect}" unless probes.is_a?(Integer) && probes.positive?
+
+      unless seed.nil? || (seed.is_a?(Integer) && seed >= 0)
+        raise SpellKit::InvalidArgumentError, "seed must be a non-negative Integer, got: #{seed.inspect}"
+      end
+
+      _rust_healthcheck
+      return keyed(_rust_healthcheck_deep({"probes" => probes, "seed" => (seed || Random.new_seed) & 0xFFFF_FFFF_FFFF_FFFF}))
+    end
+
+    return _rust_healthcheck unless incremental
+
+    raise SpellKit::InvalidArgumentError, "batch must be a positive Integer, got: #{batch.inspect}" unless batch.is_a?(Integer) && batch.positive?
+
+    unless time_budget.is_a?(Numeric) && time_budget.finite? && time_budget >= 0
+      raise SpellKit::InvalidArgumentError, "time_budget must be a non-negative number of seconds, got: #{time_budget.inspect}"
+    end
+
     _rust_healthcheck
+    keyed(_rust_healthcheck_probe({"batch" => batch, "time_budget" => time_budget.to_f}))
+  end
+
+  # The same fields as stats, serialized to a JSON String in Rust for cheap polling
+  def stats_json
+    _rust_stats_json
+  end
+
+  # {"healthy":..., "error":..., "loading":..., "generation":...} as a JSON String; never raises
+  def healthcheck_json
+    _rust_healthcheck_json
+  end
+
+  METRIC_PREFIX = /\A[a-zA-Z_:][a-zA-Z0-9_:]*\z/
+
+  # The stats counters in Prometheus text exposition format. checker: adds a checker="..."
+  # label to every sample, for scrapes covering several checkers
+  def metrics_text(prefix: "spellkit", checker: nil)
+    unless prefix.is_a?(String) && prefix.match?(METRIC_PREFIX)
+      raise SpellKit::InvalidArgumentError, "prefix must be a valid Prometheus metric name, got: #{prefix.inspect}"
+    end
+
+    options = {"prefix" => prefix}
+    options["checker"] = checker.to_s if checker
+    _rust_metrics_text(options)
   end
 
   private
 
-  def build_skip_patterns(skip_urls:, skip_emails:, skip_hostnames:, skip_code_patterns:, skip_numbers:)
-    patterns = []
+  PROTECTED_OVERLAP_SHOWN = 10
+
+  # Protected terms that are also frequent dictionary words are usually a list mistake
+  def warn_protected_overlap(confident_frequency)
+    overlap = _rust_stats["protected_overlap"]
+    return if overlap.nil? || overlap.empty?
 
-    # Priority 1: URLs, Emails, Hostnames
-    if skip_urls
-      # Match http:// or https:// URLs
-      patterns << /^https?:\/\/[^\s]+$/i
-      # Match www. URLs
-      patterns << /^www\.[^\s]+$/i
+    shown = overlap.first(PROTECTED_OVERLAP_SHOWN).join(", ")
+    shown += ", and #{overlap.size - PROTECTED_OVERLAP_SHOWN} more" if overlap.size > PROTECTED_OVERLAP_SHOWN
+    warn "SpellKit: #{overlap.size} protected term(s) already in the dictionary with frequency >= " \
+      "#{confident_frequency}: #{shown}. Pass protect_only_unknown: true to skip protecting them."
+  end
+
+  # 0 is valid but easy to pick by accident: every candidate within edit_distance passes, even
+  # a zero-frequency one, and the relative threshold for rare known words is 0 as well. Each
+  # threshold comes as [option name, value], so the warning names the option that was set
+  def warn_zero_threshold((min_name, min_frequency), (ratio_name, ratio), autocorrect_known_rare)
+    if min_frequency.zero? && ratio.zero? && min_name == ratio_name
+      message = "SpellKit: #{min_name} is 0, so any dictionary word within edit_distance is accepted as a correction"
+      message += ", and every known word below autocorrect_known_rare is replaced by any neighbour" if autocorrect_known_rare
+      return warn "#{message}."
+    end
+
+    if min_frequency.zero?
+      warn "SpellKit: #{min_name} is 0, so any dictionary word within edit_distance is accepted as a correction of an unknown word."
     end
+    if ratio.zero? && autocorrect_known_rare
+      warn "SpellKit: #{ratio_name} is 0, so every known word below autocorrect_known_rare is replaced by any neighbour."
+    end
+  end
+
+  # Overlong lines are usually a corrupt or wrongly formatted dictionary, not stray rows
+  def warn_skipped_long(max_line_length, max_word_length)
+    stats = _rust_stats
+    lines = stats["skipped_long_lines"]
+    words = stats["skipped_long_words"]
+    return if lines.zero? && words.zero?
+
+    warn "SpellKit: skipped #{lines} dictionary line(s) longer than max_line_length (#{max_line_length} bytes) " \
+      "and #{words} term(s) longer than max_word_length (#{max_word_length} characters). " \
+      "Pass strict: true to fail the load instead."
+  end
 
-    if skip_emails
-      # Match email addresses: user@domain.com, user+tag@domain.co.uk
-      patterns << /^[\w.+-]+@[\w.-]+\.\w+$/i
+  # Converts a Regexp or String to the hash Rust compiles patterns from
+  def pattern_config(pattern)
+    if pattern.is_a?(Regexp)
+      # Extract flags from Regexp.options bitmask
+      options = pattern.options
+      {
+        "source" => pattern.source,
+        "case_insensitive" => (options & Regexp::IGNORECASE) != 0,
+        "multiline" => (options & Regexp::MULTILINE) != 0,
+        "extended" => (options & Regexp::EXTENDED) != 0,
+        "anchored" => false
+      }
+    else
+      # Plain strings default to case-sensitive
+      {
+        "source" => pattern,
+        "case_insensitive" => false,
+        "multiline" => false,
+        "extended" => false,
+        "anchored" => false
+      }
     end
+  end
+
+  PATTERN_FLAGS = %i[case_insensitive multiline extended anchored].freeze
 
-    if skip_hostnames
-      # Match hostnames: example.com, sub.example.com, my-site.co.uk
-      # Must have at least one dot and valid TLD
-      patterns << /^[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)+$/i
+  # A protected_patterns entry: a Regexp, whose options become flags, a String, or
+  # {pattern: String, case_insensitive:, multiline:, extended:, anchored:, name:} with every flag
+  # optional. Hash patterns must match the whole token unless anchored: false; Regexps and Strings,
+  # as before hashes existed, protect any token they match part of. name is reported in guard
+  # details when the pattern protects a token
+  def protected_pattern_config(pattern, index)
+    return pattern_config(pattern) if pattern.is_a?(Regexp) || pattern.is_a?(String)
+
+    unless pattern.is_a?(Hash)
+      raise SpellKit::InvalidArgumentError,
+        "protected_patterns[#{index}] must be a Regexp, String, or Hash, got: #{pattern.inspect}"
     end
 
-    # Priority 2: Code patterns
-    if skip_code_patterns
-      # Match camelCase: starts lowercase, has uppercase (arrayMap, getElementById)
-      patterns << /^[a-z]+[A-Z][a-zA-Z0-9]*$/
-      # Match PascalCase: starts uppercase, has mixed case (ArrayList, MyClass)
-      patterns << /^[A-Z][a-z]+[A-Z][a-zA-Z0-9]*$/
-      # Match snake_case: lowercase with underscores (my_function, API_KEY)
-      patterns << /^[a-z]+_[a-z0-9_]+$/i
-      # Match SCREAMING_SNAKE_CASE: uppercase with underscores
-      patterns << /^[A-Z]+_[A-Z0-9_]+$/
-      # Match dotted.paths: identifier.identifier (Array.map, config.yml)
-      patterns << /^[a-zA-Z_][a-zA-Z0-9_]*\.[a-zA-Z_][a-zA-Z0-9_.]*$/
+    pattern = pattern.transform_keys(&:to_sym)
+    unknown = pattern.keys - [:pattern, :name, *PATTERN_FLAGS]
+    if unknown.any?
+      raise SpellKit::InvalidArgumentError, "protected_patterns[#{index}] has unknown keys: #{unknown.join(", ")}"
+    end
+    unless pattern[:pattern].is_a?(String)
+      raise SpellKit::InvalidArgumentError, "protected_patterns[#{index}] needs a String :pattern, got: #{pattern[:pattern].inspect}"
     end
 
-    # Priority 3: Numeric patterns
-    if skip_numbers
-      # Match version numbers: 1.0, 1.2.3, 1.2.3.4
-      patterns << /^\d+\.\d+(\.\d+)?(\.\d+)?$/
-      # Match hash/IDs: #123, #4567
-      patterns << /^#\d+$/
-      # Match measurements with common units
-      # Weight: kg, g, mg, lb, oz
-      # Distance: km, m, cm, mm, mi, ft, in
-      # Data: gb, mb, kb, tb, pb
-      # Screen: px, pt, em, rem
-      patterns << /^\d+(\.\d+)?(kg|g|mg|lb|oz|km|m|cm|mm|mi|ft|in|gb|mb|kb|tb|pb|px|pt|em|rem)$/i
-      # Match standalone numbers at start of word (5kg, 123abc)
-      patterns << /^\d/
+    unless pattern[:name].nil? || pattern[:name].is_a?(String)
+      raise SpellKit::InvalidArgumentError, "protected_patterns[#{index}] name must be a String, got: #{pattern[:name].inspect}"
     end
 
-    patterns
+    config = {"source" => pattern[:pattern], "name" => pattern[:name]}
+    PATTERN_FLAGS.each do |flag|
+      value = pattern.fetch(flag, flag == :anchored)
+      unless value == true || value == false
+        raise SpellKit::InvalidArgumentError, "protected_patterns[#{index}] #{flag} must be true or false, got: #{value.inspect}"
+      end
+
+      config[flag.to_s] = value
+    end
+    config
   end
 
-  def download_dictionary(url)
-    require "digest"
+  # A boost is {pattern: Regexp/String, multiplier:} or {terms_path:, multiplier:}
+  def boost_config(boost)
+    raise SpellKit::InvalidArgumentError, "boosts must contain Hashes, got: #{boost.inspect}" unless boost.is_a?(Hash)
+
+    boost = boost.transform_keys(&:to_sym)
+    unknown = boost.keys - %i[pattern terms_path multiplier]
+    raise SpellKit::InvalidArgumentError, "unknown boost keys: #{unknown.join(", ")}" if unknown.any?
+
+    multiplier = boost[:multiplier]
+    unless multiplier.is_a?(Numeric) && multiplier.finite? && multiplier > 0
+      raise SpellKit::InvalidArgumentError, "boost multiplier must be a positive number, got: #{multiplier.inspect}"
+    end
+
+    pattern, terms_path = boost.values_at(:pattern, :terms_path)
+    unless pattern.nil? ^ terms_path.nil?
+      raise SpellKit::InvalidArgumentError, "a boost needs exactly one of pattern: or terms_path:"
+    end
+
+    if pattern
+      unless pattern.is_a?(Regexp) || pattern.is_a?(String)
+        raise SpellKit::InvalidArgumentError, "boost pattern must be a Regexp or String, got: #{pattern.class}"
+      end
+
+      {"pattern" => pattern_config(pattern), "multiplier" => multiplier.to_f}
+    else
+      raise SpellKit::FileNotFoundError, "Boost terms file not found: #{terms_path}" unless File.exist?(terms_path.to_s)
+
+      {"terms_path" => terms_path.to_s, "multiplier" => multiplier.to_f}
+    end
+  end
+
+  # strip_accents: true is shorthand for normalize: {strip_accents: true}, and case_sensitive:
+  # true for normalize: {lowercase: false}
+  def normalize_config(normalize, strip_accents:, case_sensitive:)
+    raise SpellKit::InvalidArgumentError, "normalize must be a Hash, got: #{normalize.inspect}" unless normalize.is_a?(Hash)
+
+    normalize = normalize.transform_keys(&:to_sym)
+    unknown = normalize.keys - %i[form lowercase strip_accents strip_punctuation]
+    raise SpellKit::InvalidArgumentError, "unknown normalize keys: #{unknown.join(", ")}" if unknown.any?
+
+    if normalize.key?(:form) && !NORMALIZATION_FORMS.include?(normalize[:form].to_s)
+      raise SpellKit::InvalidArgumentError,
+        "normalize form must be one of #{NORMALIZATION_FORMS.join(", ")}, got: #{normalize[:form].inspect}"
+    end
+    %i[lowercase strip_accents strip_punctuation].each do |flag|
+      next unless normalize.key?(flag)
+      next if [true, false].include?(normalize[flag])
+
+      raise SpellKit::InvalidArgumentError, "normalize #{flag} must be true or false, got: #{normalize[flag].inspect}"
+    end
+    if strip_accents && normalize[:strip_accents] == false
+      raise SpellKit::InvalidArgumentError, "strip_accents: true conflicts with normalize: {strip_accents: false}"
+    end
+    if case_sensitive && normalize[:lowercase] == true
+      raise SpellKit::InvalidArgumentError, "case_sensitive: true conflicts with normalize: {lowercase: true}"
+    end
+
+    config = normalize.transform_keys(&:to_s)
+    config["form"] = config["form"].to_s if config.key?("form")
+    config["strip_accents"] = true if strip_accents
+    config["lowercase"] = false if case_sensitive
+    config
+  end
+
+  def apply_text_changes(text, changes)
+    rows = changes.map do |change|
+      start, stop, original, proposed = change.values_at("start", "end", "original", "proposed")
+      unless start.is_a?(Integer) && stop.is_a?(Integer) && original.is_a?(String) && proposed.is_a?(String)
+        raise SpellKit::InvalidArgumentError,
+          "text changes need Integer \"start\"/\"end\" and String \"original\"/\"proposed\", got: #{change.inspect}"
+      end
+      raise SpellKit::InvalidArgumentError, "change offsets cannot be negative, got: #{change.inspect}" if start.negative? || stop.negative?
+
+      [start, stop, original, proposed]
+    end
+
+    _rust_apply_changes(text, rows)
+  rescue ArgumentError => e
+    raise SpellKit::InvalidArgumentError, e.message
+  end
+
+  def apply_token_changes(tokens, changes)
+    result = tokens.dup
+    seen = {}
+    changes.each do |change|
+      index, original, proposed = change.values_at("index", "original", "proposed")
+      unless index.is_a?(Integer) && index.between?(0, tokens.size - 1)
+        raise SpellKit::InvalidArgumentError, "change index out of range for #{tokens.size} tokens: #{change.inspect}"
+      end
+      raise SpellKit::InvalidArgumentError, "more than one change for token #{index}" if seen[index]
+      unless tokens[index] == original
+        raise SpellKit::InvalidArgumentError,
+          "change for token #{index} expects #{original.inspect}, but the token is #{tokens[index].inspect}"
+      end
+
+      seen[index] = true
+      result[index] = proposed
+    end
+    result
+  end
+
+  # Per-call thresholds and candidate pool for the native options hash, leaving out those not given
+  def threshold_overrides(frequency_threshold, min_suggestion_frequency, frequency_ratio, max_suggestions = nil)
+    overrides = {
+      "frequency_threshold" => frequency_threshold,
+      "min_suggestion_frequency" => min_suggestion_frequency,
+      "frequency_ratio" => frequency_ratio
+    }.compact
+    overrides.each { |name, value| self.class.validate_frequency_threshold!(value, name) }
+    return overrides if max_suggestions.nil?
+
+    validate_max_suggestions!(max_suggestions)
+    overrides.merge("max_suggestions" => max_suggestions)
+  end
+
+  def validate_max_suggestions!(max_suggestions)
+    return if max_suggestions.is_a?(Integer) && max_suggestions >= 1
+
+    raise SpellKit::InvalidArgumentError, "max_suggestions must be a positive Integer, got: #{max_suggestions.inspect}"
+  end
+
+  # Per-call skipping of digit-bearing, short, and all-caps tokens, leaving out those not given
+  def skip_overrides(skip_tokens_with_digits, min_word_length, skip_all_caps = nil)
+    validate_min_word_length!(min_word_length) unless min_word_length.nil?
 
+    {
+      "skip_tokens_with_digits" => skip_tokens_with_digits.nil? ? nil : (skip_tokens_with_digits ? true : false),
+      "min_word_length" => min_word_length,
+      "skip_all_caps" => skip_all_caps.nil? ? nil : (skip_all_caps ? true : false)
+    }.compact
+  end
+
+  def validate_min_word_length!(min_word_length)
+    return if min_word_length.is_a?(Integer) && min_word_length >= 0
+
+    raise SpellKit::InvalidArgumentError, "min_word_length must be a non-negative Integer, got: #{min_word_length.inspect}"
+  end
+
+  def validate_last_token!(last_token)
+    return if LAST_TOKEN_MODES.include?(last_token.to_s)
+
+    raise SpellKit::InvalidArgumentError,
+      "last_token must be one of #{LAST_TOKEN_MODES.join(", ")}, got: #{last_token.inspect}"
+  end
+
+  def validate_on_error!(on_error)
+    return if ON_ERROR_MODES.include?(on_error.to_s)
+
+    raise SpellKit::InvalidArgumentError,
+      "on_error must be one of #{ON_ERROR_MODES.join(", ")}, got: #{on_error.inspect}"
+  end
+
+  # Hashes as the extension built them, with Symbol keys throughout if loaded with
+  # symbolize_keys: true
+  def keyed(result)
+    _rust_keyed(result)
+  end
+
+  def validate_suggestion_format!(format)
+    return if (format.is_a?(Symbol) || format.is_a?(String)) && SUGGESTION_FORMATS.include?(format.to_s)
+
+    raise SpellKit::InvalidArgumentError,
+      "format must be one of #{SUGGESTION_FORMATS.join(", ")}, got: #{format.inspect}"
+  end
+
+  def validate_word!(word)
+    raise SpellKit::InvalidArgumentError, "word cannot be nil" if word.nil?
+    raise SpellKit::InvalidArgumentError, "word cannot be empty" if word.to_s.empty?
+  end
+
+  # Shared validation for suggestion limits; returns the string-keyed hash Rust expects.
+  # max_distance is checked against the loaded edit_distance on the Rust side
+  def suggest_options(max: nil, max_distance: nil)
+    unless max.nil? || (max.is_a?(Integer) && max >= 0)
+      raise SpellKit::InvalidArgumentError, "max must be a non-negative Integer, got: #{max.inspect}"
+    end
+
+    options = {}
+    options["max"] = max unless max.nil?
+    unless max_distance.nil?
+      unless max_distance.is_a?(Integer) && max_distance >= 0
+        raise SpellKit::InvalidArgumentError, "max_distance must be a non-negative Integer, got: #{max_distance.inspect}"
+      end
+      options["max_distance"] = max_distance
+    end
+    options
+  end
+
+  # Accepts "unicode", "whitespace", or {pattern: "..."} (String or Regexp)
+  def tokenizer_config(tokenizer)
+    case tokenizer
+    when "unicode", "whitespace", :unicode, :whitespace
+      {"tokenizer" => tokenizer.to_s}
+    when Hash
+      pattern = tokenizer[:pattern] || tokenizer["pattern"]
+      raise SpellKit::InvalidArgumentError, "tokenizer hash must include :pattern" if pattern.nil?
+
+      source = pattern.is_a?(Regexp) ? pattern.source : pattern.to_s
+      {"tokenizer" => "pattern", "tokenizer_pattern" => source}
+    else
+      raise SpellKit::InvalidArgumentError,
+        "tokenizer must be \"unicode\", \"whitespace\", or {pattern: ...}, got: #{tokenizer.inspect}"
+    end
+  end
+
+  # skip: as preset names, validated against SKIP_PRESETS
+  def skip_preset_names(skip)
+    names = Array(skip).map do |name|
+      unless (name.is_a?(Symbol) || name.is_a?(String)) && SKIP_PRESETS.key?(name.to_s)
+        raise SpellKit::InvalidArgumentError,
+          "skip presets must be among #{SKIP_PRESETS.keys.join(", ")}, got: #{name.inspect}"
+      end
+
+      name.to_s
+    end
+    names.uniq
+  end
+
+  # [[word, frequency], ...] from a Hash or Array of pairs passed as dictionary:
+  def dictionary_entries(dictionary)
+    dictionary.map do |pair|
+      word, frequency = pair
+      unless pair.is_a?(Array) && pair.size == 2 && (word.is_a?(String) || word.is_a?(Symbol)) &&
+          frequency.is_a?(Integer) && frequency >= 0
+        raise SpellKit::InvalidArgumentError,
+          "dictionary pairs must be a String word and a non-negative Integer frequency, got: #{pair.inspect}"
+      end
+
+      [word.to_s, frequency]
+    end
+  end
+
+  def download_dictionary(url)
     # Create cache directory
     cache_dir = File.join(Dir.home, ".cache", "spellkit")
     FileUtils.mkdir_p(cache_dir)
 
     # Generate cache filename from URL hash
     url_hash = Digest::SHA256.hexdigest(url)[0..15]
-    cache_file = File.join(cache_dir, "dict_#{url_hash}.tsv")
+    # Keeping ".gz" lets load! decompress a gzipped download by extension
+    cache_file = File.join(cache_dir, "dict_#{url_hash}.tsv#{".gz" if URI(url).path.end_with?(".gz")}")
 
     # Return cached file if it exists
     return cache_file if File.exist?(cache_file)
@@ -314,7 +1837,7 @@ class SpellKit::Checker
     body = fetch_with_redirects(url, max_redirects: 5, open_timeout: 10, read_timeout: 30)
 
     # Write to cache
-    File.write(cache_file, body)
+    File.binwrite(cache_file, body)
     cache_file
   rescue URI::InvalidURIError => e
     raise SpellKit::InvalidArgumentError, "Invalid URL: #{url} (#{e.message})"
@@ -365,4 +1888,66 @@ class SpellKit::Checker
   rescue OpenSSL::SSL::SSLError => e
     raise SpellKit::DownloadError, "SSL verification failed: #{e.message} (#{url})"
   end
-end
\ No newline at end of file
+
+  # Every public method taking keyword options checks them before it runs, so a misspelled option
+  # raises InvalidArgumentError naming the closest accepted one instead of Ruby's bare "unknown
+  # keyword". validate_options: false ignores unknown options instead
+  OPTION_METHODS = public_instance_methods(false).select do |name|
+    instance_method(name).parameters.any? { |type, _| type == :key || type == :keyreq }
+  end.freeze
+
+  prepend(Module.new do
+    SpellKit::Checker::OPTION_METHODS.each do |name|
+      accepted = SpellKit::Checker.instance_method(name).parameters
+        .filter_map { |type, key| key if type == :key || type == :keyreq }
+
+      define_method(name) do |*args, validate_options: true, **options, &block|
+        unknown = options.keys - accepted
+        if unknown.any?
+          SpellKit::Checker.check_options!(name, unknown, accepted) if validate_options
+          options = options.slice(*accepted)
+        end
+
+        super(*args, **options, &block)
+      end
+    end
+  end)
+end
+
+# Read-only view of a Checker pinned to one dictionary generation. It holds that generation's
+# state alive, so drop snapshots once the request (or other unit of work) is done; a reload
+# frees the old dictionary only after every snapshot taken from it is garbage collected.
+# Named sets are not part of the generation and stay shared with the live checker
+class SpellKit::Snapshot
+  extend Forwardable
+
+  READ_METHODS = %i[
+    suggestions suggest_many correct? protected? lookup frequency explain correct correct_unique correct_phrase segment correct_tokens
+    correct_tokens_with_info correct_text correct_lines tokenize evaluate_corpus perturb sample_words words run_golden
+    behavior_digest stats healthcheck stats_json healthcheck_json
+  ].freeze
+
+  def_delegators :@checker, *READ_METHODS
+
+  # `checker` is the pinned view returned by Checker#_rust_snapshot; use Checker#snapshot
+  def initialize(checker)
+    @checker = checker
+  end
+end
+
+# A suggestion from Checker#suggestions, defined by the extension: `term`, `distance`,
+# `frequency`, `score` (the term's share of all dictionary counts), and `display_truncated`
+# with truncate_display:. `user?` is true for a word from user_dictionary_path or add_word!.
+# Suggestions sort the way the extension ranks them: nearest first, then user words, then most
+# frequent, then alphabetically
+class SpellKit::Suggestion
+  include Comparable
+
+  def to_h
+    {term: term, distance: distance, frequency: frequency, score: score}
+  end
+
+  def inspect
+    "#<SpellKit::Suggestion term=#{term.inspect} distance=#{distance} frequency=#{frequency} score=#{score}>"
+  end
+end
@@ -0,0 +1,127 @@
+require "tempfile"
+
+RSpec.describe "Alias resolution" do
+  let(:test_dict) do
+    dict = Tempfile.new(["alias_dict", ".tsv"])
+    dict.write("acetaminophen\t5000\n")
+    dict.write("paracetamol\t3000\n")
+    dict.write("ibuprofen\t2000\n")
+    dict.close
+    dict
+  end
+
+  let(:aliases_file) do
+    file = Tempfile.new(["aliases", ".tsv"])
+    file.write("# drug synonyms\n")
+    file.write("paracetamol\tacetaminophen\n")
+    file.write("acetaminophen\tAPAP\n")
+    file.write("tylenol\tacetaminophen\n")
+    file.close
+    file
+  end
+
+  let(:protected_file) do
+    file = Tempfile.new(["alias_protected", ".txt"])
+    file.write("Tylenol\n")
+    file.close
+    file
+  end
+
+  after do
+    test_dict.unlink
+    aliases_file.unlink
+    protected_file.unlink
+  end
+
+  before do
+    SpellKit.load!(dictionary: test_dict.path, aliases_path: aliases_file.path, protected_path: protected_file.path)
+  end
+
+  it "aliases a token after correcting it" do
+    expect(SpellKit.correct("paracetamoll", resolve_aliases: true)).to eq("acetaminophen")
+    expect(SpellKit.correct_tokens(%w[paracetamoll], resolve_aliases: true)).to eq(%w[acetaminophen])
+  end
+
+  it "aliases a token that was already correct" do
+    expect(SpellKit.correct("Paracetamol", resolve_aliases: true)).to eq("acetaminophen")
+  end
+
+  it "applies a single hop only" do
+    # paracetamol -> acetaminophen, never on to APAP
+    expect(SpellKit.correct("paracetamol", resolve_aliases: true)).to eq("acetaminophen")
+    expect(SpellKit.correct("acetaminophen", resolve_aliases: true)).to eq("APAP")
+  end
+
+  it "leaves protected tokens untouched" do
+    expect(SpellKit.correct("Tylenol", resolve_aliases: true)).to eq("Tylenol")
+    expect(SpellKit.correct_tokens(%w[Tylenol], resolve_aliases: true)).to eq(%w[Tylenol])
+  end
+
+  it "does not resolve aliases unless asked" do
+    expect(SpellKit.correct("paracetamoll")).to eq("paracetamol")
+    expect(SpellKit.correct_tokens(%w[paracetamoll])).to eq(%w[paracetamol])
+  end
+
+  it "reports the alias count in stats" do
+    expect(SpellKit.stats["aliases"]).to eq(3)
+  end
+
+  describe "details output" do
+    it "records the spelling correction and the alias hop separately" do
+      detail = SpellKit.correct_tokens(%w[paracetamoll], resolve_aliases: true, details: true).first
+
+      expect(detail).to eq(
+        "token" => "paracetamoll",
+        "output" => "acetaminophen",
+        "correction" => "paracetamol",
+        "reason" => "corrected",
+        "distance" => 1,
+        "freq" => 3000,
+        "alias" => "acetaminophen"
+      )
+    end
+
+    it "records an alias hop on an exact match" do
+      detail = SpellKit.correct_tokens(%w[paracetamol], resolve_aliases: true, details: true).first
+
+      expect(detail["reason"]).to eq("exact")
+      expect(detail["correction"]).to eq("paracetamol")
+      expect(detail["alias"]).to eq("acetaminophen")
+    end
+
+    it "records no alias for protected tokens" do
+      detail = SpellKit.correct_tokens(%w[Tylenol], resolve_aliases: true, details: true).first
+
+      expect(detail["reason"]).to eq("protected")
+      expect(detail["output"]).to eq("Tylenol")
+      expect(detail["alias"]).to be_nil
+    end
+
+    it "records no alias when aliases are not resolved" do
+      detail = SpellKit.correct_tokens(%w[paracetamoll], details: true).first
+
+      expect(detail["output"]).to eq("paracetamol")
+      expect(detail["alias"]).to be_nil
+    end
+  end
+
+  describe "loading" do
+    it "raises for a missing aliases file" do
+      expect {
+        SpellKit.load!(dictionary: test_dict.path, aliases_path: "/nonexistent/aliases.tsv")
+      }.to raise_error(SpellKit::FileNotFoundError, /Aliases file not found/)
+    end
+
+    it "rejects malformed alias lines" do
+      bad = Tempfile.new(["bad_aliases", ".tsv"])
+      bad.write("paracetamol acetaminophen\n")
+      bad.close
+
+      expect {
+        SpellKit.load!(dictionary: test_dict.path, aliases_path: bad.path)
+      }.to raise_error(ArgumentError, /Malformed alias on line 1/)
+    ensure
+      bad&.unlink
+    end
+  end
+end
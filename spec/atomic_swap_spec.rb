@@ -0,0 +1,56 @@
+require "json"
+require "tempfile"
+
+RSpec.describe "Atomic dictionary swaps" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  let(:replacement) do
+    file = Tempfile.new(["replacement", ".tsv"])
+    file.write("help\t50000\nworld\t30000\n")
+    file.close
+    file
+  end
+
+  after { replacement.unlink }
+
+  before { checker.load!(dictionary: test_unigrams) }
+
+  it "serves the old dictionary until the new one is swapped in whole" do
+    seen = []
+    checker.load!(
+      dictionary: replacement.path,
+      progress: lambda do |phase, _processed, _total|
+        seen << [phase, checker.stats["generation"], checker.correct("helo")] unless phase == "finalizing"
+      end
+    )
+
+    expect(seen).not_to be_empty
+    expect(seen.map { |_, generation, _| generation }.uniq).to eq([1])
+    expect(seen.map { |_, _, correction| correction }.uniq).to eq(["hello"])
+    expect(checker.stats["generation"]).to eq(2)
+    expect(checker.correct("helo")).to eq("help")
+  end
+
+  it "lets calls holding the old dictionary finish against it" do
+    pinned = checker.snapshot
+    checker.load!(dictionary: replacement.path)
+
+    expect(pinned.correct("helo")).to eq("hello")
+    expect(checker.correct("helo")).to eq("help")
+  end
+
+  it "reports stats and health from the same generation" do
+    checker.load!(dictionary: replacement.path)
+
+    expect(JSON.parse(checker.healthcheck_json)["generation"]).to eq(checker.stats["generation"])
+    expect(checker.stats["dictionary_size"]).to eq(2)
+  end
+
+  it "keeps the current dictionary when a load fails" do
+    expect { checker.load!(dictionary: "/nonexistent/dictionary.tsv") }.to raise_error(SpellKit::FileNotFoundError)
+
+    expect(checker.stats["generation"]).to eq(1)
+    expect(checker.correct("helo")).to eq("hello")
+  end
+end
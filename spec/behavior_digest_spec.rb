@@ -0,0 +1,64 @@
+require "tempfile"
+
+RSpec.describe "Behavior digest" do
+  let(:lines) { ["hello\t10000\n", "help\t3000\n", "world\t8000\n"] }
+
+  def dictionary(lines)
+    file = Tempfile.new(["behavior", ".tsv"])
+    file.write(lines.join)
+    file.close
+    @files << file
+    file.path
+  end
+
+  def digest(lines, **options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary(lines), **options) }.behavior_digest
+  end
+
+  before do
+    @files = []
+  end
+
+  after do
+    @files.each(&:unlink)
+  end
+
+  it "is a hex SHA-256" do
+    expect(digest(lines)).to match(/\A[0-9a-f]{64}\z/)
+  end
+
+  it "ignores the order of dictionary lines" do
+    expect(digest(lines.reverse)).to eq(digest(lines))
+  end
+
+  it "ignores monitoring-only options" do
+    expect(digest(lines, latency_stats: false)).to eq(digest(lines))
+  end
+
+  it "changes with a threshold" do
+    expect(digest(lines, frequency_threshold: 50.0)).not_to eq(digest(lines))
+  end
+
+  it "changes with an added word" do
+    expect(digest(lines + ["word\t5\n"])).not_to eq(digest(lines))
+  end
+
+  it "changes with guards" do
+    expect(digest(lines, protected_prefixes: ["CDK"])).not_to eq(digest(lines))
+  end
+
+  it "follows reloads and stays pinned in snapshots" do
+    checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary(lines)) }
+    snapshot = checker.snapshot
+    before_reload = checker.behavior_digest
+
+    checker.load!(dictionary: dictionary(lines), edit_distance: 2)
+
+    expect(checker.behavior_digest).not_to eq(before_reload)
+    expect(snapshot.behavior_digest).to eq(before_reload)
+  end
+
+  it "requires a loaded dictionary" do
+    expect { SpellKit::Checker.new.behavior_digest }.to raise_error(SpellKit::NotLoadedError, /not loaded/)
+  end
+end
@@ -0,0 +1,61 @@
+RSpec.describe "correct_tokens details for rejected corrections" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+
+  def details(*tokens)
+    SpellKit.correct_tokens(tokens, details: true)
+  end
+
+  describe "below_threshold" do
+    before do
+      SpellKit.load!(dictionary: test_unigrams, frequency_threshold: 20_000.0)
+    end
+
+    it "reports the best candidate that failed and the frequency it needed" do
+      detail = details("helo").first
+
+      expect(detail["reason"]).to eq("below_threshold")
+      expect(detail["output"]).to eq("helo")
+      expect(detail["best_rejected"]).to eq(
+        "term" => "hello", "distance" => 1, "freq" => 10000, "required_freq" => 20_000.0
+      )
+    end
+
+    it "reports the highest-ranked rejection when several candidates fail" do
+      # helo is one edit from hello (10000), help (3000), and hell (2500)
+      expect(details("helo", "wrld").map { |d| d["best_rejected"]["term"] }).to eq(%w[hello world])
+    end
+  end
+
+  describe "no_candidates" do
+    before do
+      SpellKit.load!(dictionary: test_unigrams)
+    end
+
+    it "reports nil when there were no candidates at all" do
+      detail = details("xyzzy").first
+
+      expect(detail["reason"]).to eq("no_candidates")
+      expect(detail).to have_key("best_rejected")
+      expect(detail["best_rejected"]).to be_nil
+    end
+  end
+
+  describe "other reasons" do
+    before do
+      SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file)
+    end
+
+    it "omits the field for corrected tokens" do
+      expect(details("helo").first).not_to have_key("best_rejected")
+    end
+
+    it "omits the field for known tokens" do
+      expect(details("hello").first).not_to have_key("best_rejected")
+    end
+
+    it "omits the field for protected tokens" do
+      expect(details("CDK10").first).not_to have_key("best_rejected")
+    end
+  end
+end
@@ -0,0 +1,111 @@
+RSpec.describe "Frequency boosts" do
+  let(:dictionary) { File.expand_path("fixtures/temp_boost_unigrams.tsv", __dir__) }
+  let(:glossary) { File.expand_path("fixtures/temp_boost_glossary.txt", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  before do
+    # "onkology" is one edit from both, and equal frequencies fall back to alphabetical order
+    File.write(dictionary, "oncology\t1000\nontology\t1000\nrarity\t5\n")
+    File.write(glossary, "# ontology glossary\nontology\n")
+  end
+
+  after do
+    FileUtils.rm_f([dictionary, glossary])
+  end
+
+  it "leaves the ranking alone without boosts" do
+    checker.load!(dictionary: dictionary)
+    expect(checker.correct("onkology")).to eq("oncology")
+  end
+
+  it "flips the choice between otherwise-equal candidates with a terms file boost" do
+    checker.load!(dictionary: dictionary, boosts: [{terms_path: glossary, multiplier: 5.0}])
+    expect(checker.correct("onkology")).to eq("ontology")
+  end
+
+  it "flips the choice with a pattern boost" do
+    checker.load!(dictionary: dictionary, boosts: [{pattern: /^ont/, multiplier: 2}])
+    expect(checker.correct("onkology")).to eq("ontology")
+  end
+
+  it "reports the applied boosts in details" do
+    checker.load!(dictionary: dictionary, boosts: [{terms_path: glossary, multiplier: 5.0}])
+    details = checker.correct_tokens(["onkology"], details: true).first
+
+    expect(details["output"]).to eq("ontology")
+    expect(details["freq"]).to eq(1000)
+    expect(details["boost"]).to eq(5.0)
+    expect(details["boosts"]).to eq([{"terms_path" => glossary, "multiplier" => 5.0}])
+  end
+
+  it "reports no boosts for unboosted decisions" do
+    checker.load!(dictionary: dictionary, boosts: [{terms_path: glossary, multiplier: 5.0}])
+    details = checker.correct_tokens(["oncology"], details: true).first
+
+    expect(details["boost"]).to eq(1.0)
+    expect(details["boosts"]).to eq([])
+  end
+
+  it "multiplies matching boosts up to max_boost" do
+    checker.load!(
+      dictionary: dictionary,
+      boosts: [{pattern: "ology$", multiplier: 10}, {terms_path: glossary, multiplier: 10}],
+      max_boost: 20
+    )
+    details = checker.correct_tokens(["onkology"], details: true).first
+
+    expect(details["output"]).to eq("ontology")
+    expect(details["boost"]).to eq(20.0)
+    expect(details["boosts"].size).to eq(2)
+  end
+
+  it "applies boosts to the frequency threshold" do
+    checker.load!(dictionary: dictionary)
+    expect(checker.correct("rarty")).to eq("rarty")
+
+    checker.load!(dictionary: dictionary, boosts: [{pattern: "^rar", multiplier: 3}])
+    expect(checker.correct("rarty")).to eq("rarity")
+  end
+
+  it "does not change stored frequencies" do
+    checker.load!(dictionary: dictionary, boosts: [{terms_path: glossary, multiplier: 5.0}])
+    expect(checker.suggestions("onkology", 2).map { |s| s.frequency }).to eq([1000, 1000])
+    expect(checker.stats["boosts"]).to eq(1)
+  end
+
+  describe "validation" do
+    it "requires exactly one of pattern or terms_path" do
+      expect {
+        checker.load!(dictionary: dictionary, boosts: [{multiplier: 2}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /exactly one/)
+
+      expect {
+        checker.load!(dictionary: dictionary, boosts: [{pattern: "x", terms_path: glossary, multiplier: 2}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /exactly one/)
+    end
+
+    it "requires a positive multiplier" do
+      expect {
+        checker.load!(dictionary: dictionary, boosts: [{pattern: "x", multiplier: 0}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /multiplier/)
+    end
+
+    it "rejects a missing terms file" do
+      expect {
+        checker.load!(dictionary: dictionary, boosts: [{terms_path: "/nonexistent/glossary.txt", multiplier: 2}])
+      }.to raise_error(SpellKit::FileNotFoundError, /Boost terms file not found/)
+    end
+
+    it "rejects an invalid pattern" do
+      expect {
+        checker.load!(dictionary: dictionary, boosts: [{pattern: "(unclosed", multiplier: 2}])
+      }.to raise_error(SpellKit::InvalidPatternError, /Invalid boost pattern/)
+    end
+
+    it "rejects a non-positive max_boost" do
+      expect {
+        checker.load!(dictionary: dictionary, max_boost: 0)
+      }.to raise_error(SpellKit::InvalidArgumentError, /max_boost/)
+    end
+  end
+end
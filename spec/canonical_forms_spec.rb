@@ -57,17 +57,17 @@ RSpec.describe "Canonical Form Preservation" do
 
     it "returns canonical forms in suggestions" do
       suggestions = SpellKit.suggestions("nasa", 1)
-      expect(suggestions.first["term"]).to eq("NASA")
+      expect(suggestions.first.term).to eq("NASA")
     end
 
     it "preserves mixed case in suggestions" do
       suggestions = SpellKit.suggestions("iphone", 1)
-      expect(suggestions.first["term"]).to eq("iPhone")
+      expect(suggestions.first.term).to eq("iPhone")
     end
 
     it "preserves apostrophes in suggestions" do
       suggestions = SpellKit.suggestions("mcdonalds", 1)
-      expect(suggestions.first["term"]).to eq("McDonald's")
+      expect(suggestions.first.term).to eq("McDonald's")
     end
   end
 
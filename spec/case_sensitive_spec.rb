@@ -0,0 +1,61 @@
+require "tempfile"
+
+RSpec.describe "case_sensitive:" do
+  let(:dictionary) do
+    file = Tempfile.new(["case_sensitive", ".tsv"])
+    file.write("WAS\t500\nwas\t90000\nBRCA1\t300\nthe\t80000\n")
+    file.close
+    file
+  end
+
+  let(:protected_file) do
+    file = Tempfile.new(["case_sensitive", ".txt"])
+    file.write("wsa\n")
+    file.close
+    file
+  end
+
+  after do
+    dictionary.unlink
+    protected_file.unlink
+  end
+
+  def checker(**options)
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: dictionary.path, protected_path: protected_file.path, protected_patterns: [/^CDK\d+$/], **options)
+    end
+  end
+
+  it "answers for each casing independently" do
+    c = checker(case_sensitive: true)
+
+    expect(c.correct?("WAS")).to be(true)
+    expect(c.correct?("was")).to be(true)
+    expect(c.correct?("Was")).to be(false)
+    expect(c.correct_tokens(%w[WAZ Was thE brca1])).to eq(%w[WAS was the brca1])
+    expect(c.stats).to include("case_sensitive" => true, "dictionary_size" => 4)
+  end
+
+  it "coexists with a case-folding checker" do
+    sensitive = checker(case_sensitive: true)
+    folded = checker
+
+    expect(sensitive.correct("WAZ")).to eq("WAS")
+    expect(folded.correct("WAZ")).to eq("was")
+    expect(folded.correct("brca1")).to eq("BRCA1")
+    expect(sensitive.correct("brca1")).to eq("brca1")
+    expect(folded.stats).to include("case_sensitive" => false, "dictionary_size" => 3)
+  end
+
+  it "keeps guards working" do
+    c = checker(case_sensitive: true)
+
+    expect(c.correct_tokens(%w[WSA CDK12], details: true).map { |d| d["reason"] }).to eq(%w[protected protected])
+  end
+
+  it "is shorthand for normalize: {lowercase: false}" do
+    expect(checker(case_sensitive: true).behavior_digest).to eq(checker(normalize: {lowercase: false}).behavior_digest)
+    expect { checker(case_sensitive: true, normalize: {lowercase: true}) }
+      .to raise_error(SpellKit::InvalidArgumentError, /conflicts/)
+  end
+end
@@ -0,0 +1,60 @@
+RSpec.describe "CJK and Thai text" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  before do
+    checker.load!(dictionary: test_unigrams)
+  end
+
+  it "corrects English around Japanese text and leaves the Japanese byte-identical" do
+    japanese = "日本語のテキストです"
+    result = checker.correct_text("helo #{japanese}。wrld")
+
+    expect(result["text"]).to eq("hello #{japanese}。world")
+    expect(result["text"].byteslice(6, japanese.bytesize).b).to eq(japanese.b)
+    expect(result["corrections"].map { |c| c["original"] }).to eq(%w[helo wrld])
+  end
+
+  it "passes Thai and Chinese runs through" do
+    text = "ภาษาไทย testt 中文文本 buffr"
+    expect(checker.correct_text(text)["text"]).to eq("ภาษาไทย test 中文文本 buffer")
+  end
+
+  it "leaves whole tokens containing CJK alone with the whitespace tokenizer" do
+    result = checker.correct_text("テキストhelo helo", tokenizer: "whitespace")
+    expect(result["text"]).to eq("テキストhelo hello")
+  end
+
+  it "does not report runs by default" do
+    expect(checker.correct_text("helo 日本語")).not_to have_key("cjk")
+  end
+
+  describe 'cjk: "flag"' do
+    it "reports each run with byte offsets" do
+      result = checker.correct_text("helo 日本語のテキスト wrld ภาษาไทย", cjk: "flag")
+
+      expect(result["text"]).to eq("hello 日本語のテキスト world ภาษาไทย")
+      expect(result["cjk"]).to eq([
+        {"text" => "日本語のテキスト", "start" => 5, "end" => 29},
+        {"text" => "ภาษาไทย", "start" => 35, "end" => 56}
+      ])
+    end
+
+    it "reports an empty list for text without CJK" do
+      expect(checker.correct_text("helo wrld", cjk: "flag")["cjk"]).to eq([])
+    end
+
+    it "only reports text content in html mode" do
+      result = checker.correct_text('<p title="日本">helo 日本</p>', mode: "html", cjk: "flag")
+
+      expect(result["text"]).to eq('<p title="日本">hello 日本</p>')
+      expect(result["cjk"]).to eq([{"text" => "日本", "start" => 23, "end" => 29}])
+    end
+  end
+
+  it "rejects unknown cjk modes" do
+    expect {
+      checker.correct_text("helo", cjk: "segment")
+    }.to raise_error(SpellKit::InvalidArgumentError, /cjk must be one of/)
+  end
+end
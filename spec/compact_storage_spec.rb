@@ -0,0 +1,86 @@
+require "tempfile"
+
+RSpec.describe "Compact storage mode" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:queries) { %w[helo wrld testt tset protien hello lyssis analysys xyzzy] }
+
+  def write_dict(content)
+    file = Tempfile.new(["compact_dict", ".tsv"])
+    file.write(content)
+    file.close
+    (@dicts ||= []) << file
+    file.path
+  end
+
+  after do
+    @dicts&.each(&:unlink)
+  end
+
+  describe "identical outputs" do
+    let(:full) { SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) } }
+    let(:compact) { SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, compact: true) } }
+
+    it "returns the same suggestions for a typical dictionary" do
+      queries.each do |word|
+        expect(compact.suggestions(word, 5)).to eq(full.suggestions(word, 5)), word
+      end
+    end
+
+    it "returns the same corrections" do
+      expect(compact.correct_tokens(queries)).to eq(full.correct_tokens(queries))
+      expect(compact.correct_text("helo wrld, testt")).to eq(full.correct_text("helo wrld, testt"))
+    end
+
+    it "reports the mode in stats" do
+      expect(compact.stats["compact"]).to be true
+      expect(full.stats["compact"]).to be false
+      expect(compact.stats["dictionary_size"]).to eq(full.stats["dictionary_size"])
+    end
+  end
+
+  describe "canonical fallback" do
+    it "returns the normalized key when the canonical form matches it" do
+      SpellKit.load!(dictionary: write_dict("hello\t1000\nNASA\t800\niPhone\t600\n"), compact: true)
+
+      expect(SpellKit.correct("HELLO")).to eq("hello")
+      expect(SpellKit.correct("nasa")).to eq("NASA")
+      expect(SpellKit.correct("iphone")).to eq("iPhone")
+    end
+  end
+
+  describe "saturation" do
+    it "caps frequencies at the u32 maximum" do
+      SpellKit.load!(dictionary: write_dict("hello\t10000000000\nworld\t8000\n"), compact: true)
+
+      expect(SpellKit.suggestions("hello", 1).first.frequency).to eq(4_294_967_295)
+      expect(SpellKit.suggestions("world", 1).first.frequency).to eq(8000)
+    end
+
+    it "saturates merged duplicates instead of overflowing" do
+      SpellKit.load!(dictionary: write_dict("hello\t4294967000\nHello\t1000\n"), compact: true, on_duplicate: :sum)
+
+      expect(SpellKit.suggestions("hello", 1).first.frequency).to eq(4_294_967_295)
+    end
+  end
+
+  describe "frequency_scale" do
+    it "multiplies frequencies back up, rounded down to a multiple of the scale" do
+      SpellKit.load!(dictionary: write_dict("hello\t10000000000\nworld\t8500\n"), compact: true, frequency_scale: 1000)
+
+      expect(SpellKit.suggestions("hello", 1).first.frequency).to eq(10_000_000_000)
+      expect(SpellKit.suggestions("world", 1).first.frequency).to eq(8000)
+    end
+
+    it "requires compact mode" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, frequency_scale: 1000)
+      }.to raise_error(SpellKit::InvalidArgumentError, /frequency_scale requires compact: true/)
+    end
+
+    it "rejects non-positive scales" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, compact: true, frequency_scale: 0)
+      }.to raise_error(SpellKit::InvalidArgumentError, /frequency_scale must be a positive Integer/)
+    end
+  end
+end
@@ -0,0 +1,83 @@
+RSpec.describe "correct_lines bulk API" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file)
+  end
+
+  describe "token mode" do
+    it "corrects each line as a single token" do
+      expect(SpellKit.correct_lines("helo\nwrld\ntestt")).to eq("hello\nworld\ntest")
+    end
+
+    it "matches correct for every field" do
+      fields = %w[helo wrld CDK10 xyzzy Hello]
+
+      expect(SpellKit.correct_lines(fields.join("\n")).split("\n")).to eq(fields.map { |f| SpellKit.correct(f) })
+    end
+
+    it "passes empty lines through" do
+      expect(SpellKit.correct_lines("helo\n\n\nwrld")).to eq("hello\n\n\nworld")
+      expect(SpellKit.correct_lines("")).to eq("")
+    end
+
+    it "preserves a trailing separator" do
+      expect(SpellKit.correct_lines("helo\nwrld\n")).to eq("hello\nworld\n")
+      expect(SpellKit.correct_lines("\n")).to eq("\n")
+    end
+
+    it "preserves CRLF line endings" do
+      expect(SpellKit.correct_lines("helo\r\nwrld\r\n\r\ntestt")).to eq("hello\r\nworld\r\n\r\ntest")
+    end
+
+    it "does not treat a whole line with spaces as several words" do
+      expect(SpellKit.correct_lines("helo wrld")).to eq("helo wrld")
+    end
+  end
+
+  describe "text mode" do
+    it "runs each line through the text pipeline" do
+      input = "helo wrld, q4h\r\n\r\nthe testt.\r\n"
+
+      expect(SpellKit.correct_lines(input, mode: "text")).to eq("hello world, q4h\r\n\r\nthe test.\r\n")
+    end
+
+    it "matches correct_text for every line" do
+      lines = ["helo wrld", "CDK10 testt", ""]
+      expected = lines.map { |l| SpellKit.correct_text(l)["text"] }
+
+      expect(SpellKit.correct_lines(lines.join("\n"), mode: :text).split("\n", -1)).to eq(expected)
+    end
+  end
+
+  describe "custom separators" do
+    it "splits and rejoins on a multi-character separator" do
+      expect(SpellKit.correct_lines("helo||wrld||||testt||", separator: "||")).to eq("hello||world||||test||")
+    end
+
+    it "leaves newlines inside fields alone when another separator is used" do
+      expect(SpellKit.correct_lines("helo\tline\nwrld", separator: "\t")).to eq("hello\tline\nwrld")
+    end
+  end
+
+  describe "validation" do
+    it "rejects an empty separator" do
+      expect {
+        SpellKit.correct_lines("helo", separator: "")
+      }.to raise_error(SpellKit::InvalidArgumentError, /separator must be a non-empty String/)
+    end
+
+    it "rejects unknown modes" do
+      expect {
+        SpellKit.correct_lines("helo", mode: "sentence")
+      }.to raise_error(SpellKit::InvalidArgumentError, /mode must be one of token, text/)
+    end
+
+    it "rejects non-String input" do
+      expect {
+        SpellKit.correct_lines(%w[helo])
+      }.to raise_error(SpellKit::InvalidArgumentError, /input must be a String/)
+    end
+  end
+end
@@ -0,0 +1,36 @@
+require "tempfile"
+
+RSpec.describe "correct_phrase" do
+  let(:dictionary) do
+    file = Tempfile.new(["phrase", ".tsv"])
+    file.write("spell\t500\nchecker\t400\ncheck\t600\nfor\t10000\nruby\t300\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  before { SpellKit.load!(dictionary: dictionary.path, edit_distance: 2, protected_patterns: [/^RSpec$/]) }
+
+  it "corrects each word of a phrase" do
+    expect(SpellKit.correct_phrase("spel cheker for ruby")).to eq("output" => "spell checker for ruby", "distance" => 2)
+  end
+
+  it "splits run-on words" do
+    expect(SpellKit.correct_phrase("spellcheker")).to eq("output" => "spell checker", "distance" => 2)
+  end
+
+  it "merges accidentally split words" do
+    expect(SpellKit.correct_phrase("chec ker")).to eq("output" => "checker", "distance" => 1)
+  end
+
+  it "keeps punctuation and protected words" do
+    expect(SpellKit.correct_phrase("spel cheker, for RSpec!")["output"]).to eq("spell checker, for RSpec!")
+    expect(SpellKit.correct_phrase("chec RSpec")["output"]).to eq("check RSpec")
+  end
+
+  it "works on snapshots and validates its input" do
+    expect(SpellKit.default.snapshot.correct_phrase("chec ker")["output"]).to eq("checker")
+    expect { SpellKit.correct_phrase(nil) }.to raise_error(SpellKit::InvalidArgumentError, /phrase must be a String/)
+  end
+end
@@ -0,0 +1,49 @@
+require "tempfile"
+
+RSpec.describe "correct_text offsets and guards" do
+  let(:dictionary) do
+    file = Tempfile.new(["text_offsets", ".tsv"])
+    file.write("hello\t10000\nworld\t8000\nknow\t6000\nknown\t5000\nwell\t5000\ndon't\t4000\ncafé\t3000\n")
+    file.close
+    file
+  end
+
+  let(:checker) do
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: dictionary.path, protected_patterns: [/^wrld$/])
+    end
+  end
+
+  after { dictionary.unlink }
+
+  it "reports byte offsets into the original string" do
+    text = "Café helo, wrld – ünd helo!"
+    result = checker.correct_text(text)
+
+    expect(result["text"]).to eq("Café hello, wrld – ünd hello!")
+    expect(result["corrections"]).to eq([
+      {"original" => "helo", "corrected" => "hello", "start" => 6, "end" => 10},
+      {"original" => "helo", "corrected" => "hello", "start" => 26, "end" => 30}
+    ])
+    result["corrections"].each do |correction|
+      expect(text.byteslice(correction["start"]...correction["end"])).to eq(correction["original"])
+    end
+  end
+
+  it "keeps words with apostrophes and hyphens whole" do
+    result = checker.correct_text("don't knwo wel-knwn helo-wrld 5-FU")
+
+    expect(result["text"]).to eq("don't know wel-knwn helo-wrld 5-FU")
+    expect(result["corrections"].map { |c| c["original"] }).to eq(%w[knwo])
+  end
+
+  it "corrects protected words with guard: false" do
+    expect(checker.correct_text("helo wrld")["text"]).to eq("hello wrld")
+
+    result = checker.correct_text("helo wrld", guard: false)
+    expect(result["text"]).to eq("hello world")
+    expect(result["corrections"].last).to eq("original" => "wrld", "corrected" => "world", "start" => 5, "end" => 9)
+    expect(checker.correct_text("wrld", guard: false, dry_run: true).first).to include("proposed" => "world")
+    expect(checker.correct_text("<b>wrld</b>", mode: "html", guard: false)["text"]).to eq("<b>world</b>")
+  end
+end
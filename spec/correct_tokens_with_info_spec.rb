@@ -0,0 +1,48 @@
+require "tempfile"
+
+RSpec.describe "correct_tokens_with_info" do
+  let(:dictionary) do
+    file = Tempfile.new(["with_info", ".tsv"])
+    file.write("hello\t10000\nworld\t8000\nrare\t5\n")
+    file.close
+    file
+  end
+
+  let(:checker) do
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: dictionary.path, protected_patterns: [/^CDK\d+$/], frequency_threshold: 100)
+    end
+  end
+
+  after { dictionary.unlink }
+
+  it "reports every reason of the standard pipeline" do
+    info = checker.correct_tokens_with_info(%w[hello helo CDK10 rore qzxv])
+
+    expect(info).to eq([
+      {token: "hello", corrected: "hello", changed: false, distance: 0, frequency: 10000, reason: "exact", guard: nil},
+      {token: "helo", corrected: "hello", changed: true, distance: 1, frequency: 10000, reason: "corrected", guard: nil},
+      {token: "CDK10", corrected: "CDK10", changed: false, distance: nil, frequency: nil, reason: "protected",
+       guard: {kind: "pattern", matched: "^CDK\\d+$", index: 0, name: nil}},
+      # "rare" is the only candidate, and 5 is below the threshold of 100
+      {token: "rore", corrected: "rore", changed: false, distance: nil, frequency: nil, reason: "below_threshold", guard: nil},
+      {token: "qzxv", corrected: "qzxv", changed: false, distance: nil, frequency: nil, reason: "no_candidates", guard: nil}
+    ])
+  end
+
+  it "agrees with correct_tokens" do
+    tokens = %w[Helo wrld rore CDK10]
+
+    expect(checker.correct_tokens_with_info(tokens).map { |entry| entry[:corrected] }).to eq(checker.correct_tokens(tokens))
+    expect(checker.correct_tokens_with_info(tokens, preserve_case: true).map { |entry| entry[:corrected] })
+      .to eq(checker.correct_tokens(tokens, preserve_case: true))
+  end
+
+  it "takes correct_tokens options" do
+    info = checker.correct_tokens_with_info(%w[rore], frequency_threshold: 1).first
+
+    expect(info).to include(corrected: "rare", changed: true, reason: "corrected", frequency: 5)
+    expect { checker.correct_tokens_with_info(%w[helo], details: true) }
+      .to raise_error(SpellKit::InvalidArgumentError, /unknown option for correct_tokens_with_info: details/)
+  end
+end
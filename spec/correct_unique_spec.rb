@@ -0,0 +1,44 @@
+RSpec.describe "correct_unique" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:counts) { {"helo" => 120, "hello" => 4000, "wrld" => 7, "qzxv" => 2} }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  it "maps every word to its correction and echoes its count" do
+    expect(SpellKit.correct_unique(counts)).to eq(
+      "helo" => {"corrected" => "hello", "count" => 120},
+      "hello" => {"corrected" => "hello", "count" => 4000},
+      "wrld" => {"corrected" => "world", "count" => 7},
+      "qzxv" => {"corrected" => "qzxv", "count" => 2}
+    )
+  end
+
+  it "leaves unchanged words out with changed_only: true" do
+    result = SpellKit.correct_unique(counts, changed_only: true)
+
+    expect(result.keys).to eq(%w[helo wrld])
+    expect(result["helo"]).to eq("corrected" => "hello", "count" => 120)
+  end
+
+  it "agrees with correct for every word" do
+    result = SpellKit.correct_unique(counts)
+
+    counts.each_key { |word| expect(result[word]["corrected"]).to eq(SpellKit.correct(word)) }
+  end
+
+  it "gives the same result across threads" do
+    table = 2000.times.to_h { |i| [%w[helo wrld buffr protien][i % 4] + ("x" * (i / 4 % 3)) + i.to_s, i] }
+
+    expect(SpellKit.correct_unique(table, threads: 4)).to eq(SpellKit.correct_unique(table))
+  end
+
+  it "validates its arguments" do
+    expect { SpellKit.correct_unique(%w[helo]) }.to raise_error(SpellKit::InvalidArgumentError, /counts must be a Hash/)
+    expect { SpellKit.correct_unique({helo: 1}) }.to raise_error(SpellKit::InvalidArgumentError, /String keys/)
+    expect {
+      SpellKit.correct_unique(counts, threads: 0)
+    }.to raise_error(SpellKit::InvalidArgumentError, /threads must be a positive Integer/)
+  end
+end
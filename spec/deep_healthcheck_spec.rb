@@ -0,0 +1,33 @@
+RSpec.describe "Deep healthcheck" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) } }
+
+  it "finds sampled words again from a typo and reports probe latency" do
+    result = checker.healthcheck(deep: true, probes: 15, seed: 3)
+
+    expect(result).to include("status" => "ok", "dictionary_size" => 20, "probes" => 15, "probes_passed" => 15)
+    expect(result["probe_latency_us"]).to be_a(Float).and be >= 0
+  end
+
+  it "is available on snapshots and follows symbolize_keys" do
+    expect(checker.snapshot.healthcheck(deep: true)).to include("status" => "ok", "probes" => 10)
+
+    symbolized = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, symbolize_keys: true) }
+    expect(symbolized.healthcheck(deep: true, probes: 1)).to include(status: "ok", probes_passed: 1)
+  end
+
+  it "keeps returning nil without deep:" do
+    expect(checker.healthcheck).to be_nil
+  end
+
+  it "raises NotLoadedError before load!" do
+    expect { SpellKit::Checker.new.healthcheck(deep: true) }.to raise_error(SpellKit::NotLoadedError)
+  end
+
+  it "validates its options" do
+    expect { checker.healthcheck(deep: true, probes: 0) }.to raise_error(SpellKit::InvalidArgumentError, /probes must be a positive Integer/)
+    expect { checker.healthcheck(deep: true, seed: -1) }.to raise_error(SpellKit::InvalidArgumentError, /seed must be/)
+    expect { checker.healthcheck(deep: true, incremental: true) }
+      .to raise_error(SpellKit::InvalidArgumentError, /deep: true can't be combined with incremental: true/)
+  end
+end
@@ -0,0 +1,41 @@
+require "tempfile"
+
+RSpec.describe "denylist_path:" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def with_denylist(content)
+    Tempfile.create(["denylist", ".txt"]) do |file|
+      file.write(content)
+      file.close
+      yield SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, denylist_path: file.path) }
+    end
+  end
+
+  it "never suggests or corrects to a denied word" do
+    with_denylist("# never offered\nHELLO\n") do |checker|
+      expect(SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }.correct("helo")).to eq("hello")
+
+      expect(checker.correct("helo")).to eq("help")
+      expect(checker.suggestions("helo", 10).map(&:term)).not_to include("hello")
+      expect(checker.suggestions("helo", 1).map(&:term)).to eq(["help"])
+      expect(checker.suggest_many(%w[helo], 10).first.map(&:term)).not_to include("hello")
+      expect(checker.correct_tokens(%w[helo wrld])).to eq(%w[help world])
+      expect(checker.stats["denylist"]).to eq(1)
+    end
+  end
+
+  it "leaves a denied word alone when it is typed" do
+    with_denylist("hello\n") do |checker|
+      expect(checker.correct?("hello")).to be(true)
+      expect(checker.correct("hello")).to eq("hello")
+      expect(checker.correct("Hello")).to eq("hello")
+      expect(checker.correct_tokens(%w[hello], details: true).first).to include("output" => "hello", "reason" => "exact")
+      expect(checker.suggestions("hello", 5).first).to have_attributes(term: "hello", distance: 0)
+    end
+  end
+
+  it "raises FileNotFoundError for a missing file" do
+    expect { SpellKit::Checker.new.load!(dictionary: test_unigrams, denylist_path: "/nonexistent/denylist.txt") }
+      .to raise_error(SpellKit::FileNotFoundError, "Denylist file not found: /nonexistent/denylist.txt")
+  end
+end
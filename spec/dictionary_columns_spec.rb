@@ -0,0 +1,57 @@
+require "tempfile"
+
+RSpec.describe "Dictionary separator and columns" do
+  def dictionary(content)
+    file = Tempfile.new(["dictionary", ".txt"])
+    file.write(content)
+    file.close
+    (@files ||= []) << file
+    file.path
+  end
+
+  after { @files&.each(&:unlink) }
+
+  it "reads comma-separated count,term exports" do
+    path = dictionary("count,term\n10000,hello\n8000,world\n3000,help\n")
+    SpellKit.load!(dictionary: path, separator: ",", term_index: 1, count_index: 0)
+
+    expect(SpellKit.stats).to include("dictionary_size" => 3, "skipped_invalid_freq" => 1)
+    expect(SpellKit.correct("wrld")).to eq("world")
+    expect(SpellKit.suggestions("helo", 1).first).to have_attributes(term: "hello", frequency: 10000)
+  end
+
+  it "reads tab-separated files with extra columns" do
+    path = dictionary("hello\t10000\tnoun\nworld\t8000\tnoun\n")
+    SpellKit.load!(dictionary: path, separator: "\t")
+
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_malformed" => 0)
+    expect(SpellKit.correct?("world")).to be(true)
+  end
+
+  it "reads pipe-separated files and skips lines with too few columns" do
+    path = dictionary("en|hello|10000\nen|world|8000\nen|orphan\n\n")
+    SpellKit.load!(dictionary: path, separator: "|", term_index: 1, count_index: 2)
+
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_malformed" => 2)
+    expect(SpellKit.correct("helo")).to eq("hello")
+  end
+
+  it "swaps columns with the default separator" do
+    path = dictionary("10000 hello\n8000\tworld\n")
+    SpellKit.load!(dictionary: path, term_index: 1, count_index: 0)
+
+    expect(SpellKit.stats).to include("dictionary_size" => 2)
+    expect(SpellKit.correct?("hello")).to be(true)
+  end
+
+  it "validates the options" do
+    path = dictionary("hello\t10000\n")
+
+    expect { SpellKit.load!(dictionary: path, separator: "") }
+      .to raise_error(SpellKit::InvalidArgumentError, /separator must be a non-empty String/)
+    expect { SpellKit.load!(dictionary: path, term_index: -1) }
+      .to raise_error(SpellKit::InvalidArgumentError, /term_index must be a non-negative Integer/)
+    expect { SpellKit.load!(dictionary: path, term_index: 1) }
+      .to raise_error(SpellKit::InvalidArgumentError, /term_index and count_index must be different columns/)
+  end
+end
@@ -0,0 +1,50 @@
+require "tempfile"
+
+RSpec.describe "dictionary size limits" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:oversized) do
+    file = Tempfile.new(["oversized", ".tsv"])
+    file.write("hello\t100\n" * 1000)
+    file.close
+    file
+  end
+
+  after { oversized.unlink }
+
+  it "rejects a file over max_dictionary_bytes before reading it" do
+    expect(Digest::SHA256).not_to receive(:file)
+
+    expect { SpellKit.load!(dictionary: oversized.path, max_dictionary_bytes: 1024) }
+      .to raise_error(SpellKit::DictionaryError, /10000 bytes, over max_dictionary_bytes \(1024\); nothing was read/)
+  end
+
+  it "aborts parsing past max_words and keeps the previous dictionary serving" do
+    SpellKit.load!(dictionary: test_unigrams)
+    size = SpellKit.stats["dictionary_size"]
+
+    expect { SpellKit.load!(dictionary: oversized.path, max_words: 10) }
+      .to raise_error(SpellKit::DictionaryError, /more than max_words \(10\) entries; stopped at line 11 after 110 of 10000 bytes/)
+
+    expect(SpellKit.stats["dictionary_size"]).to eq(size)
+    expect(SpellKit.correct("helo")).to eq("hello")
+  end
+
+  it "keeps a checker's previous dictionary when its reload fails" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: test_unigrams)
+
+    expect { checker.load!(dictionary: oversized.path, max_words: 10) }.to raise_error(SpellKit::DictionaryError)
+    expect(checker.correct("wrld")).to eq("world")
+  end
+
+  it "allows nil for no limit" do
+    SpellKit.load!(dictionary: oversized.path, max_dictionary_bytes: nil, max_words: nil)
+
+    expect(SpellKit.stats["dictionary_size"]).to eq(1)
+  end
+
+  it "validates the limits" do
+    expect { SpellKit.load!(dictionary: test_unigrams, max_words: 0) }
+      .to raise_error(SpellKit::InvalidArgumentError, /max_words must be a positive Integer or nil/)
+  end
+end
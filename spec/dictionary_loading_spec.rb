@@ -11,7 +11,7 @@ RSpec.describe "Dictionary Loading" do
 
       # Verify it loaded correctly
       suggestions = SpellKit.suggestions("helo", 1)
-      expect(suggestions.first["term"]).to eq("hello")
+      expect(suggestions.first.term).to eq("hello")
     end
 
     it "uses cached dictionary on subsequent loads" do
@@ -57,7 +57,7 @@ RSpec.describe "Dictionary Loading" do
 
       SpellKit.load!(dictionary: "https://example.com/redirect1")
       suggestions = SpellKit.suggestions("test", 1)
-      expect(suggestions.first["term"]).to eq("test")
+      expect(suggestions.first.term).to eq("test")
     end
 
     it "follows relative redirects" do
@@ -70,7 +70,7 @@ RSpec.describe "Dictionary Loading" do
 
       SpellKit.load!(dictionary: "https://example.com/path/dict.txt")
       suggestions = SpellKit.suggestions("test", 1)
-      expect(suggestions.first["term"]).to eq("test")
+      expect(suggestions.first.term).to eq("test")
     end
 
     it "raises error on too many redirects" do
@@ -181,7 +181,7 @@ RSpec.describe "Dictionary Loading" do
       SpellKit.load!(dictionary: test_dict)
 
       suggestions = SpellKit.suggestions("helo", 1)
-      expect(suggestions.first["term"]).to eq("hello")
+      expect(suggestions.first.term).to eq("hello")
     end
 
     it "raises error if file doesn't exist" do
@@ -219,8 +219,8 @@ RSpec.describe "Dictionary Loading" do
       # Verify suggestions work
       suggestions = SpellKit.suggestions("helo", 5)
       expect(suggestions).not_to be_empty
-      expect(suggestions.first).to have_key("term")
-      expect(suggestions.first).to have_key("freq")
+      expect(suggestions.first.term).to be_a(String)
+      expect(suggestions.first.frequency).to be_a(Integer)
     end
   end
 end
\ No newline at end of file
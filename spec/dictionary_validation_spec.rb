@@ -255,7 +255,7 @@ RSpec.describe "Dictionary Validation" do
       dup_dict.write("hello\t3000\n")  # Duplicate (higher frequency, so keeps "hello" canonical)
       dup_dict.close
 
-      SpellKit.load!(dictionary: dup_dict.path)
+      SpellKit.load!(dictionary: dup_dict.path, on_duplicate: :sum)
       stats = SpellKit.stats
 
       # dictionary_size now counts only unique normalized entries
@@ -265,7 +265,7 @@ RSpec.describe "Dictionary Validation" do
       # Verify hello was loaded with summed frequency (1000 + 3000 = 4000)
       expect(SpellKit.correct("helo")).to eq("hello")
       suggestions = SpellKit.suggestions("hello", 1)
-      expect(suggestions.first["freq"]).to eq(4000)
+      expect(suggestions.first.frequency).to eq(4000)
 
       dup_dict.unlink
     end
@@ -279,7 +279,7 @@ RSpec.describe "Dictionary Validation" do
       case_dict.write("test\t5000\n")
       case_dict.close
 
-      SpellKit.load!(dictionary: case_dict.path)
+      SpellKit.load!(dictionary: case_dict.path, on_duplicate: :sum)
       stats = SpellKit.stats
 
       # 5 lines, but only 3 unique normalized forms
@@ -288,12 +288,12 @@ RSpec.describe "Dictionary Validation" do
 
       # Higher-frequency canonical wins, frequencies are summed
       hello_suggestions = SpellKit.suggestions("hello", 1)
-      expect(hello_suggestions.first["term"]).to eq("HELLO")  # 2000 > 1000
-      expect(hello_suggestions.first["freq"]).to eq(3000)     # 1000 + 2000
+      expect(hello_suggestions.first.term).to eq("HELLO")  # 2000 > 1000
+      expect(hello_suggestions.first.frequency).to eq(3000)     # 1000 + 2000
 
       world_suggestions = SpellKit.suggestions("world", 1)
-      expect(world_suggestions.first["term"]).to eq("World")  # 4000 > 3000
-      expect(world_suggestions.first["freq"]).to eq(7000)     # 3000 + 4000
+      expect(world_suggestions.first.term).to eq("World")  # 4000 > 3000
+      expect(world_suggestions.first.frequency).to eq(7000)     # 3000 + 4000
 
       case_dict.unlink
     end
@@ -304,12 +304,12 @@ RSpec.describe "Dictionary Validation" do
       equal_dict.write("HELLO\t1000\n")  # Equal frequency
       equal_dict.close
 
-      SpellKit.load!(dictionary: equal_dict.path)
+      SpellKit.load!(dictionary: equal_dict.path, on_duplicate: :sum)
 
       suggestions = SpellKit.suggestions("hello", 1)
       # When frequencies are equal, first entry's canonical wins
-      expect(suggestions.first["term"]).to eq("hello")
-      expect(suggestions.first["freq"]).to eq(2000)
+      expect(suggestions.first.term).to eq("hello")
+      expect(suggestions.first.frequency).to eq(2000)
 
       equal_dict.unlink
     end
@@ -322,7 +322,7 @@ RSpec.describe "Dictionary Validation" do
       multi_dict.write("TeSt\t50\n")
       multi_dict.close
 
-      SpellKit.load!(dictionary: multi_dict.path)
+      SpellKit.load!(dictionary: multi_dict.path, on_duplicate: :sum)
       stats = SpellKit.stats
 
       # 4 entries, 1 unique normalized form, 3 duplicates
@@ -331,8 +331,8 @@ RSpec.describe "Dictionary Validation" do
 
       # Highest frequency canonical (TEST with 5000) should win
       suggestions = SpellKit.suggestions("test", 1)
-      expect(suggestions.first["term"]).to eq("TEST")
-      expect(suggestions.first["freq"]).to eq(5350)  # 100+5000+200+50
+      expect(suggestions.first.term).to eq("TEST")
+      expect(suggestions.first.frequency).to eq(5350)  # 100+5000+200+50
 
       multi_dict.unlink
     end
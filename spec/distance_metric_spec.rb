@@ -0,0 +1,38 @@
+require "tempfile"
+
+RSpec.describe "distance_metric" do
+  let(:dictionary) do
+    file = Tempfile.new(["distance_metric", ".tsv"])
+    file.write("the\t50000\nreceive\t800\nfrom\t20000\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  it "counts a transposition as two edits by default" do
+    SpellKit.load!(dictionary: dictionary.path)
+
+    expect(SpellKit.correct("teh")).to eq("teh")
+    expect(SpellKit.stats["distance_metric"]).to eq("levenshtein")
+  end
+
+  context "with distance_metric: :damerau" do
+    before { SpellKit.load!(dictionary: dictionary.path, edit_distance: 1, distance_metric: :damerau) }
+
+    it "corrects adjacent swaps at edit_distance 1" do
+      expect(SpellKit.correct("teh")).to eq("the")
+      expect(SpellKit.correct_tokens(%w[recieve form])).to eq(%w[receive from])
+      expect(SpellKit.stats["distance_metric"]).to eq("damerau")
+    end
+
+    it "reports the swap as distance 1" do
+      expect(SpellKit.suggestions("teh").first).to have_attributes(term: "the", distance: 1)
+    end
+  end
+
+  it "rejects an unknown metric" do
+    expect { SpellKit.load!(dictionary: dictionary.path, distance_metric: "hamming") }
+      .to raise_error(SpellKit::InvalidArgumentError, /distance_metric must be one of levenshtein, damerau/)
+  end
+end
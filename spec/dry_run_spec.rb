@@ -0,0 +1,101 @@
+RSpec.describe "Dry-run corrections" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  describe "correct_text(dry_run: true)" do
+    let(:text) { "The helo wrld, and the testt." }
+
+    it "proposes changes with offsets and confidence instead of returning text" do
+      changes = SpellKit.correct_text(text, dry_run: true)
+
+      expect(changes).to be_an(Array)
+      expect(changes.map { |c| c.values_at("original", "proposed", "start", "end") }).to eq([
+        ["helo", "hello", 4, 8],
+        ["wrld", "world", 9, 13],
+        ["testt", "test", 23, 28]
+      ])
+      expect(changes.map { |c| c["confidence"] }).to all(be_between(0.0, 1.0))
+      expect(changes[1]["confidence"]).to eq(1.0)
+      expect(changes[0]["confidence"]).to be < 1.0
+    end
+
+    it "reproduces the full correction when every change is applied" do
+      changes = SpellKit.correct_text(text, dry_run: true)
+
+      expect(SpellKit.apply_changes(text, changes)).to eq(SpellKit.correct_text(text)["text"])
+    end
+
+    it "applies only the accepted subset" do
+      changes = SpellKit.correct_text(text, dry_run: true)
+      accepted = changes.reject { |c| c["original"] == "wrld" }
+
+      expect(SpellKit.apply_changes(text, accepted)).to eq("The hello wrld, and the test.")
+      expect(SpellKit.apply_changes(text, [])).to eq(text)
+    end
+
+    it "accepts changes rebuilt with symbol keys" do
+      change = {original: "helo", proposed: "hello", start: 4, end: 8}
+
+      expect(SpellKit.apply_changes(text, [change])).to eq("The hello wrld, and the testt.")
+    end
+
+    it "round-trips HTML with entity-encoded replacements" do
+      html = "<p>helo &amp; wrld</p>"
+      changes = SpellKit.correct_text(html, mode: "html", dry_run: true)
+
+      expect(SpellKit.apply_changes(html, changes)).to eq(SpellKit.correct_text(html, mode: "html")["text"])
+    end
+
+    it "rejects changes that don't match the text" do
+      changes = SpellKit.correct_text(text, dry_run: true)
+
+      expect { SpellKit.apply_changes("The hell wrld, and the testt.", changes) }
+        .to raise_error(SpellKit::InvalidArgumentError, /expects "helo"/)
+      expect { SpellKit.apply_changes("The helo", changes) }
+        .to raise_error(SpellKit::InvalidArgumentError, /out of bounds/)
+      expect { SpellKit.apply_changes(text, [changes[0], changes[0]]) }
+        .to raise_error(SpellKit::InvalidArgumentError, /overlaps/)
+      expect { SpellKit.apply_changes(text, [{"start" => 4}]) }
+        .to raise_error(SpellKit::InvalidArgumentError, /need Integer/)
+    end
+  end
+
+  describe "correct_tokens(dry_run: true)" do
+    let(:tokens) { %w[helo buffer wrld] }
+
+    it "proposes changes by index" do
+      changes = SpellKit.correct_tokens(tokens, dry_run: true)
+
+      expect(changes.map { |c| c.values_at("index", "original", "proposed") }).to eq([
+        [0, "helo", "hello"],
+        [2, "wrld", "world"]
+      ])
+      expect(changes.last["confidence"]).to eq(1.0)
+    end
+
+    it "applies an accepted subset to the token array" do
+      changes = SpellKit.correct_tokens(tokens, dry_run: true)
+
+      expect(SpellKit.apply_changes(tokens, changes)).to eq(SpellKit.correct_tokens(tokens))
+      expect(SpellKit.apply_changes(tokens, changes.drop(1))).to eq(%w[helo buffer world])
+      expect(tokens).to eq(%w[helo buffer wrld])
+    end
+
+    it "rejects changes for other tokens" do
+      changes = SpellKit.correct_tokens(tokens, dry_run: true)
+
+      expect { SpellKit.apply_changes(%w[help buffer wrld], changes) }
+        .to raise_error(SpellKit::InvalidArgumentError, /expects "helo"/)
+      expect { SpellKit.apply_changes(%w[helo], changes) }
+        .to raise_error(SpellKit::InvalidArgumentError, /out of range/)
+    end
+
+    it "can't be combined with options that reshape the output" do
+      expect { SpellKit.correct_tokens(tokens, dry_run: true, alignment: true) }
+        .to raise_error(SpellKit::InvalidArgumentError, /dry_run: true can't be combined/)
+    end
+  end
+end
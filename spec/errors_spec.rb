@@ -0,0 +1,58 @@
+require "tempfile"
+
+RSpec.describe "SpellKit errors" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  it "derives every SpellKit error from SpellKit::Error" do
+    expect(SpellKit::Error.superclass).to eq(StandardError)
+    [
+      SpellKit::NotLoadedError, SpellKit::DictionaryError, SpellKit::DictionaryParseError, SpellKit::InvalidPatternError,
+      SpellKit::HealthcheckError, SpellKit::FileNotFoundError, SpellKit::InvalidArgumentError, SpellKit::DownloadError
+    ].each do |error|
+      expect(error.superclass).to eq(SpellKit::Error), error.name
+    end
+  end
+
+  it "raises NotLoadedError from every call that needs a dictionary" do
+    checker = SpellKit::Checker.new
+
+    [
+      -> { checker.suggestions("helo") },
+      -> { checker.correct?("hello") },
+      -> { checker.correct("helo") },
+      -> { checker.correct_tokens(%w[helo]) },
+      -> { checker.healthcheck }
+    ].each do |call|
+      expect(&call).to raise_error(SpellKit::NotLoadedError, /Dictionary not loaded/)
+    end
+  end
+
+  it "raises DictionaryParseError for an entry rejected under strict:" do
+    Tempfile.create(["errors", ".tsv"]) do |file|
+      file.write("hello\t10000\nworld\tmany\n")
+      file.close
+
+      expect { SpellKit::Checker.new.load!(dictionary: file.path, strict: true) }
+        .to raise_error(SpellKit::DictionaryParseError, "Dictionary line 2 is malformed: frequency \"many\" is not a non-negative integer")
+    end
+  end
+
+  it "raises InvalidPatternError for a pattern that doesn't compile" do
+    expect { SpellKit::Checker.new.load!(dictionary: test_unigrams, protected_patterns: ["[unclosed"]) }
+      .to raise_error(SpellKit::InvalidPatternError, /\Aprotected_patterns\[0\]: Invalid regex pattern/)
+  end
+
+  it "is caught by rescue SpellKit::Error" do
+    caught = [
+      -> { SpellKit::Checker.new.suggestions("helo") },
+      -> { SpellKit::Checker.new.load!(dictionary: test_unigrams, boosts: [{pattern: "(", multiplier: 2.0}]) },
+      -> { SpellKit::Checker.new.load!(dictionary: {"hello" => 10, "help" => 5}, max_words: 1) }
+    ].map do |call|
+      call.call
+    rescue SpellKit::Error => e
+      e.class
+    end
+
+    expect(caught).to eq([SpellKit::NotLoadedError, SpellKit::InvalidPatternError, SpellKit::DictionaryError])
+  end
+end
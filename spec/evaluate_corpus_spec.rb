@@ -0,0 +1,74 @@
+require "tempfile"
+require "stringio"
+
+RSpec.describe "Corpus evaluation" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+  let(:corpus) { File.expand_path("fixtures/corpus.txt", __dir__) }
+  let(:output) { Tempfile.new(["corrections", ".tsv"]).tap(&:close) }
+
+  after { output.unlink }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file)
+  end
+
+  it "writes one row per corrected token" do
+    SpellKit.evaluate_corpus(corpus, output_path: output.path)
+
+    rows = File.readlines(output.path, chomp: true).map { |line| line.split("\t") }
+    expect(rows).to eq([
+      %w[helo hello 1 10000 corrected],
+      %w[wrld world 1 8000 corrected],
+      %w[buffers buffer 1 1500 corrected],
+      %w[lyssis lysis 1 2000 corrected],
+      %w[testt test 1 5000 corrected]
+    ])
+  end
+
+  it "returns summary counts by reason" do
+    summary = SpellKit.evaluate_corpus(corpus, output_path: output.path)
+
+    expect(summary["lines"]).to eq(5)
+    expect(summary["tokens"]).to eq(12)
+    expect(summary["changed"]).to eq(5)
+    expect(summary["reasons"]).to eq(
+      "exact" => 4,
+      "protected" => 1,
+      "corrected" => 5,
+      "below_threshold" => 0,
+      "no_candidates" => 2,
+      "ambiguous" => 0,
+      "numeric" => 0,
+      "has_digits" => 0,
+      "too_short" => 0,
+      "all_caps" => 0
+    )
+  end
+
+  it "accepts an IO as input" do
+    summary = SpellKit.evaluate_corpus(StringIO.new("helo\nwrld\n"), output_path: output.path)
+
+    expect(summary["changed"]).to eq(2)
+    expect(File.read(output.path)).to eq("helo\thello\t1\t10000\tcorrected\nwrld\tworld\t1\t8000\tcorrected\n")
+  end
+
+  it "writes an empty report when nothing changes" do
+    summary = SpellKit.evaluate_corpus(StringIO.new("hello world\n"), output_path: output.path)
+
+    expect(summary["changed"]).to eq(0)
+    expect(File.read(output.path)).to eq("")
+  end
+
+  it "rejects unsupported formats" do
+    expect {
+      SpellKit.evaluate_corpus(corpus, output_path: output.path, format: "csv")
+    }.to raise_error(SpellKit::InvalidArgumentError, /format must be "tsv"/)
+  end
+
+  it "raises for a missing corpus file" do
+    expect {
+      SpellKit.evaluate_corpus("/nonexistent/corpus.txt", output_path: output.path)
+    }.to raise_error(SpellKit::FileNotFoundError)
+  end
+end
@@ -0,0 +1,73 @@
+require "tempfile"
+
+RSpec.describe "Suggestions with exclude_exact" do
+  let(:test_dict) do
+    dict = Tempfile.new(["exclude_exact", ".tsv"])
+    dict.write("Hello\t10000\n")
+    dict.write("hell\t5000\n")
+    dict.write("help\t3000\n")
+    dict.write("caf\u00e9\t2000\n")
+    dict.write("cafe\t1000\n")
+    dict.close
+    dict
+  end
+
+  after do
+    test_dict.unlink
+  end
+
+  before do
+    SpellKit.load!(dictionary: test_dict.path)
+  end
+
+  describe "case-differing exact matches" do
+    it "returns the canonical form first when exclude_exact is false" do
+      result = SpellKit.suggestions("hello", 5, exclude_exact: false)
+
+      expect(result.first).to have_attributes(term: "Hello", distance: 0, frequency: 10000)
+    end
+
+    it "never returns the input's own canonical when exclude_exact is true" do
+      %w[hello HELLO Hello].each do |word|
+        terms = SpellKit.suggestions(word, 5, exclude_exact: true).map { |s| s.term }
+
+        expect(terms).not_to include("Hello")
+        expect(terms.first).to eq("hell")
+      end
+    end
+
+    it "fills the freed slot with the next alternative" do
+      result = SpellKit.suggestions("hello", 1, exclude_exact: true)
+
+      expect(result.map { |s| s.term }).to eq(["hell"])
+    end
+  end
+
+  describe "composition-differing exact matches" do
+    let(:decomposed) { "cafe\u0301" }
+
+    it "matches a decomposed query to the precomposed entry when exclude_exact is false" do
+      result = SpellKit.suggestions(decomposed, 5, exclude_exact: false)
+
+      expect(result.first.term).to eq("caf\u00e9")
+      expect(result.first.distance).to eq(0)
+    end
+
+    it "excludes the precomposed entry for a decomposed query when exclude_exact is true" do
+      terms = SpellKit.suggestions(decomposed, 5, exclude_exact: true).map { |s| s.term }
+
+      expect(terms).not_to include("caf\u00e9")
+      expect(terms).to include("cafe")
+    end
+  end
+
+  it "keeps exclude_exact off by default" do
+    expect(SpellKit.suggestions("hello").first.term).to eq("Hello")
+  end
+
+  it "rejects non-boolean values" do
+    expect {
+      SpellKit.suggestions("hello", 5, exclude_exact: "yes")
+    }.to raise_error(SpellKit::InvalidArgumentError, /exclude_exact must be true or false/)
+  end
+end
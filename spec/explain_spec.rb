@@ -0,0 +1,90 @@
+require "tempfile"
+
+RSpec.describe "explain" do
+  let(:dictionary) do
+    file = Tempfile.new(["explain", ".tsv"])
+    file.write("hello\t10000\nhelp\t3000\nworld\t8000\nrare\t5\n")
+    file.close
+    file
+  end
+
+  let(:protected_file) do
+    file = Tempfile.new(["explain_protected", ".txt"])
+    file.write("Helpp\n")
+    file.close
+    file
+  end
+
+  let(:checker) do
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: dictionary.path, protected_path: protected_file.path, protected_prefixes: ["CDK"],
+        protected_patterns: [/^BRCA\d$/], frequency_threshold: 100)
+    end
+  end
+
+  after do
+    dictionary.unlink
+    protected_file.unlink
+  end
+
+  it "explains a word whose only candidate fails the threshold" do
+    info = checker.explain("(Rore),")
+
+    expect(info).to include(
+      "word" => "(Rore),",
+      "term" => "Rore",
+      "normalized" => "rore",
+      "in_dictionary" => false,
+      "frequency" => nil,
+      "guard" => nil,
+      "candidates" => [{"term" => "rare", "distance" => 1, "frequency" => 5}],
+      "required_frequency" => 100.0
+    )
+    expect(info["decision"]).to include("output" => "(Rore),", "reason" => "below_threshold")
+    expect(info["decision"]["best_rejected"]).to include("term" => "rare", "required_freq" => 100.0)
+  end
+
+  it "explains a correction and a dictionary word" do
+    expect(checker.explain("helo")["decision"]).to include("output" => "hello", "reason" => "corrected")
+    expect(checker.explain("helo")["candidates"].map { |c| c["term"] }).to eq(%w[hello help])
+
+    info = checker.explain("Help")
+    expect(info).to include("in_dictionary" => true, "frequency" => 3000, "required_frequency" => 300_000.0)
+    expect(info["decision"]).to include("reason" => "exact")
+  end
+
+  it "names the guard that protects a word" do
+    expect(checker.explain("CDK12")["guard"]).to eq("kind" => "prefix", "matched" => "cdk")
+    expect(checker.explain("helpp")["guard"]).to eq("kind" => "set", "matched" => "helpp", "form" => "literal")
+    expect(checker.explain("HELPP")["guard"]).to include("matched" => "helpp", "form" => "lowercase")
+    expect(checker.explain("BRCA3")["guard"]).to eq("kind" => "pattern", "matched" => "^BRCA\\d$", "index" => 0, "name" => nil)
+    expect(checker.explain("helpp")["decision"]).to include("output" => "helpp", "reason" => "protected")
+  end
+
+  it "reports the guard in the decision details" do
+    expect(checker.correct_tokens(%w[CDK12 helo], details: true).map { |detail| detail["guard"] })
+      .to eq([{"kind" => "prefix", "matched" => "cdk"}, nil])
+    expect(checker.explain("BRCA3")["decision"]["guard"]).to eq(checker.explain("BRCA3")["guard"])
+  end
+
+  it "shows what would happen without the guards" do
+    info = checker.explain("helpp", guard: false)
+
+    expect(info["guard"]).to be_nil
+    expect(info["decision"]).to include("output" => "help", "reason" => "corrected")
+  end
+
+  it "agrees with correct and isn't counted in guard stats" do
+    %w[helo rore helpp CDK12 world qzxv].each do |word|
+      expect(checker.explain(word)["decision"]["output"]).to eq(checker.correct(word)), word
+    end
+
+    checker.reset_stats!
+    checker.explain("CDK12")
+    expect(checker.stats["guard_hits"]).to all(satisfy { |_, hits| hits.zero? })
+  end
+
+  it "rejects an empty word" do
+    expect { checker.explain("") }.to raise_error(SpellKit::InvalidArgumentError, /word cannot be empty/)
+  end
+end
@@ -0,0 +1,56 @@
+require "tempfile"
+
+RSpec.describe "Dictionary export" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:queries) { %w[helo wrld tset hello lyssis protien xyzzyq] }
+
+  def export(checker)
+    Tempfile.create(["exported", ".tsv"]) do |file|
+      checker.export_dictionary(file.path)
+      yield file.path
+    end
+  end
+
+  it "reloads with identical stats and suggestions" do
+    original = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }
+
+    export(original) do |path|
+      reloaded = SpellKit::Checker.new.tap { |c| c.load!(dictionary: path, strict: true) }
+      varying = %w[source loaded_at generations latency warmup]
+
+      expect(reloaded.stats.except(*varying)).to eq(original.stats.except(*varying))
+      queries.each do |word|
+        expect(reloaded.suggestions(word, 10, format: :hash)).to eq(original.suggestions(word, 10, format: :hash)), word
+        expect(reloaded.correct(word)).to eq(original.correct(word)), word
+      end
+    end
+  end
+
+  it "writes canonical spellings with merged and runtime counts, most frequent first" do
+    checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: {"Hello" => 50, "hello" => 900, "iPhone" => 400, "rare" => 400}) }
+    checker.add_word!("kubectl", 700)
+
+    export(checker) do |path|
+      expect(File.read(path)).to eq("hello\t900\nkubectl\t700\niPhone\t400\nrare\t400\n")
+    end
+    expect(checker.words).to eq([["hello", 900], ["kubectl", 700], ["iPhone", 400], ["rare", 400]])
+    expect(checker.words(limit: 2)).to eq([["hello", 900], ["kubectl", 700]])
+    expect(checker.words(limit: 0)).to eq([])
+  end
+
+  it "is available on the module and snapshots" do
+    SpellKit.load!(dictionary: test_unigrams)
+
+    expect(SpellKit.words(limit: 1)).to eq([["hello", 10000]])
+    expect(SpellKit.snapshot.words(limit: 1)).to eq([["hello", 10000]])
+  end
+
+  it "validates its arguments" do
+    checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }
+
+    expect { checker.export_dictionary("out.tsv", format: :csv) }.to raise_error(SpellKit::InvalidArgumentError, /format must be one of tsv/)
+    expect { checker.export_dictionary(nil) }.to raise_error(SpellKit::InvalidArgumentError, /path must be a String/)
+    expect { checker.words(limit: -1) }.to raise_error(SpellKit::InvalidArgumentError, /limit must be a non-negative Integer/)
+    expect { SpellKit::Checker.new.words }.to raise_error(SpellKit::NotLoadedError)
+  end
+end
@@ -0,0 +1,25 @@
+RSpec.describe "Frequency combination" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  %w[sum max base_only].each do |combine|
+    it "loads with #{combine} and reports it" do
+      checker.load!(dictionary: test_unigrams, frequency_combine: combine)
+
+      expect(checker.stats["frequency_combine"]).to eq(combine)
+      expect(checker.lookup("hello")[:frequency]).to eq(10000)
+    end
+  end
+
+  it "defaults to sum" do
+    checker.load!(dictionary: test_unigrams)
+
+    expect(checker.stats["frequency_combine"]).to eq("sum")
+  end
+
+  it "rejects unknown combinations" do
+    expect {
+      checker.load!(dictionary: test_unigrams, frequency_combine: "min")
+    }.to raise_error(SpellKit::InvalidArgumentError, /frequency_combine must be one of sum, max, base_only/)
+  end
+end
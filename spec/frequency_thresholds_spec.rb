@@ -0,0 +1,70 @@
+require "tempfile"
+
+RSpec.describe "min_suggestion_frequency and frequency_ratio" do
+  let(:dictionary) do
+    file = Tempfile.new(["frequency_thresholds", ".tsv"])
+    file.write("hello\t10000\nhelo\t50\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary.path, autocorrect_known_rare: 100, **options) }
+  end
+
+  it "applies the absolute floor to unknown words only" do
+    strict_floor = checker(min_suggestion_frequency: 20_000, frequency_ratio: 100)
+
+    expect(strict_floor.correct("hellp")).to eq("hellp")
+    expect(strict_floor.correct_tokens(%w[hellp])).to eq(%w[hellp])
+    expect(strict_floor.correct_tokens(%w[hellp], details: true).first).to include("reason" => "below_threshold")
+    # 100 x 50 = 5000, and the floor doesn't apply to a known word
+    expect(strict_floor.correct("helo")).to eq("hello")
+    expect(strict_floor.correct_tokens(%w[helo])).to eq(%w[hello])
+  end
+
+  it "applies the ratio to dictionary words only" do
+    strict_ratio = checker(min_suggestion_frequency: 1_000, frequency_ratio: 300)
+
+    expect(strict_ratio.correct("hellp")).to eq("hello")
+    expect(strict_ratio.correct_tokens(%w[hellp])).to eq(%w[hello])
+    # 300 x 50 = 15000 is out of reach
+    expect(strict_ratio.correct("helo")).to eq("helo")
+    expect(strict_ratio.correct_tokens(%w[helo])).to eq(%w[helo])
+  end
+
+  it "fills in whichever is unset from frequency_threshold" do
+    expect(checker(frequency_threshold: 20_000).correct("hellp")).to eq("hellp")
+    expect(checker(frequency_threshold: 20_000, min_suggestion_frequency: 1_000).correct("hellp")).to eq("hello")
+    expect(checker(frequency_threshold: 20_000, min_suggestion_frequency: 1_000).correct("helo")).to eq("helo")
+    expect(checker(frequency_threshold: 20_000, frequency_ratio: 100).correct("helo")).to eq("hello")
+  end
+
+  it "is set from a configure block" do
+    SpellKit.configure do |config|
+      config.dictionary = dictionary.path
+      config.autocorrect_known_rare = 100
+      config.min_suggestion_frequency = 20_000
+      config.frequency_ratio = 100
+    end
+
+    expect(SpellKit.correct("hellp")).to eq("hellp")
+    expect(SpellKit.correct("helo")).to eq("hello")
+  end
+
+  it "validates each option under its own name" do
+    expect { checker(min_suggestion_frequency: -1) }
+      .to raise_error(SpellKit::InvalidArgumentError, /min_suggestion_frequency must be non-negative/)
+    expect { checker(frequency_ratio: Float::NAN) }
+      .to raise_error(SpellKit::InvalidArgumentError, /frequency_ratio must be finite/)
+    expect { SpellKit::Configuration.new.frequency_ratio = "5" }
+      .to raise_error(SpellKit::InvalidArgumentError, /frequency_ratio must be a number/)
+  end
+
+  it "names the zero option in the load warning" do
+    expect { checker(min_suggestion_frequency: 0) }.to output(/min_suggestion_frequency is 0/).to_stderr
+    expect { checker(frequency_ratio: 0) }.to output(/frequency_ratio is 0.*below autocorrect_known_rare/).to_stderr
+  end
+end
@@ -0,0 +1,48 @@
+RSpec.describe "Generation accounting" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  before do
+    checker.load!(dictionary: test_unigrams)
+  end
+
+  # Taken in a separate frame so no stack slot keeps the snapshot alive once it's dropped
+  def hold_snapshot(holder)
+    holder << checker.snapshot
+    nil
+  end
+
+  it "reports the loaded generation with its size" do
+    generations = checker.stats["generations"]
+
+    expect(generations.size).to eq(1)
+    expect(generations.first["generation"]).to eq(1)
+    expect(generations.first["current"]).to be true
+    expect(generations.first["estimated_bytes"]).to be > 0
+    expect(generations.first["age_s"]).to be >= 0
+    expect(checker.stats["retired_generations_total"]).to eq(0)
+  end
+
+  it "frees a replaced generation nothing holds" do
+    checker.load!(dictionary: test_unigrams)
+
+    expect(checker.stats["generations"].map { |g| g["generation"] }).to eq([2])
+    expect(checker.stats["retired_generations_total"]).to eq(1)
+  end
+
+  it "keeps a snapshotted generation until the snapshot is collected" do
+    holder = []
+    hold_snapshot(holder)
+    checker.load!(dictionary: test_unigrams)
+
+    generations = checker.stats["generations"]
+    expect(generations.map { |g| g["generation"] }).to eq([1, 2])
+    expect(generations.map { |g| g["current"] }).to eq([false, true])
+
+    holder.clear
+    GC.start(full_mark: true, immediate_sweep: true)
+
+    expect(checker.stats["generations"].map { |g| g["generation"] }).to eq([2])
+    expect(checker.stats["retired_generations_total"]).to eq(1)
+  end
+end
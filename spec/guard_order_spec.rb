@@ -0,0 +1,71 @@
+RSpec.describe "Guard evaluation order and hit counters" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+
+  before do
+    SpellKit.load!(
+      dictionary: test_unigrams,
+      protected_path: protected_file,
+      protected_prefixes: ["rx:"],
+      protected_patterns: [/^[A-Z]{3,4}\d+$/]
+    )
+  end
+
+  it "reports guards cheapest first" do
+    expect(SpellKit.stats["guard_order"]).to eq(%w[shape prefix set pattern])
+  end
+
+  it "starts every counter at zero" do
+    expect(SpellKit.stats["guard_hits"]).to eq("shape" => 0, "prefix" => 0, "set" => 0, "pattern" => 0)
+  end
+
+  it "counts the guard that protected each token" do
+    SpellKit.correct_tokens(%w[rx:foo RX:BRCA1 CDK10 brca1 ABCD12 XYZ99 helo hello])
+
+    expect(SpellKit.stats["guard_hits"]).to eq("shape" => 0, "prefix" => 2, "set" => 2, "pattern" => 2)
+  end
+
+  it "stops at the first guard that fires" do
+    # CDK10 is in the protected set and also matches the pattern
+    SpellKit.correct("CDK10")
+
+    expect(SpellKit.stats["guard_hits"]).to eq("shape" => 0, "prefix" => 0, "set" => 1, "pattern" => 0)
+  end
+
+  it "counts length and shape skips ahead of the other guards" do
+    SpellKit.load!(dictionary: test_unigrams, protected_patterns: [/^[A-Z]{3,4}\d+$/], skip_tokens_with_digits: true, min_word_length: 3)
+    SpellKit.correct_tokens(%w[CDK10 ab helo])
+
+    expect(SpellKit.stats["guard_hits"]).to eq("shape" => 2, "prefix" => 0, "set" => 0, "pattern" => 0)
+  end
+
+  it "accumulates across calls" do
+    3.times { SpellKit.correct("rx:amoxicilin") }
+    SpellKit.correct_tokens(%w[rx:a rx:b])
+
+    expect(SpellKit.stats["guard_hits"]["prefix"]).to eq(5)
+  end
+
+  it "does not count tokens no guard protects" do
+    SpellKit.correct_tokens(%w[helo wrld testt hello])
+
+    expect(SpellKit.stats["guard_hits"].values.sum).to eq(0)
+  end
+
+  it "resets counters on reload" do
+    SpellKit.correct("CDK10")
+    SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file)
+
+    expect(SpellKit.stats["guard_hits"]["set"]).to eq(0)
+  end
+
+  it "keeps per-pattern flags when patterns are evaluated together" do
+    SpellKit.load!(dictionary: test_unigrams, protected_patterns: [/^abc\d+$/i, "^XYZ-\\d+$"])
+
+    SpellKit.correct("ABC12")   # case-insensitive pattern
+    SpellKit.correct("XYZ-12")  # case-sensitive pattern
+    SpellKit.correct("xyz-12")  # neither form matches the case-sensitive pattern
+
+    expect(SpellKit.stats["guard_hits"]["pattern"]).to eq(2)
+  end
+end
@@ -286,6 +286,69 @@ RSpec.describe "Guards & Domain Policies (M2)" do
       expect(SpellKit.correct("il6")).to eq("il6")
       expect(SpellKit.correct("Il-6")).to eq("Il-6")
     end
+
+    it "takes flags from a Hash entry" do
+      SpellKit.load!(
+        dictionary: test_unigrams,
+        protected_patterns: [{pattern: "^hgnc:\\d+$", case_insensitive: true}, {"pattern" => "^cdk \\d+$", "extended" => true}]
+      )
+
+      reasons = SpellKit.correct_tokens(%w[hgnc:1234 HGNC:1234 cdk10 CDK10], details: true).map { |d| d["reason"] }
+      expect(reasons.first(3)).to all(eq("protected"))
+      expect(reasons.last).not_to eq("protected")
+    end
+
+    it "anchors Hash patterns to the whole token" do
+      SpellKit.load!(dictionary: test_unigrams, protected_patterns: ["\\d+"])
+      expect(SpellKit.protected?("covid19variantt")).to be(true)
+      expect(SpellKit.protected?("1234")).to be(true)
+
+      SpellKit.load!(dictionary: test_unigrams, protected_patterns: [{pattern: "\\d+"}])
+      expect(SpellKit.protected?("covid19variantt")).to be(false)
+      expect(SpellKit.protected?("1234")).to be(true)
+
+      SpellKit.load!(dictionary: test_unigrams, protected_patterns: [{pattern: "\\d+", anchored: false}])
+      expect(SpellKit.protected?("covid19variantt")).to be(true)
+    end
+
+    it "reports which pattern protected a token" do
+      SpellKit.load!(dictionary: test_unigrams, protected_patterns: [/^TP\d+$/, {pattern: "CDK\\d+", name: "gene_ids"}],
+        skip: [:hex])
+
+      expect(SpellKit.explain("CDK10")["guard"]).to include("index" => 1, "name" => "gene_ids")
+      expect(SpellKit.explain("TP53")["guard"]).to include("index" => 0, "name" => nil)
+      expect(SpellKit.explain("0xFF")["guard"]).to include("name" => "hex")
+    end
+
+    it "rejects a non-String pattern name" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, protected_patterns: [{pattern: "x", name: :genes}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /protected_patterns\[0\] name must be a String/)
+    end
+
+    it "reports an invalid pattern with its index" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, protected_patterns: [/^ok$/, {pattern: "(unclosed"}])
+      }.to raise_error(SpellKit::InvalidPatternError, /protected_patterns\[1\]: Invalid regex pattern/)
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, skip: [:urls], protected_patterns: ["(unclosed"])
+      }.to raise_error(SpellKit::InvalidPatternError, /protected_patterns\[0\]/)
+    end
+
+    it "rejects malformed entries" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, protected_patterns: ["^ok$", 42])
+      }.to raise_error(SpellKit::InvalidArgumentError, /protected_patterns\[1\] must be a Regexp, String, or Hash/)
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, protected_patterns: [{pattern: "^ok$", ignore_case: true}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /protected_patterns\[0\] has unknown keys: ignore_case/)
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, protected_patterns: [{pattern: "^ok$", case_insensitive: "yes"}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /case_insensitive must be true or false/)
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, protected_patterns: [{case_insensitive: true}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /needs a String :pattern/)
+    end
   end
 
   describe "normalized variant protection" do
@@ -0,0 +1,45 @@
+require "tempfile"
+
+RSpec.describe "Loading without the GVL" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  let(:large_dictionary) do
+    file = Tempfile.new(["large", ".tsv"])
+    100_000.times { |i| file.puts("word#{i.to_s(36)}\t#{i + 1}") }
+    file.close
+    file
+  end
+
+  after { large_dictionary.unlink }
+
+  it "lets other Ruby threads run while the dictionary is parsed and indexed" do
+    count = 0
+    worker = Thread.new { loop { count += 1 } }
+    counts = {}
+
+    SpellKit.load!(
+      dictionary: large_dictionary.path,
+      edit_distance: 2,
+      progress: lambda do |phase, processed, total|
+        counts[:start] ||= count if phase == "parsing" && processed == 0
+        counts[:end] = count if phase == "indexing" && processed == total
+      end
+    )
+    worker.kill
+
+    expect(counts[:end]).to be > counts[:start]
+  end
+
+  it "keeps serving the previous dictionary from other threads during the load" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: test_unigrams)
+    results = []
+    reader = Thread.new { loop { results << checker.correct("helo") } }
+
+    checker.load!(dictionary: large_dictionary.path, edit_distance: 2)
+    reader.kill
+
+    expect(results).not_to be_empty
+    expect(results).to include("hello")
+  end
+end
@@ -0,0 +1,58 @@
+require "tempfile"
+require "zlib"
+
+RSpec.describe "Gzip-compressed dictionaries" do
+  let(:gzipped) { File.expand_path("fixtures/test_unigrams.tsv.gz", __dir__) }
+  let(:plain) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def tempfile(name, content)
+    file = Tempfile.new(name)
+    file.binmode
+    file.write(content)
+    file.close
+    (@files ||= []) << file
+    file.path
+  end
+
+  after { @files&.each(&:unlink) }
+
+  it "decompresses a .gz dictionary while loading it" do
+    SpellKit.load!(dictionary: gzipped)
+
+    expect(SpellKit.stats).to include("dictionary_size" => 20, "skipped_malformed" => 0)
+    expect(SpellKit.correct("helo")).to eq("hello")
+    expect(SpellKit.suggestions("protien", 1)).to eq(SpellKit::Checker.new.tap { |c| c.load!(dictionary: plain) }.suggestions("protien", 1))
+  end
+
+  it "decompresses any path with compression: :gzip" do
+    path = tempfile(["dictionary", ".bin"], File.binread(gzipped))
+
+    SpellKit.load!(dictionary: path, compression: :gzip)
+    expect(SpellKit.stats["dictionary_size"]).to eq(20)
+
+    expect { SpellKit.load!(dictionary: path, compression: :none) }.to raise_error(RuntimeError, /valid UTF-8/)
+  end
+
+  it "decompresses a .gz protected_path" do
+    protected_path = tempfile(["protected", ".txt.gz"], Zlib.gzip("CDK10\nBRCA1\n"))
+    SpellKit.load!(dictionary: gzipped, protected_path: protected_path)
+
+    expect(SpellKit.correct_tokens(%w[CDK10 BRCA1 helo])).to eq(%w[CDK10 BRCA1 hello])
+  end
+
+  it "raises on a corrupt stream and keeps the previous dictionary" do
+    bytes = File.binread(gzipped)
+    bytes[bytes.bytesize / 2, 8] = "\xFF".b * 8
+    corrupt = tempfile(["corrupt", ".tsv.gz"], bytes)
+
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: plain)
+    expect { checker.load!(dictionary: corrupt) }.to raise_error(RuntimeError, /Failed to decompress dictionary file/)
+    expect(checker.correct("helo")).to eq("hello")
+  end
+
+  it "validates compression:" do
+    expect { SpellKit.load!(dictionary: plain, compression: :zip) }
+      .to raise_error(SpellKit::InvalidArgumentError, /compression must be one of auto, gzip, none/)
+  end
+end
@@ -14,7 +14,7 @@ RSpec.describe "Hot Reload & Manifests (M3)" do
 
       # Verify initial state
       suggestions = SpellKit.suggestions("helo", 1)
-      expect(suggestions.first["term"]).to eq("hello")
+      expect(suggestions.first.term).to eq("hello")
 
       # Create new dictionary with different content
       File.write(temp_unigrams, "help\t50000\nworld\t30000")
@@ -24,8 +24,8 @@ RSpec.describe "Hot Reload & Manifests (M3)" do
 
       # Verify new state - "help" should now be the only suggestion
       suggestions = SpellKit.suggestions("helo", 1)
-      expect(suggestions.first["term"]).to eq("help")
-      expect(suggestions.first["freq"]).to eq(50000)
+      expect(suggestions.first.term).to eq("help")
+      expect(suggestions.first.frequency).to eq(50000)
     end
   end
 
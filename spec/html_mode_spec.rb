@@ -0,0 +1,124 @@
+RSpec.describe "correct_text HTML mode" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  def correct_html(html)
+    SpellKit.correct_text(html, mode: "html")
+  end
+
+  describe "tags" do
+    it "corrects text content between tags" do
+      expect(correct_html("<p>helo <b>wrld</b></p>")["text"]).to eq("<p>hello <b>world</b></p>")
+    end
+
+    it "leaves tag names and attribute values alone" do
+      html = %(<helo class="helo wrld" data-x='testt'>testt</helo>)
+
+      expect(correct_html(html)["text"]).to eq(%(<helo class="helo wrld" data-x='testt'>test</helo>))
+    end
+
+    it "handles > inside quoted attribute values" do
+      expect(correct_html(%(<a title="a>helo">helo</a>))["text"]).to eq(%(<a title="a>helo">hello</a>))
+    end
+
+    it "leaves comments alone" do
+      expect(correct_html("<!-- helo --> helo")["text"]).to eq("<!-- helo --> hello")
+    end
+
+    it "reports byte offsets into the original HTML" do
+      corrections = correct_html("<p>helo</p>")["corrections"]
+
+      expect(corrections).to eq([{"original" => "helo", "corrected" => "hello", "start" => 3, "end" => 7}])
+    end
+
+    it "treats a bare < as text" do
+      expect(correct_html("1 < 2 helo")["text"]).to eq("1 < 2 hello")
+    end
+  end
+
+  describe "entities" do
+    it "passes entities between words through unchanged" do
+      expect(correct_html("helo &amp; wrld &lt;3")["text"]).to eq("hello &amp; world &lt;3")
+    end
+
+    it "decodes entities inside a word before correcting it" do
+      SpellKit.load!(dictionary: write_dict("caf\u00e9\t5000\n"))
+
+      # caf&eacute; is one known word, not "caf" + "eacute"
+      expect(correct_html("caf&eacute;")["text"]).to eq("caf&eacute;")
+      expect(correct_html("caff&eacute;")["text"]).to eq("caf\u00e9")
+    end
+
+    it "re-encodes special characters in replacements" do
+      SpellKit.load!(dictionary: write_dict("AT&T\t5000\n"))
+
+      result = SpellKit.correct_text("<p>AT&amp;TT</p>", mode: "html", tokenizer: "whitespace")
+
+      expect(result["text"]).to eq("<p>AT&amp;T</p>")
+    end
+
+    it "leaves words touching an undecoded entity alone" do
+      expect(correct_html("helo&bogus;wrld helo")["text"]).to eq("helo&bogus;wrld hello")
+    end
+  end
+
+  describe "skip elements" do
+    it "skips the contents of code, pre, and script" do
+      html = "<code>helo</code> <pre>wrld\ntestt</pre> <script>var helo = 1;</script> helo"
+
+      expect(correct_html(html)["text"]).to eq("<code>helo</code> <pre>wrld\ntestt</pre> <script>var helo = 1;</script> hello")
+    end
+
+    it "matches skip elements case-insensitively and with attributes" do
+      expect(correct_html(%(<CODE class="x">helo</Code> helo))["text"]).to eq(%(<CODE class="x">helo</Code> hello))
+    end
+
+    it "skips markup nested inside a skip element" do
+      expect(correct_html("<pre><b>helo</b></pre>helo")["text"]).to eq("<pre><b>helo</b></pre>hello")
+    end
+
+    it "does not treat similarly named elements as skip elements" do
+      expect(correct_html("<preview>helo</preview>")["text"]).to eq("<preview>hello</preview>")
+    end
+  end
+
+  describe "broken markup" do
+    it "passes an unterminated tag and everything after it through" do
+      expect(correct_html(%(helo <a href="x>helo wrld))["text"]).to eq(%(hello <a href="x>helo wrld))
+    end
+
+    it "passes an unclosed skip element through to the end" do
+      expect(correct_html("helo <code>helo wrld")["text"]).to eq("hello <code>helo wrld")
+    end
+
+    it "passes an unterminated comment through" do
+      expect(correct_html("helo <!-- helo")["text"]).to eq("hello <!-- helo")
+    end
+  end
+
+  it "keeps the default text mode unchanged" do
+    expect(SpellKit.correct_text("<b>helo</b>")["text"]).to eq("<b>hello</b>")
+  end
+
+  it "rejects unknown modes" do
+    expect {
+      SpellKit.correct_text("helo", mode: "markdown")
+    }.to raise_error(SpellKit::InvalidArgumentError, /mode must be one of text, html/)
+  end
+
+  def write_dict(content)
+    @dicts ||= []
+    file = Tempfile.new(["html_dict", ".tsv"])
+    file.write(content)
+    file.close
+    @dicts << file
+    file.path
+  end
+
+  after do
+    @dicts&.each(&:unlink)
+  end
+end
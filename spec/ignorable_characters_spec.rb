@@ -0,0 +1,48 @@
+require "tempfile"
+
+RSpec.describe "Zero-width and bidi characters" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) do
+    file = Tempfile.new(["protected", ".txt"])
+    file.write("CDK\u200B10\n")
+    file.close
+    file
+  end
+
+  after do
+    protected_file.unlink
+  end
+
+  context "by default" do
+    before do
+      SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file.path)
+    end
+
+    it "matches tokens with an embedded zero-width space to their clean entry" do
+      expect(SpellKit.correct?("hel\u200Blo")).to be true
+      expect(SpellKit.correct("hel\u200Blo")).to eq("hello")
+      expect(SpellKit.lookup("wor\u200Bld")[:frequency]).to eq(8000)
+    end
+
+    it "matches tokens with bidi marks" do
+      expect(SpellKit.correct?("\u200Eworld")).to be true
+      expect(SpellKit.correct_tokens(["\u200Fhelo\u200E"])).to eq(["hello"])
+      expect(SpellKit.suggestions("\u200Ehelo", 1).first.term).to eq("hello")
+    end
+
+    it "applies the same cleaning to guards" do
+      expect(SpellKit.correct_tokens(["CDK10", "\u200ECDK\u200B10"], details: true).map { |d| d["reason"] })
+        .to eq(%w[protected protected])
+    end
+
+    it "leaves text alone where only ignorables would change" do
+      expect(SpellKit.correct_text("hello\u200B world")["text"]).to eq("hello\u200B world")
+    end
+  end
+
+  it "keeps ignorables with strip_ignorable: false" do
+    SpellKit.load!(dictionary: test_unigrams, strip_ignorable: false)
+
+    expect(SpellKit.correct?("hel\u200Blo")).to be false
+  end
+end
@@ -0,0 +1,45 @@
+RSpec.describe "Incremental healthcheck" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) } }
+
+  it "verifies one batch per call and covers the dictionary over several calls" do
+    first = checker.healthcheck(incremental: true, batch: 8)
+
+    expect(first).to include("healthy" => true, "checked" => 8, "verified" => 8, "total" => 20, "passes" => 0, "failures" => [])
+    expect(first["coverage"]).to eq(40.0)
+
+    second = checker.healthcheck(incremental: true, batch: 8)
+    expect(second).to include("verified" => 16, "passes" => 0)
+
+    third = checker.healthcheck(incremental: true, batch: 8)
+    expect(third).to include("checked" => 4, "verified" => 20, "coverage" => 100.0, "passes" => 1)
+
+    # Wraps around for the next pass, keeping full coverage
+    expect(checker.healthcheck(incremental: true, batch: 8)).to include("checked" => 8, "coverage" => 100.0, "passes" => 1)
+  end
+
+  it "starts over after a reload" do
+    2.times { checker.healthcheck(incremental: true, batch: 8) }
+    checker.load!(dictionary: test_unigrams)
+
+    expect(checker.healthcheck(incremental: true, batch: 8)).to include("verified" => 8, "passes" => 0)
+  end
+
+  it "stops a batch when the time budget runs out, but always makes progress" do
+    result = checker.healthcheck(incremental: true, batch: 20, time_budget: 0)
+
+    expect(result["checked"]).to be_between(1, 20)
+  end
+
+  it "keeps the plain healthcheck unchanged" do
+    expect(checker.healthcheck).to be_nil
+    expect { SpellKit::Checker.new.healthcheck(incremental: true) }.to raise_error(SpellKit::NotLoadedError, /not loaded/)
+  end
+
+  it "validates batch and time_budget" do
+    expect { checker.healthcheck(incremental: true, batch: 0) }
+      .to raise_error(SpellKit::InvalidArgumentError, /batch must be a positive Integer/)
+    expect { checker.healthcheck(incremental: true, time_budget: -1) }
+      .to raise_error(SpellKit::InvalidArgumentError, /time_budget must be a non-negative number/)
+  end
+end
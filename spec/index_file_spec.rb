@@ -0,0 +1,40 @@
+require "tmpdir"
+
+RSpec.describe "index_path" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:dir) { Dir.mktmpdir("spellkit-index") }
+  let(:index) { File.join(dir, "test.spkindex") }
+
+  after { FileUtils.remove_entry(dir) }
+
+  it "loads a saved index with identical suggestions" do
+    SpellKit.load!(dictionary: test_unigrams, edit_distance: 2)
+    expected = %w[helo temprture protien tst zzzzzz].map { |word| SpellKit.suggestions(word, 5) }
+    SpellKit.save_index(index)
+
+    SpellKit.load!(index_path: index, edit_distance: 2)
+
+    expect(%w[helo temprture protien tst zzzzzz].map { |word| SpellKit.suggestions(word, 5) }).to eq(expected)
+    expect(SpellKit.stats["dictionary_size"]).to eq(20)
+    expect(SpellKit.stats["source"]).to include("kind" => "index", "path_or_url" => index)
+  end
+
+  it "rejects an index built with another edit_distance" do
+    SpellKit.load!(dictionary: test_unigrams, edit_distance: 2)
+    SpellKit.save_index(index)
+
+    expect { SpellKit.load!(index_path: index, edit_distance: 1) }
+      .to raise_error(ArgumentError, /built with edit_distance 2, but edit_distance 1 was requested; rebuild it with save_index/)
+    expect(SpellKit.stats["source"]["kind"]).to eq("file")
+  end
+
+  it "rejects a file that isn't an index" do
+    expect { SpellKit.load!(index_path: test_unigrams) }
+      .to raise_error(ArgumentError, /is not a SpellKit index file/)
+  end
+
+  it "raises when the index file is missing" do
+    expect { SpellKit.load!(index_path: File.join(dir, "missing.spkindex")) }
+      .to raise_error(SpellKit::FileNotFoundError, /Index file not found/)
+  end
+end
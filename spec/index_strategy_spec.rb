@@ -0,0 +1,38 @@
+RSpec.describe "Index strategy" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, edit_distance: 2, **options) }
+  end
+
+  it "returns the same suggestions as the full index" do
+    full = checker
+    expanded = checker(index_strategy: "query_expanded")
+    queries = full.sample_words(50, seed: 11).flat_map do |word|
+      [word] + full.perturb(word, distance: 2, count: 3, seed: 5)
+    end
+
+    queries.each do |query|
+      expect(expanded.suggestions(query, 20)).to eq(full.suggestions(query, 20)), "query #{query.inspect}"
+      expect(expanded.correct(query)).to eq(full.correct(query))
+    end
+  end
+
+  it "reports the strategy in stats" do
+    expect(checker.stats["index_strategy"]).to eq("full")
+    expect(checker(index_strategy: "query_expanded").stats["index_strategy"]).to eq("query_expanded")
+  end
+
+  it "falls back to the full index at edit distance 1" do
+    c = SpellKit::Checker.new
+    c.load!(dictionary: test_unigrams, index_strategy: "query_expanded")
+
+    expect(c.stats["index_strategy"]).to eq("full")
+  end
+
+  it "rejects unknown strategies" do
+    expect {
+      checker(index_strategy: "partial")
+    }.to raise_error(SpellKit::InvalidArgumentError, /index_strategy must be one of full, query_expanded/)
+  end
+end
@@ -0,0 +1,17 @@
+RSpec.describe "Deletes map memory" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before { SpellKit.load!(dictionary: test_unigrams, edit_distance: 2) }
+
+  it "reports the id-bucket size next to the string-bucket estimate" do
+    deletes_bytes = SpellKit.stats["deletes_bytes"]
+
+    expect(deletes_bytes["ids"]).to be > 0
+    expect(deletes_bytes["ids"]).to be < deletes_bytes["strings"]
+  end
+
+  it "resolves bucket ids back to words for suggestions" do
+    expect(SpellKit.suggestions("helo", 3).first).to have_attributes(term: "hello", distance: 1)
+    expect(SpellKit.correct("wrld")).to eq("world")
+  end
+end
@@ -0,0 +1,83 @@
+RSpec.describe "Last token policy" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  before do
+    checker.load!(dictionary: test_unigrams, protected_path: protected_file)
+  end
+
+  # "hel" is one edit from "help" (3000) and "hell" (2500), but is usually the start of "hello"
+  describe "correct_tokens" do
+    it "corrects the partial final token under normal" do
+      expect(checker.correct_tokens(%w[buffer hel])).to eq(%w[buffer help])
+      expect(checker.correct_tokens(%w[buffer hel], last_token: "normal")).to eq(%w[buffer help])
+    end
+
+    it "leaves the final token untouched under skip" do
+      expect(checker.correct_tokens(%w[buffr hel], last_token: "skip")).to eq(%w[buffer hel])
+    end
+
+    it "completes the final token under complete" do
+      expect(checker.correct_tokens(%w[buffr hel], last_token: "complete")).to eq(%w[buffer hello])
+      expect(checker.correct_tokens(%w[cell incub], last_token: "complete")).to eq(%w[cell incubation])
+    end
+
+    it "only applies the policy to the final token" do
+      expect(checker.correct_tokens(%w[hel hel], last_token: "skip")).to eq(%w[help hel])
+    end
+
+    it "keeps known final tokens under complete" do
+      expect(checker.correct_tokens(%w[buffer hell], last_token: "complete")).to eq(%w[buffer hell])
+    end
+
+    it "keeps protected final tokens under complete" do
+      checker.load!(dictionary: test_unigrams, protected_patterns: [/^hel$/])
+      expect(checker.correct_tokens(%w[buffer hel], last_token: "complete")).to eq(%w[buffer hel])
+    end
+
+    it "never falls back to edit-distance correction under complete" do
+      expect(checker.correct_tokens(%w[buffer wrld], last_token: "complete")).to eq(%w[buffer wrld])
+    end
+
+    it "reports the policy in details" do
+      completed = checker.correct_tokens(%w[buffer hel], last_token: "complete", details: true).last
+      expect(completed["reason"]).to eq("completed")
+      expect(completed["output"]).to eq("hello")
+      expect(completed["distance"]).to eq(2)
+      expect(completed["freq"]).to eq(10000)
+
+      skipped = checker.correct_tokens(%w[buffer hel], last_token: "skip", details: true).last
+      expect(skipped["reason"]).to eq("skipped")
+      expect(skipped["output"]).to eq("hel")
+    end
+
+    it "rejects unknown policies" do
+      expect {
+        checker.correct_tokens(%w[hel], last_token: "prefix")
+      }.to raise_error(SpellKit::InvalidArgumentError, /last_token must be one of/)
+    end
+  end
+
+  describe "correct_text" do
+    it "treats the final word token before trailing punctuation as the last token" do
+      expect(checker.correct_text("buffer hel?")["text"]).to eq("buffer help?")
+      expect(checker.correct_text("buffer hel?", last_token: "skip")["text"]).to eq("buffer hel?")
+      expect(checker.correct_text("buffer hel? ", last_token: "complete")["text"]).to eq("buffer hello? ")
+    end
+
+    it "ignores trailing numbers when finding the last word token" do
+      result = checker.correct_text("cell incub 42", last_token: "complete")
+      expect(result["text"]).to eq("cell incubation 42")
+      expect(result["corrections"]).to eq([
+        {"original" => "incub", "corrected" => "incubation", "start" => 5, "end" => 10}
+      ])
+    end
+
+    it "is not supported in html mode" do
+      expect {
+        checker.correct_text("<p>hel</p>", mode: "html", last_token: "skip")
+      }.to raise_error(SpellKit::InvalidArgumentError, /only supported in text mode/)
+    end
+  end
+end
@@ -0,0 +1,80 @@
+RSpec.describe "Latency histograms" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  it "starts empty after load" do
+    latency = SpellKit.stats["latency"]
+
+    expect(latency["bucket_bounds_us"].first(4)).to eq([1, 2, 4, 8])
+    %w[suggest correct_tokens].each do |kind|
+      expect(latency[kind]["count"]).to eq(0)
+      expect(latency[kind]["buckets"].size).to eq(latency["bucket_bounds_us"].size + 1)
+      expect(latency[kind]).to include("p50_us" => nil, "p95_us" => nil, "p99_us" => nil)
+    end
+  end
+
+  it "counts every suggestion call and corrected token" do
+    200.times { SpellKit.suggestions("helo") }
+    SpellKit.suggest_many(%w[wrld tset], 3)
+    50.times { SpellKit.correct_tokens(%w[helo wrld tset]) }
+
+    latency = SpellKit.stats["latency"]
+
+    expect(latency["suggest"]["count"]).to eq(202)
+    expect(latency["suggest"]["buckets"].sum).to eq(202)
+    expect(latency["correct_tokens"]["count"]).to eq(150)
+    expect(latency["correct_tokens"]["buckets"].sum).to eq(150)
+  end
+
+  it "reports ordered percentiles within the recorded buckets" do
+    300.times { |i| SpellKit.suggestions(i.even? ? "helo" : "temperatre") }
+
+    suggest = SpellKit.stats["latency"]["suggest"]
+    bounds = SpellKit.stats["latency"]["bucket_bounds_us"]
+    used = suggest["buckets"].each_index.select { |i| suggest["buckets"][i] > 0 }
+    lowest = used.first.zero? ? 0 : bounds[used.first - 1]
+    highest = bounds[used.last] || bounds.last
+
+    expect(suggest["p50_us"]).to be <= suggest["p95_us"]
+    expect(suggest["p95_us"]).to be <= suggest["p99_us"]
+    [suggest["p50_us"], suggest["p99_us"]].each do |percentile|
+      expect(percentile).to be > lowest
+      expect(percentile).to be <= highest
+    end
+  end
+
+  it "clears histograms and guard hits with reset_stats!" do
+    SpellKit.load!(dictionary: test_unigrams, protected_prefixes: ["CDK"])
+    SpellKit.correct_tokens(%w[CDK10 helo])
+    SpellKit.suggestions("helo")
+
+    expect(SpellKit.reset_stats!).to be_a(SpellKit::Checker)
+
+    stats = SpellKit.stats
+    expect(stats["latency"]["suggest"]["count"]).to eq(0)
+    expect(stats["latency"]["correct_tokens"]["count"]).to eq(0)
+    expect(stats["guard_hits"]["prefix"]).to eq(0)
+  end
+
+  it "starts over on reload" do
+    SpellKit.suggestions("helo")
+    SpellKit.load!(dictionary: test_unigrams)
+
+    expect(SpellKit.stats["latency"]["suggest"]["count"]).to eq(0)
+  end
+
+  it "can be disabled at load" do
+    SpellKit.load!(dictionary: test_unigrams, latency_stats: false)
+    SpellKit.suggestions("helo")
+
+    expect(SpellKit.stats["latency"]).to be_nil
+    expect { SpellKit.reset_stats! }.not_to raise_error
+  end
+
+  it "makes reset_stats! a no-op before load" do
+    expect { SpellKit::Checker.new.reset_stats! }.not_to raise_error
+  end
+end
@@ -0,0 +1,84 @@
+require "tempfile"
+
+RSpec.describe "Load progress reporting" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def load_with_progress(dictionary)
+    events = []
+    SpellKit.load!(dictionary: dictionary, progress: ->(phase, processed, total) { events << [phase, processed, total] })
+    events
+  end
+
+  it "reports every phase in order" do
+    events = load_with_progress(test_unigrams)
+
+    expect(events.map(&:first).uniq).to eq(%w[parsing indexing finalizing])
+  end
+
+  it "reports monotonically increasing progress within each phase" do
+    dict = Tempfile.new(["large", ".tsv"])
+    5000.times { |i| dict.puts("word#{i}\t#{i + 1}") }
+    dict.close
+
+    events = load_with_progress(dict.path)
+
+    events.group_by(&:first).each do |phase, phase_events|
+      processed = phase_events.map { |e| e[1] }
+      expect(processed).to eq(processed.sort), "#{phase} progress went backwards"
+      expect(processed.last).to eq(phase_events.last[2]), "#{phase} did not finish at its total"
+    end
+
+    indexing_total = events.find { |e| e.first == "indexing" }[2]
+    expect(indexing_total).to eq(5000)
+  ensure
+    dict&.unlink
+  end
+
+  it "reports parsing progress in bytes of the dictionary file" do
+    events = load_with_progress(test_unigrams)
+
+    parsing_total = events.find { |e| e.first == "parsing" }[2]
+    expect(parsing_total).to eq(File.size(test_unigrams))
+  end
+
+  it "accepts any object responding to call" do
+    collector = Class.new do
+      attr_reader :phases
+
+      def initialize
+        @phases = []
+      end
+
+      def call(phase, _processed, _total)
+        @phases << phase
+      end
+    end.new
+
+    SpellKit.load!(dictionary: test_unigrams, progress: collector)
+
+    expect(collector.phases).to include("parsing", "indexing", "finalizing")
+  end
+
+  it "rejects progress values that are not callable" do
+    expect {
+      SpellKit.load!(dictionary: test_unigrams, progress: "tick")
+    }.to raise_error(SpellKit::InvalidArgumentError, /progress must respond to #call/)
+  end
+
+  it "aborts the load and keeps the previous dictionary when the callback raises" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: test_unigrams)
+
+    temp = Tempfile.new(["other", ".tsv"])
+    temp.write("help\t50000\n")
+    temp.close
+
+    expect {
+      checker.load!(dictionary: temp.path, progress: ->(phase, _p, _t) { raise "stop" if phase == "indexing" })
+    }.to raise_error(RuntimeError, "stop")
+
+    expect(checker.stats["dictionary_size"]).to eq(20)
+  ensure
+    temp&.unlink
+  end
+end
@@ -0,0 +1,49 @@
+require "tempfile"
+
+RSpec.describe "load! report" do
+  let(:dictionary) do
+    file = Tempfile.new(["report", ".tsv"])
+    file.write(<<~TSV)
+      # exported 2024-01-01
+      hello\t10000
+
+      world\tmany
+      help\t3000
+        # trailing note
+      Hello\t50
+      world\t8000
+    TSV
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  it "reports what was read, loaded, skipped, and merged" do
+    report = SpellKit::Checker.new.load!(dictionary: dictionary.path)
+
+    expect(report).to include(
+      "lines_read" => 8,
+      "entries_loaded" => 3,
+      "lines_skipped" => 4,
+      "duplicates_merged" => 1
+    )
+    expect(report["load_time_ms"]).to be_a(Float).and be > 0
+    expect(report["index_entries"]).to be > 3
+  end
+
+  it "is returned by SpellKit.load! and reload!" do
+    expect(SpellKit.load!(dictionary: dictionary.path)["entries_loaded"]).to eq(3)
+    expect(SpellKit.reload!["lines_read"]).to eq(8)
+    expect(SpellKit.correct("wrld")).to eq("world")
+  end
+
+  it "raises on the first malformed line with strict: true, passing over comments and blank lines" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: File.expand_path("fixtures/test_unigrams.tsv", __dir__))
+
+    expect { checker.load!(dictionary: dictionary.path, strict: true) }
+      .to raise_error(SpellKit::DictionaryParseError, /Dictionary line 4 is malformed: frequency "many" is not a non-negative integer/)
+    expect(checker.stats["dictionary_size"]).to eq(20)
+  end
+end
@@ -0,0 +1,89 @@
+require "tempfile"
+
+RSpec.describe "Overlong dictionary lines" do
+  let(:huge_line_dict) do
+    dict = Tempfile.new(["huge_line", ".tsv"])
+    dict.write("hello\t10000\n")
+    # A concatenated blob with no newlines, split into two "fields" by a stray tab
+    dict.write("a" * 25_000_000)
+    dict.write("\t")
+    dict.write("b" * 25_000_000)
+    dict.write("\nworld\t8000\n")
+    dict.close
+    dict
+  end
+
+  let(:long_word_dict) do
+    dict = Tempfile.new(["long_word", ".tsv"])
+    dict.write("hello\t10000\n#{"x" * 150}\t500\nworld\t8000\n")
+    dict.close
+    dict
+  end
+
+  after do
+    huge_line_dict.unlink
+    long_word_dict.unlink
+  end
+
+  it "skips a huge line quickly and warns" do
+    started = Process.clock_gettime(Process::CLOCK_MONOTONIC)
+
+    expect {
+      SpellKit.load!(dictionary: huge_line_dict.path)
+    }.to output(/skipped 1 dictionary line\(s\) longer than max_line_length \(1024 bytes\)/).to_stderr
+
+    expect(Process.clock_gettime(Process::CLOCK_MONOTONIC) - started).to be < 5
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_long_lines" => 1, "skipped_malformed" => 0)
+    expect(SpellKit.correct("helo")).to eq("hello")
+    expect(SpellKit.correct("wrld")).to eq("world")
+  end
+
+  it "skips terms longer than max_word_length" do
+    expect {
+      SpellKit.load!(dictionary: long_word_dict.path)
+    }.to output(/1 term\(s\) longer than max_word_length \(100 characters\)/).to_stderr
+
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_long_words" => 1)
+  end
+
+  it "accepts custom caps" do
+    expect {
+      SpellKit.load!(dictionary: long_word_dict.path, max_word_length: 200)
+    }.not_to output.to_stderr
+
+    expect(SpellKit.stats).to include("dictionary_size" => 3, "skipped_long_words" => 0)
+  end
+
+  it "does not warn for a clean dictionary" do
+    expect {
+      SpellKit.load!(dictionary: File.expand_path("fixtures/test_unigrams.tsv", __dir__))
+    }.not_to output.to_stderr
+
+    expect(SpellKit.stats).to include("skipped_long_lines" => 0, "skipped_long_words" => 0)
+  end
+
+  it "raises in strict mode and keeps the previous dictionary" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: long_word_dict.path, max_word_length: 200)
+
+    expect {
+      checker.load!(dictionary: huge_line_dict.path, strict: true)
+    }.to raise_error(SpellKit::DictionaryParseError, /Dictionary line 2 is longer than max_line_length/)
+
+    expect {
+      checker.load!(dictionary: long_word_dict.path, strict: true)
+    }.to raise_error(SpellKit::DictionaryParseError, /Dictionary term on line 2 is longer than max_word_length/)
+
+    expect(checker.stats["dictionary_size"]).to eq(3)
+  end
+
+  it "validates the caps" do
+    expect {
+      SpellKit.load!(dictionary: long_word_dict.path, max_line_length: 0)
+    }.to raise_error(SpellKit::InvalidArgumentError, /max_line_length must be a positive Integer/)
+
+    expect {
+      SpellKit.load!(dictionary: long_word_dict.path, max_word_length: "100")
+    }.to raise_error(SpellKit::InvalidArgumentError, /max_word_length must be a positive Integer/)
+  end
+end
@@ -0,0 +1,66 @@
+RSpec.describe "Dictionary lookup" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  it "returns every field for a known word" do
+    expect(SpellKit.lookup("world")).to eq(term: "world", canonical: "world", frequency: 8000, rank: 2, metadata: nil)
+  end
+
+  it "reports the term as looked up next to its canonical form" do
+    SpellKit.load!(dictionary: {"iPhone" => 900, "hello" => 10000})
+
+    expect(SpellKit.lookup("iphone")).to include(term: "iphone", canonical: "iPhone", frequency: 900)
+  end
+
+  it "ranks the most frequent word first" do
+    expect(SpellKit.lookup("hello")[:rank]).to eq(1)
+  end
+
+  it "returns nil for unknown words" do
+    expect(SpellKit.lookup("wrold")).to be_nil
+  end
+
+  it "finds the same entry for casing variants" do
+    expect(SpellKit.lookup("HELLO").except(:term)).to eq(SpellKit.lookup("hello").except(:term))
+    expect(SpellKit.lookup("Hello").except(:term)).to eq(SpellKit.lookup("hello").except(:term))
+  end
+
+  describe "frequency" do
+    it "returns the count of a known word, normalized like correct?" do
+      expect(SpellKit.frequency("world")).to eq(8000)
+      expect(SpellKit.frequency("WORLD")).to eq(8000)
+      expect(SpellKit.frequency("wrold")).to be_nil
+    end
+
+    it "follows case_sensitive:" do
+      checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: {"WAS" => 500, "was" => 90000}, case_sensitive: true) }
+
+      expect(checker.frequency("WAS")).to eq(500)
+      expect(checker.frequency("was")).to eq(90000)
+      expect(checker.frequency("Was")).to be_nil
+    end
+
+    it "validates input" do
+      expect { SpellKit.frequency(nil) }.to raise_error(SpellKit::InvalidArgumentError)
+    end
+  end
+
+  it "ignores protected terms" do
+    SpellKit.load!(dictionary: test_unigrams, protected_prefixes: ["hel"], protected_as_known: true)
+
+    expect(SpellKit.lookup("helper")).to be_nil
+    expect(SpellKit.lookup("help")[:frequency]).to eq(3000)
+  end
+
+  it "is available on snapshots" do
+    expect(SpellKit.snapshot.lookup("test")[:frequency]).to eq(5000)
+    expect(SpellKit.snapshot.frequency("test")).to eq(5000)
+  end
+
+  it "validates input" do
+    expect { SpellKit.lookup("") }.to raise_error(SpellKit::InvalidArgumentError)
+  end
+end
@@ -0,0 +1,53 @@
+RSpec.describe "max_suggestions:" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  # Five rare user words outrank "cat" at distance 1 from "bat", and all of them fall below
+  # min_suggestion_frequency
+  def crowded(**options)
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: {"cat" => 5000, "hello" => 10000}, **options)
+      %w[bad bag ban bar bay].each { |word| c.add_word!(word, 2) }
+    end
+  end
+
+  it "sets how many suggestions a call gets by default" do
+    checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, max_suggestions: 2) }
+
+    expect(checker.stats["max_suggestions"]).to eq(2)
+    expect(checker.suggestions("helo").map(&:term)).to eq(%w[hello help])
+    expect(checker.suggestions("helo", 3).length).to eq(3)
+    expect(checker.suggest_many(%w[helo hel]).map(&:length)).to eq([2, 2])
+    default = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }
+    expect(default.suggestions("helo", nil)).to eq(default.suggestions("helo", 5))
+  end
+
+  it "returns no suggestions for a max of 0" do
+    checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }
+
+    expect(checker.suggestions("helo", 0)).to eq([])
+    expect(checker.suggest_many(%w[helo wrld], 0)).to eq([[], []])
+  end
+
+  it "widens the candidate pool corrections try" do
+    expect(crowded.correct("bat")).to eq("bat")
+    expect(crowded.correct_tokens(%w[bat])).to eq(%w[bat])
+
+    expect(crowded(max_suggestions: 6).correct("bat")).to eq("cat")
+    expect(crowded(max_suggestions: 6).correct_tokens(%w[bat])).to eq(%w[cat])
+  end
+
+  it "accepts a per-call pool" do
+    checker = crowded
+
+    expect(checker.correct("bat", max_suggestions: 6)).to eq("cat")
+    expect(checker.correct_tokens(%w[bat hellp], max_suggestions: 6)).to eq(%w[cat hello])
+    expect(checker.correct_tokens_with_info(%w[bat], max_suggestions: 6).first).to include(corrected: "cat")
+    expect(checker.correct("bat")).to eq("bat")
+  end
+
+  it "validates the option" do
+    expect { crowded(max_suggestions: 0) }.to raise_error(SpellKit::InvalidArgumentError, /max_suggestions must be a positive Integer/)
+    expect { crowded.correct("bat", max_suggestions: 0) }.to raise_error(SpellKit::InvalidArgumentError, /max_suggestions/)
+    expect { crowded.correct_tokens(%w[bat], max_suggestions: "6") }.to raise_error(SpellKit::InvalidArgumentError, /max_suggestions/)
+  end
+end
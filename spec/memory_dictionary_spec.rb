@@ -0,0 +1,34 @@
+RSpec.describe "In-memory dictionaries" do
+  it "loads a Hash of word => frequency" do
+    SpellKit.load!(dictionary: {"hello" => 1000, "help" => 750, "world" => 500})
+
+    expect(SpellKit.stats).to include("dictionary_size" => 3)
+    expect(SpellKit.stats["source"]).to include("kind" => "memory", "sha256" => nil, "bytes" => 0)
+    expect(SpellKit.correct("wrld")).to eq("world")
+    expect(SpellKit.suggestions("helo", 2).map { |s| s.term }).to eq(%w[hello help])
+  end
+
+  it "loads an Array of pairs, checked like dictionary lines" do
+    SpellKit.load!(dictionary: [["hello", 1000], [:world, 500], ["New York", 90], ["hello", 10]])
+
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_multiword" => 1, "skipped_duplicates" => 1)
+    expect(SpellKit.suggestions("helo", 1).first).to have_attributes(term: "hello", frequency: 1000)
+  end
+
+  it "reloads the same pairs" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: {"hello" => 1000})
+    checker.reload!
+
+    expect(checker.correct("helo")).to eq("hello")
+  end
+
+  it "rejects malformed pairs" do
+    expect { SpellKit.load!(dictionary: {"hello" => "1000"}) }
+      .to raise_error(SpellKit::InvalidArgumentError, /dictionary pairs must be a String word and a non-negative Integer frequency/)
+    expect { SpellKit.load!(dictionary: [["hello"]]) }
+      .to raise_error(SpellKit::InvalidArgumentError, /dictionary pairs must be/)
+    expect { SpellKit.load!(dictionary: {"hello" => 1}, index_path: "words.spkindex") }
+      .to raise_error(SpellKit::InvalidArgumentError, /either index_path or an in-memory dictionary/)
+  end
+end
@@ -0,0 +1,25 @@
+RSpec.describe "Memory stats" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def words(count)
+    ("aaa".."zzz").first(count).to_h { |prefix| ["#{prefix}word", 100] }
+  end
+
+  it "reports the estimated size, delete entries, and average bucket size" do
+    report = SpellKit.load!(dictionary: test_unigrams)
+    stats = SpellKit.stats
+
+    expect(stats["estimated_bytes"]).to be > stats["deletes_bytes"]["ids"]
+    expect(stats["estimated_bytes"]).to eq(stats["generations"].last["estimated_bytes"])
+    expect(stats["delete_entries"]).to be >= report["index_entries"]
+    expect(stats["average_bucket_size"]).to be_within(1e-9).of(stats["delete_entries"].fdiv(report["index_entries"]))
+  end
+
+  it "grows with the dictionary" do
+    small = SpellKit::Checker.new.tap { |c| c.load!(dictionary: words(100)) }
+    large = SpellKit::Checker.new.tap { |c| c.load!(dictionary: words(2000)) }
+
+    expect(large.stats["estimated_bytes"]).to be > 10 * small.stats["estimated_bytes"]
+    expect(large.stats["delete_entries"]).to be > 10 * small.stats["delete_entries"]
+  end
+end
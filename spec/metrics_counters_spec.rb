@@ -0,0 +1,49 @@
+RSpec.describe "Correction metrics" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) } }
+
+  it "counts calls and what happened to each token" do
+    checker.protect!(["helo"])
+    checker.suggestions("wrld")
+    checker.suggest_many(%w[wrld tset])
+    checker.correct("wrld")
+    checker.correct_tokens(%w[helo wrld hello xyzzyq])
+
+    expect(checker.stats["metrics"]).to eq(
+      "suggest" => 3, "correct" => 1, "correct_tokens" => 1,
+      "tokens_processed" => 5, "tokens_changed" => 2, "tokens_guarded" => 1
+    )
+  end
+
+  it "resets with reset_metrics! only" do
+    checker.correct_tokens(%w[wrld])
+    checker.reset_stats!
+    expect(checker.stats["metrics"]["tokens_changed"]).to eq(1)
+
+    expect(checker.reset_metrics!).to be(checker)
+    expect(checker.stats["metrics"].values).to all(eq(0))
+  end
+
+  it "carries over across load! and counts snapshot calls" do
+    checker.correct("wrld")
+    checker.load!(dictionary: test_unigrams)
+    checker.snapshot.correct("wrld")
+
+    expect(checker.stats["metrics"]).to include("correct" => 2, "tokens_changed" => 2)
+  end
+
+  it "counts every call from concurrent threads" do
+    8.times.map do
+      Thread.new do
+        50.times do
+          checker.correct_tokens(%w[wrld hello], threads: 2)
+          checker.suggestions("wrld")
+        end
+      end
+    end.each(&:join)
+
+    expect(checker.stats["metrics"]).to include(
+      "suggest" => 400, "correct_tokens" => 400, "tokens_processed" => 800, "tokens_changed" => 400
+    )
+  end
+end
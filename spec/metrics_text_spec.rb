@@ -0,0 +1,68 @@
+RSpec.describe "Prometheus metrics" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  # {[name, labels] => value} and {name => type} from exposition text
+  def parse(text)
+    samples = {}
+    types = {}
+    text.each_line do |line|
+      if (match = line.match(/\A# TYPE (\S+) (\w+)\n\z/))
+        types[match[1]] = match[2]
+      elsif (match = line.match(/\A([a-zA-Z_:][a-zA-Z0-9_:]*)(?:\{(.*)\})? (\S+)\n\z/))
+        labels = match[2].to_s.scan(/(\w+)="((?:[^"\\]|\\.)*)"/).to_h
+        samples[[match[1], labels]] = Float(match[3])
+      elsif !line.start_with?("# HELP ")
+        raise "unparsable line: #{line.inspect}"
+      end
+    end
+    [samples, types]
+  end
+
+  before do
+    checker.load!(dictionary: test_unigrams, protected_prefixes: ["hel"])
+    checker.suggestions("wrld")
+    checker.suggestions("tset")
+    checker.correct_tokens(%w[helo wrld])
+  end
+
+  it "reports gauges, counters, and histograms after a workload" do
+    samples, types = parse(checker.metrics_text)
+
+    expect(types).to include(
+      "spellkit_dictionary_size" => "gauge",
+      "spellkit_generation" => "gauge",
+      "spellkit_protected_total" => "counter",
+      "spellkit_retired_generations_total" => "counter",
+      "spellkit_suggest_seconds" => "histogram",
+      "spellkit_correct_tokens_seconds" => "histogram"
+    )
+    expect(samples[["spellkit_loaded", {}]]).to eq(1)
+    expect(samples[["spellkit_dictionary_size", {}]]).to eq(20)
+    expect(samples[["spellkit_generation", {}]]).to eq(1)
+    expect(samples[["spellkit_protected_total", {"guard" => "prefix"}]]).to eq(1)
+    expect(samples[["spellkit_protected_total", {"guard" => "set"}]]).to eq(0)
+    expect(samples[["spellkit_suggest_seconds_count", {}]]).to eq(2)
+    expect(samples[["spellkit_suggest_seconds_bucket", {"le" => "+Inf"}]]).to eq(2)
+    expect(samples[["spellkit_correct_tokens_seconds_count", {}]]).to eq(2)
+  end
+
+  it "adds the checker label and prefix to every sample" do
+    samples, = parse(checker.metrics_text(prefix: "app_spell", checker: "medical"))
+
+    expect(samples.keys.map(&:first)).to all(start_with("app_spell_"))
+    expect(samples.keys.map(&:last)).to all(include("checker" => "medical"))
+  end
+
+  it "rejects invalid prefixes" do
+    expect { checker.metrics_text(prefix: "spell-kit") }
+      .to raise_error(SpellKit::InvalidArgumentError, /prefix must be a valid Prometheus metric name/)
+  end
+
+  it "only reports load state before load!" do
+    samples, = parse(SpellKit::Checker.new.metrics_text)
+
+    expect(samples[["spellkit_loaded", {}]]).to eq(0)
+    expect(samples.keys.map(&:first)).not_to include("spellkit_dictionary_size")
+  end
+end
@@ -50,8 +50,8 @@ RSpec.describe "Multiple Instances" do
       species_suggestions = species_checker.suggestions("mose", 3)
       symbols_suggestions = symbols_checker.suggestions("brca", 3)
 
-      expect(species_suggestions.map { |s| s["term"] }).to include("mouse")
-      expect(symbols_suggestions.map { |s| s["term"] }).to include("BRCA1")  # Returns canonical form
+      expect(species_suggestions.map { |s| s.term }).to include("mouse")
+      expect(symbols_suggestions.map { |s| s.term }).to include("BRCA1")  # Returns canonical form
     end
 
     it "maintains separate edit distances per instance" do
@@ -110,13 +110,13 @@ RSpec.describe "Multiple Instances" do
 
       threads << Thread.new do
         100.times do
-          results << checker1.suggestions("helo", 1).first["term"]
+          results << checker1.suggestions("helo", 1).first.term
         end
       end
 
       threads << Thread.new do
         100.times do
-          results << checker2.suggestions("mose", 1).first["term"]
+          results << checker2.suggestions("mose", 1).first.term
         end
       end
 
@@ -137,7 +137,7 @@ RSpec.describe "Multiple Instances" do
       10.times do
         threads << Thread.new do
           50.times do
-            results << checker.suggestions("helo", 1).first["term"]
+            results << checker.suggestions("helo", 1).first.term
           end
         end
       end
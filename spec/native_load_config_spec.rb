@@ -0,0 +1,44 @@
+# The native load! underneath Checker#load! reads a config hash; hand-built configs may use
+# Symbol or String keys
+RSpec.describe "Native load! config keys" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  it "accepts Symbol keys" do
+    checker._rust_load!({dictionary_path: test_unigrams, edit_distance: 2, frequency_threshold: 1.0})
+
+    expect(checker.stats["edit_distance"]).to eq(2)
+    expect(checker.correct("hlelo")).to eq("hello")
+  end
+
+  it "accepts a mix of Symbol and String keys" do
+    checker._rust_load!({
+      :dictionary_path => test_unigrams,
+      "protected_patterns" => [{"source" => "^hel"}],
+      :edit_distance => 1
+    })
+
+    expect(checker.correct("helo")).to eq("helo")
+    expect(checker.correct("wrld")).to eq("world")
+  end
+
+  it "prefers the Symbol key when both are present" do
+    checker._rust_load!({:dictionary_path => test_unigrams, "dictionary_path" => "/nonexistent.tsv", :edit_distance => 2,
+                         "edit_distance" => 1})
+
+    expect(checker.stats["edit_distance"]).to eq(2)
+  end
+
+  it "takes word and frequency pairs in place of a path, but not both" do
+    checker._rust_load!({dictionary: [["hello", 1000], ["world", 800]]})
+    expect(checker.correct("wrld")).to eq("world")
+
+    expect { checker._rust_load!({dictionary_path: test_unigrams, dictionary: {"hello" => 1}}) }
+      .to raise_error(ArgumentError, /either dictionary_path or dictionary/)
+  end
+
+  it "names both key forms when the dictionary path is missing" do
+    expect { checker._rust_load!({edit_distance: 1}) }
+      .to raise_error(ArgumentError, /dictionary_path is required \(as :dictionary_path or "dictionary_path"\)/)
+  end
+end
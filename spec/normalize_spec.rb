@@ -0,0 +1,83 @@
+require "tempfile"
+require "tmpdir"
+
+RSpec.describe "normalize:" do
+  let(:dictionary) do
+    file = Tempfile.new(["normalize", ".tsv"])
+    file.write("hello\t10000\nNASA\t5000\ne-mail\t3000\ncafé\t2000\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary.path, **options) }
+  end
+
+  it "keeps the previous normalization by default" do
+    c = checker
+
+    expect(c.correct_tokens(%w[HELLO nasa ＨＥＬＬＯ], output: "normalized")).to eq(%w[hello nasa hello])
+    expect(c.stats["normalize"]).to eq(
+      "form" => "nfkd", "lowercase" => true, "strip_accents" => false, "strip_punctuation" => false
+    )
+    expect(c.behavior_digest).to eq(checker(normalize: {form: :nfkd, lowercase: true}).behavior_digest)
+  end
+
+  it "keeps case significant with lowercase: false" do
+    c = checker(normalize: {form: :nfc, lowercase: false})
+
+    expect(c.correct?("NASA")).to be(true)
+    expect(c.correct?("nasa")).to be(false)
+    expect(c.correct_tokens(%W[cafe\u0301], output: "normalized")).to eq(%W[caf\u00e9])
+    expect(c.stats["normalize"]).to include("form" => "nfc", "lowercase" => false)
+  end
+
+  it "ignores punctuation inside words with strip_punctuation: true" do
+    c = checker(normalize: {strip_punctuation: true})
+
+    expect(c.correct("email")).to eq("e-mail")
+    expect(c.correct_tokens(%w[email], details: true).first).to include("reason" => "exact", "distance" => 0)
+  end
+
+  it "treats strip_accents: as shorthand" do
+    shorthand = checker(strip_accents: true)
+
+    expect(shorthand.correct("cafe")).to eq("café")
+    expect(shorthand.stats["normalize"]).to include("strip_accents" => true)
+    expect(shorthand.behavior_digest).to eq(checker(normalize: {strip_accents: true}).behavior_digest)
+  end
+
+  it "keys named sets with the loaded normalization" do
+    c = checker(normalize: {strip_punctuation: true})
+    c.define_set(:mail, %w[e-mail])
+
+    expect(c.correct("emal", within: :mail)).to eq("e-mail")
+    expect(c.correct("emal", within: %w[e-mail])).to eq("e-mail")
+  end
+
+  it "must match the normalization a saved index was built with" do
+    Dir.mktmpdir do |dir|
+      path = File.join(dir, "normalize.idx")
+      checker(normalize: {lowercase: false}).save_index(path)
+
+      loaded = SpellKit::Checker.new.tap { |c| c.load!(index_path: path, normalize: {lowercase: false}) }
+      expect(loaded.correct?("NASA")).to be(true)
+      expect { SpellKit::Checker.new.load!(index_path: path) }
+        .to raise_error(ArgumentError, /built with lowercase: false, but lowercase: true was requested/)
+    end
+  end
+
+  it "rejects unknown keys and values" do
+    expect { checker(normalize: {form: :nfx}) }
+      .to raise_error(SpellKit::InvalidArgumentError, /normalize form must be one of nfc, nfd, nfkc, nfkd/)
+    expect { checker(normalize: {casefold: true}) }
+      .to raise_error(SpellKit::InvalidArgumentError, /unknown normalize keys: casefold/)
+    expect { checker(normalize: {lowercase: "yes"}) }
+      .to raise_error(SpellKit::InvalidArgumentError, /normalize lowercase must be true or false/)
+    expect { checker(normalize: :nfc) }.to raise_error(SpellKit::InvalidArgumentError, /normalize must be a Hash/)
+    expect { checker(strip_accents: true, normalize: {strip_accents: false}) }
+      .to raise_error(SpellKit::InvalidArgumentError, /conflicts/)
+  end
+end
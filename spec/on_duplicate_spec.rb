@@ -0,0 +1,50 @@
+require "tempfile"
+
+RSpec.describe "on_duplicate" do
+  let(:dictionary) do
+    file = Tempfile.new(["duplicates", ".tsv"])
+    file.write("hello\t100\nHello\t300\nworld\t50\nhello\t200\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  def hello
+    SpellKit.suggestions("hello", 1).first
+  end
+
+  it "keeps the larger count of a repeated word by default" do
+    SpellKit.load!(dictionary: dictionary.path)
+
+    expect(hello).to have_attributes(term: "Hello", frequency: 300)
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_duplicates" => 2)
+  end
+
+  it "adds the counts with on_duplicate: :sum" do
+    SpellKit.load!(dictionary: dictionary.path, on_duplicate: :sum)
+
+    expect(hello).to have_attributes(term: "Hello", frequency: 600)
+    expect(SpellKit.stats["skipped_duplicates"]).to eq(2)
+  end
+
+  it "raises naming the offending term with on_duplicate: :error, keeping the previous dictionary" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: File.expand_path("fixtures/test_unigrams.tsv", __dir__))
+
+    expect { checker.load!(dictionary: dictionary.path, on_duplicate: :error) }
+      .to raise_error(SpellKit::DictionaryParseError, /Dictionary term "Hello" duplicates "hello", loaded earlier/)
+    expect(checker.correct("protien")).to eq("protein")
+  end
+
+  it "applies to in-memory pairs too" do
+    SpellKit.load!(dictionary: [["hello", 100], ["HELLO", 40]], on_duplicate: :sum)
+
+    expect(hello).to have_attributes(term: "hello", frequency: 140)
+  end
+
+  it "validates the option" do
+    expect { SpellKit.load!(dictionary: dictionary.path, on_duplicate: :last) }
+      .to raise_error(SpellKit::InvalidArgumentError, /on_duplicate must be one of sum, max, error/)
+  end
+end
@@ -0,0 +1,70 @@
+RSpec.describe "Option validation" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  it "rejects a misspelled load! option with a suggestion" do
+    expect { SpellKit.load!(dictionary: test_unigrams, frequency_treshold: 5) }
+      .to raise_error(SpellKit::InvalidArgumentError,
+        /unknown option for load!: frequency_treshold \(did you mean frequency_threshold\?\)\. Accepted options: .*edit_distance/)
+  end
+
+  it "rejects misspelled per-call options" do
+    expect { SpellKit.correct_tokens(%w[helo], detials: true) }
+      .to raise_error(SpellKit::InvalidArgumentError, /for correct_tokens: detials \(did you mean details\?\)/)
+    expect { SpellKit.suggestions("helo", 3, exclude_exct: true) }
+      .to raise_error(SpellKit::InvalidArgumentError, /did you mean exclude_exact\?/)
+    expect { SpellKit.correct_text("helo", mod: "html") }
+      .to raise_error(SpellKit::InvalidArgumentError, /did you mean mode\?/)
+  end
+
+  it "lists every unknown option, suggesting only close ones" do
+    expect { SpellKit.correct("helo", withn: nil, colour: true) }
+      .to raise_error(SpellKit::InvalidArgumentError, /unknown options for correct: withn \(did you mean within\?\), colour\./)
+  end
+
+  it "ignores unknown options with validate_options: false" do
+    expect(SpellKit.correct_tokens(%w[helo], detials: true, validate_options: false)).to eq(%w[hello])
+    expect { SpellKit.load!(dictionary: test_unigrams, frequency_treshold: 5, validate_options: false) }.not_to raise_error
+  end
+
+  it "validates options on snapshots" do
+    expect { SpellKit.snapshot.correct_tokens(%w[helo], detials: true) }
+      .to raise_error(SpellKit::InvalidArgumentError, /did you mean details\?/)
+  end
+
+  it "rejects mistyped values in the native load! too" do
+    native = ->(config) { SpellKit::Checker.new.send(:_rust_load!, {"dictionary_path" => test_unigrams}.merge(config)) }
+
+    expect { native.call("edit_distance" => "1") }.to raise_error(TypeError)
+    expect { native.call("edit_distance" => nil) }.to raise_error(TypeError)
+    expect { native.call("frequency_threshold" => "0") }.to raise_error(TypeError)
+    expect { native.call("confident_frequency" => "1000") }.to raise_error(TypeError)
+    expect { native.call("edit_distance" => 3) }.to raise_error(ArgumentError, "edit_distance must be 1 or 2, got: 3")
+  end
+
+  it "accepts every configuration option" do
+    config = SpellKit::Configuration.new
+    config.dictionary = test_unigrams
+
+    expect { SpellKit::Checker.new.load!(**config.to_h) }.not_to raise_error
+  end
+
+  it "accepts every documented option of the per-call methods" do
+    checker = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }
+
+    expect {
+      checker.suggestions("helo", 3, exclude_exact: true, structural_match: true, truncate_display: 10)
+      checker.correct("helo", resolve_aliases: false, within: nil)
+      checker.correct_tokens(%w[helo], output: "canonical", resolve_aliases: false, details: false,
+        last_token: "normal", on_error: "raise", unknown: "keep", expand: false, alignment: false, dry_run: false)
+      checker.correct_text("helo", tokenizer: nil, mode: "text", last_token: "normal", cjk: "passthrough", dry_run: false)
+      checker.correct_lines("helo", separator: "\n", mode: "token")
+      checker.perturb("hello", distance: 1, count: 2, seed: 1, allow_known: false)
+      checker.sample_words(2, seed: 1, weighted: true)
+      checker.metrics_text(prefix: "spellkit", checker: nil)
+    }.not_to raise_error
+  end
+end
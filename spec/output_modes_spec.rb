@@ -0,0 +1,33 @@
+RSpec.describe "correct_tokens output modes" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+  # corrected (lower, title, upper), known with different casing, protected, unchanged unknown
+  let(:tokens) { %w[helo Helo HELO Hello CDK10 Xyzzy] }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file)
+  end
+
+  it "returns dictionary canonical forms by default" do
+    expect(SpellKit.correct_tokens(tokens)).to eq(%w[hello hello hello hello CDK10 Xyzzy])
+    expect(SpellKit.correct_tokens(tokens, output: "canonical")).to eq(SpellKit.correct_tokens(tokens))
+  end
+
+  it "returns the normalized form of every output token" do
+    expect(SpellKit.correct_tokens(tokens, output: "normalized")).to eq(%w[hello hello hello hello cdk10 xyzzy])
+  end
+
+  it "carries the input casing onto corrected tokens" do
+    expect(SpellKit.correct_tokens(tokens, output: "preserve_case")).to eq(%w[hello Hello HELLO Hello CDK10 Xyzzy])
+  end
+
+  it "accepts symbols" do
+    expect(SpellKit.correct_tokens(%w[HELO], output: :normalized)).to eq(%w[hello])
+  end
+
+  it "rejects unknown modes" do
+    expect {
+      SpellKit.correct_tokens(tokens, output: "upcase")
+    }.to raise_error(SpellKit::InvalidArgumentError, /output must be one of/)
+  end
+end
@@ -0,0 +1,44 @@
+require "benchmark"
+
+RSpec.describe "correct_tokens with threads:" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:tokens) { %w[helo wrld tst buffr protien zzzzzz HELLO lyssis] * 6_250 }
+
+  before { SpellKit.load!(dictionary: test_unigrams, edit_distance: 2) }
+
+  it "returns the same tokens in the same order as the sequential path" do
+    sequential = nil
+    parallel = nil
+    sequential_time = Benchmark.realtime { sequential = SpellKit.correct_tokens(tokens) }
+    parallel_time = Benchmark.realtime { parallel = SpellKit.correct_tokens(tokens, threads: 4) }
+
+    expect(parallel).to eq(sequential)
+    expect(parallel.first(4)).to eq(%w[hello world test buffer])
+    puts "\ncorrect_tokens(#{tokens.size}): sequential #{(sequential_time * 1000).round(1)}ms, 4 threads #{(parallel_time * 1000).round(1)}ms" if ENV["BENCH"]
+  end
+
+  it "matches the sequential path for details, alignment, dry_run, and the last token" do
+    input = %w[helo alot wrld hel]
+    [
+      {details: true},
+      {alignment: true, unknown: "remove"},
+      {dry_run: true},
+      {last_token: "complete"}
+    ].each do |options|
+      expect(SpellKit.correct_tokens(input, threads: 3, **options)).to eq(SpellKit.correct_tokens(input, **options)), options.inspect
+    end
+  end
+
+  it "keeps non-String tokens in place with on_error: \"partial\"" do
+    result = SpellKit.correct_tokens(["helo", 42, "wrld"], on_error: "partial", threads: 2)
+
+    expect(result[0]).to eq("hello")
+    expect(result[1]).to include("error")
+    expect(result[2]).to eq("world")
+  end
+
+  it "rejects a non-positive thread count" do
+    expect { SpellKit.correct_tokens(%w[helo], threads: 0) }
+      .to raise_error(SpellKit::InvalidArgumentError, /threads must be a positive Integer/)
+  end
+end
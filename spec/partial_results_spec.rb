@@ -0,0 +1,78 @@
+RSpec.describe "on_error for batch calls" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  describe "suggest_many" do
+    let(:words) { ["helo", 42, "wrld", nil, "", :tst] }
+
+    it "raises on the first bad word by default" do
+      expect { SpellKit.suggest_many(words, 1) }.to raise_error(SpellKit::InvalidArgumentError)
+    end
+
+    it "returns error hashes at failing positions and results elsewhere in partial mode" do
+      results = SpellKit.suggest_many(words, 1, on_error: "partial")
+
+      expect(results.length).to eq(words.length)
+      expect(results[0].first.term).to eq("hello")
+      expect(results[2].first.term).to eq("world")
+
+      [1, 3, 4, 5].each do |i|
+        expect(results[i]).to be_a(Hash), "position #{i}"
+        expect(results[i]["error"]).to be_a(String)
+      end
+      expect(results[3]["error"]).to match(/word cannot be nil/)
+      expect(results[4]["error"]).to match(/word cannot be empty/)
+    end
+
+    it "keeps per-word options aligned with their words" do
+      results = SpellKit.suggest_many(["helo", nil, "hel"], [{max: 1}, {max: 1}, {max: 5}], on_error: "partial")
+
+      expect(results[0].map { |s| s.term }).to eq(["hello"])
+      expect(results[1]).to have_key("error")
+      expect(results[2].length).to be > 1
+    end
+
+    it "still raises for structural problems" do
+      expect {
+        SpellKit.suggest_many("helo", 1, on_error: "partial")
+      }.to raise_error(SpellKit::InvalidArgumentError, /words must be an Array/)
+
+      expect {
+        SpellKit.suggest_many(["helo", nil], [{max: 1}], on_error: "partial")
+      }.to raise_error(SpellKit::InvalidArgumentError, /must align/)
+    end
+
+    it "rejects unknown policies" do
+      expect {
+        SpellKit.suggest_many(%w[helo], 1, on_error: "ignore")
+      }.to raise_error(SpellKit::InvalidArgumentError, /on_error must be one of raise, partial/)
+    end
+  end
+
+  describe "correct_tokens" do
+    let(:tokens) { ["helo", 7, "wrld", nil] }
+
+    it "raises on a non-String token by default" do
+      expect { SpellKit.correct_tokens(tokens) }.to raise_error(TypeError)
+    end
+
+    it "returns error hashes at failing positions in partial mode" do
+      results = SpellKit.correct_tokens(tokens, on_error: "partial")
+
+      expect(results[0]).to eq("hello")
+      expect(results[1]).to include("error")
+      expect(results[2]).to eq("world")
+      expect(results[3]).to include("error")
+    end
+
+    it "combines with details" do
+      results = SpellKit.correct_tokens(["helo", 7], details: true, on_error: "partial")
+
+      expect(results[0]["reason"]).to eq("corrected")
+      expect(results[1].keys).to eq(["error"])
+    end
+  end
+end
@@ -0,0 +1,57 @@
+require "tempfile"
+
+RSpec.describe "Per-call frequency thresholds" do
+  let(:dictionary) do
+    file = Tempfile.new(["per_call_threshold", ".tsv"])
+    file.write("hello\t10000\nhelo\t50\nrare\t5\n")
+    file.close
+    file
+  end
+
+  let(:checker) do
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: dictionary.path, frequency_threshold: 100, autocorrect_known_rare: 100)
+    end
+  end
+
+  after { dictionary.unlink }
+
+  it "overrides the loaded threshold in correct for that call only" do
+    digest = checker.behavior_digest
+
+    expect(checker.correct("rore")).to eq("rore")
+    expect(checker.correct("rore", frequency_threshold: 1)).to eq("rare")
+    expect(checker.correct("rore", min_suggestion_frequency: 1)).to eq("rare")
+    expect(checker.correct("rore")).to eq("rore")
+    expect(checker.behavior_digest).to eq(digest)
+  end
+
+  it "overrides the loaded threshold in correct_tokens for that call only" do
+    digest = checker.behavior_digest
+
+    expect(checker.correct_tokens(%w[rore helo])).to eq(%w[rore hello])
+    expect(checker.correct_tokens(%w[rore helo], frequency_threshold: 1)).to eq(%w[rare hello])
+    # 1000 x 50 is out of reach for the known word, while the floor stays at 1
+    expect(checker.correct_tokens(%w[rore helo], frequency_threshold: 1, frequency_ratio: 1000)).to eq(%w[rare helo])
+    expect(checker.correct_tokens(%w[rore helo], threads: 2, frequency_threshold: 1)).to eq(%w[rare hello])
+    expect(checker.correct_tokens(%w[rore], details: true, frequency_threshold: 1).first).to include("reason" => "corrected")
+    expect(checker.correct_tokens(%w[rore], dry_run: true, frequency_threshold: 1).first).to include("proposed" => "rare")
+    expect(checker.correct_tokens(%w[rar], last_token: "complete", frequency_threshold: 1)).to eq(%w[rare])
+    expect(checker.correct_tokens(%w[rore helo])).to eq(%w[rore hello])
+    expect(checker.behavior_digest).to eq(digest)
+  end
+
+  it "combines with within:" do
+    expect(checker.correct("rore", within: %w[rare], frequency_threshold: 1)).to eq("rare")
+    expect(checker.correct("rore", within: %w[rare])).to eq("rore")
+  end
+
+  it "rejects thresholds that aren't non-negative numbers" do
+    expect { checker.correct("rore", frequency_threshold: -1) }
+      .to raise_error(SpellKit::InvalidArgumentError, /frequency_threshold must be non-negative/)
+    expect { checker.correct_tokens(%w[rore], frequency_ratio: Float::INFINITY) }
+      .to raise_error(SpellKit::InvalidArgumentError, /frequency_ratio must be finite/)
+    expect { checker.correct_tokens(%w[rore], min_suggestion_frequency: "1") }
+      .to raise_error(SpellKit::InvalidArgumentError, /min_suggestion_frequency must be a number/)
+  end
+end
@@ -0,0 +1,82 @@
+RSpec.describe "perturb" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  # Levenshtein distance, independent of the extension
+  def levenshtein(a, b)
+    prev = (0..b.length).to_a
+    a.each_char.with_index(1) do |ca, i|
+      curr = [i]
+      b.each_char.with_index(1) do |cb, j|
+        curr << [prev[j] + 1, curr[j - 1] + 1, prev[j - 1] + (ca == cb ? 0 : 1)].min
+      end
+      prev = curr
+    end
+    prev.last
+  end
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  it "returns typos at exactly the requested distance" do
+    [1, 2, 3].each do |distance|
+      typos = SpellKit.perturb("protein", distance: distance, count: 20, seed: 1)
+
+      expect(typos.map { |t| levenshtein("protein", t) }.uniq).to eq([distance])
+    end
+  end
+
+  it "returns the requested number of distinct typos" do
+    typos = SpellKit.perturb("temperature", count: 25, seed: 3)
+
+    expect(typos.size).to eq(25)
+    expect(typos.uniq).to eq(typos)
+    expect(typos).not_to include("temperature")
+  end
+
+  it "defaults to five typos one edit away" do
+    typos = SpellKit.perturb("sample", seed: 9)
+
+    expect(typos.size).to eq(5)
+    expect(typos.map { |t| levenshtein("sample", t) }.uniq).to eq([1])
+  end
+
+  it "works on the normalized form" do
+    typos = SpellKit.perturb("PROTEIN", count: 10, seed: 4)
+
+    expect(typos.map { |t| levenshtein("protein", t) }.uniq).to eq([1])
+  end
+
+  it "is deterministic under a fixed seed" do
+    first = SpellKit.perturb("analysis", distance: 2, count: 10, seed: 1234)
+    second = SpellKit.perturb("analysis", distance: 2, count: 10, seed: 1234)
+
+    expect(second).to eq(first)
+    expect(SpellKit.perturb("analysis", distance: 2, count: 10, seed: 99)).not_to eq(first)
+  end
+
+  it "excludes dictionary words" do
+    # hell, help, and hello are all one edit apart
+    typos = SpellKit.perturb("hell", count: 300, seed: 5)
+
+    expect(typos).not_to include("help", "hello", "cell")
+    expect(typos.none? { |t| SpellKit.correct?(t) }).to be true
+  end
+
+  it "allows dictionary words with allow_known: true" do
+    typos = SpellKit.perturb("hell", count: 300, seed: 5, allow_known: true)
+
+    expect(typos.any? { |t| SpellKit.correct?(t) }).to be true
+  end
+
+  it "returns fewer typos when not enough exist" do
+    # Every one-edit typo of a one-letter word is at most 26 * 3 strings
+    expect(SpellKit.perturb("a", count: 1000, seed: 2).size).to be < 1000
+  end
+
+  it "validates arguments" do
+    expect { SpellKit.perturb("hello", distance: 0) }.to raise_error(SpellKit::InvalidArgumentError, /distance/)
+    expect { SpellKit.perturb("hello", count: -1) }.to raise_error(SpellKit::InvalidArgumentError, /count/)
+    expect { SpellKit.perturb("hello", seed: "x") }.to raise_error(SpellKit::InvalidArgumentError, /seed/)
+  end
+end
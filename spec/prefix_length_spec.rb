@@ -0,0 +1,52 @@
+require "tempfile"
+
+RSpec.describe "prefix_length" do
+  let(:dictionary) do
+    file = Tempfile.new(["prefix_length", ".tsv"])
+    file.write(<<~TSV)
+      acetylcholinesterase\t900
+      acetylcholine\t800
+      phosphofructokinase\t700
+      phosphorylation\t650
+      immunoglobulin\t600
+      temperature\t500
+      tempering\t400
+      cell\t300
+    TSV
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary.path, edit_distance: 2, **options) }
+  end
+
+  def estimated_bytes(checker)
+    checker.stats["generations"].last["estimated_bytes"]
+  end
+
+  it "gives the same suggestions as whole-word indexing with a smaller index" do
+    whole = checker(prefix_length: nil)
+    prefixed = checker
+    queries = %w[acetylcholinesterse xacetylcholine tempratures immunoglobin phosphorilation cel] +
+      whole.sample_words(20, seed: 3).flat_map { |word| whole.perturb(word, distance: 2, count: 3, seed: 7) }
+
+    queries.each do |query|
+      expect(prefixed.suggestions(query, 10)).to eq(whole.suggestions(query, 10)), "query #{query.inspect}"
+    end
+    expect(prefixed.correct("acetylcholinesterse")).to eq("acetylcholinesterase")
+    expect(estimated_bytes(prefixed)).to be < estimated_bytes(whole)
+  end
+
+  it "reports the prefix length in stats" do
+    expect(checker.stats["prefix_length"]).to eq(7)
+    expect(checker(prefix_length: nil).stats["prefix_length"]).to be_nil
+  end
+
+  it "rejects a prefix no longer than edit_distance" do
+    expect { checker(prefix_length: 2) }
+      .to raise_error(SpellKit::InvalidArgumentError, /prefix_length must be an Integer greater than edit_distance \(2\)/)
+  end
+end
@@ -0,0 +1,57 @@
+require "tempfile"
+
+RSpec.describe "preserve_case" do
+  let(:dictionary) do
+    file = Tempfile.new(["preserve_case", ".tsv"])
+    file.write("the\t50000\nécole\t3000\nstraße\t2000\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary.path, distance_metric: "damerau", **options) }
+  end
+
+  it "carries the input's casing over in correct" do
+    c = checker
+
+    expect(c.correct("TEH", preserve_case: true)).to eq("THE")
+    expect(c.correct("Teh", preserve_case: true)).to eq("The")
+    expect(c.correct("teh", preserve_case: true)).to eq("the")
+    expect(c.correct("tEh", preserve_case: true)).to eq("the")
+    expect(c.correct("Teh")).to eq("the")
+  end
+
+  it "uppercases non-ASCII letters" do
+    c = checker
+
+    expect(c.correct("Écolle", preserve_case: true)).to eq("École")
+    expect(c.correct("ÉCOLLE", preserve_case: true)).to eq("ÉCOLE")
+    expect(c.correct("STRASE", preserve_case: true)).to eq("STRASSE")
+  end
+
+  it "carries the input's casing over in correct_tokens" do
+    c = checker
+
+    expect(c.correct_tokens(%w[TEH Teh teh tEh Écolle], preserve_case: true)).to eq(%w[THE The the the École])
+    expect(c.correct_tokens(%w[Teh], preserve_case: true, details: true).first).to include("output" => "The")
+    expect(c.correct_tokens(%w[Teh])).to eq(%w[the])
+  end
+
+  it "uses the load! default when the call doesn't say" do
+    c = checker(preserve_case: true)
+
+    expect(c.correct("Teh")).to eq("The")
+    expect(c.correct_tokens(%w[TEH])).to eq(%w[THE])
+    expect(c.correct("Teh", preserve_case: false)).to eq("the")
+    expect(c.correct_tokens(%w[TEH], preserve_case: false)).to eq(%w[the])
+    expect(c.correct_tokens(%w[TEH], output: "normalized")).to eq(%w[the])
+  end
+
+  it "rejects preserve_case: true with another output mode" do
+    expect { checker.correct_tokens(%w[Teh], preserve_case: true, output: "normalized") }
+      .to raise_error(SpellKit::InvalidArgumentError, /preserve_case: true can't be combined with output: "normalized"/)
+  end
+end
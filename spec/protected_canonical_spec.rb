@@ -0,0 +1,43 @@
+require "tempfile"
+
+RSpec.describe "Protected terms with canonical forms" do
+  let(:dictionary) do
+    file = Tempfile.new(["protected_canonical", ".tsv"])
+    file.write("hello\t10000\nworld\t8000\nsalt\t3000\n")
+    file.close
+    file
+  end
+
+  let(:protected_file) do
+    file = Tempfile.new(["protected_canonical", ".txt"])
+    file.write("# variant<TAB>canonical\nnacl\tNaCl\nph\tpH\nwrld\n")
+    file.close
+    file
+  end
+
+  let(:checker) do
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: dictionary.path, protected_path: protected_file.path)
+    end
+  end
+
+  after do
+    dictionary.unlink
+    protected_file.unlink
+  end
+
+  it "rewrites any casing of a mapped term to its canonical form" do
+    expect(checker.correct("nacl")).to eq("NaCl")
+    expect(checker.correct("NACL")).to eq("NaCl")
+    expect(checker.correct("PH")).to eq("pH")
+  end
+
+  it "still corrects the tokens around it" do
+    expect(checker.correct_tokens(%w[nacl helo (ph), wrld])).to eq(%w[NaCl hello (pH), wrld])
+    expect(checker.correct_tokens(%w[NACL], details: true).first).to include("output" => "NaCl", "reason" => "protected")
+  end
+
+  it "rewrites mapped terms in correct_text" do
+    expect(checker.correct_text("the nacl solution at ph 7")["text"]).to eq("the NaCl solution at pH 7")
+  end
+end
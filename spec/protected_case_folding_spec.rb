@@ -0,0 +1,77 @@
+require "tempfile"
+
+RSpec.describe "Case folding for protected terms" do
+  let(:dictionary) do
+    file = Tempfile.new(["folding", ".tsv"])
+    file.write("hello\t10000\nstrasse\t5000\nistanbul\t5000\n")
+    file.close
+    file
+  end
+
+  let(:protected_terms) do
+    file = Tempfile.new(["folding", ".txt"])
+    file.write("straße\nistanbul\nΟΔΟΣ\n")
+    file.close
+    file
+  end
+
+  after do
+    dictionary.unlink
+    protected_terms.unlink
+  end
+
+  def reason(word)
+    SpellKit.correct_tokens([word], details: true).first["reason"]
+  end
+
+  context "by default" do
+    before { SpellKit.load!(dictionary: dictionary.path, protected_path: protected_terms.path) }
+
+    it "protects casings of ß that don't round-trip through lowercase" do
+      %w[straße Straße STRASSE strasse STRAẞE].each do |word|
+        expect(reason(word)).to eq("protected"), word
+      end
+    end
+
+    it "folds without Turkish rules, so dotted and dotless i don't match plain i" do
+      %w[istanbul ISTANBUL].each do |word|
+        expect(reason(word)).to eq("protected"), word
+      end
+      %w[İstanbul İSTANBUL ıstanbul].each do |word|
+        expect(reason(word)).not_to eq("protected"), word
+      end
+    end
+
+    it "matches capital, medial, and final sigma" do
+      %w[ΟΔΟΣ οδος οδοσ Οδος].each do |word|
+        expect(reason(word)).to eq("protected"), word
+      end
+    end
+
+    it "still corrects unprotected words" do
+      expect(SpellKit.correct("helo")).to eq("hello")
+    end
+  end
+
+  context "with protected_case_sensitive: true" do
+    before do
+      SpellKit.load!(dictionary: dictionary.path, protected_path: protected_terms.path, protected_prefixes: ["HEL"],
+        protected_case_sensitive: true)
+    end
+
+    it "protects terms only as written" do
+      expect(reason("straße")).to eq("protected")
+      expect(reason("ΟΔΟΣ")).to eq("protected")
+      expect(reason("STRASSE")).not_to eq("protected")
+      expect(reason("İSTANBUL")).not_to eq("protected")
+      expect(reason("οδος")).not_to eq("protected")
+    end
+
+    it "protects prefixes only as written" do
+      expect(reason("HELO")).to eq("protected")
+      expect(SpellKit.correct_tokens(["HELO"], details: true).first["guard"]).to include("kind" => "prefix", "matched" => "HEL")
+      expect(reason("helo")).to eq("corrected")
+      expect(reason("Helo")).not_to eq("protected")
+    end
+  end
+end
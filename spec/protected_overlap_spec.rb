@@ -0,0 +1,128 @@
+require "tempfile"
+
+RSpec.describe "Protected terms that are dictionary words" do
+  let(:test_dict) do
+    dict = Tempfile.new(["overlap_dict", ".tsv"])
+    dict.write("hello\t10000\n")
+    dict.write("hallo\t20\n")
+    dict.write("world\t8000\n")
+    dict.close
+    dict
+  end
+
+  let(:protected_file) do
+    file = Tempfile.new(["overlap_protected", ".txt"])
+    file.write("hello\n")
+    file.write("hallo\n")
+    file.write("Zyxel\n")
+    file.close
+    file
+  end
+
+  after do
+    test_dict.unlink
+    protected_file.unlink
+  end
+
+  def load_checker(**options)
+    SpellKit.load!(dictionary: test_dict.path, protected_path: protected_file.path, **options)
+  end
+
+  describe "load warning" do
+    it "lists protected terms that are confidently known dictionary words" do
+      expect { load_checker }.to output(
+        /1 protected term\(s\) already in the dictionary with frequency >= 1000: hello\. Pass protect_only_unknown: true/
+      ).to_stderr
+    end
+
+    it "reports the overlap in stats" do
+      expect { load_checker }.to output.to_stderr
+
+      expect(SpellKit.stats["protected_overlap"]).to eq(%w[hello])
+    end
+
+    it "uses confident_frequency as the threshold" do
+      expect { load_checker(confident_frequency: 10) }.to output(/2 protected term\(s\).*: hello, hallo\./).to_stderr
+    end
+
+    it "does not warn when no protected term is a frequent dictionary word" do
+      expect { load_checker(confident_frequency: 100_000) }.not_to output.to_stderr
+      expect(SpellKit.stats["protected_overlap"]).to eq([])
+    end
+
+    it "does not warn for terms skipped by protect_only_unknown" do
+      expect { load_checker(protect_only_unknown: true) }.not_to output.to_stderr
+    end
+  end
+
+  describe "protect_only_unknown with autocorrect_known_rare" do
+    it "keeps every listed term protected by default" do
+      expect { load_checker(autocorrect_known_rare: 100) }.to output.to_stderr
+
+      expect(SpellKit.correct("HELLO")).to eq("HELLO")
+      expect(SpellKit.correct("hallo")).to eq("hallo")
+    end
+
+    it "stops protecting confidently known terms" do
+      load_checker(autocorrect_known_rare: 100, protect_only_unknown: true)
+
+      # An unprotected exact match returns the canonical form instead of the input
+      expect(SpellKit.correct("HELLO")).to eq("hello")
+    end
+
+    it "keeps protecting rare known terms so autocorrect leaves them alone" do
+      load_checker(autocorrect_known_rare: 100, protect_only_unknown: true)
+
+      expect(SpellKit.correct("hallo")).to eq("hallo")
+    end
+
+    it "lets rare known words be autocorrected once they are no longer protected" do
+      load_checker(autocorrect_known_rare: 100, protect_only_unknown: true, confident_frequency: 10)
+
+      expect(SpellKit.correct("hallo")).to eq("hello")
+    end
+  end
+
+  describe "autocorrect_known_rare" do
+    before do
+      SpellKit.load!(dictionary: test_dict.path, autocorrect_known_rare: 100)
+    end
+
+    it "corrects known words below the limit to a frequent neighbour" do
+      expect(SpellKit.correct("hallo")).to eq("hello")
+      expect(SpellKit.correct_tokens(%w[hallo world])).to eq(%w[hello world])
+    end
+
+    it "keeps known words at or above the limit" do
+      expect(SpellKit.correct("world")).to eq("world")
+    end
+
+    it "applies to correct_text" do
+      expect(SpellKit.correct_text("hallo world")["text"]).to eq("hello world")
+    end
+
+    it "rejects non-positive limits" do
+      expect {
+        SpellKit.load!(dictionary: test_dict.path, autocorrect_known_rare: 0)
+      }.to raise_error(SpellKit::InvalidArgumentError, /autocorrect_known_rare must be a positive Integer/)
+    end
+  end
+
+  describe "protected_as_known" do
+    it "makes protected terms known" do
+      expect { load_checker(protected_as_known: true) }.to output.to_stderr
+
+      expect(SpellKit.correct?("Zyxel")).to be true
+      expect(SpellKit.correct("Zyxel")).to eq("Zyxel")
+    end
+
+    it "does not re-insert protected terms that are already dictionary words" do
+      expect { load_checker(protected_as_known: true) }.to output.to_stderr
+
+      stats = SpellKit.stats
+      expect(stats["dictionary_size"]).to eq(3)
+      expect(stats["skipped_duplicates"]).to eq(0)
+      expect(SpellKit.suggestions("hello", 5).count { |s| s.term == "hello" }).to eq(1)
+    end
+  end
+end
@@ -0,0 +1,53 @@
+require "tempfile"
+
+RSpec.describe "Protected prefixes" do
+  # Every token below is one edit away from a dictionary word, so only a guard keeps it unchanged
+  let(:dictionary) do
+    file = Tempfile.new(["prefixes", ".tsv"])
+    file.write("rxc\t5000\nicd10a\t5000\nhello\t10000\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  before do
+    SpellKit.load!(dictionary: dictionary.path, protected_prefixes: ["rx:", "ICD10:"])
+  end
+
+  it "protects tokens starting with a prefix" do
+    expect(SpellKit.correct("rx:c")).to eq("rx:c")
+    expect(SpellKit.correct("ICD10:a")).to eq("ICD10:a")
+  end
+
+  it "matches prefixes case-insensitively, like protected terms" do
+    expect(SpellKit.correct("RX:c")).to eq("RX:c")
+    expect(SpellKit.correct("icd10:a")).to eq("icd10:a")
+  end
+
+  it "does not protect near-miss prefixes" do
+    expect(SpellKit.correct("rx-c")).to eq("rxc")
+    expect(SpellKit.correct("icd10a")).to eq("icd10a")
+  end
+
+  it "does not protect the prefix appearing mid-token" do
+    expect(SpellKit.correct("xrx:c")).to eq("xrx:c")
+    expect(SpellKit.correct("helo")).to eq("hello")
+  end
+
+  it "checks the raw token rather than its normalized form" do
+    # NFKD turns the fullwidth colon into ":" for dictionary lookup, but the raw token
+    # does not start with "rx:", so the prefix guard leaves it eligible for correction
+    expect(SpellKit.correct("rx：c")).to eq("rxc")
+  end
+
+  it "applies in batch correction" do
+    expect(SpellKit.correct_tokens(%w[rx:c helo ICD10:a])).to eq(%w[rx:c hello ICD10:a])
+  end
+
+  it "rejects non-string prefixes" do
+    expect {
+      SpellKit.load!(dictionary: dictionary.path, protected_prefixes: [:rx])
+    }.to raise_error(SpellKit::InvalidArgumentError, /protected_prefixes must be an Array of Strings/)
+  end
+end
@@ -0,0 +1,66 @@
+require "tempfile"
+
+RSpec.describe "Punctuation stripping and guards" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) do
+    file = Tempfile.new(["protected", ".txt"])
+    file.write("helo\n")
+    file.close
+    file
+  end
+
+  # Every wrapping style that is stripped, as [leading, trailing]
+  WRAPPINGS = [
+    ["(", ")"], ["(", "),"], ["[", "]."], ["{", "}"], ["<", ">"],
+    ["\"", "\""], ["'", "'"], ["“", "”"], ["‘", "’"], ["«", "»"], ["‹", "›"],
+    ["", "."], ["", ","], ["", ";"], ["", ":"], ["", "!"], ["", "?"], ["", "…"], ["", "..."],
+    ["¿", "?"], ["¡", "!"], ["—", "—"], ["–", ""]
+  ].freeze
+
+  before do
+    SpellKit.load!(
+      dictionary: test_unigrams,
+      protected_path: protected_file.path,
+      protected_patterns: [/^COVID-\d+$/],
+      protected_prefixes: ["wrl"]
+    )
+  end
+
+  after do
+    protected_file.unlink
+  end
+
+  WRAPPINGS.each do |lead, trail|
+    context "wrapped as #{lead}…#{trail}" do
+      %w[helo COVID-19 wrld].each do |term|
+        token = "#{lead}#{term}#{trail}"
+
+        it "protects #{token.inspect} in correct, correct_tokens, and correct_text" do
+          expect(SpellKit.correct(token)).to eq(token)
+          expect(SpellKit.correct_tokens([token])).to eq([token])
+          expect(SpellKit.correct_text("see #{token} now", tokenizer: "whitespace")["text"]).to eq("see #{token} now")
+        end
+      end
+
+      it "corrects the core and re-attaches the punctuation" do
+        token = "#{lead}tesst#{trail}"
+        expected = "#{lead}test#{trail}"
+
+        expect(SpellKit.correct(token)).to eq(expected)
+        expect(SpellKit.correct_tokens([token])).to eq([expected])
+        expect(SpellKit.correct_text("a #{token} b", tokenizer: "whitespace")["text"]).to eq("a #{expected} b")
+      end
+    end
+  end
+
+  it "reports the core decision in details" do
+    result = SpellKit.correct_tokens(["(COVID-19),"], details: true).first
+
+    expect(result["output"]).to eq("(COVID-19),")
+    expect(result["reason"]).to eq("protected")
+  end
+
+  it "keeps the input's casing past the punctuation" do
+    expect(SpellKit.correct_tokens(["(Tesst),"], output: "preserve_case")).to eq(["(Test),"])
+  end
+end
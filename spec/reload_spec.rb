@@ -0,0 +1,67 @@
+require "tempfile"
+
+RSpec.describe "reload!" do
+  let(:dictionary) do
+    file = Tempfile.new(["reload", ".tsv"])
+    file.write("hello\t1000\nworld\t800\n")
+    file.close
+    file
+  end
+  let(:protected_file) do
+    file = Tempfile.new(["protected", ".txt"])
+    file.write("helo\n")
+    file.close
+    file
+  end
+  let(:checker) { SpellKit::Checker.new }
+
+  after do
+    dictionary.unlink
+    protected_file.unlink
+  end
+
+  it "re-reads the dictionary with the options of the last load!" do
+    checker.load!(dictionary: dictionary.path, protected_path: protected_file.path, frequency_threshold: 5.0)
+    File.write(dictionary.path, "hello\t1000\nworld\t800\nbuffer\t600\n")
+
+    checker.reload!
+
+    expect(checker.stats["dictionary_size"]).to eq(3)
+    expect(checker.stats["generation"]).to eq(2)
+    expect(checker.correct("bufer")).to eq("buffer")
+    expect(checker.correct("helo")).to eq("helo")
+  end
+
+  it "updates loaded_at" do
+    checker.load!(dictionary: dictionary.path)
+    loaded_at = checker.stats["loaded_at"]
+
+    checker.reload!
+
+    expect(checker.stats["loaded_at"]).to be >= loaded_at
+  end
+
+  it "keeps serving the old dictionary when the reload fails" do
+    checker.load!(dictionary: dictionary.path, max_words: 2)
+    File.write(dictionary.path, "hello\t1000\nworld\t800\nbuffer\t600\n")
+
+    expect { checker.reload! }.to raise_error(SpellKit::DictionaryError)
+
+    expect(checker.stats["dictionary_size"]).to eq(2)
+    expect(checker.stats["generation"]).to eq(1)
+    expect(checker.correct("wrld")).to eq("world")
+  end
+
+  it "raises NotLoadedError before any load!" do
+    expect { checker.reload! }.to raise_error(SpellKit::NotLoadedError, /call load! first/)
+  end
+
+  it "reloads the module-level default" do
+    SpellKit.load!(dictionary: dictionary.path)
+    File.write(dictionary.path, "hello\t1000\n")
+
+    SpellKit.reload!
+
+    expect(SpellKit.stats["dictionary_size"]).to eq(1)
+  end
+end
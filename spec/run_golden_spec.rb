@@ -0,0 +1,63 @@
+require "yaml"
+
+RSpec.describe "run_golden" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+  let(:golden) { YAML.load_file(File.expand_path("fixtures/golden.yml", __dir__)) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file)
+  end
+
+  it "passes every pair the pipeline gets right" do
+    result = SpellKit.run_golden(golden["passing"])
+
+    expect(result["passed"]).to eq(6)
+    expect(result["failed"]).to eq([])
+  end
+
+  it "reports each failing pair with what the pipeline returned and why" do
+    result = SpellKit.run_golden(golden["failing"])
+
+    expect(result["passed"]).to eq(0)
+    expect(result["failed"]).to eq([
+      {"input" => "helo", "expected" => "help", "got" => "hello", "reason" => "corrected"},
+      {"input" => "xyzzy", "expected" => "xylophone", "got" => "xyzzy", "reason" => "no_candidates"},
+      {"input" => "CDK10", "expected" => "CDK1", "got" => "CDK10", "reason" => "protected"}
+    ])
+  end
+
+  it "reports below-threshold rejections" do
+    SpellKit.load!(dictionary: test_unigrams, frequency_threshold: 100_000.0)
+
+    result = SpellKit.run_golden([%w[helo hello]])
+
+    expect(result["failed"]).to eq([
+      {"input" => "helo", "expected" => "hello", "got" => "helo", "reason" => "below_threshold"}
+    ])
+  end
+
+  it "handles mixed fixtures in order" do
+    result = SpellKit.run_golden(golden["passing"] + golden["failing"])
+
+    expect(result["passed"]).to eq(6)
+    expect(result["failed"].map { |f| f["input"] }).to eq(%w[helo xyzzy CDK10])
+  end
+
+  it "works on a Checker instance" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: test_unigrams)
+
+    expect(checker.run_golden([%w[helo hello]])["passed"]).to eq(1)
+  end
+
+  it "rejects malformed pairs" do
+    expect {
+      SpellKit.run_golden([%w[helo]])
+    }.to raise_error(SpellKit::InvalidArgumentError, /pairs must be an Array of \[input, expected\] String pairs/)
+
+    expect {
+      SpellKit.run_golden("helo")
+    }.to raise_error(SpellKit::InvalidArgumentError)
+  end
+end
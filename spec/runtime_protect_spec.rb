@@ -0,0 +1,112 @@
+require "tempfile"
+
+RSpec.describe "protect!, unprotect!, and protected?" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  let(:protected_file) do
+    file = Tempfile.new(["runtime_protect", ".txt"])
+    file.write("wrld\n")
+    file.close
+    file
+  end
+
+  let(:checker) do
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: test_unigrams, protected_path: protected_file.path, protected_patterns: [/^CDK\d+$/])
+    end
+  end
+
+  after { protected_file.unlink }
+
+  it "protects terms for the next call" do
+    expect(checker.correct_tokens(%w[helo testt])).to eq(%w[hello test])
+
+    expect(checker.protect!(["HELO", "testt"])).to be(checker)
+    expect(checker.correct_tokens(%w[helo Testt])).to eq(%w[helo Testt])
+    expect(checker.correct_tokens(%w[Helo], details: true).first).to include("reason" => "protected")
+  end
+
+  it "takes a single term" do
+    checker.protect!("helo")
+
+    expect(checker.correct("helo")).to eq("helo")
+  end
+
+  it "removes runtime and load-time terms" do
+    checker.protect!("helo")
+
+    expect(checker.unprotect!("Helo")).to be(true)
+    expect(checker.unprotect!("helo")).to be(false)
+    expect(checker.correct("helo")).to eq("hello")
+
+    expect(checker.unprotect!("wrld")).to be(true)
+    expect(checker.correct("wrld")).to eq("world")
+  end
+
+  it "reports what any guard protects without counting it" do
+    checker.reset_stats!
+
+    expect(checker.protected?("wrld")).to be(true)
+    expect(checker.protected?("(Wrld),")).to be(true)
+    expect(checker.protected?("CDK12")).to be(true)
+    expect(checker.protected?("helo")).to be(false)
+    expect(checker.stats["guard_hits"]).to all(satisfy { |_, hits| hits.zero? })
+  end
+
+  it "leaves snapshots taken before a change as they were" do
+    snapshot = checker.snapshot
+
+    checker.protect!("helo")
+    checker.unprotect!("wrld")
+
+    expect(snapshot.correct_tokens(%w[helo wrld])).to eq(%w[hello wrld])
+    expect(snapshot.protected?("helo")).to be(false)
+    expect(checker.correct_tokens(%w[helo wrld])).to eq(%w[helo world])
+  end
+
+  it "changes the behavior digest" do
+    digest = checker.behavior_digest
+
+    checker.protect!("helo")
+    expect(checker.behavior_digest).not_to eq(digest)
+    checker.unprotect!("helo")
+    expect(checker.behavior_digest).to eq(digest)
+  end
+
+  it "is visible to concurrent correct_tokens calls once protect! returns" do
+    running = true
+    readers = 4.times.map do
+      Thread.new do
+        results = []
+        results << checker.correct_tokens(%w[helo]).first while running
+        results
+      end
+    end
+
+    checker.protect!("helo")
+    after_protect = 4.times.map { Thread.new { checker.correct_tokens(%w[helo]).first } }.map(&:value)
+    running = false
+
+    expect(after_protect).to all(eq("helo"))
+    readers.each { |reader| expect(reader.value - %w[hello helo]).to be_empty }
+  end
+
+  it "doesn't carry runtime protections across reload!" do
+    checker.protect!("helo")
+    checker.reload!
+
+    expect(checker.protected?("helo")).to be(false)
+    expect(checker.protected?("wrld")).to be(true)
+  end
+
+  it "rejects empty or non-String terms" do
+    expect { checker.protect!([""]) }.to raise_error(SpellKit::InvalidArgumentError, /protect! takes a non-empty String/)
+    expect { checker.protect!([:helo]) }.to raise_error(SpellKit::InvalidArgumentError, /protect! takes a non-empty String/)
+    expect { checker.unprotect!("") }.to raise_error(SpellKit::InvalidArgumentError, /word cannot be empty/)
+    expect { checker.protected?(nil) }.to raise_error(SpellKit::InvalidArgumentError, /word cannot be nil/)
+  end
+
+  it "raises before load!" do
+    expect { SpellKit::Checker.new.protect!("helo") }.to raise_error(SpellKit::NotLoadedError, /not loaded/)
+  end
+end
@@ -0,0 +1,59 @@
+RSpec.describe "add_word! and remove_word!" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) } }
+
+  it "serves an added word on the next call" do
+    expect(checker.correct("kubernets")).to eq("kubernets")
+
+    expect(checker.add_word!("kubernetes", 500)).to be(true)
+    expect(checker.correct("kubernets")).to eq("kubernetes")
+    expect(checker.correct?("kubernetes")).to be(true)
+    expect(checker.add_word!("kubernetes", 10)).to be(false)
+  end
+
+  it "stops suggesting a removed word" do
+    expect(checker.remove_word!("hello")).to be(true)
+
+    expect(checker.correct?("hello")).to be(false)
+    expect(checker.suggestions("helo", 5).map { |s| s.term }).not_to include("hello")
+    expect(checker.remove_word!("hello")).to be(false)
+    expect(checker.remove_word!("notaword")).to be(false)
+  end
+
+  it "keeps dictionary_size in step with edits" do
+    size = checker.stats["dictionary_size"]
+
+    checker.add_word!("kubernetes", 500)
+    expect(checker.stats["dictionary_size"]).to eq(size + 1)
+    checker.remove_word!("cell")
+    checker.remove_word!("kubernetes")
+    expect(checker.stats["dictionary_size"]).to eq(size - 1)
+  end
+
+  it "leaves snapshots taken before an edit as they were" do
+    snapshot = checker.snapshot
+    size = snapshot.stats["dictionary_size"]
+
+    checker.add_word!("kubernetes", 500)
+    checker.remove_word!("hello")
+
+    expect(snapshot.correct?("kubernetes")).to be(false)
+    expect(snapshot.correct?("hello")).to be(true)
+    expect(snapshot.stats["dictionary_size"]).to eq(size)
+    expect(checker.correct?("kubernetes")).to be(true)
+    expect(checker.snapshot.correct?("kubernetes")).to be(true)
+  end
+
+  it "keeps added words across reload!" do
+    checker.add_word!("kubernetes", 500)
+    checker.reload!
+
+    expect(checker.correct?("kubernetes")).to be(true)
+  end
+
+  it "rejects phrases and non-positive frequencies" do
+    expect { checker.add_word!("two words", 5) }.to raise_error(SpellKit::InvalidArgumentError)
+    expect { checker.add_word!("kubernetes", 0) }.to raise_error(SpellKit::InvalidArgumentError, /frequency/)
+    expect { checker.add_word!("kubernetes", "5") }.to raise_error(SpellKit::InvalidArgumentError, /frequency/)
+  end
+end
@@ -0,0 +1,91 @@
+require "tempfile"
+
+RSpec.describe "sample_words" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def dictionary_file(content)
+    file = Tempfile.new(["sample", ".tsv"])
+    file.write(content)
+    file.flush
+    file
+  end
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  it "returns the requested number of dictionary words" do
+    sample = SpellKit.sample_words(50, seed: 1)
+
+    expect(sample.size).to eq(50)
+    expect(sample.all? { |word| SpellKit.correct?(word) }).to be true
+  end
+
+  it "is deterministic under a fixed seed" do
+    first = SpellKit.sample_words(100, seed: 1234)
+
+    expect(SpellKit.sample_words(100, seed: 1234)).to eq(first)
+    expect(SpellKit.sample_words(100, seed: 99)).not_to eq(first)
+    expect(SpellKit.sample_words(100, seed: 1234, weighted: false)).not_to eq(first)
+  end
+
+  it "samples in proportion to frequency" do
+    file = dictionary_file("common\t9000\nrare\t1000\n")
+    SpellKit.load!(dictionary: file.path)
+
+    sample = SpellKit.sample_words(10_000, seed: 5)
+
+    # Expected 9000 of 10000; the tolerance is well over five standard deviations
+    expect(sample.count("common")).to be_within(200).of(9000)
+    expect(sample.count("rare")).to be_within(200).of(1000)
+  ensure
+    file&.close!
+  end
+
+  it "samples uniformly with weighted: false" do
+    file = dictionary_file("common\t9000\nrare\t1000\n")
+    SpellKit.load!(dictionary: file.path)
+
+    sample = SpellKit.sample_words(10_000, seed: 5, weighted: false)
+
+    expect(sample.count("rare")).to be_within(300).of(5000)
+  ensure
+    file&.close!
+  end
+
+  it "returns canonical forms" do
+    file = dictionary_file("NASA\t500\n")
+    SpellKit.load!(dictionary: file.path)
+
+    expect(SpellKit.sample_words(3, seed: 1)).to eq(["NASA"] * 3)
+  ensure
+    file&.close!
+  end
+
+  it "samples the reloaded dictionary" do
+    SpellKit.sample_words(10, seed: 1)
+
+    file = dictionary_file("alpha\t100\nbeta\t100\n")
+    SpellKit.load!(dictionary: file.path)
+
+    expect(SpellKit.sample_words(20, seed: 1).uniq.sort).to eq(%w[alpha beta])
+  ensure
+    file&.close!
+  end
+
+  it "is available on snapshots" do
+    snapshot = SpellKit.snapshot
+
+    expect(snapshot.sample_words(10, seed: 3)).to eq(SpellKit.sample_words(10, seed: 3))
+  end
+
+  it "validates arguments" do
+    expect { SpellKit.sample_words(-1) }.to raise_error(SpellKit::InvalidArgumentError, /count/)
+    expect { SpellKit.sample_words("5") }.to raise_error(SpellKit::InvalidArgumentError, /count/)
+    expect { SpellKit.sample_words(5, seed: -1) }.to raise_error(SpellKit::InvalidArgumentError, /seed/)
+  end
+
+  it "requires a loaded dictionary" do
+    expect { SpellKit::Checker.new.sample_words(5) }.to raise_error(SpellKit::NotLoadedError, /not loaded/)
+  end
+end
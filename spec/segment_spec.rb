@@ -0,0 +1,48 @@
+require "tempfile"
+
+RSpec.describe "segment" do
+  let(:dictionary) do
+    file = Tempfile.new(["segment", ".tsv"])
+    file.write("the\t5000\nquick\t300\nbrown\t200\nfox\t100\nnow\t500\nhere\t400\nno\t300\nwhere\t200\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  before { SpellKit.load!(dictionary: dictionary.path) }
+
+  it "splits text into dictionary words with a log probability" do
+    result = SpellKit.segment("TheQuickBrownFox")
+
+    expect(result["segments"]).to eq(%w[the quick brown fox])
+    expected = [5000, 300, 200, 100].sum { |frequency| Math.log(frequency / 7000.0) }
+    expect(result["score"]).to be_within(1e-9).of(expected)
+  end
+
+  it "lets frequency decide between readings" do
+    expect(SpellKit.segment("nowhere")["segments"]).to eq(%w[now here])
+
+    file = Tempfile.new(["segment", ".tsv"])
+    file.write("now\t100\nhere\t100\nno\t900\nwhere\t800\n")
+    file.close
+    SpellKit.load!(dictionary: file.path)
+    expect(SpellKit.segment("nowhere")["segments"]).to eq(%w[no where])
+  ensure
+    file&.unlink
+  end
+
+  it "keeps unknown stretches verbatim" do
+    expect(SpellKit.segment("thexyzfox")["segments"]).to eq(%w[the xyz fox])
+  end
+
+  it "respects max_segment_length" do
+    expect(SpellKit.segment("QuickFox", max_segment_length: 3)["segments"]).to eq(%w[Quick fox])
+  end
+
+  it "validates its input" do
+    expect { SpellKit.segment("the fox") }.to raise_error(SpellKit::InvalidArgumentError, /whitespace/)
+    expect { SpellKit.segment("thefox", max_segment_length: 0) }
+      .to raise_error(SpellKit::InvalidArgumentError, /max_segment_length must be a positive Integer/)
+  end
+end
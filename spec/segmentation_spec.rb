@@ -0,0 +1,88 @@
+RSpec.describe "Segmentation versus correction" do
+  let(:dictionary) { File.expand_path("fixtures/temp_segmentation_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  # "alot" is one edit from "allot" (and "lot") and splits into "a lot". The split scores
+  # 50000 * 3000 / 60160, about 2493, against 5000 for the correction
+  before do
+    File.write(dictionary, "a\t50000\nlot\t3000\nallot\t5000\nbuffer\t150\nfor\t2000\nbuff\t10\n")
+  end
+
+  after do
+    FileUtils.rm_f(dictionary)
+  end
+
+  def load(**options)
+    checker.load!(dictionary: dictionary, segmentation: true, **options)
+  end
+
+  it "is off by default" do
+    checker.load!(dictionary: dictionary)
+    expect(checker.correct("alot")).to eq("allot")
+    expect(checker.correct_tokens(["alot"], details: true).first).not_to have_key("path")
+  end
+
+  it "resolves alot differently under each policy" do
+    load(prefer_segmentation_over_distance: true)
+    expect(checker.correct("alot")).to eq("a lot")
+
+    load(prefer_segmentation_over_distance: false)
+    expect(checker.correct("alot")).to eq("allot")
+  end
+
+  it "compares scores when no preference is given" do
+    load
+    expect(checker.correct_tokens(%w[alot lotfor])).to eq(["allot", "lot for"])
+  end
+
+  it "reports the winning path and both scores in details" do
+    load(prefer_segmentation_over_distance: false)
+    details = checker.correct_tokens(["alot"], details: true).first
+
+    expect(details["output"]).to eq("allot")
+    expect(details["path"]).to eq("correction")
+    expect(details["scores"]["correction"]).to eq(5000.0)
+    expect(details["scores"]["segmentation"]).to be_within(0.01).of(50000 * 3000 / 60160.0)
+
+    load(prefer_segmentation_over_distance: true)
+    details = checker.correct_tokens(["alot"], details: true).first
+    expect(details["output"]).to eq("a lot")
+    expect(details["path"]).to eq("segmentation")
+    expect(details["reason"]).to eq("corrected")
+  end
+
+  describe "tokens with only one path" do
+    it "keeps a correction-only token under either policy" do
+      [true, false].each do |preference|
+        load(prefer_segmentation_over_distance: preference)
+        details = checker.correct_tokens(["bufer"], details: true).first
+
+        expect(details["output"]).to eq("buffer")
+        expect(details["path"]).to eq("correction")
+        expect(details["scores"]["segmentation"]).to be_nil
+      end
+    end
+
+    it "keeps a segmentation-only token under either policy" do
+      [true, false].each do |preference|
+        load(prefer_segmentation_over_distance: preference)
+        details = checker.correct_tokens(["lotfor"], details: true).first
+
+        expect(details["output"]).to eq("lot for")
+        expect(details["path"]).to eq("segmentation")
+        expect(details["scores"]["correction"]).to be_nil
+      end
+    end
+  end
+
+  it "never splits known words" do
+    load(prefer_segmentation_over_distance: true)
+    expect(checker.correct("allot")).to eq("allot")
+  end
+
+  it "rejects non-boolean preferences" do
+    expect {
+      load(prefer_segmentation_over_distance: "yes")
+    }.to raise_error(SpellKit::InvalidArgumentError, /prefer_segmentation_over_distance/)
+  end
+end
@@ -0,0 +1,49 @@
+require "tempfile"
+
+RSpec.describe "skip_all_caps:" do
+  def checker(**options)
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: {"nasal" => 3000, "https" => 2000, "hello" => 10000}, **options)
+    end
+  end
+
+  let(:tokens) { %w[NASA Nasa HTTP helo] }
+
+  it "corrects acronyms outside the dictionary by default" do
+    expect(checker.correct_tokens(tokens)).to eq(%w[nasal nasal https hello])
+  end
+
+  it "leaves all-caps tokens as written while mixed case is still corrected" do
+    skipping = checker(skip_all_caps: true)
+
+    expect(skipping.correct_tokens(tokens)).to eq(%w[NASA nasal HTTP hello])
+    expect(skipping.correct("(NASA),")).to eq("(NASA),")
+    expect(skipping.correct("ÉTÉ")).to eq("ÉTÉ")
+    expect(skipping.correct_tokens_with_info(tokens).map { |info| info[:reason] })
+      .to eq(%w[all_caps corrected all_caps corrected])
+    expect(skipping.correct_tokens_with_info(%w[NASA]).first).to include(corrected: "NASA", changed: false, guard: nil)
+    expect(skipping.stats["skip_all_caps"]).to eq(true)
+  end
+
+  it "works with preserve_case" do
+    expect(checker(preserve_case: true).correct_tokens(%w[NASA Nasa])).to eq(%w[NASAL Nasal])
+    expect(checker(skip_all_caps: true, preserve_case: true).correct_tokens(%w[NASA Nasa])).to eq(%w[NASA Nasal])
+    expect(checker(skip_all_caps: true).correct("NASA", preserve_case: true)).to eq("NASA")
+  end
+
+  it "lets guards decide first" do
+    Tempfile.create(["protected", ".txt"]) do |file|
+      file.write("js\tJavaScript\n")
+      file.close
+
+      info = checker(skip_all_caps: true, protected_path: file.path).correct_tokens_with_info(%w[JS]).first
+      expect(info).to include(corrected: "JavaScript", reason: "protected")
+    end
+  end
+
+  it "can be set or relaxed for one call" do
+    expect(checker.correct_tokens(tokens, skip_all_caps: true)).to eq(%w[NASA nasal HTTP hello])
+    expect(checker.correct("HTTP", skip_all_caps: true)).to eq("HTTP")
+    expect(checker(skip_all_caps: true).correct_tokens(tokens, skip_all_caps: false)).to eq(%w[nasal nasal https hello])
+  end
+end
@@ -270,6 +270,50 @@ RSpec.describe "Skip Patterns" do
     end
   end
 
+  describe "skip: presets" do
+    let(:uuid) { "123e4567-e89b-12d3-a456-426614174000" }
+
+    it "passes emails and UUIDs through while correcting the words next to them" do
+      SpellKit.load!(dictionary: test_unigrams, skip: [:emails, :uuids])
+
+      tokens = ["helo", "user@example.com", uuid, "wrld"]
+      expect(SpellKit.correct_tokens(tokens)).to eq(["hello", "user@example.com", uuid, "world"])
+      expect(SpellKit.correct_tokens(tokens, details: true).map { |d| d["reason"] })
+        .to eq(%w[corrected protected protected corrected])
+    end
+
+    it "covers hex values, mentions, and hashtags" do
+      SpellKit.load!(dictionary: test_unigrams, skip: %w[hex mentions hashtags])
+
+      %w[0xFF #1a2b3c 9fceb02 @hello-team #helo].each do |token|
+        expect(SpellKit.correct_tokens([token], details: true).first["reason"]).to eq("protected"), token
+      end
+      expect(SpellKit.correct("helo")).to eq("hello")
+    end
+
+    it "combines with the skip_* flags and protected_patterns, and lists the active presets in stats" do
+      SpellKit.load!(dictionary: test_unigrams, skip: [:uuids, :urls], skip_emails: true,
+        protected_patterns: [/^CUSTOM-\d+$/])
+
+      expect(SpellKit.stats["skip_presets"]).to eq(%w[urls emails uuids])
+      expect(SpellKit.correct("user@test.com")).to eq("user@test.com")
+      expect(SpellKit.correct("CUSTOM-123")).to eq("CUSTOM-123")
+      expect(SpellKit.correct(uuid.upcase)).to eq(uuid.upcase)
+    end
+
+    it "reports no presets by default" do
+      SpellKit.load!(dictionary: test_unigrams)
+
+      expect(SpellKit.stats["skip_presets"]).to eq([])
+    end
+
+    it "rejects unknown presets" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, skip: [:phone_numbers])
+      }.to raise_error(SpellKit::InvalidArgumentError, /skip presets must be among urls, emails, .*got: :phone_numbers/)
+    end
+  end
+
   describe "when skip flags are false" do
     before do
       SpellKit.load!(
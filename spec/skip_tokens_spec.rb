@@ -0,0 +1,49 @@
+RSpec.describe "skip_tokens_with_digits: and min_word_length:" do
+  # structural_match: false, so digits alone don't stop "a1" from becoming "at"
+  def checker(**options)
+    SpellKit::Checker.new.tap do |c|
+      c.load!(dictionary: {"at" => 5000, "bid" => 1000, "hello" => 10000}, structural_match: false, **options)
+    end
+  end
+
+  let(:tokens) { %w[2024 a1 bi2 t aa helo] }
+
+  it "corrects numbers, codes, and short words into dictionary words by default" do
+    expect(checker.correct_tokens(tokens)).to eq(%w[2024 at bid at at hello])
+  end
+
+  it "leaves them as written when configured at load" do
+    skipping = checker(skip_tokens_with_digits: true, min_word_length: 3)
+
+    expect(skipping.correct_tokens(tokens)).to eq(%w[2024 a1 bi2 t aa hello])
+    expect(skipping.correct("(a1),")).to eq("(a1),")
+    expect(skipping.correct_tokens_with_info(tokens).map { |info| info[:reason] })
+      .to eq(%w[has_digits has_digits has_digits too_short too_short corrected])
+    expect(skipping.correct_tokens_with_info(%w[aa]).first).to include(corrected: "aa", changed: false, distance: nil, frequency: nil)
+    expect(skipping.stats).to include("skip_tokens_with_digits" => true, "min_word_length" => 3)
+  end
+
+  it "skips before the guards are checked" do
+    skipping = checker(skip_tokens_with_digits: true, protected_patterns: [/\A\d+\z/])
+
+    expect(skipping.correct_tokens_with_info(%w[2024]).first).to include(reason: "has_digits", guard: nil)
+  end
+
+  it "can be set or relaxed for one call" do
+    open = checker
+    skipping = checker(skip_tokens_with_digits: true, min_word_length: 3)
+
+    expect(open.correct_tokens(tokens, skip_tokens_with_digits: true)).to eq(%w[2024 a1 bi2 at at hello])
+    expect(open.correct_tokens(tokens, min_word_length: 2)).to eq(%w[2024 at bid t at hello])
+    expect(open.correct("aa", min_word_length: 3)).to eq("aa")
+    expect(skipping.correct_tokens(tokens, skip_tokens_with_digits: false, min_word_length: 0)).to eq(%w[2024 at bid at at hello])
+    expect(skipping.correct_tokens_with_info(%w[a1], skip_tokens_with_digits: false).first[:reason]).to eq("corrected")
+  end
+
+  it "validates min_word_length" do
+    expect { checker(min_word_length: -1) }
+      .to raise_error(SpellKit::InvalidArgumentError, "min_word_length must be a non-negative Integer, got: -1")
+    expect { checker.correct_tokens(%w[aa], min_word_length: "3") }
+      .to raise_error(SpellKit::InvalidArgumentError, /min_word_length must be a non-negative Integer/)
+  end
+end
@@ -0,0 +1,64 @@
+RSpec.describe "Snapshots" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:temp_unigrams) { File.expand_path("fixtures/temp_snapshot_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  before do
+    checker.load!(dictionary: test_unigrams)
+    File.write(temp_unigrams, "help\t50000\nworld\t30000\n")
+  end
+
+  after do
+    FileUtils.rm_f(temp_unigrams)
+  end
+
+  it "keeps answering from the dictionary it was taken from after a reload" do
+    snapshot = checker.snapshot
+    expect(snapshot.correct?("hello")).to be true
+
+    checker.load!(dictionary: temp_unigrams)
+
+    expect(snapshot.correct?("hello")).to be true
+    expect(snapshot.suggestions("helo", 1).first.term).to eq("hello")
+    expect(snapshot.correct("helo")).to eq("hello")
+    expect(snapshot.correct_tokens(%w[helo wrld])).to eq(%w[hello world])
+    expect(snapshot.stats["dictionary_size"]).to eq(20)
+  end
+
+  it "does not hold the live checker back" do
+    checker.snapshot
+    checker.load!(dictionary: temp_unigrams)
+
+    expect(checker.correct?("hello")).to be false
+    expect(checker.suggestions("helo", 1).first.term).to eq("help")
+    expect(checker.stats["dictionary_size"]).to eq(2)
+  end
+
+  it "validates arguments like the checker" do
+    snapshot = checker.snapshot
+
+    expect { snapshot.correct?(nil) }.to raise_error(SpellKit::InvalidArgumentError)
+    expect { snapshot.correct_tokens("helo") }.to raise_error(SpellKit::InvalidArgumentError)
+  end
+
+  it "shares named sets with the live checker" do
+    snapshot = checker.snapshot
+    checker.define_set(:assays, %w[lysis analysis])
+
+    expect(snapshot.correct("lysys", within: :assays)).to eq("lysis")
+  end
+
+  it "exposes only read methods" do
+    snapshot = checker.snapshot
+
+    expect(snapshot).not_to respond_to(:load!)
+    expect(snapshot).not_to respond_to(:define_set)
+  end
+
+  it "is available at the module level" do
+    SpellKit.load!(dictionary: test_unigrams)
+
+    expect(SpellKit.snapshot).to be_a(SpellKit::Snapshot)
+    expect(SpellKit.snapshot.correct?("hello")).to be true
+  end
+end
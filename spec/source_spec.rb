@@ -0,0 +1,59 @@
+require "json"
+require "webmock/rspec"
+require "digest"
+
+RSpec.describe "Dictionary source in stats" do
+  let(:test_dict) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  it "describes a plain file load" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: test_dict)
+
+    source = checker.stats["source"]
+    expect(source["kind"]).to eq("file")
+    expect(source["path_or_url"]).to eq(test_dict)
+    expect(source["sha256"]).to eq(Digest::SHA256.file(test_dict).hexdigest)
+    expect(source["bytes"]).to eq(File.size(test_dict))
+    expect(source["compiled_version"]).to be_nil
+  end
+
+  it "describes a URL load by its URL and the downloaded content" do
+    body = "hello\t10000\nworld\t5000\n"
+    stub_request(:get, "https://example.com/source-dict.txt")
+      .to_return(status: 200, body: body)
+
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: "https://example.com/source-dict.txt")
+
+    source = checker.stats["source"]
+    expect(source["kind"]).to eq("url")
+    expect(source["path_or_url"]).to eq("https://example.com/source-dict.txt")
+    expect(source["sha256"]).to eq(Digest::SHA256.hexdigest(body))
+    expect(source["bytes"]).to eq(body.bytesize)
+  end
+
+  it "is replaced on reload" do
+    stub_request(:get, "https://example.com/source-reload.txt")
+      .to_return(status: 200, body: "hello\t10000\n")
+
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: "https://example.com/source-reload.txt")
+    checker.load!(dictionary: test_dict)
+
+    stats = checker.stats
+    expect(stats["generation"]).to eq(2)
+    expect(stats["source"]["kind"]).to eq("file")
+    expect(stats["source"]["path_or_url"]).to eq(test_dict)
+  end
+
+  it "is absent before the first load" do
+    expect(SpellKit::Checker.new.stats).not_to have_key("source")
+  end
+
+  it "appears in stats_json" do
+    checker = SpellKit::Checker.new
+    checker.load!(dictionary: test_dict)
+
+    expect(JSON.parse(checker.stats_json)["source"]["kind"]).to eq("file")
+  end
+end
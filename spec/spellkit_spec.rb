@@ -59,17 +59,17 @@ RSpec.describe SpellKit do
     it "returns suggestions for misspelled words" do
       suggestions = SpellKit.suggestions("helo", 3)
       expect(suggestions).to be_an(Array)
-      expect(suggestions.first).to include("term", "distance", "freq")
+      expect(suggestions).to all(be_a(SpellKit::Suggestion))
       # "hello" comes first because it has higher frequency than "help"
-      expect(suggestions.first["term"]).to eq("hello")
-      expect(suggestions.first["distance"]).to eq(1)
-      expect(suggestions.first["freq"]).to eq(10000)
+      expect(suggestions.first.term).to eq("hello")
+      expect(suggestions.first.distance).to eq(1)
+      expect(suggestions.first.frequency).to eq(10000)
     end
 
     it "returns exact match with distance 0" do
       suggestions = SpellKit.suggestions("hello", 1)
-      expect(suggestions.first["term"]).to eq("hello")
-      expect(suggestions.first["distance"]).to eq(0)
+      expect(suggestions.first.term).to eq("hello")
+      expect(suggestions.first.distance).to eq(0)
     end
 
     it "returns empty array for words too far from dictionary" do
@@ -240,6 +240,17 @@ RSpec.describe SpellKit do
         expect(corrected).to eq(%w[heo st hello test])
       end
 
+      it "applies distance-2 corrections in text and detailed output with edit_distance: 2" do
+        SpellKit.load!(dictionary: test_unigrams, edit_distance: 2)
+
+        # "temprture" -> "temperature" (distance 2: insert 'e' and 'a')
+        expect(SpellKit.correct_text("the protien temprture")["text"]).to eq("the protein temperature")
+
+        detail = SpellKit.correct_tokens(["temprture"], details: true).first
+        expect(detail["output"]).to eq("temperature")
+        expect(detail["distance"]).to eq(2)
+      end
+
       it "respects frequency threshold with edit_distance: 2" do
         # "incubation" has frequency 600 in test dictionary
         # Set threshold to 1000, so 600 < 1000 = rejection
@@ -0,0 +1,89 @@
+require "json"
+
+RSpec.describe "JSON stats and healthcheck" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  describe "#stats_json" do
+    it "matches stats before loading" do
+      expect(JSON.parse(checker.stats_json)).to eq(checker.stats)
+      expect(checker.stats).to eq("loaded" => false, "loading" => false, "generation" => 0)
+    end
+
+    it "matches stats after loading" do
+      checker.load!(dictionary: test_unigrams, protected_path: protected_file, protected_prefixes: ["rx:"])
+      checker.correct_tokens(%w[helo BRCA1 rx:abc])
+
+      stats = checker.stats
+      expect(JSON.parse(checker.stats_json)).to eq(stats)
+      expect(stats["guard_hits"]).to eq("shape" => 0, "prefix" => 1, "set" => 1, "pattern" => 0)
+    end
+
+    it "returns a String" do
+      checker.load!(dictionary: test_unigrams)
+      expect(checker.stats_json).to be_a(String)
+    end
+  end
+
+  describe "generation" do
+    it "counts successful loads" do
+      checker.load!(dictionary: test_unigrams)
+      expect(checker.stats["generation"]).to eq(1)
+
+      checker.load!(dictionary: test_unigrams)
+      expect(JSON.parse(checker.stats_json)["generation"]).to eq(2)
+    end
+
+    it "is not advanced by a failed load" do
+      checker.load!(dictionary: test_unigrams)
+      expect {
+        checker.load!(dictionary: test_unigrams, progress: ->(*) { raise "stop" })
+      }.to raise_error(RuntimeError, "stop")
+
+      expect(checker.stats["generation"]).to eq(1)
+      expect(checker.stats["loading"]).to be false
+    end
+
+    it "is pinned by snapshots" do
+      checker.load!(dictionary: test_unigrams)
+      snapshot = checker.snapshot
+      checker.load!(dictionary: test_unigrams)
+
+      expect(JSON.parse(snapshot.stats_json)["generation"]).to eq(1)
+      expect(checker.stats["generation"]).to eq(2)
+    end
+  end
+
+  describe "loading flag" do
+    it "is set while load! runs" do
+      seen = []
+      checker.load!(dictionary: test_unigrams, progress: ->(*) { seen << checker.stats["loading"] })
+
+      expect(seen).to all(be true)
+      expect(checker.stats["loading"]).to be false
+    end
+  end
+
+  describe "#healthcheck_json" do
+    it "reports an unloaded checker without raising" do
+      expect(JSON.parse(checker.healthcheck_json)).to eq(
+        "healthy" => false, "error" => "Dictionary not loaded", "loading" => false, "generation" => 0
+      )
+    end
+
+    it "reports a loaded checker" do
+      checker.load!(dictionary: test_unigrams)
+      expect(JSON.parse(checker.healthcheck_json)).to eq(
+        "healthy" => true, "error" => nil, "loading" => false, "generation" => 1
+      )
+    end
+  end
+
+  it "is available at the module level" do
+    SpellKit.load!(dictionary: test_unigrams)
+
+    expect(JSON.parse(SpellKit.stats_json)).to eq(SpellKit.stats)
+    expect(JSON.parse(SpellKit.healthcheck_json)["healthy"]).to be true
+  end
+end
@@ -0,0 +1,53 @@
+require "tempfile"
+require "tmpdir"
+
+RSpec.describe "strip_accents" do
+  let(:dictionary) do
+    file = Tempfile.new(["strip_accents", ".tsv"])
+    file.write("café\t5000\nnaïve\t3000\nMüller\t2000\nhello\t10000\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: dictionary.path, **options) }
+  end
+
+  it "matches words typed without accents and returns them accented" do
+    c = checker(strip_accents: true)
+
+    expect(c.correct("cafe")).to eq("café")
+    expect(c.correct("naive")).to eq("naïve")
+    expect(c.correct("Muller")).to eq("Müller")
+    expect(c.correct_tokens(%w[cafe naiv helo])).to eq(%w[café naïve hello])
+    expect(c.correct?("cafe")).to be(true)
+    expect(c.correct_tokens(%w[cafe], details: true).first).to include("reason" => "exact", "distance" => 0)
+  end
+
+  it "keeps accents significant by default" do
+    c = checker
+
+    expect(c.correct?("cafe")).to be(false)
+    expect(c.correct("naiv")).to eq("naiv")
+    expect(c.stats["strip_accents"]).to be(false)
+    expect(checker(strip_accents: true).stats["strip_accents"]).to be(true)
+  end
+
+  it "is part of the behavior digest" do
+    expect(checker.behavior_digest).not_to eq(checker(strip_accents: true).behavior_digest)
+  end
+
+  it "must match the options a saved index was built with" do
+    Dir.mktmpdir do |dir|
+      path = File.join(dir, "accents.idx")
+      checker(strip_accents: true).save_index(path)
+
+      loaded = SpellKit::Checker.new.tap { |c| c.load!(index_path: path, strip_accents: true) }
+      expect(loaded.correct("cafe")).to eq("café")
+      expect { SpellKit::Checker.new.load!(index_path: path) }
+        .to raise_error(ArgumentError, /built with strip_accents: true, but strip_accents: false was requested/)
+    end
+  end
+end
@@ -0,0 +1,56 @@
+require "tempfile"
+
+RSpec.describe "Structural match" do
+  let(:test_dict) do
+    dict = Tempfile.new(["structural_match", ".tsv"])
+    dict.write("hello\t1000\n")
+    dict.write("h3llo\t50000\n")
+    dict.write("bid\t9000\n")
+    dict.write("b13\t100\n")
+    dict.write("covids\t8000\n")
+    dict.close
+    dict
+  end
+
+  after do
+    test_dict.unlink
+  end
+
+  context "with the default structural_match: true" do
+    before do
+      SpellKit.load!(dictionary: test_dict.path)
+    end
+
+    it "corrects digit-bearing tokens only to digit-bearing candidates" do
+      expect(SpellKit.correct("b12")).to eq("b13")
+      expect(SpellKit.correct("covid1")).to eq("covid1")
+    end
+
+    it "never corrects alphabetic tokens into candidates with digits" do
+      expect(SpellKit.correct("hallo")).to eq("hello")
+      expect(SpellKit.correct_tokens(%w[hallo b12])).to eq(%w[hello b13])
+    end
+
+    it "leaves suggestions unfiltered unless asked" do
+      expect(SpellKit.suggestions("hallo").map { |s| s.term }).to include("h3llo")
+      expect(SpellKit.suggestions("hallo", 5, structural_match: true).map { |s| s.term }).to eq(["hello"])
+    end
+
+    it "rejects non-boolean values for suggestions" do
+      expect {
+        SpellKit.suggestions("hallo", 5, structural_match: "yes")
+      }.to raise_error(SpellKit::InvalidArgumentError, /structural_match must be true or false/)
+    end
+  end
+
+  context "with structural_match: false" do
+    before do
+      SpellKit.load!(dictionary: test_dict.path, structural_match: false)
+    end
+
+    it "ranks candidates by frequency regardless of shape" do
+      expect(SpellKit.correct("hallo")).to eq("h3llo")
+      expect(SpellKit.correct("b12")).to eq("bid")
+    end
+  end
+end
@@ -0,0 +1,83 @@
+RSpec.describe "suggest_many" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams, edit_distance: 2)
+  end
+
+  it "applies an Integer limit to every word" do
+    results = SpellKit.suggest_many(%w[helo wrld], 2)
+
+    expect(results.length).to eq(2)
+    expect(results).to all(satisfy { |suggestions| suggestions.length <= 2 })
+    expect(results[0].first.term).to eq("hello")
+    expect(results[1].first.term).to eq("world")
+  end
+
+  it "matches single-word suggestions" do
+    words = %w[helo tst incubatio]
+
+    expect(SpellKit.suggest_many(words, 3)).to eq(words.map { |w| SpellKit.suggestions(w, 3) })
+  end
+
+  it "applies per-word limits and distance cutoffs" do
+    results = SpellKit.suggest_many(
+      %w[helo hel hel],
+      [{max: 1}, {max: 5, max_distance: 1}, {max: 5}]
+    )
+
+    expect(results[0].map { |s| s.term }).to eq(["hello"])
+
+    # "hello" is two edits from "hel", so the distance-1 cutoff drops it
+    expect(results[1].map { |s| s.distance }).to all(be <= 1)
+    expect(results[1].map { |s| s.term }).not_to include("hello")
+    expect(results[2].map { |s| s.term }).to include("hello")
+  end
+
+  it "accepts string keys in per-word options" do
+    results = SpellKit.suggest_many(%w[helo], [{"max" => 1}])
+
+    expect(results[0].length).to eq(1)
+  end
+
+  it "returns an exact lookup at max_distance 0" do
+    results = SpellKit.suggest_many(%w[hello helo], [{max_distance: 0}, {max_distance: 0}])
+
+    expect(results[0].map { |s| s.term }).to eq(["hello"])
+    expect(results[1]).to be_empty
+  end
+
+  describe "validation" do
+    it "requires per-word options to align with words" do
+      expect {
+        SpellKit.suggest_many(%w[helo wrld], [{max: 1}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /must align with words \(got 1 for 2 words\)/)
+    end
+
+    it "rejects unknown per-word keys" do
+      expect {
+        SpellKit.suggest_many(%w[helo], [{maximum: 1}])
+      }.to raise_error(SpellKit::InvalidArgumentError, /unknown suggest options: maximum/)
+    end
+
+    it "rejects negative limits the same way as suggestions" do
+      expect { SpellKit.suggestions("helo", -1) }.to raise_error(SpellKit::InvalidArgumentError, /max must be/)
+      expect { SpellKit.suggest_many(%w[helo], -1) }.to raise_error(SpellKit::InvalidArgumentError, /max must be/)
+      expect { SpellKit.suggest_many(%w[helo], [{max: -1}]) }.to raise_error(SpellKit::InvalidArgumentError, /max must be/)
+    end
+
+    it "rejects max_distance above the loaded edit distance" do
+      SpellKit.load!(dictionary: test_unigrams, edit_distance: 1)
+
+      expect {
+        SpellKit.suggest_many(%w[helo], [{max_distance: 2}])
+      }.to raise_error(ArgumentError, /exceeds the loaded edit_distance 1/)
+    end
+
+    it "rejects empty words" do
+      expect {
+        SpellKit.suggest_many(["helo", ""], 1)
+      }.to raise_error(SpellKit::InvalidArgumentError, /word cannot be empty/)
+    end
+  end
+end
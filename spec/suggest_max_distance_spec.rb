@@ -0,0 +1,37 @@
+RSpec.describe "suggestions with max_distance" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  before { SpellKit.load!(dictionary: test_unigrams, edit_distance: 2) }
+
+  it "searches the full loaded distance by default" do
+    expect(SpellKit.suggestions("heo").map { |s| s.term }).to include("hello")
+  end
+
+  it "leaves out candidates beyond max_distance" do
+    # "heo" is two edits from "hello", "help", and "hell", with nothing closer
+    expect(SpellKit.suggestions("heo", max_distance: 1)).to eq([])
+
+    suggestions = SpellKit.suggestions("hellp", 10, max_distance: 1)
+    expect(suggestions.map { |s| s.distance }.uniq).to eq([1])
+    expect(suggestions.map { |s| s.term }).to eq(%w[hello help hell])
+  end
+
+  it "ranks and truncates the same way as a full search" do
+    full = SpellKit.suggestions("hellp", 2)
+    expect(SpellKit.suggestions("hellp", 2, max_distance: 1)).to eq(full)
+  end
+
+  it "is an exact lookup at 0" do
+    expect(SpellKit.suggestions("hello", max_distance: 0, format: :hash)).to eq([{"term" => "hello", "distance" => 0, "freq" => 10000}])
+    expect(SpellKit.suggestions("helo", max_distance: 0)).to eq([])
+  end
+
+  it "raises above the loaded edit_distance" do
+    SpellKit.load!(dictionary: test_unigrams, edit_distance: 1)
+
+    expect { SpellKit.suggestions("heo", max_distance: 2) }
+      .to raise_error(ArgumentError, /max_distance 2 exceeds the loaded edit_distance 1/)
+    expect { SpellKit.suggestions("heo", max_distance: -1) }
+      .to raise_error(SpellKit::InvalidArgumentError, /max_distance must be a non-negative Integer/)
+  end
+end
@@ -0,0 +1,45 @@
+RSpec.describe SpellKit::Suggestion do
+  before do
+    SpellKit.load!(dictionary: {"hello" => 6000, "help" => 3000, "hell" => 1000, "held" => 3000, "helot" => 10})
+  end
+
+  it "reads term, distance, frequency, and score" do
+    suggestion = SpellKit.suggestions("helo", 1).first
+
+    expect(suggestion).to be_a(SpellKit::Suggestion)
+    expect(suggestion).to have_attributes(term: "hello", distance: 1, frequency: 6000)
+    expect(suggestion.score).to be_within(1e-9).of(6000.0 / 13_010)
+    expect(suggestion.to_h).to eq(term: "hello", distance: 1, frequency: 6000, score: suggestion.score)
+  end
+
+  it "sorts the way the extension ranks" do
+    suggestions = SpellKit.suggestions("helo", 10)
+
+    expect(suggestions.map(&:term)).to eq(%w[hello held help hell helot])
+    expect(suggestions.shuffle(random: Random.new(1)).sort).to eq(suggestions)
+    expect(suggestions.first).to be < suggestions.last
+    expect(suggestions.max.term).to eq("helot")
+  end
+
+  it "compares equal by term, distance, and frequency" do
+    expect(SpellKit.suggestions("helo", 1).first).to eq(SpellKit.suggestions("helo", 1).first)
+    expect(SpellKit.suggestions("helo", 1).first).not_to eq({"term" => "hello", "distance" => 1, "freq" => 6000})
+    expect(SpellKit.suggestions("helo", 1).first <=> "hello").to be_nil
+  end
+
+  it "keeps the hash format behind format: :hash" do
+    expect(SpellKit.suggestions("helo", 2, format: :hash)).to eq([
+      {"term" => "hello", "distance" => 1, "freq" => 6000},
+      {"term" => "held", "distance" => 1, "freq" => 3000}
+    ])
+    expect(SpellKit.suggest_many(%w[helo], 1, format: "hash")).to eq([[{"term" => "hello", "distance" => 1, "freq" => 6000}]])
+    expect(SpellKit.suggest_many(%w[helo], 1).first.first).to be_a(SpellKit::Suggestion)
+  end
+
+  it "rejects unknown formats" do
+    expect { SpellKit.suggestions("helo", 1, format: :json) }
+      .to raise_error(SpellKit::InvalidArgumentError, /format must be one of objects, hash/)
+    expect { SpellKit.suggest_many(%w[helo], 1, format: nil) }
+      .to raise_error(SpellKit::InvalidArgumentError, /format must be one of objects, hash/)
+  end
+end
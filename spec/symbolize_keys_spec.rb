@@ -0,0 +1,54 @@
+RSpec.describe "symbolize_keys:" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, **options) }
+  end
+
+  it "keeps String keys by default" do
+    c = checker
+
+    expect(c.stats).to include("dictionary_size" => 20, "symbolize_keys" => false)
+    expect(c.stats["normalize"]).to include("form" => "nfkd")
+    expect(c.suggestions("helo", 1, format: :hash)).to eq([{"term" => "hello", "distance" => 1, "freq" => 10000}])
+    expect(c.explain("helo")["decision"]).to include("reason" => "corrected")
+  end
+
+  it "uses Symbol keys at every level" do
+    c = checker(symbolize_keys: true)
+
+    expect(c.stats).to include(dictionary_size: 20, symbolize_keys: true)
+    expect(c.stats[:normalize]).to eq(form: "nfkd", lowercase: true, strip_accents: false, strip_punctuation: false)
+    expect(c.suggestions("helo", 1, format: :hash)).to eq([{term: "hello", distance: 1, freq: 10000}])
+    expect(c.suggest_many(["helo", nil], 1, format: :hash, on_error: "partial"))
+      .to match([[{term: "hello", distance: 1, freq: 10000}], {error: a_string_including("nil")}])
+    expect(c.explain("helo")[:decision]).to include(reason: "corrected")
+    expect(c.correct_tokens(%w[helo], details: true).first).to include(token: "helo", output: "hello", reason: "corrected")
+    expect(c.correct_phrase("helo wrld")).to eq(output: "hello world", distance: 2)
+    expect(c.run_golden([%w[helo hello]])).to include(passed: 1, failed: [])
+  end
+
+  it "leaves values, Suggestion objects, and word-keyed results alone" do
+    c = checker(symbolize_keys: true)
+
+    expect(c.stats[:format]).to eq("counts")
+    expect(c.suggestions("helo", 1).first).to have_attributes(term: "hello")
+    expect(c.correct_unique({"helo" => 3})).to eq("helo" => {corrected: "hello", count: 3})
+    expect(c.correct_tokens_with_info(%w[helo]).first).to include(token: "helo", corrected: "hello", frequency: 10000)
+    expect(c.stats_json).to include("\"symbolize_keys\":true")
+  end
+
+  it "returns the load! report in the same shape" do
+    expect(SpellKit::Checker.new.load!(dictionary: test_unigrams).keys).to all(be_a(String))
+    expect(SpellKit::Checker.new.load!(dictionary: test_unigrams, symbolize_keys: true).keys).to all(be_a(Symbol))
+  end
+
+  it "follows the load a snapshot pins" do
+    c = checker(symbolize_keys: true)
+    snapshot = c.snapshot
+    c.load!(dictionary: test_unigrams)
+
+    expect(snapshot.stats).to have_key(:generation)
+    expect(c.stats).to have_key("generation")
+  end
+end
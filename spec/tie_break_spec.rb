@@ -0,0 +1,46 @@
+require "tempfile"
+
+RSpec.describe "tie_break" do
+  # "aod" is one substitution from both, at the same frequency
+  let(:dictionary) do
+    file = Tempfile.new(["tie_break", ".tsv"])
+    file.write("and\t500\nabd\t500\n")
+    file.close
+    file
+  end
+
+  after { dictionary.unlink }
+
+  it "picks the alphabetically first candidate by default" do
+    SpellKit.load!(dictionary: dictionary.path)
+
+    expect(SpellKit.correct("aod")).to eq("abd")
+    expect(SpellKit.stats["tie_break"]).to eq("lexicographic")
+  end
+
+  context "with tie_break: \"none\"" do
+    before { SpellKit.load!(dictionary: dictionary.path, tie_break: "none") }
+
+    it "declines to correct an exact tie" do
+      expect(SpellKit.correct("aod")).to eq("aod")
+      expect(SpellKit.correct_tokens(%w[aod], details: true).first["reason"]).to eq("ambiguous")
+    end
+
+    it "still suggests both candidates" do
+      expect(SpellKit.suggestions("aod").map { |s| s.term }).to eq(%w[abd and])
+    end
+
+    it "corrects when one candidate is closer" do
+      expect(SpellKit.correct("anf")).to eq("and")
+    end
+
+    it "drops ambiguous tokens with unknown: \"remove\"" do
+      expect(SpellKit.correct_tokens(%w[aod anf], unknown: "remove")).to eq(%w[and])
+    end
+  end
+
+  it "rejects unknown policies" do
+    expect { SpellKit.load!(dictionary: dictionary.path, tie_break: "random") }
+      .to raise_error(SpellKit::InvalidArgumentError, /tie_break must be one of lexicographic, none/)
+  end
+end
@@ -0,0 +1,69 @@
+RSpec.describe "correct_tokens alignment" do
+  let(:dictionary) { File.expand_path("fixtures/temp_alignment_unigrams.tsv", __dir__) }
+  let(:checker) { SpellKit::Checker.new }
+
+  before do
+    File.write(dictionary, "a\t50000\nlot\t3000\nbuffer\t150\nhello\t10000\nworld\t8000\n")
+    checker.load!(dictionary: dictionary, segmentation: true, prefer_segmentation_over_distance: true)
+  end
+
+  after do
+    FileUtils.rm_f(dictionary)
+  end
+
+  # Every output token must come from exactly the input token its range names
+  def provenance(tokens, alignment)
+    tokens.each_index.map { |out| alignment.index { |range| range.cover?(out) } }
+  end
+
+  it "maps each token to itself when nothing changes shape" do
+    result = checker.correct_tokens(%w[helo wrld buffer], alignment: true)
+
+    expect(result["tokens"]).to eq(%w[hello world buffer])
+    expect(result["alignment"]).to eq([0...1, 1...2, 2...3])
+  end
+
+  it "gives a removed token an empty range" do
+    input = %w[helo qzxv wrld]
+    result = checker.correct_tokens(input, unknown: "remove", alignment: true)
+
+    expect(result["tokens"]).to eq(%w[hello world])
+    expect(result["alignment"]).to eq([0...1, 1...1, 1...2])
+    expect(result["alignment"][1].to_a).to be_empty
+    expect(provenance(result["tokens"], result["alignment"])).to eq([0, 2])
+  end
+
+  it "gives an expanded token one index per word" do
+    input = %w[helo alot buffer]
+    result = checker.correct_tokens(input, expand: true, alignment: true)
+
+    expect(result["tokens"]).to eq(%w[hello a lot buffer])
+    expect(result["alignment"]).to eq([0...1, 1...3, 3...4])
+    expect(result["tokens"][result["alignment"][1]]).to eq(%w[a lot])
+    expect(provenance(result["tokens"], result["alignment"])).to eq([0, 1, 1, 2])
+  end
+
+  it "keeps multi-word outputs and unknown tokens by default" do
+    expect(checker.correct_tokens(%w[alot qzxv])).to eq(["a lot", "qzxv"])
+  end
+
+  it "aligns error entries under on_error: partial" do
+    result = checker.correct_tokens(["helo", 42, "qzxv"], on_error: "partial", unknown: "remove", alignment: true)
+
+    expect(result["tokens"].size).to eq(2)
+    expect(result["tokens"][1]).to have_key("error")
+    expect(result["alignment"]).to eq([0...1, 1...2, 2...2])
+  end
+
+  it "rejects reshaping options with details" do
+    expect {
+      checker.correct_tokens(%w[alot], details: true, expand: true)
+    }.to raise_error(SpellKit::InvalidArgumentError, /only apply without details/)
+  end
+
+  it "rejects unknown modes" do
+    expect {
+      checker.correct_tokens(%w[helo], unknown: "drop")
+    }.to raise_error(SpellKit::InvalidArgumentError, /unknown must be one of keep, remove/)
+  end
+end
@@ -0,0 +1,85 @@
+RSpec.describe "Tokenizer selection" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:text) { "The helo wrld, q4h 5-FU testt." }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams)
+  end
+
+  describe "token boundaries" do
+    it "splits on UAX-29 word boundaries by default" do
+      expect(SpellKit.tokenize(text)).to eq(%w[The helo wrld q4h 5 FU testt])
+    end
+
+    it "splits only on whitespace with the whitespace tokenizer" do
+      expect(SpellKit.tokenize(text, tokenizer: "whitespace")).to eq(%w[The helo wrld, q4h 5-FU testt.])
+    end
+
+    it "uses each pattern match as a token with a custom pattern" do
+      expect(SpellKit.tokenize(text, tokenizer: {pattern: "[\\w-]+"})).to eq(%w[The helo wrld q4h 5-FU testt])
+    end
+
+    it "accepts a Regexp as the custom pattern" do
+      expect(SpellKit.tokenize(text, tokenizer: {pattern: /[\w-]+/})).to eq(%w[The helo wrld q4h 5-FU testt])
+    end
+  end
+
+  describe "corrections under each tokenizer" do
+    it "corrects every word token with the unicode tokenizer" do
+      result = SpellKit.correct_text(text)
+
+      expect(result["text"]).to eq("The hello world, q4h 5-FU test.")
+      expect(result["corrections"].map { |c| c["original"] }).to eq(%w[helo wrld testt])
+    end
+
+    it "leaves tokens with attached punctuation alone with the whitespace tokenizer" do
+      result = SpellKit.correct_text(text, tokenizer: "whitespace")
+
+      # "wrld," and "testt." are two edits away from their dictionary words
+      expect(result["text"]).to eq("The hello wrld, q4h 5-FU testt.")
+      expect(result["corrections"].map { |c| c["original"] }).to eq(%w[helo])
+    end
+
+    it "keeps hyphenated tokens whole with a custom pattern" do
+      result = SpellKit.correct_text(text, tokenizer: {pattern: "[\\w-]+"})
+
+      expect(result["text"]).to eq("The hello world, q4h 5-FU test.")
+      expect(result["corrections"].map { |c| [c["start"], c["end"]] }).to eq([[4, 8], [9, 13], [24, 29]])
+    end
+  end
+
+  describe "load-time default" do
+    it "uses the tokenizer configured in load!" do
+      SpellKit.load!(dictionary: test_unigrams, tokenizer: "whitespace")
+
+      expect(SpellKit.stats["tokenizer"]).to eq("whitespace")
+      expect(SpellKit.correct_text(text)["text"]).to eq("The hello wrld, q4h 5-FU testt.")
+    end
+
+    it "lets a per-call tokenizer override the load-time default" do
+      SpellKit.load!(dictionary: test_unigrams, tokenizer: "whitespace")
+
+      expect(SpellKit.correct_text(text, tokenizer: "unicode")["text"]).to eq("The hello world, q4h 5-FU test.")
+    end
+  end
+
+  describe "validation" do
+    it "rejects unknown tokenizer names" do
+      expect {
+        SpellKit.correct_text(text, tokenizer: "sentencepiece")
+      }.to raise_error(SpellKit::InvalidArgumentError, /tokenizer must be/)
+    end
+
+    it "rejects a pattern hash without a pattern" do
+      expect {
+        SpellKit.correct_text(text, tokenizer: {})
+      }.to raise_error(SpellKit::InvalidArgumentError, /must include :pattern/)
+    end
+
+    it "reports invalid tokenizer patterns" do
+      expect {
+        SpellKit.correct_text(text, tokenizer: {pattern: "[unclosed"})
+      }.to raise_error(SpellKit::InvalidPatternError, /Invalid tokenizer pattern/)
+    end
+  end
+end
@@ -0,0 +1,58 @@
+require "stringio"
+require "tempfile"
+
+RSpec.describe "SpellKit.train_from_text" do
+  let(:corpus) do
+    <<~TEXT
+      The printer won't print after the update.
+      Printer queue stuck; the update failed again.
+      Reset the printer, then retry the update.
+      Typo: the pritner
+    TEXT
+  end
+
+  def train(input, **options)
+    Tempfile.create(["trained", ".tsv"]) do |output|
+      summary = SpellKit.train_from_text(input, output.path, **options)
+      yield summary, output.path
+    end
+  end
+
+  it "counts normalized words above min_count into a loadable dictionary" do
+    Tempfile.create(["corpus", ".txt"]) do |file|
+      file.write(corpus)
+      file.close
+
+      train(file.path) do |summary, path|
+        expect(summary).to eq("lines" => 4, "tokens" => 24, "distinct" => 15, "written" => 3)
+        expect(File.read(path)).to eq("the\t6\nprinter\t3\nupdate\t3\n")
+
+        checker = SpellKit::Checker.new
+        checker.load!(dictionary: path, strict: true, frequency_threshold: 1)
+        expect(checker.correct("printr")).to eq("printer")
+        expect(checker.frequency("Printer")).to eq(3)
+      end
+    end
+  end
+
+  it "reads an IO and keeps every word with min_count: 1" do
+    train(StringIO.new(corpus), min_count: 1) do |summary, path|
+      expect(summary["written"]).to eq(15)
+      expect(File.readlines(path, chomp: true)).to include("pritner\t1", "won't\t1")
+    end
+  end
+
+  it "is available on Checker without a loaded dictionary" do
+    train(StringIO.new("alpha beta alpha\n")) do |summary, path|
+      expect(SpellKit::Checker.train_from_text(StringIO.new("alpha\n"), path, min_count: 1)).to include("written" => 1)
+      expect(summary["written"]).to eq(1)
+    end
+  end
+
+  it "validates its arguments" do
+    expect { SpellKit.train_from_text("missing.txt", "out.tsv") }.to raise_error(SpellKit::FileNotFoundError, /Corpus file not found/)
+    expect { SpellKit.train_from_text(StringIO.new(""), nil) }.to raise_error(SpellKit::InvalidArgumentError, /output_path is required/)
+    expect { SpellKit.train_from_text(StringIO.new(""), "out.tsv", min_count: 0) }
+      .to raise_error(SpellKit::InvalidArgumentError, /min_count must be a positive Integer/)
+  end
+end
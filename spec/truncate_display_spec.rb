@@ -0,0 +1,53 @@
+require "tempfile"
+
+RSpec.describe "Display truncation of suggestions" do
+  let(:test_dict) do
+    dict = Tempfile.new(["truncate_display", ".tsv"])
+    dict.write("cafe\u0301ine\t5000\n")
+    dict.write("thumbs\u{1F44D}up\t3000\n")
+    dict.write("short\t2000\n")
+    dict.close
+    dict
+  end
+
+  before do
+    SpellKit.load!(dictionary: test_dict.path)
+  end
+
+  after do
+    test_dict.unlink
+  end
+
+  it "leaves terms that fit alone" do
+    result = SpellKit.suggestions("short", 1, truncate_display: 24).first
+
+    expect(result.display_truncated).to eq("short")
+  end
+
+  it "keeps a combining mark with its base at the boundary" do
+    result = SpellKit.suggestions("cafe\u0301ine", 1, truncate_display: 5).first
+
+    expect(result.term).to eq("cafe\u0301ine")
+    expect(result.display_truncated).to eq("cafe\u0301…")
+    expect(SpellKit.suggestions("cafe\u0301ine", 1, truncate_display: 4).first.display_truncated).to eq("caf…")
+  end
+
+  it "keeps emoji whole at the boundary" do
+    term = "thumbs\u{1F44D}up"
+
+    expect(SpellKit.suggestions(term, 1, truncate_display: 8).first.display_truncated).to eq("thumbs…")
+    expect(SpellKit.suggestions(term, 1, truncate_display: 9).first.display_truncated).to eq("thumbs\u{1F44D}…")
+    expect(SpellKit.suggestions(term, 1, truncate_display: 9).first.term).to eq(term)
+  end
+
+  it "only adds the field when asked" do
+    expect(SpellKit.suggestions("short", 1).first.display_truncated).to be_nil
+    expect(SpellKit.suggestions("short", 1, format: :hash).first).not_to have_key("display_truncated")
+  end
+
+  it "rejects non-positive widths" do
+    expect {
+      SpellKit.suggestions("short", 1, truncate_display: 0)
+    }.to raise_error(SpellKit::InvalidArgumentError, /truncate_display must be a positive Integer/)
+  end
+end
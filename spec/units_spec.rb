@@ -0,0 +1,57 @@
+RSpec.describe "units" do
+  let(:dictionary) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  it "leaves numeric tokens to the normal pipeline by default" do
+    SpellKit.load!(dictionary: dictionary)
+
+    expect(SpellKit.correct?("500mg")).to be false
+    expect(SpellKit.stats["units"]).to eq("off")
+  end
+
+  context "with units: \"skip\"" do
+    before { SpellKit.load!(dictionary: dictionary, units: "skip") }
+
+    it "leaves numbers with known units exactly as written" do
+      expect(SpellKit.correct_tokens(["helo", "500MG", "37.5", "(10ml),"])).to eq(["hello", "500MG", "37.5", "(10ml),"])
+      expect(SpellKit.correct?("500MG")).to be true
+    end
+
+    it "reports them as numeric" do
+      details = SpellKit.correct_tokens(["500MG"], details: true)
+      expect(details.first["reason"]).to eq("numeric")
+    end
+
+    it "handles decimal and degree symbols" do
+      expect(SpellKit.correct_tokens(["37.5°c", "1,000.5ml"])).to eq(["37.5°c", "1,000.5ml"])
+    end
+  end
+
+  context "with units: \"normalize\"" do
+    before { SpellKit.load!(dictionary: dictionary, units: "normalize") }
+
+    it "rewrites the unit in its listed form" do
+      expect(SpellKit.correct_tokens(["500MG", "10 ML", "37.5°c", "98.6°f."]))
+        .to eq(["500mg", "10ml", "37.5°C", "98.6°F."])
+      expect(SpellKit.correct_text("Take 500MG twice", tokenizer: "whitespace")["text"]).to eq("Take 500mg twice")
+    end
+
+    it "leaves numbers with unknown units to the normal pipeline" do
+      expect(SpellKit.correct?("500zz")).to be false
+      expect(SpellKit.correct_tokens(["500zz"], details: true).first["reason"]).not_to eq("numeric")
+    end
+  end
+
+  it "takes a custom unit list" do
+    SpellKit.load!(dictionary: dictionary, units: "normalize", unit_list: ["Tbsp", "mL"])
+
+    expect(SpellKit.correct_tokens(["2tbsp", "5ML"])).to eq(["2Tbsp", "5mL"])
+    expect(SpellKit.correct?("5mg")).to be false
+  end
+
+  it "validates its options" do
+    expect { SpellKit.load!(dictionary: dictionary, units: "strip") }
+      .to raise_error(SpellKit::InvalidArgumentError, /units must be one of off, skip, normalize/)
+    expect { SpellKit.load!(dictionary: dictionary, unit_list: "mg") }
+      .to raise_error(SpellKit::InvalidArgumentError, /unit_list must be an Array/)
+  end
+end
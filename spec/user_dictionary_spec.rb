@@ -0,0 +1,75 @@
+require "tempfile"
+
+RSpec.describe "User dictionary overlay" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  def with_file(content)
+    Tempfile.create(["user", ".tsv"]) do |file|
+      file.write(content)
+      file.close
+      yield file.path
+    end
+  end
+
+  def checker(**options)
+    SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams, **options) }
+  end
+
+  it "prefers a low-frequency user word over a high-frequency shared word at the same distance" do
+    with_file("# tenant terms\nhelot\t12\n") do |path|
+      shared = checker
+      tenant = checker(user_dictionary_path: path)
+
+      # "helot" (12) and "hello" (10000) are both one edit from "hellot"
+      expect(shared.correct("hellot")).to eq("hello")
+      expect(tenant.correct("hellot")).to eq("helot")
+      expect(tenant.suggestions("hellot", 2).map { |s| [s.term, s.user?] }).to eq([["helot", true], ["hello", false]])
+      expect(tenant.frequency("helot")).to eq(12)
+    end
+  end
+
+  it "still ranks by distance first" do
+    with_file("helot\t12\n") do |path|
+      tenant = checker(user_dictionary_path: path)
+
+      expect(tenant.correct("hellp")).to eq("hello")
+      expect(tenant.correct("wrld")).to eq("world")
+    end
+  end
+
+  it "ranks words added with add_word! the same way" do
+    c = checker
+    c.add_word!("helot", 12)
+
+    expect(c.correct("hellot")).to eq("helot")
+  end
+
+  it "exports only the user layer, and reloads it without counting it twice" do
+    with_file("helot\t12\nhelp\n") do |path|
+      c = checker(user_dictionary_path: path)
+      c.add_word!("Kubectl", 50)
+      c.reload!
+
+      Tempfile.create(["exported", ".tsv"]) do |exported|
+        c.export_user_dictionary(exported.path)
+        expect(File.read(exported.path)).to eq("Kubectl\t50\nhelot\t12\nhelp\t1\n")
+
+        restored = checker(user_dictionary_path: exported.path)
+        expect(restored.correct("hellot")).to eq("helot")
+        expect(restored.correct("kubectll")).to eq("Kubectl")
+      end
+    end
+  end
+
+  it "validates the file" do
+    expect { checker(user_dictionary_path: "/nonexistent/user.tsv") }
+      .to raise_error(SpellKit::FileNotFoundError, "User dictionary file not found: /nonexistent/user.tsv")
+
+    with_file("helot\t12\ntwo words\t3\n") do |path|
+      expect { checker(user_dictionary_path: path) }
+        .to raise_error(SpellKit::DictionaryParseError, "User dictionary line 2 is malformed: expected a single word")
+    end
+
+    expect { checker.export_user_dictionary(nil) }.to raise_error(SpellKit::InvalidArgumentError, /path must be a String/)
+  end
+end
@@ -0,0 +1,74 @@
+RSpec.describe "Index warm-up" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+
+  describe "#warmup" do
+    before do
+      SpellKit.load!(dictionary: test_unigrams)
+    end
+
+    it "runs a word and a typo per sample and reports what it touched" do
+      result = SpellKit.warmup(sample: 200, seed: 3)
+
+      expect(result["queries"]).to eq(400)
+      expect(result["entries_touched"]).to be > 0
+      expect(result["elapsed_ms"]).to be >= 0
+    end
+
+    it "touches the same entries for the same seed" do
+      first = SpellKit.warmup(sample: 100, seed: 9)
+      second = SpellKit.warmup(sample: 100, seed: 9)
+
+      expect(second["entries_touched"]).to eq(first["entries_touched"])
+    end
+
+    it "leaves the latency histograms alone" do
+      SpellKit.warmup(sample: 50)
+
+      expect(SpellKit.stats["latency"]["suggest"]["count"]).to eq(0)
+    end
+
+    it "rejects invalid sample sizes" do
+      expect {
+        SpellKit.warmup(sample: -1)
+      }.to raise_error(SpellKit::InvalidArgumentError, /sample must be a non-negative Integer/)
+    end
+
+    it "requires a loaded dictionary" do
+      expect { SpellKit::Checker.new.warmup }.to raise_error(SpellKit::NotLoadedError, /not loaded/)
+    end
+  end
+
+  describe "load! with warmup:" do
+    it "warms the new index before swapping it in" do
+      checker = SpellKit::Checker.new
+      checker.load!(dictionary: test_unigrams)
+      generations = []
+
+      checker.load!(dictionary: test_unigrams, warmup: 100, progress: lambda { |phase, _processed, _total|
+        generations << [phase, checker.stats["generation"]]
+      })
+
+      expect(generations).to include(["warmup", 1])
+      expect(generations.last).to eq(["finalizing", 2])
+      expect(checker.stats["warmup"]).to include("queries" => 200)
+    end
+
+    it "uses the default sample for warmup: true" do
+      SpellKit.load!(dictionary: test_unigrams, warmup: true)
+
+      expect(SpellKit.stats["warmup"]["queries"]).to eq(2 * SpellKit::Checker::WARMUP_SAMPLE)
+    end
+
+    it "reports no warm-up by default" do
+      SpellKit.load!(dictionary: test_unigrams)
+
+      expect(SpellKit.stats["warmup"]).to be_nil
+    end
+
+    it "rejects invalid values" do
+      expect {
+        SpellKit.load!(dictionary: test_unigrams, warmup: "yes")
+      }.to raise_error(SpellKit::InvalidArgumentError, /warmup must be/)
+    end
+  end
+end
@@ -0,0 +1,97 @@
+RSpec.describe "Corrections restricted to a closed set" do
+  let(:test_unigrams) { File.expand_path("fixtures/test_unigrams.tsv", __dir__) }
+  let(:protected_file) { File.expand_path("fixtures/protected.txt", __dir__) }
+
+  before do
+    SpellKit.load!(dictionary: test_unigrams, protected_path: protected_file, edit_distance: 2)
+  end
+
+  describe "inline sets" do
+    it "lands inside the set even when a closer out-of-set word exists" do
+      # hellp is one edit from hello, help, and hell, and two from cell
+      expect(SpellKit.correct("hellp")).to eq("hello")
+      expect(SpellKit.correct("hellp", within: %w[cell tissue])).to eq("cell")
+    end
+
+    it "picks the best in-set candidate" do
+      expect(SpellKit.correct("helo", within: %w[help hell])).to eq("help")
+    end
+
+    it "corrects a known word that is outside the set" do
+      expect(SpellKit.correct("hello", within: %w[hell])).to eq("hell")
+    end
+
+    it "keeps a word that is already in the set" do
+      expect(SpellKit.correct("Hello", within: %w[hello help])).to eq("hello")
+    end
+
+    it "matches set terms case-insensitively" do
+      expect(SpellKit.correct("helo", within: %w[HELLO])).to eq("hello")
+    end
+
+    it "returns the word unchanged when nothing in the set is close enough" do
+      expect(SpellKit.correct("helo", within: %w[protein enzyme])).to eq("helo")
+      expect(SpellKit.correct("xyzzy", within: %w[hello])).to eq("xyzzy")
+    end
+
+    it "still applies the frequency threshold" do
+      SpellKit.load!(dictionary: test_unigrams, edit_distance: 2, frequency_threshold: 5000.0)
+
+      expect(SpellKit.correct("hellp", within: %w[cell])).to eq("hellp")
+    end
+
+    it "leaves protected words alone" do
+      expect(SpellKit.correct("CDK10", within: %w[cell])).to eq("CDK10")
+    end
+  end
+
+  describe "named sets" do
+    it "reuses a registered set by name" do
+      SpellKit.define_set(:lab, %w[cell tissue sample])
+
+      expect(SpellKit.correct("hellp", within: :lab)).to eq("cell")
+      expect(SpellKit.correct("tisue", within: :lab)).to eq("tissue")
+      expect(SpellKit.correct("sampel", within: "lab")).to eq("sample")
+    end
+
+    it "replaces a set when it is redefined" do
+      SpellKit.define_set(:greetings, %w[hello])
+      SpellKit.define_set(:greetings, %w[help])
+
+      expect(SpellKit.correct("helo", within: :greetings)).to eq("help")
+    end
+
+    it "keeps sets across reloads" do
+      SpellKit.define_set(:lab, %w[cell])
+      SpellKit.default.load!(dictionary: test_unigrams, edit_distance: 2)
+
+      expect(SpellKit.correct("hellp", within: :lab)).to eq("cell")
+    end
+
+    it "keeps sets per checker" do
+      other = SpellKit::Checker.new.tap { |c| c.load!(dictionary: test_unigrams) }
+      SpellKit.define_set(:lab, %w[cell])
+
+      expect { other.correct("helo", within: :lab) }.to raise_error(ArgumentError, /Unknown set: lab/)
+    end
+
+    it "raises for an unknown set name" do
+      expect {
+        SpellKit.correct("helo", within: :nope)
+      }.to raise_error(ArgumentError, /Unknown set: nope/)
+    end
+  end
+
+  describe "validation" do
+    it "rejects non-String terms" do
+      expect { SpellKit.correct("helo", within: [1, 2]) }.to raise_error(SpellKit::InvalidArgumentError)
+      expect { SpellKit.define_set(:bad, [1]) }.to raise_error(SpellKit::InvalidArgumentError)
+    end
+
+    it "rejects other within values" do
+      expect {
+        SpellKit.correct("helo", within: 5)
+      }.to raise_error(SpellKit::InvalidArgumentError, /within must be an Array of terms or a set name/)
+    end
+  end
+end
@@ -0,0 +1,62 @@
+require "tempfile"
+
+RSpec.describe "Wordlist dictionaries" do
+  def dictionary(content)
+    file = Tempfile.new(["dictionary", ".txt"])
+    file.write(content)
+    file.close
+    (@files ||= []) << file
+    file.path
+  end
+
+  after { @files&.each(&:unlink) }
+
+  # Like /usr/share/dict/words: one word per line, mixed case and possessives included
+  let(:words) { dictionary("A\nA's\nAarhus\naardvark\naardvark's\nabacus\nhello\nhelp\nworld\n") }
+
+  it "loads one word per line with format: :wordlist" do
+    SpellKit.load!(dictionary: words, format: :wordlist)
+
+    expect(SpellKit.stats).to include("dictionary_size" => 9, "skipped_malformed" => 0, "format" => "wordlist")
+    expect(SpellKit.correct?("aardvark")).to be(true)
+    expect(SpellKit.correct?("aardvak")).to be(false)
+    expect(SpellKit.suggestions("aardvak", 1).first).to have_attributes(term: "aardvark", frequency: 1)
+  end
+
+  it "skips every line as malformed without it" do
+    SpellKit.load!(dictionary: words)
+
+    expect(SpellKit.stats).to include("dictionary_size" => 0, "skipped_malformed" => 9, "format" => "counts")
+  end
+
+  it "detects the format from the first line with format: :auto" do
+    SpellKit.load!(dictionary: words, format: :auto)
+    expect(SpellKit.stats).to include("dictionary_size" => 9, "format" => "wordlist")
+
+    SpellKit.load!(dictionary: File.expand_path("fixtures/test_unigrams.tsv", __dir__), format: :auto)
+    expect(SpellKit.stats).to include("dictionary_size" => 20, "format" => "counts")
+  end
+
+  it "gives every word default_frequency:" do
+    SpellKit.load!(dictionary: words, format: :wordlist, default_frequency: 50)
+
+    expect(SpellKit.suggestions("wrld", 1).first).to have_attributes(term: "world", frequency: 50)
+  end
+
+  it "raises on a mixed file in strict mode" do
+    mixed = dictionary("hello\nworld\t8000\nhelp\n")
+
+    SpellKit.load!(dictionary: mixed, format: :auto)
+    expect(SpellKit.stats).to include("dictionary_size" => 2, "skipped_malformed" => 1)
+
+    expect { SpellKit.load!(dictionary: mixed, format: :auto, strict: true) }
+      .to raise_error(SpellKit::DictionaryParseError, /line 2 has a frequency column, but the file's format is wordlist \(detected from line 1\)/)
+  end
+
+  it "validates the options" do
+    expect { SpellKit.load!(dictionary: words, format: :csv) }
+      .to raise_error(SpellKit::InvalidArgumentError, /format must be one of counts, wordlist, auto/)
+    expect { SpellKit.load!(dictionary: words, format: :wordlist, default_frequency: 0) }
+      .to raise_error(SpellKit::InvalidArgumentError, /default_frequency must be a positive Integer/)
+  end
+end
@@ -0,0 +1,72 @@
+require "tempfile"
+
+RSpec.describe "frequency_threshold at and below zero" do
+  let(:test_dict) do
+    dict = Tempfile.new(["zero_threshold", ".tsv"])
+    dict.write("hello\t10000\n")
+    dict.write("helo\t1\n")
+    dict.write("zero\t0\n")
+    dict.close
+    dict
+  end
+
+  after do
+    test_dict.unlink
+  end
+
+  def load(**options)
+    SpellKit.load!(dictionary: test_dict.path, frequency_threshold: 0, **options)
+  end
+
+  describe "with a threshold of 0" do
+    it "accepts any candidate for unknown words, even a zero-frequency one" do
+      load
+
+      expect(SpellKit.correct("zer")).to eq("zero")
+      expect(SpellKit.correct_tokens(["zer"], details: true).first).to include("reason" => "corrected", "freq" => 0)
+    end
+
+    it "leaves known words alone without autocorrect_known_rare" do
+      load
+
+      expect(SpellKit.correct("helo")).to eq("helo")
+    end
+
+    it "replaces rare known words by any neighbour under the relative threshold" do
+      load(autocorrect_known_rare: 2)
+
+      expect(SpellKit.correct("helo")).to eq("hello")
+    end
+
+    it "warns at load" do
+      expect { load }.to output(/frequency_threshold is 0/).to_stderr
+      expect { load(autocorrect_known_rare: 2) }.to output(/below autocorrect_known_rare/).to_stderr
+    end
+
+    it "does not warn for positive thresholds" do
+      expect {
+        SpellKit.load!(dictionary: test_dict.path, frequency_threshold: 0.5)
+      }.not_to output(/frequency_threshold/).to_stderr
+    end
+  end
+
+  describe "validation" do
+    it "rejects negative thresholds at load" do
+      expect {
+        SpellKit.load!(dictionary: test_dict.path, frequency_threshold: -0.1)
+      }.to raise_error(SpellKit::InvalidArgumentError, /must be non-negative/)
+    end
+
+    it "rejects invalid thresholds in the configuration setter" do
+      config = SpellKit::Configuration.new
+
+      expect { config.frequency_threshold = -1 }.to raise_error(SpellKit::InvalidArgumentError, /must be non-negative/)
+      expect { config.frequency_threshold = Float::NAN }.to raise_error(SpellKit::InvalidArgumentError, /must be finite/)
+      expect { config.frequency_threshold = "5" }.to raise_error(SpellKit::InvalidArgumentError, /must be a number/)
+      expect(config.frequency_threshold).to eq(10.0)
+
+      config.frequency_threshold = 0
+      expect(config.frequency_threshold).to eq(0)
+    end
+  end
+end